@@ -0,0 +1,180 @@
+//! `vaultix escrow` subcommands.
+
+use clap::{Args, Subcommand, ValueEnum};
+use vaultix_sdk::{DisputeReason, Milestone, MilestoneStatus, VaultixClient};
+
+use crate::keys;
+
+#[derive(Subcommand)]
+pub enum EscrowCommand {
+    /// Create an escrow as its depositor
+    Create {
+        #[arg(long)]
+        id: u64,
+        /// Recipient address (`G...`)
+        #[arg(long)]
+        recipient: String,
+        /// Token contract address (`C...`)
+        #[arg(long)]
+        token: String,
+        /// Milestone as AMOUNT:DESCRIPTION; repeat for each milestone, in order
+        #[arg(long = "milestone", value_parser = parse_milestone, required = true)]
+        milestones: Vec<Milestone>,
+        /// Unix timestamp after which the escrow expires
+        #[arg(long)]
+        deadline: u64,
+        #[command(flatten)]
+        signer: SignerArgs,
+    },
+    /// Deposit the escrow's total into the contract
+    Fund {
+        #[arg(long)]
+        id: u64,
+        #[command(flatten)]
+        signer: SignerArgs,
+    },
+    /// Release a milestone to the recipient
+    Release {
+        #[arg(long)]
+        id: u64,
+        /// Index of the milestone to release
+        #[arg(long)]
+        milestone: u32,
+        #[command(flatten)]
+        signer: SignerArgs,
+    },
+    /// Raise a dispute as either party
+    Dispute {
+        #[arg(long)]
+        id: u64,
+        #[arg(long, value_enum)]
+        reason: Reason,
+        #[command(flatten)]
+        signer: SignerArgs,
+    },
+    /// Show an escrow and its milestones
+    Status {
+        #[arg(long)]
+        id: u64,
+    },
+}
+
+#[derive(Args)]
+pub struct SignerArgs {
+    /// Keystore entry to sign with; defaults to VAULTIX_SECRET_KEY
+    #[arg(long)]
+    key: Option<String>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Reason {
+    NonDelivery,
+    Quality,
+    Scope,
+    Fraud,
+    Other,
+}
+
+impl From<Reason> for DisputeReason {
+    fn from(reason: Reason) -> Self {
+        match reason {
+            Reason::NonDelivery => DisputeReason::NonDelivery,
+            Reason::Quality => DisputeReason::Quality,
+            Reason::Scope => DisputeReason::Scope,
+            Reason::Fraud => DisputeReason::Fraud,
+            Reason::Other => DisputeReason::Other,
+        }
+    }
+}
+
+impl EscrowCommand {
+    pub async fn run(self, client: &VaultixClient) -> Result<(), String> {
+        match self {
+            EscrowCommand::Create {
+                id,
+                recipient,
+                token,
+                milestones,
+                deadline,
+                signer,
+            } => {
+                let depositor = signer.keypair()?;
+                client
+                    .create_escrow(&depositor, id, &recipient, &token, &milestones, deadline)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                println!("created escrow {id}");
+            }
+            EscrowCommand::Fund { id, signer } => {
+                client
+                    .deposit_funds(&signer.keypair()?, id)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                println!("funded escrow {id}");
+            }
+            EscrowCommand::Release {
+                id,
+                milestone,
+                signer,
+            } => {
+                client
+                    .release_milestone(&signer.keypair()?, id, milestone)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                println!("released milestone {milestone} of escrow {id}");
+            }
+            EscrowCommand::Dispute { id, reason, signer } => {
+                client
+                    .raise_dispute(&signer.keypair()?, id, reason.into())
+                    .await
+                    .map_err(|e| e.to_string())?;
+                println!("raised a dispute on escrow {id}");
+            }
+            EscrowCommand::Status { id } => {
+                let escrow = client.get_escrow(id).await.map_err(|e| e.to_string())?;
+                let milestones = client.get_milestones(id).await.map_err(|e| e.to_string())?;
+                println!("escrow {id}: {:?}", escrow.status);
+                println!("  depositor:  {}", escrow.depositor);
+                println!("  recipient:  {}", escrow.recipient);
+                println!("  token:      {}", escrow.token_address);
+                println!(
+                    "  released:   {} of {}",
+                    escrow.total_released, escrow.total_amount
+                );
+                println!("  deadline:   {}", escrow.deadline);
+                for (index, milestone) in milestones.iter().enumerate() {
+                    println!(
+                        "  [{index}] {:>10} {:<9} {}",
+                        milestone.amount,
+                        format!("{:?}", milestone.status),
+                        milestone.description
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SignerArgs {
+    fn keypair(&self) -> Result<vaultix_sdk::Keypair, String> {
+        keys::signer(self.key.as_deref())
+    }
+}
+
+/// Parses `AMOUNT:DESCRIPTION` into a pending milestone.
+pub fn parse_milestone(value: &str) -> Result<Milestone, String> {
+    let (amount, description) = value.split_once(':').ok_or("expected AMOUNT:DESCRIPTION")?;
+    let amount = amount
+        .trim()
+        .parse::<i128>()
+        .map_err(|_| format!("invalid amount `{amount}`"))?;
+    if amount <= 0 {
+        return Err("milestone amounts must be positive".into());
+    }
+    Ok(Milestone {
+        amount,
+        status: MilestoneStatus::Pending,
+        description: description.trim().to_string(),
+    })
+}
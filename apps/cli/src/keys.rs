@@ -0,0 +1,129 @@
+//! Signing keys, from the environment or a local keystore.
+//!
+//! The keystore is a directory (`$VAULTIX_KEYSTORE`, or `~/.config/vaultix/keys`) holding one
+//! file per named identity with its `S...` secret key.
+
+use std::{
+    fs,
+    io::{self, BufRead},
+    path::PathBuf,
+};
+
+use clap::Subcommand;
+use vaultix_sdk::Keypair;
+
+/// Environment variable holding a secret key, used when no `--key` is given.
+pub const SECRET_KEY_ENV: &str = "VAULTIX_SECRET_KEY";
+
+#[derive(Subcommand)]
+pub enum KeysCommand {
+    /// Store a secret key under NAME, reading it from stdin
+    Add { name: String },
+    /// Print the account address of a stored key
+    Address { name: String },
+    /// List stored key names
+    List,
+}
+
+impl KeysCommand {
+    pub fn run(self) -> Result<(), String> {
+        let keystore = Keystore::default_location()?;
+        match self {
+            KeysCommand::Add { name } => {
+                let mut secret = String::new();
+                io::stdin()
+                    .lock()
+                    .read_line(&mut secret)
+                    .map_err(|e| e.to_string())?;
+                let keypair = keystore.add(&name, secret.trim())?;
+                println!("{}", keypair.address());
+            }
+            KeysCommand::Address { name } => println!("{}", keystore.load(&name)?.address()),
+            KeysCommand::List => {
+                for name in keystore.names()? {
+                    println!("{name}");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct Keystore {
+    dir: PathBuf,
+}
+
+impl Keystore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn default_location() -> Result<Self, String> {
+        if let Some(dir) = std::env::var_os("VAULTIX_KEYSTORE") {
+            return Ok(Self::new(dir.into()));
+        }
+        let home = std::env::var_os("HOME").ok_or("HOME is not set; set VAULTIX_KEYSTORE")?;
+        Ok(Self::new(PathBuf::from(home).join(".config/vaultix/keys")))
+    }
+
+    /// Validates and stores `secret` under `name`, replacing any existing entry.
+    pub fn add(&self, name: &str, secret: &str) -> Result<Keypair, String> {
+        let keypair = Keypair::from_secret(secret).map_err(|e| e.to_string())?;
+        fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+        let path = self.path(name)?;
+        fs::write(&path, secret).map_err(|e| e.to_string())?;
+        restrict_permissions(&path)?;
+        Ok(keypair)
+    }
+
+    pub fn load(&self, name: &str) -> Result<Keypair, String> {
+        let secret = fs::read_to_string(self.path(name)?)
+            .map_err(|_| format!("no key named `{name}` in {}", self.dir.display()))?;
+        Keypair::from_secret(secret.trim()).map_err(|e| e.to_string())
+    }
+
+    pub fn names(&self) -> Result<Vec<String>, String> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Ok(Vec::new());
+        };
+        let mut names = entries
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .collect::<Vec<_>>();
+        names.sort();
+        Ok(names)
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf, String> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!("invalid key name `{name}`"));
+        }
+        Ok(self.dir.join(name))
+    }
+}
+
+/// The key named by `--key`, or the one in `VAULTIX_SECRET_KEY`.
+pub fn signer(name: Option<&str>) -> Result<Keypair, String> {
+    match name {
+        Some(name) => Keystore::default_location()?.load(name),
+        None => {
+            let secret = std::env::var(SECRET_KEY_ENV)
+                .map_err(|_| format!("no signing key; pass --key or set {SECRET_KEY_ENV}"))?;
+            Keypair::from_secret(secret.trim()).map_err(|e| e.to_string())
+        }
+    }
+}
+
+#[cfg(unix)]
+fn restrict_permissions(path: &std::path::Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600)).map_err(|e| e.to_string())
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &std::path::Path) -> Result<(), String> {
+    Ok(())
+}
@@ -0,0 +1,101 @@
+//! `vaultix` command-line tool.
+//!
+//! Manages escrows on a deployed Vaultix contract without writing code:
+//!
+//! ```text
+//! vaultix keys add alice            # store a secret key, read from stdin
+//! vaultix escrow create --id 1 --recipient G... --token C... \
+//!     --milestone 500:Design --milestone 500:Build --deadline 1767225600 --key alice
+//! vaultix escrow fund --id 1 --key alice
+//! vaultix escrow release --id 1 --milestone 0 --key alice
+//! vaultix escrow dispute --id 1 --reason quality --key alice
+//! vaultix escrow status --id 1
+//! ```
+//!
+//! The network, RPC endpoint and contract come from flags or `VAULTIX_*` environment
+//! variables. Signing keys come from `VAULTIX_SECRET_KEY` or a named keystore entry.
+
+mod escrow;
+mod keys;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use vaultix_sdk::{VaultixClient, MAINNET_PASSPHRASE, TESTNET_PASSPHRASE};
+
+#[derive(Parser)]
+#[command(name = "vaultix", version, about = "Manage Vaultix escrows")]
+struct Cli {
+    #[command(flatten)]
+    network: NetworkArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(clap::Args)]
+struct NetworkArgs {
+    /// Network the contract is deployed on
+    #[arg(long, env = "VAULTIX_NETWORK", value_enum, default_value_t = Network::Testnet, global = true)]
+    network: Network,
+    /// Soroban RPC endpoint; defaults to the public testnet endpoint
+    #[arg(long, env = "VAULTIX_RPC_URL", global = true)]
+    rpc_url: Option<String>,
+    /// Escrow contract address (`C...`)
+    #[arg(long, env = "VAULTIX_CONTRACT_ID", global = true)]
+    contract: Option<String>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Network {
+    Testnet,
+    Mainnet,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create, fund, release, dispute and inspect escrows
+    #[command(subcommand)]
+    Escrow(escrow::EscrowCommand),
+    /// Manage the local keystore
+    #[command(subcommand)]
+    Keys(keys::KeysCommand),
+}
+
+impl NetworkArgs {
+    fn client(&self) -> Result<VaultixClient, String> {
+        let (passphrase, default_rpc) = match self.network {
+            Network::Testnet => (TESTNET_PASSPHRASE, Some(TESTNET_RPC_URL)),
+            Network::Mainnet => (MAINNET_PASSPHRASE, None),
+        };
+        let rpc_url =
+            self.rpc_url.as_deref().or(default_rpc).ok_or(
+                "mainnet has no default RPC endpoint; pass --rpc-url or set VAULTIX_RPC_URL",
+            )?;
+        let contract = self
+            .contract
+            .as_deref()
+            .ok_or("no contract given; pass --contract or set VAULTIX_CONTRACT_ID")?;
+        VaultixClient::try_new(rpc_url, contract, passphrase)
+            .map_err(|_| format!("`{contract}` is not a contract address"))
+    }
+}
+
+const TESTNET_RPC_URL: &str = "https://soroban-testnet.stellar.org";
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Escrow(command) => match cli.network.client() {
+            Ok(client) => command.run(&client).await,
+            Err(message) => Err(message),
+        },
+        Command::Keys(command) => command.run(),
+    };
+
+    if let Err(message) = result {
+        eprintln!("error: {message}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,65 @@
+use clap::Parser;
+
+use crate::{escrow::parse_milestone, keys::Keystore, Cli};
+
+const SECRET: &str = "SADQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQP54X";
+
+#[test]
+fn test_parses_escrow_commands() {
+    let milestone = parse_milestone("500: Design mockups").unwrap();
+    assert_eq!(milestone.amount, 500);
+    assert_eq!(milestone.description, "Design mockups");
+    assert!(parse_milestone("Design").is_err());
+    assert!(parse_milestone("-5:Refund").is_err());
+
+    let cli = Cli::try_parse_from([
+        "vaultix",
+        "escrow",
+        "create",
+        "--id",
+        "1",
+        "--recipient",
+        "GRECIPIENT",
+        "--token",
+        "CTOKEN",
+        "--milestone",
+        "500:Design",
+        "--milestone",
+        "500:Build",
+        "--deadline",
+        "1767225600",
+    ]);
+    assert!(cli.is_ok());
+
+    // At least one milestone is required
+    let cli = Cli::try_parse_from([
+        "vaultix",
+        "escrow",
+        "create",
+        "--id",
+        "1",
+        "--recipient",
+        "G",
+        "--token",
+        "C",
+        "--deadline",
+        "1",
+    ]);
+    assert!(cli.is_err());
+}
+
+#[test]
+fn test_keystore_round_trip() {
+    let dir = std::env::temp_dir().join(format!("vaultix-keys-{}", std::process::id()));
+    let keystore = Keystore::new(dir.clone());
+
+    let stored = keystore.add("alice", SECRET).unwrap();
+    assert_eq!(keystore.load("alice").unwrap().address(), stored.address());
+    assert_eq!(keystore.names().unwrap(), vec!["alice".to_string()]);
+
+    assert!(keystore.add("bob", "not-a-secret").is_err());
+    assert!(keystore.add("../escape", SECRET).is_err());
+    assert!(keystore.load("carol").is_err());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
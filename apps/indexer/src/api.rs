@@ -0,0 +1,75 @@
+//! HTTP/JSON API over the indexed escrows.
+//!
+//! | route                     | returns                                              |
+//! |---------------------------|------------------------------------------------------|
+//! | `GET /health`             | `{ "ledger": n }`, the ledger indexing resumes from  |
+//! | `GET /escrows`            | escrows, newest first; filter with `depositor`, `recipient`, `status`, `limit`, `offset` |
+//! | `GET /escrows/:id`        | one escrow, or 404                                   |
+//! | `GET /escrows/:id/events` | the escrow's events, oldest first                    |
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde_json::json;
+
+use crate::store::{EscrowFilter, Store};
+
+pub fn router(store: Arc<Store>) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/escrows", get(list_escrows))
+        .route("/escrows/:id", get(get_escrow))
+        .route("/escrows/:id/events", get(escrow_events))
+        .with_state(store)
+}
+
+async fn health(State(store): State<Arc<Store>>) -> Response {
+    match store.cursor() {
+        Ok(ledger) => Json(json!({ "ledger": ledger })).into_response(),
+        Err(error) => internal(error),
+    }
+}
+
+async fn list_escrows(
+    State(store): State<Arc<Store>>,
+    Query(filter): Query<EscrowFilter>,
+) -> Response {
+    match store.escrows(&filter) {
+        Ok(escrows) => Json(escrows).into_response(),
+        Err(error) => internal(error),
+    }
+}
+
+async fn get_escrow(State(store): State<Arc<Store>>, Path(id): Path<u64>) -> Response {
+    match store.escrow(id) {
+        Ok(Some(escrow)) => Json(escrow).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "escrow not found" })),
+        )
+            .into_response(),
+        Err(error) => internal(error),
+    }
+}
+
+async fn escrow_events(State(store): State<Arc<Store>>, Path(id): Path<u64>) -> Response {
+    match store.events(id) {
+        Ok(events) => Json(events).into_response(),
+        Err(error) => internal(error),
+    }
+}
+
+fn internal(error: rusqlite::Error) -> Response {
+    eprintln!("api: {error}");
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({ "error": "internal error" })),
+    )
+        .into_response()
+}
@@ -0,0 +1,91 @@
+//! Follows the contract's events and keeps the store up to date.
+
+use std::{collections::BTreeSet, sync::Arc, time::Duration};
+
+use vaultix_sdk::{Error as SdkError, VaultixClient};
+
+use crate::store::Store;
+
+/// Pause between polls once the indexer has caught up with the chain.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+pub struct Indexer {
+    client: VaultixClient,
+    store: Arc<Store>,
+}
+
+impl Indexer {
+    pub fn new(client: VaultixClient, store: Arc<Store>) -> Self {
+        Self { client, store }
+    }
+
+    /// Indexes forever from the stored cursor, or from `start_ledger` on the first run.
+    /// Errors are logged and retried; a restart resumes where the last run stopped.
+    pub async fn run(self, start_ledger: Option<u32>) {
+        loop {
+            match self.sync_once(start_ledger).await {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(error) => eprintln!("indexer: {error}"),
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Indexes one page of events. Returns whether more pages are waiting.
+    pub async fn sync_once(&self, start_ledger: Option<u32>) -> Result<bool, String> {
+        let from = match (
+            self.store.cursor().map_err(|e| e.to_string())?,
+            start_ledger,
+        ) {
+            (Some(cursor), _) => cursor,
+            (None, Some(start)) => start,
+            (None, None) => {
+                self.client
+                    .rpc()
+                    .get_latest_ledger()
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .sequence
+            }
+        };
+
+        let page = self
+            .client
+            .get_events(from)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut touched = BTreeSet::new();
+        for event in &page.events {
+            if self.store.apply_event(event).map_err(|e| e.to_string())? {
+                touched.insert(event.escrow_id);
+            }
+        }
+        for escrow_id in touched {
+            self.refresh(escrow_id, page.latest_ledger).await?;
+        }
+
+        // A full page may end mid-ledger, so the next page re-reads from its last ledger;
+        // events already indexed are skipped by id.
+        let next = match page.events.last() {
+            Some(last) if page.truncated => last.ledger,
+            _ => page.latest_ledger.max(from),
+        };
+        self.store.set_cursor(next).map_err(|e| e.to_string())?;
+        Ok(page.truncated && next > from)
+    }
+
+    /// Replaces the event-derived row with the contract's view of the escrow. Escrows that
+    /// were closed no longer exist on-chain and keep their event-derived state.
+    async fn refresh(&self, escrow_id: u64, ledger: u32) -> Result<(), String> {
+        match self.client.get_escrow(escrow_id).await {
+            Ok(escrow) => self
+                .store
+                .save_snapshot(escrow_id, &escrow, ledger)
+                .map_err(|e| e.to_string()),
+            Err(SdkError::Contract(_)) => Ok(()),
+            Err(error) => Err(error.to_string()),
+        }
+    }
+}
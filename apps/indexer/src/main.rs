@@ -0,0 +1,70 @@
+//! Vaultix event indexer.
+//!
+//! Follows a Vaultix contract's `escrow_v1` events over Soroban RPC, materializes escrows into
+//! a SQLite database and serves them as JSON for dashboards that need listings the on-chain
+//! getters cannot provide. Configured through the environment:
+//!
+//! | variable                | meaning                                         | default                   |
+//! |-------------------------|-------------------------------------------------|---------------------------|
+//! | `VAULTIX_RPC_URL`       | Soroban RPC endpoint                            | public testnet endpoint   |
+//! | `VAULTIX_CONTRACT_ID`   | escrow contract to index                        | required                  |
+//! | `VAULTIX_NETWORK`       | `testnet` or `mainnet`                          | `testnet`                 |
+//! | `INDEXER_DB`            | SQLite database path                            | `vaultix-indexer.db`      |
+//! | `INDEXER_ADDR`          | address the API listens on                      | `127.0.0.1:8080`          |
+//! | `INDEXER_START_LEDGER`  | first ledger to index on a fresh database       | the latest ledger         |
+
+mod api;
+mod indexer;
+mod store;
+
+use std::sync::Arc;
+
+use vaultix_sdk::{VaultixClient, MAINNET_PASSPHRASE, TESTNET_PASSPHRASE};
+
+use crate::{indexer::Indexer, store::Store};
+
+const TESTNET_RPC_URL: &str = "https://soroban-testnet.stellar.org";
+
+#[tokio::main]
+async fn main() {
+    if let Err(message) = run().await {
+        eprintln!("error: {message}");
+        std::process::exit(1);
+    }
+}
+
+async fn run() -> Result<(), String> {
+    let passphrase = match env("VAULTIX_NETWORK").as_deref() {
+        None | Some("testnet") => TESTNET_PASSPHRASE,
+        Some("mainnet") => MAINNET_PASSPHRASE,
+        Some(other) => return Err(format!("unknown network `{other}`")),
+    };
+    let rpc_url = env("VAULTIX_RPC_URL").unwrap_or_else(|| TESTNET_RPC_URL.to_string());
+    let contract = env("VAULTIX_CONTRACT_ID").ok_or("VAULTIX_CONTRACT_ID is not set")?;
+    let client = VaultixClient::try_new(&rpc_url, &contract, passphrase)
+        .map_err(|_| format!("`{contract}` is not a contract address"))?;
+
+    let db = env("INDEXER_DB").unwrap_or_else(|| "vaultix-indexer.db".to_string());
+    let store = Arc::new(Store::open(&db).map_err(|e| format!("opening {db}: {e}"))?);
+    let start_ledger = env("INDEXER_START_LEDGER")
+        .map(|ledger| ledger.parse().map_err(|_| "invalid INDEXER_START_LEDGER"))
+        .transpose()?;
+
+    let addr = env("INDEXER_ADDR").unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .map_err(|e| format!("binding {addr}: {e}"))?;
+    println!("indexing {contract}, serving on {addr}");
+
+    tokio::spawn(Indexer::new(client, store.clone()).run(start_ledger));
+    axum::serve(listener, api::router(store))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+fn env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,282 @@
+//! SQLite store holding indexed events and the latest known state of each escrow.
+
+use std::{path::Path, sync::Mutex};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use vaultix_sdk::{Escrow, EscrowEvent};
+
+pub type Result<T> = rusqlite::Result<T>;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS escrows (
+    id             INTEGER PRIMARY KEY,
+    depositor      TEXT NOT NULL,
+    recipient      TEXT,
+    token_address  TEXT,
+    status         TEXT NOT NULL,
+    total_amount   TEXT NOT NULL,
+    total_released TEXT NOT NULL,
+    deadline       INTEGER,
+    created_ledger INTEGER NOT NULL,
+    updated_ledger INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS escrows_depositor ON escrows (depositor);
+CREATE INDEX IF NOT EXISTS escrows_recipient ON escrows (recipient);
+CREATE INDEX IF NOT EXISTS escrows_status ON escrows (status);
+
+CREATE TABLE IF NOT EXISTS events (
+    id        TEXT PRIMARY KEY,
+    ledger    INTEGER NOT NULL,
+    escrow_id INTEGER NOT NULL,
+    action    TEXT NOT NULL,
+    actor     TEXT NOT NULL,
+    amount    TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS events_escrow ON events (escrow_id, ledger);
+
+CREATE TABLE IF NOT EXISTS cursor (
+    id     INTEGER PRIMARY KEY CHECK (id = 0),
+    ledger INTEGER NOT NULL
+);
+";
+
+/// An escrow as served by the API. Amounts are strings so `i128` values survive JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EscrowRow {
+    pub id: u64,
+    pub depositor: String,
+    pub recipient: Option<String>,
+    pub token_address: Option<String>,
+    pub status: String,
+    pub total_amount: String,
+    pub total_released: String,
+    pub deadline: Option<u64>,
+    pub created_ledger: u32,
+    pub updated_ledger: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EventRow {
+    pub id: String,
+    pub ledger: u32,
+    pub escrow_id: u64,
+    pub action: String,
+    pub actor: String,
+    pub amount: String,
+}
+
+/// Filters for listing escrows; unset fields match everything.
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+pub struct EscrowFilter {
+    pub depositor: Option<String>,
+    pub recipient: Option<String>,
+    pub status: Option<String>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+const DEFAULT_PAGE: u32 = 50;
+const MAX_PAGE: u32 = 500;
+
+pub struct Store {
+    conn: Mutex<Connection>,
+}
+
+impl Store {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    #[cfg(test)]
+    pub fn in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Ledger to resume indexing from, if any events were indexed before.
+    pub fn cursor(&self) -> Result<Option<u32>> {
+        self.conn()
+            .query_row("SELECT ledger FROM cursor WHERE id = 0", [], |row| {
+                row.get(0)
+            })
+            .optional()
+    }
+
+    pub fn set_cursor(&self, ledger: u32) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO cursor (id, ledger) VALUES (0, ?1)
+             ON CONFLICT (id) DO UPDATE SET ledger = excluded.ledger",
+            params![ledger],
+        )?;
+        Ok(())
+    }
+
+    /// Records `event` and folds it into its escrow's row. Returns `false` for an event that
+    /// was already indexed, which happens when a ledger is re-scanned.
+    pub fn apply_event(&self, event: &EscrowEvent) -> Result<bool> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        let inserted = tx.execute(
+            "INSERT OR IGNORE INTO events (id, ledger, escrow_id, action, actor, amount)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                event.id,
+                event.ledger,
+                event.escrow_id,
+                event.action,
+                event.actor,
+                event.amount.to_string()
+            ],
+        )?;
+        if inserted == 0 {
+            return Ok(false);
+        }
+
+        if event.action == "create" {
+            tx.execute(
+                "INSERT OR IGNORE INTO escrows (id, depositor, status, total_amount,
+                     total_released, created_ledger, updated_ledger)
+                 VALUES (?1, ?2, 'Created', ?3, '0', ?4, ?4)",
+                params![
+                    event.escrow_id,
+                    event.actor,
+                    event.amount.to_string(),
+                    event.ledger
+                ],
+            )?;
+        } else if let Some(status) = status_after(&event.action) {
+            tx.execute(
+                "UPDATE escrows SET status = ?2, updated_ledger = ?3 WHERE id = ?1",
+                params![event.escrow_id, status, event.ledger],
+            )?;
+        }
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// Overwrites an escrow's row with state read from the contract.
+    pub fn save_snapshot(&self, escrow_id: u64, escrow: &Escrow, ledger: u32) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO escrows (id, depositor, recipient, token_address, status, total_amount,
+                 total_released, deadline, created_ledger, updated_ledger)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9)
+             ON CONFLICT (id) DO UPDATE SET
+                 depositor = excluded.depositor,
+                 recipient = excluded.recipient,
+                 token_address = excluded.token_address,
+                 status = excluded.status,
+                 total_amount = excluded.total_amount,
+                 total_released = excluded.total_released,
+                 deadline = excluded.deadline,
+                 updated_ledger = excluded.updated_ledger",
+            params![
+                escrow_id,
+                escrow.depositor,
+                escrow.recipient,
+                escrow.token_address,
+                format!("{:?}", escrow.status),
+                escrow.total_amount.to_string(),
+                escrow.total_released.to_string(),
+                escrow.deadline,
+                ledger
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn escrow(&self, id: u64) -> Result<Option<EscrowRow>> {
+        self.conn()
+            .query_row(
+                &format!("SELECT {ESCROW_COLUMNS} FROM escrows WHERE id = ?1"),
+                params![id],
+                escrow_row,
+            )
+            .optional()
+    }
+
+    pub fn escrows(&self, filter: &EscrowFilter) -> Result<Vec<EscrowRow>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ESCROW_COLUMNS} FROM escrows
+             WHERE (?1 IS NULL OR depositor = ?1)
+               AND (?2 IS NULL OR recipient = ?2)
+               AND (?3 IS NULL OR status = ?3)
+             ORDER BY id DESC LIMIT ?4 OFFSET ?5"
+        ))?;
+        let rows = stmt.query_map(
+            params![
+                filter.depositor,
+                filter.recipient,
+                filter.status,
+                filter.limit.unwrap_or(DEFAULT_PAGE).min(MAX_PAGE),
+                filter.offset.unwrap_or(0)
+            ],
+            escrow_row,
+        )?;
+        rows.collect()
+    }
+
+    pub fn events(&self, escrow_id: u64) -> Result<Vec<EventRow>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, ledger, escrow_id, action, actor, amount FROM events
+             WHERE escrow_id = ?1 ORDER BY ledger, id",
+        )?;
+        let rows = stmt.query_map(params![escrow_id], |row| {
+            Ok(EventRow {
+                id: row.get(0)?,
+                ledger: row.get(1)?,
+                escrow_id: row.get(2)?,
+                action: row.get(3)?,
+                actor: row.get(4)?,
+                amount: row.get(5)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+const ESCROW_COLUMNS: &str = "id, depositor, recipient, token_address, status, total_amount,
+    total_released, deadline, created_ledger, updated_ledger";
+
+fn escrow_row(row: &rusqlite::Row<'_>) -> Result<EscrowRow> {
+    Ok(EscrowRow {
+        id: row.get(0)?,
+        depositor: row.get(1)?,
+        recipient: row.get(2)?,
+        token_address: row.get(3)?,
+        status: row.get(4)?,
+        total_amount: row.get(5)?,
+        total_released: row.get(6)?,
+        deadline: row.get(7)?,
+        created_ledger: row.get(8)?,
+        updated_ledger: row.get(9)?,
+    })
+}
+
+/// Status implied by an action, for escrows the contract can no longer be asked about
+/// (closed or archived) or when a snapshot read fails.
+fn status_after(action: &str) -> Option<&'static str> {
+    match action {
+        "activated" | "accepted" => Some("Created"),
+        "deposit" | "resumed" | "taken" | "netted" => Some("Active"),
+        "completed" => Some("Completed"),
+        "cancelled" | "clawback" | "recovered" | "expired" => Some("Cancelled"),
+        "disputed" | "escalated" => Some("Disputed"),
+        "resolved" | "settled" => Some("Resolved"),
+        _ => None,
+    }
+}
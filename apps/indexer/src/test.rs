@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use vaultix_sdk::{Escrow, EscrowEvent, EscrowStatus, Resolution};
+
+use crate::{
+    api,
+    store::{EscrowFilter, Store},
+};
+
+fn event(id: &str, ledger: u32, escrow_id: u64, action: &str, amount: i128) -> EscrowEvent {
+    EscrowEvent {
+        id: id.to_string(),
+        ledger,
+        action: action.to_string(),
+        escrow_id,
+        actor: "GDEPOSITOR".to_string(),
+        amount,
+    }
+}
+
+#[test]
+fn test_store_folds_events_and_snapshots() {
+    let store = Store::in_memory().unwrap();
+    assert_eq!(store.cursor().unwrap(), None);
+
+    assert!(store
+        .apply_event(&event("1-1", 10, 1, "create", 1000))
+        .unwrap());
+    assert!(store
+        .apply_event(&event("1-2", 11, 1, "deposit", 1000))
+        .unwrap());
+    // Re-scanned ledgers do not duplicate events
+    assert!(!store
+        .apply_event(&event("1-2", 11, 1, "deposit", 1000))
+        .unwrap());
+    assert!(store
+        .apply_event(&event("2-1", 12, 2, "create", 300))
+        .unwrap());
+    store.set_cursor(12).unwrap();
+
+    let escrow = store.escrow(1).unwrap().unwrap();
+    assert_eq!(escrow.status, "Active");
+    assert_eq!(escrow.total_amount, "1000");
+    assert_eq!(escrow.recipient, None);
+    assert_eq!(store.events(1).unwrap().len(), 2);
+
+    let snapshot = Escrow {
+        depositor: "GDEPOSITOR".into(),
+        recipient: "GRECIPIENT".into(),
+        token_address: "CTOKEN".into(),
+        total_amount: 1000,
+        total_released: 400,
+        milestone_count: 2,
+        released_mask: 1,
+        disputed_mask: 0,
+        status: EscrowStatus::Active,
+        deadline: 1706400000,
+        resolution: Resolution::None,
+        assignable: false,
+    };
+    store.save_snapshot(1, &snapshot, 12).unwrap();
+    let escrow = store.escrow(1).unwrap().unwrap();
+    assert_eq!(escrow.recipient.as_deref(), Some("GRECIPIENT"));
+    assert_eq!(escrow.total_released, "400");
+    assert_eq!(escrow.created_ledger, 10);
+
+    let by_recipient = EscrowFilter {
+        recipient: Some("GRECIPIENT".into()),
+        ..Default::default()
+    };
+    assert_eq!(store.escrows(&by_recipient).unwrap().len(), 1);
+    let created = EscrowFilter {
+        status: Some("Created".into()),
+        ..Default::default()
+    };
+    assert_eq!(store.escrows(&created).unwrap()[0].id, 2);
+    assert_eq!(store.cursor().unwrap(), Some(12));
+}
+
+#[tokio::test]
+async fn test_api_serves_indexed_escrows() {
+    let store = Arc::new(Store::in_memory().unwrap());
+    store
+        .apply_event(&event("1-1", 10, 7, "create", 500))
+        .unwrap();
+    store.set_cursor(10).unwrap();
+
+    let get = |uri: &str| {
+        api::router(store.clone()).oneshot(Request::get(uri).body(Body::empty()).unwrap())
+    };
+    let json = |response: axum::response::Response| async {
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice::<serde_json::Value>(&bytes).unwrap()
+    };
+
+    let response = get("/escrows?depositor=GDEPOSITOR").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let escrows = json(response).await;
+    assert_eq!(escrows[0]["id"], 7);
+    assert_eq!(escrows[0]["total_amount"], "500");
+
+    let events = json(get("/escrows/7/events").await.unwrap()).await;
+    assert_eq!(events[0]["action"], "create");
+
+    assert_eq!(
+        get("/escrows/8").await.unwrap().status(),
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(json(get("/health").await.unwrap()).await["ledger"], 10);
+}
@@ -0,0 +1,15 @@
+//! End-to-end scenarios for the Vaultix escrow.
+//!
+//! The tests here deploy the release escrow WASM, not the native contract the unit tests use,
+//! against a real Stellar asset contract, and drive complete lifecycles through it: creation,
+//! funding, submission, release and completion, and disputes settled each way. Token balances
+//! of every party and of the contract are checked after each step, so a regression in how
+//! funds move shows up even when the escrow's own bookkeeping looks right.
+//!
+//! Build the escrow first with `cargo build --target wasm32v1-none --release -p onchain`.
+
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,318 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, vec, Address, BytesN, Env, String, Vec,
+};
+
+mod escrow {
+    soroban_sdk::contractimport!(file = "../target/wasm32v1-none/release/onchain.wasm");
+}
+
+/// An escrow instance deployed from the release WASM, with a Stellar asset to escrow
+struct Deployment<'a> {
+    env: Env,
+    vaultix: escrow::Client<'a>,
+    token: token::Client<'a>,
+    treasury: Address,
+    depositor: Address,
+    recipient: Address,
+}
+
+impl<'a> Deployment<'a> {
+    fn new(env: &'a Env) -> Self {
+        env.mock_all_auths();
+        // Instantiating the full escrow WASM exceeds the default test budget
+        env.budget().reset_unlimited();
+
+        let vaultix = escrow::Client::new(env, &env.register_contract_wasm(None, escrow::WASM));
+        let treasury = Address::generate(env);
+        vaultix.setup(&Address::generate(env), &treasury, &None);
+        let token_address = env.register_stellar_asset_contract(Address::generate(env));
+
+        Self {
+            env: env.clone(),
+            vaultix,
+            token: token::Client::new(env, &token_address),
+            treasury,
+            depositor: Address::generate(env),
+            recipient: Address::generate(env),
+        }
+    }
+
+    /// Pending milestones, one per amount.
+    fn milestones(&self, amounts: &[i128]) -> Vec<escrow::Milestone> {
+        let mut milestones = vec![&self.env];
+        for amount in amounts {
+            milestones.push_back(escrow::Milestone {
+                amount: *amount,
+                status: escrow::MilestoneStatus::Pending,
+                description: String::from_str(&self.env, "Deliverable"),
+            });
+        }
+        milestones
+    }
+
+    /// Creates escrow `escrow_id` with one milestone per amount and funds it from a freshly
+    /// minted balance.
+    fn funded_escrow(&self, escrow_id: u64, amounts: &[i128]) {
+        let total = amounts.iter().sum();
+        token::StellarAssetClient::new(&self.env, &self.token.address)
+            .mint(&self.depositor, &total);
+        self.vaultix.create_escrow(
+            &escrow_id,
+            &self.depositor,
+            &self.recipient,
+            &self.token.address,
+            &self.milestones(amounts),
+            &1706400000u64,
+        );
+        self.assert_balances(total, 0, 0, 0);
+
+        self.token
+            .approve(&self.depositor, &self.vaultix.address, &total, &200);
+        self.vaultix.deposit_funds(&escrow_id);
+        self.assert_balances(0, 0, 0, total);
+    }
+
+    fn assert_balances(&self, depositor: i128, recipient: i128, treasury: i128, held: i128) {
+        assert_eq!(self.token.balance(&self.depositor), depositor, "depositor");
+        assert_eq!(self.token.balance(&self.recipient), recipient, "recipient");
+        assert_eq!(self.token.balance(&self.treasury), treasury, "treasury");
+        assert_eq!(self.token.balance(&self.vaultix.address), held, "contract");
+    }
+
+    /// Raises a dispute and lets the evidence window close.
+    fn dispute(&self, escrow_id: u64, raiser: &Address) {
+        self.vaultix
+            .raise_dispute(&escrow_id, raiser, &escrow::DisputeReason::Quality);
+        assert_eq!(
+            self.vaultix.get_escrow(&escrow_id).status,
+            escrow::EscrowStatus::Disputed
+        );
+        self.env.ledger().with_mut(|ledger| ledger.timestamp += 1);
+    }
+}
+
+#[test]
+fn test_happy_path_moves_tokens_at_every_step() {
+    let env = Env::default();
+    let world = Deployment::new(&env);
+    world.funded_escrow(1, &[1_000, 500]);
+    let vaultix = &world.vaultix;
+
+    // Submissions only record the deliverable
+    vaultix.submit_milestone(&1, &0, &BytesN::from_array(&env, &[1; 32]), &None);
+    world.assert_balances(0, 0, 0, 1_500);
+
+    // The 0.5% platform fee goes to the treasury, rounded down
+    vaultix.release_milestone(&1, &0);
+    world.assert_balances(0, 995, 5, 500);
+
+    vaultix.submit_milestone(&1, &1, &BytesN::from_array(&env, &[2; 32]), &None);
+    vaultix.release_milestone(&1, &1);
+    world.assert_balances(0, 1_493, 7, 0);
+
+    vaultix.complete_escrow(&1);
+    let escrow = vaultix.get_escrow(&1);
+    assert_eq!(escrow.status, escrow::EscrowStatus::Completed);
+    assert_eq!(escrow.total_released, 1_500);
+    world.assert_balances(0, 1_493, 7, 0);
+}
+
+#[test]
+fn test_dispute_won_by_recipient_pays_out_the_rest() {
+    let env = Env::default();
+    let world = Deployment::new(&env);
+    world.funded_escrow(1, &[1_000, 500]);
+    let vaultix = &world.vaultix;
+
+    vaultix.release_milestone(&1, &0);
+    world.assert_balances(0, 995, 5, 500);
+
+    world.dispute(1, &world.recipient);
+    world.assert_balances(0, 995, 5, 500);
+    vaultix.resolve_dispute(
+        &1,
+        &world.recipient,
+        &escrow::RulingReason::WorkAccepted,
+        &None,
+    );
+    assert_eq!(
+        vaultix.get_escrow(&1).status,
+        escrow::EscrowStatus::Resolved
+    );
+    world.assert_balances(0, 1_495, 5, 0);
+}
+
+#[test]
+fn test_dispute_won_by_depositor_refunds_held_funds() {
+    let env = Env::default();
+    let world = Deployment::new(&env);
+    world.funded_escrow(1, &[1_000, 500]);
+    let vaultix = &world.vaultix;
+
+    vaultix.release_milestone(&1, &0);
+    world.dispute(1, &world.depositor);
+    vaultix.resolve_dispute(
+        &1,
+        &world.depositor,
+        &escrow::RulingReason::NotDelivered,
+        &None,
+    );
+    assert_eq!(
+        vaultix.get_escrow(&1).status,
+        escrow::EscrowStatus::Resolved
+    );
+    world.assert_balances(500, 995, 5, 0);
+}
+
+#[test]
+fn test_settlement_is_accepted_only_as_proposed() {
+    let env = Env::default();
+    let world = Deployment::new(&env);
+    world.funded_escrow(1, &[1_000]);
+    let vaultix = &world.vaultix;
+
+    world.dispute(1, &world.depositor);
+    vaultix.propose_settlement(&1, &world.recipient, &700);
+    // The recipient replaces the proposal after the depositor read it
+    vaultix.propose_settlement(&1, &world.recipient, &900);
+    assert_eq!(
+        vaultix.try_accept_settlement(&1, &world.depositor, &700),
+        Err(Ok(escrow::ExtendedError::SettlementMismatch.into()))
+    );
+    world.assert_balances(0, 0, 0, 1_000);
+
+    vaultix.accept_settlement(&1, &world.depositor, &900);
+    world.assert_balances(100, 900, 0, 0);
+}
+
+#[test]
+fn test_partial_ruling_resumes_escrow_for_remaining_milestones() {
+    let env = Env::default();
+    let world = Deployment::new(&env);
+    world.funded_escrow(1, &[1_000, 500]);
+    let vaultix = &world.vaultix;
+
+    // The awarded milestone is paid in full; the escrow goes back to Active for the rest
+    world.dispute(1, &world.recipient);
+    vaultix.resolve_dispute_partial(&1, &vec![&env, 0]);
+    assert_eq!(vaultix.get_escrow(&1).status, escrow::EscrowStatus::Active);
+    world.assert_balances(0, 1_000, 0, 500);
+
+    vaultix.release_milestone(&1, &1);
+    world.assert_balances(0, 1_498, 2, 0);
+}
+
+mod fee_token {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env};
+
+    /// Token burning a fee from every `transfer_from`: 1% rounded down, or a flat 5 units
+    #[contract]
+    pub struct FeeOnTransferToken;
+
+    #[contractimpl]
+    impl FeeOnTransferToken {
+        pub fn set_flat_fee(env: Env, flat: bool) {
+            env.storage().instance().set(&symbol_short!("flat"), &flat);
+        }
+
+        pub fn mint(env: Env, to: Address, amount: i128) {
+            let balance = Self::balance(env.clone(), to.clone());
+            env.storage().persistent().set(&to, &(balance + amount));
+        }
+
+        pub fn balance(env: Env, id: Address) -> i128 {
+            env.storage().persistent().get(&id).unwrap_or(0)
+        }
+
+        pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+            Self::mint(env.clone(), from, -amount);
+            Self::mint(env, to, amount);
+        }
+
+        pub fn transfer_from(
+            env: Env,
+            _spender: Address,
+            from: Address,
+            to: Address,
+            amount: i128,
+        ) {
+            let flat: bool = env
+                .storage()
+                .instance()
+                .get(&symbol_short!("flat"))
+                .unwrap_or(false);
+            let fee = if flat { 5 } else { amount / 100 };
+            Self::mint(env.clone(), from, -amount);
+            Self::mint(env, to, amount - fee);
+        }
+    }
+}
+
+#[test]
+fn test_fee_on_transfer_deposits_are_rejected() {
+    let env = Env::default();
+    let world = Deployment::new(&env);
+    let vaultix = &world.vaultix;
+    let token = env.register_contract(None, fee_token::FeeOnTransferToken);
+    let fee_token = fee_token::FeeOnTransferTokenClient::new(&env, &token);
+    fee_token.mint(&world.depositor, &2_000);
+    let create = |escrow_id: u64, amount: i128| {
+        vaultix.create_escrow(
+            &escrow_id,
+            &world.depositor,
+            &world.recipient,
+            &token,
+            &world.milestones(&[amount]),
+            &1706400000u64,
+        )
+    };
+
+    // Only 990 arrives, so the deposit is rolled back instead of pulling again
+    create(1, 1_000);
+    assert_eq!(
+        vaultix.try_deposit_funds(&1),
+        Err(Ok(escrow::ExtendedError::FundingShortfall.into()))
+    );
+    assert_eq!(fee_token.balance(&vaultix.address), 0);
+    assert_eq!(fee_token.balance(&world.depositor), 2_000);
+    assert_eq!(vaultix.get_escrow(&1).status, escrow::EscrowStatus::Created);
+
+    // A fee too small to round up to a unit delivers the full amount
+    create(2, 99);
+    vaultix.deposit_funds(&2);
+    assert_eq!(fee_token.balance(&vaultix.address), 99);
+
+    create(3, 100);
+    fee_token.set_flat_fee(&true);
+    assert_eq!(
+        vaultix.try_deposit_funds(&3),
+        Err(Ok(escrow::ExtendedError::FundingShortfall.into()))
+    );
+}
+
+#[test]
+fn test_fee_overflow_is_rejected_without_paying_out() {
+    let env = Env::default();
+    let world = Deployment::new(&env);
+    world.funded_escrow(1, &[1_000]);
+    let vaultix = &world.vaultix;
+
+    // A 5 unit fee converted at this rate no longer fits in an i128
+    let config = escrow::FeeToken {
+        token: env.register_stellar_asset_contract(Address::generate(&env)),
+        rate: i128::MAX / 2,
+    };
+    vaultix.set_fee_token(&world.treasury, &world.token.address, &Some(config));
+
+    assert_eq!(
+        vaultix.try_preview_release(&1, &0),
+        Err(Ok(escrow::ExtendedError::FeeOverflow.into()))
+    );
+    assert_eq!(
+        vaultix.try_release_milestone(&1, &0),
+        Err(Ok(escrow::ExtendedError::FeeOverflow.into()))
+    );
+    assert_eq!(vaultix.get_escrow(&1).total_released, 0);
+    world.assert_balances(0, 0, 0, 1_000);
+}
@@ -0,0 +1,228 @@
+//! Factory deploying dedicated Vaultix escrow instances for white-label operators.
+//!
+//! The factory admin uploads the escrow contract's WASM and records its hash here. An operator
+//! then deploys their own instance in one call: the factory deploys the WASM, runs the escrow's
+//! `setup` with the operator's admin, treasury and fee, restricts it to the operator's tokens,
+//! and records the deployment so anyone can look up which instances it produced.
+//!
+//! Instance addresses are derived from the operator and their salt, so one operator cannot
+//! claim an address another is about to deploy to.
+
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractimpl, contracttype, symbol_short, xdr::ToXdr,
+    Address, BytesN, Env, Symbol, Vec,
+};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    InvalidFee = 3,
+}
+
+/// Per-tenant settings applied to a freshly deployed escrow instance
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TenantConfig {
+    pub admin: Address,       // Holds every role on the instance
+    pub treasury: Address,    // Receives the instance's platform fees
+    pub fee_bps: i128,        // Platform fee in basis points
+    pub tokens: Vec<Address>, // Tokens the instance accepts; empty accepts any token
+}
+
+/// Registry entry for an instance deployed by the factory
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deployment {
+    pub instance: Address,
+    pub operator: Address,
+    pub wasm_hash: BytesN<32>, // Escrow code the instance was deployed with
+    pub deployed_at: u64,
+}
+
+/// The escrow entrypoints the factory calls while setting up an instance.
+#[contractclient(name = "EscrowClient")]
+pub trait EscrowInterface {
+    fn setup(env: Env, admin: Address, treasury: Address, fee_bps: Option<i128>);
+    fn set_allowed_tokens(env: Env, caller: Address, tokens: Vec<Address>);
+}
+
+const BPS_DENOMINATOR: i128 = 10_000;
+const TTL_THRESHOLD: u32 = 518_400;
+const TTL_EXTEND_TO: u32 = 2_000_000;
+
+#[contract]
+pub struct VaultixFactory;
+
+#[contractimpl]
+impl VaultixFactory {
+    /// Sets the factory admin and the escrow WASM hash new instances are deployed from.
+    ///
+    /// # Errors
+    /// * `AlreadyInitialized` - If the factory was already initialized
+    pub fn init(env: Env, admin: Address, wasm_hash: BytesN<32>) -> Result<(), Error> {
+        if env.storage().instance().has(&admin_key()) {
+            return Err(Error::AlreadyInitialized);
+        }
+        admin.require_auth();
+
+        env.storage().instance().set(&admin_key(), &admin);
+        env.storage().instance().set(&wasm_hash_key(), &wasm_hash);
+        extend_instance(&env);
+        Ok(())
+    }
+
+    /// Points future deployments at a new escrow WASM. Existing instances are unaffected.
+    ///
+    /// # Errors
+    /// * `NotInitialized` - If the factory was not initialized
+    pub fn set_wasm_hash(env: Env, wasm_hash: BytesN<32>) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&admin_key())
+            .ok_or(Error::NotInitialized)?;
+        admin.require_auth();
+
+        env.storage().instance().set(&wasm_hash_key(), &wasm_hash);
+        extend_instance(&env);
+        env.events().publish((symbol_short!("wasm"),), wasm_hash);
+        Ok(())
+    }
+
+    /// Returns the escrow WASM hash new instances are deployed from.
+    pub fn get_wasm_hash(env: Env) -> Result<BytesN<32>, Error> {
+        env.storage()
+            .instance()
+            .get(&wasm_hash_key())
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Deploys and configures an escrow instance for `operator`. The operator must authorize
+    /// the call, as must `config.admin` and `config.treasury` for the instance's setup.
+    ///
+    /// # Returns
+    /// The address of the new instance
+    ///
+    /// # Errors
+    /// * `NotInitialized` - If the factory was not initialized
+    /// * `InvalidFee` - If the fee is outside 0..=10000 basis points
+    pub fn deploy(
+        env: Env,
+        operator: Address,
+        salt: BytesN<32>,
+        config: TenantConfig,
+    ) -> Result<Address, Error> {
+        operator.require_auth();
+
+        if !(0..=BPS_DENOMINATOR).contains(&config.fee_bps) {
+            return Err(Error::InvalidFee);
+        }
+        let wasm_hash = Self::get_wasm_hash(env.clone())?;
+
+        let instance = env
+            .deployer()
+            .with_current_contract(operator_salt(&env, &operator, &salt))
+            .deploy(wasm_hash.clone());
+
+        let escrow = EscrowClient::new(&env, &instance);
+        escrow.setup(&config.admin, &config.treasury, &Some(config.fee_bps));
+        if !config.tokens.is_empty() {
+            escrow.set_allowed_tokens(&config.admin, &config.tokens);
+        }
+
+        let deployment = Deployment {
+            instance: instance.clone(),
+            operator: operator.clone(),
+            wasm_hash,
+            deployed_at: env.ledger().timestamp(),
+        };
+        let key = deployment_key(&instance);
+        env.storage().persistent().set(&key, &deployment);
+        extend_persistent(&env, &key);
+
+        let mut deployments = Self::get_deployments(env.clone(), operator.clone());
+        deployments.push_back(instance.clone());
+        let key = operator_key(&operator);
+        env.storage().persistent().set(&key, &deployments);
+        extend_persistent(&env, &key);
+
+        let count = Self::get_deployment_count(env.clone());
+        env.storage().instance().set(&count_key(), &(count + 1));
+        extend_instance(&env);
+
+        env.events()
+            .publish((symbol_short!("deployed"), operator), instance.clone());
+        Ok(instance)
+    }
+
+    /// Computes the address `deploy` would give `operator`'s instance for `salt`.
+    pub fn get_instance_address(env: Env, operator: Address, salt: BytesN<32>) -> Address {
+        env.deployer()
+            .with_current_contract(operator_salt(&env, &operator, &salt))
+            .deployed_address()
+    }
+
+    /// Returns the registry entry for `instance`, if this factory deployed it.
+    pub fn get_deployment(env: Env, instance: Address) -> Option<Deployment> {
+        env.storage().persistent().get(&deployment_key(&instance))
+    }
+
+    /// Instances deployed for `operator`, oldest first.
+    pub fn get_deployments(env: Env, operator: Address) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&operator_key(&operator))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Number of instances this factory has deployed.
+    pub fn get_deployment_count(env: Env) -> u32 {
+        env.storage().instance().get(&count_key()).unwrap_or(0)
+    }
+}
+
+fn admin_key() -> Symbol {
+    symbol_short!("admin")
+}
+
+fn wasm_hash_key() -> Symbol {
+    symbol_short!("wasm")
+}
+
+fn count_key() -> Symbol {
+    symbol_short!("count")
+}
+
+fn deployment_key(instance: &Address) -> (Symbol, Address) {
+    (symbol_short!("deployed"), instance.clone())
+}
+
+fn operator_key(operator: &Address) -> (Symbol, Address) {
+    (symbol_short!("operator"), operator.clone())
+}
+
+fn operator_salt(env: &Env, operator: &Address, salt: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = operator.clone().to_xdr(env);
+    preimage.append(&salt.clone().into());
+    env.crypto().sha256(&preimage)
+}
+
+fn extend_instance(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+fn extend_persistent(env: &Env, key: &(Symbol, Address)) {
+    env.storage()
+        .persistent()
+        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+#[cfg(test)]
+mod test;
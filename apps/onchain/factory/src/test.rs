@@ -0,0 +1,63 @@
+use super::*;
+use soroban_sdk::{testutils::Address as _, vec, Address, BytesN, Env};
+
+mod escrow {
+    soroban_sdk::contractimport!(file = "../target/wasm32v1-none/release/onchain.wasm");
+}
+
+fn setup_factory(env: &Env) -> VaultixFactoryClient<'_> {
+    let factory_id = env.register_contract(None, VaultixFactory);
+    let factory = VaultixFactoryClient::new(env, &factory_id);
+    let wasm_hash = env.deployer().upload_contract_wasm(escrow::WASM);
+    factory.init(&Address::generate(env), &wasm_hash);
+    factory
+}
+
+#[test]
+fn test_deploy_configures_and_registers_instance() {
+    let env = Env::default();
+    // The tenant admin and treasury authorize the instance's setup, nested under the deploy
+    env.mock_all_auths_allowing_non_root_auth();
+    // Instantiating the full escrow WASM exceeds the default test budget
+    env.budget().reset_unlimited();
+
+    let factory = setup_factory(&env);
+    let operator = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let token = Address::generate(&env);
+    let salt = BytesN::from_array(&env, &[7; 32]);
+
+    let config = TenantConfig {
+        admin: admin.clone(),
+        treasury: treasury.clone(),
+        fee_bps: 250,
+        tokens: vec![&env, token.clone()],
+    };
+    let expected = factory.get_instance_address(&operator, &salt);
+    let instance = factory.deploy(&operator, &salt, &config);
+    assert_eq!(instance, expected);
+
+    let escrow = escrow::Client::new(&env, &instance);
+    assert_eq!(escrow.get_admin(), admin);
+    assert_eq!(escrow.get_config().fee_bps, 250);
+    assert_eq!(escrow.get_allowed_tokens(), vec![&env, token]);
+
+    let deployment = factory.get_deployment(&instance).unwrap();
+    assert_eq!(deployment.operator, operator);
+    assert_eq!(factory.get_deployments(&operator), vec![&env, instance]);
+    assert_eq!(factory.get_deployment_count(), 1);
+
+    // The same salt from another operator yields a different instance
+    let other = Address::generate(&env);
+    assert_ne!(factory.get_instance_address(&other, &salt), expected);
+
+    let bad_fee = TenantConfig {
+        fee_bps: 10_001,
+        ..config
+    };
+    assert_eq!(
+        factory.try_deploy(&other, &salt, &bad_fee),
+        Err(Ok(Error::InvalidFee))
+    );
+}
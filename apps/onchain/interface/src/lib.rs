@@ -0,0 +1,182 @@
+//! Types, errors and a client for calling a Vaultix escrow contract.
+//!
+//! Depend on this crate instead of the escrow implementation to call Vaultix from another
+//! contract: `VaultixClient` covers the core escrow lifecycle and decodes results into the
+//! same `Escrow`, `Milestone` and `Error` types the contract itself uses.
+
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+use soroban_sdk::{contractclient, contracterror, contracttype, Address, Env, String, Vec};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    EscrowNotFound = 1,
+    EscrowAlreadyExists = 2,
+    MilestoneNotFound = 3,
+    MilestoneAlreadyReleased = 4,
+    UnauthorizedAccess = 5,
+    InvalidMilestoneAmount = 6,
+    TotalAmountMismatch = 7,
+    InsufficientBalance = 8,
+    EscrowNotActive = 9,
+    VectorTooLarge = 10,
+    ZeroAmount = 11,
+    InvalidDeadline = 12,
+    SelfDealing = 13,
+    EscrowAlreadyFunded = 14, // NEW: Prevent double funding
+    TokenTransferFailed = 15, // NEW: Token transfer error
+    TreasuryNotInitialized = 16,
+    InvalidFeeConfiguration = 17,
+    AdminNotInitialized = 18,
+    AlreadyInitialized = 19,
+    InvalidEscrowStatus = 20,
+    AlreadyInDispute = 21,
+    InvalidWinner = 22,
+    ContractPaused = 23,
+    ClaimNotFound = 24,
+    ClaimAlreadyProcessed = 25,
+    InvalidRating = 26,
+    AlreadyRated = 27,
+    DescriptionTooLong = 28,
+    TemplateNotFound = 29,
+    InvalidConfiguration = 30,
+    AmountOutOfRange = 31,
+    RateLimited = 32,
+    AddressBlocked = 33,
+    NotAllowlisted = 34,
+    ReservedEscrowId = 35,
+    IncompatibleSchema = 36,
+    NotArbitrator = 37,
+    DisputeNotQueued = 38,
+    StakeLocked = 39,
+    NoSettlementProposed = 40,
+    EvidenceWindowClosed = 41,
+    EvidenceWindowOpen = 42,
+    MilestoneAlreadySubmitted = 43,
+    MilestoneNotSubmitted = 44,
+    DisputeTooEarly = 45,
+    DisputeCooldown = 46,
+    RecoveryNotReady = 47,
+    ApprovalRequired = 48,
+    ParentNotCompleted = 49,
+    RulingNotReady = 50,
+}
+
+/// Error codes from 51 on. A contract error enum exports at most 50 cases, all taken by `Error`,
+/// so these abort the call instead of being returned; callers see the same contract error codes.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ExtendedError {
+    FeeOverflow = 51, // A fee, or a share of one, does not fit the amount it is taken from
+    SplitMismatch = 52, // Parts of a split payout do not add up to the amount being split
+    BondUnderflow = 53, // A charge exceeds the bond or stake it is taken from
+    FundingShortfall = 54, // The contract received less than a deposit pulled
+    SettlementMismatch = 55, // The settlement being accepted is not the one proposed
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MilestoneStatus {
+    Pending,
+    Released,
+    Disputed,
+    Unfunded, // Reported for milestones not yet covered by a progressive deposit
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Milestone {
+    pub amount: i128,
+    pub status: MilestoneStatus,
+    pub description: String,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EscrowStatus {
+    Created,   // Escrow created but funds not yet deposited
+    Active,    // Funds deposited and locked in contract
+    Completed, // All milestones released
+    Cancelled, // Escrow cancelled, funds refunded
+    Disputed,
+    Resolved,
+    Draft,    // Terms still being negotiated; milestones editable until activated
+    Proposed, // Awaiting the recipient's acceptance of the terms
+    Open,     // Funded bounty waiting for someone to claim it as recipient
+    Frozen,   // Escrowed funds were clawed back by the asset issuer; awaiting reconciliation
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Resolution {
+    None,
+    Depositor,
+    Recipient,
+    Settled, // Split agreed between the parties
+}
+
+/// Why a party raised a dispute, so arbitrators can triage without off-chain context.
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DisputeReason {
+    NonDelivery,
+    Quality,
+    Scope,
+    Fraud,
+    Other,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Escrow {
+    pub depositor: Address,
+    pub recipient: Address,
+    pub token_address: Address, // NEW: Token contract address
+    pub total_amount: i128,
+    pub total_released: i128,
+    pub milestone_count: u32, // Milestones are stored as separate entries keyed by index
+    pub released_mask: u64,   // Bit i set when milestone i has been released
+    pub disputed_mask: u64,   // Bit i set when milestone i is under (or lost) a dispute
+    pub status: EscrowStatus,
+    pub deadline: u64, // NEW: Deadline for escrow completion
+    pub resolution: Resolution,
+    pub assignable: bool, // Depositor opt-in for claim assignment
+}
+
+/// Core escrow entrypoints, for contracts that create and drive escrows on a Vaultix instance.
+#[contractclient(name = "VaultixClient")]
+pub trait VaultixInterface {
+    fn create_escrow(
+        env: Env,
+        escrow_id: u64,
+        depositor: Address,
+        recipient: Address,
+        token_address: Address,
+        milestones: Vec<Milestone>,
+        deadline: u64,
+    ) -> Result<(), Error>;
+    fn deposit_funds(env: Env, escrow_id: u64) -> Result<(), Error>;
+    fn release_milestone(env: Env, escrow_id: u64, milestone_index: u32) -> Result<(), Error>;
+    fn confirm_delivery(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+        buyer: Address,
+    ) -> Result<(), Error>;
+    fn raise_dispute(
+        env: Env,
+        escrow_id: u64,
+        caller: Address,
+        reason: DisputeReason,
+    ) -> Result<(), Error>;
+    fn cancel_escrow(env: Env, escrow_id: u64) -> Result<(), Error>;
+    fn complete_escrow(env: Env, escrow_id: u64) -> Result<(), Error>;
+    fn get_escrow(env: Env, escrow_id: u64) -> Result<Escrow, Error>;
+    fn get_milestones(env: Env, escrow_id: u64) -> Result<Vec<Milestone>, Error>;
+    fn get_state(env: Env, escrow_id: u64) -> Result<EscrowStatus, Error>;
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,77 @@
+use super::*;
+use soroban_sdk::{testutils::Address as _, token, vec, Address, Env, String};
+
+mod escrow {
+    soroban_sdk::contractimport!(file = "../target/wasm32v1-none/release/onchain.wasm");
+}
+
+/// Ceiling for the release escrow WASM. Raise it deliberately, alongside whatever feature
+/// needed the room, rather than letting the binary creep.
+const ESCROW_WASM_BUDGET: usize = 448 * 1024;
+
+#[test]
+fn test_escrow_wasm_fits_size_budget() {
+    assert!(
+        escrow::WASM.len() <= ESCROW_WASM_BUDGET,
+        "escrow WASM is {} bytes, budget is {ESCROW_WASM_BUDGET}",
+        escrow::WASM.len()
+    );
+}
+
+#[test]
+fn test_client_drives_escrow_lifecycle() {
+    let env = Env::default();
+    env.mock_all_auths();
+    // Instantiating the full escrow WASM exceeds the default test budget
+    env.budget().reset_unlimited();
+
+    let vaultix_id = env.register_contract_wasm(None, escrow::WASM);
+    let vaultix = VaultixClient::new(&env, &vaultix_id);
+    escrow::Client::new(&env, &vaultix_id).setup(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &None,
+    );
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token_address = env.register_stellar_asset_contract(Address::generate(&env));
+    token::StellarAssetClient::new(&env, &token_address).mint(&depositor, &1000);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Work"),
+        },
+    ];
+    vaultix.create_escrow(
+        &1,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    assert_eq!(
+        vaultix.try_release_milestone(&1, &0),
+        Err(Ok(Error::EscrowNotActive))
+    );
+
+    token::Client::new(&env, &token_address).approve(&depositor, &vaultix_id, &1000, &200);
+    vaultix.deposit_funds(&1);
+    vaultix.release_milestone(&1, &0);
+
+    let escrow: Escrow = vaultix.get_escrow(&1);
+    assert_eq!(escrow.total_released, 1000);
+    assert_eq!(
+        vaultix.get_milestones(&1).get(0).unwrap().status,
+        MilestoneStatus::Released
+    );
+    assert_eq!(vaultix.get_state(&1), EscrowStatus::Active);
+    assert!(matches!(
+        vaultix.try_get_escrow(&2),
+        Err(Ok(Error::EscrowNotFound))
+    ));
+}
@@ -0,0 +1,237 @@
+//! Registry of legitimate Vaultix escrow deployments.
+//!
+//! The registry admin approves operators. An approved operator's escrow instance lists itself
+//! by calling `register` from its own `register_with_registry` entrypoint, so an entry can only
+//! be created by the instance it describes. Wallets look an address up with `is_registered`
+//! before letting a user fund it; revoking an operator delists all of their instances at once.
+
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, String,
+    Symbol, Vec,
+};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    OperatorNotApproved = 3,
+    OperatorMismatch = 4,
+    NotRegistered = 5,
+}
+
+/// Registry entry for a Vaultix escrow instance
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Registration {
+    pub instance: Address,
+    pub operator: Address,
+    pub version: String, // Contract version reported at the latest registration
+    pub registered_at: u64,
+    pub updated_at: u64,
+}
+
+const TTL_THRESHOLD: u32 = 518_400;
+const TTL_EXTEND_TO: u32 = 2_000_000;
+
+#[contract]
+pub struct VaultixRegistry;
+
+#[contractimpl]
+impl VaultixRegistry {
+    /// Sets the registry admin who approves operators.
+    ///
+    /// # Errors
+    /// * `AlreadyInitialized` - If the registry was already initialized
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        if env.storage().instance().has(&admin_key()) {
+            return Err(Error::AlreadyInitialized);
+        }
+        admin.require_auth();
+
+        env.storage().instance().set(&admin_key(), &admin);
+        extend_instance(&env);
+        Ok(())
+    }
+
+    /// Allows `operator`'s instances to register (admin only).
+    ///
+    /// # Errors
+    /// * `NotInitialized` - If the registry was not initialized
+    pub fn approve_operator(env: Env, operator: Address) -> Result<(), Error> {
+        require_admin(&env)?;
+
+        let key = approved_key(&operator);
+        env.storage().persistent().set(&key, &true);
+        extend_persistent(&env, &key);
+        env.events()
+            .publish((symbol_short!("approved"), operator), ());
+        Ok(())
+    }
+
+    /// Withdraws `operator`'s approval (admin only). Their instances stay on record but
+    /// `is_registered` reports them as illegitimate.
+    ///
+    /// # Errors
+    /// * `NotInitialized` - If the registry was not initialized
+    pub fn revoke_operator(env: Env, operator: Address) -> Result<(), Error> {
+        require_admin(&env)?;
+
+        env.storage().persistent().remove(&approved_key(&operator));
+        env.events()
+            .publish((symbol_short!("revoked"), operator), ());
+        Ok(())
+    }
+
+    /// Returns whether `operator` is currently approved.
+    pub fn is_operator_approved(env: Env, operator: Address) -> bool {
+        env.storage().persistent().has(&approved_key(&operator))
+    }
+
+    /// Records or refreshes `instance`'s entry. Both the instance itself and its operator
+    /// must authorize, so only a deployment can list itself.
+    ///
+    /// # Errors
+    /// * `OperatorNotApproved` - If the registry admin has not approved `operator`
+    /// * `OperatorMismatch` - If `instance` is already registered under another operator
+    pub fn register(
+        env: Env,
+        instance: Address,
+        operator: Address,
+        version: String,
+    ) -> Result<(), Error> {
+        instance.require_auth();
+        operator.require_auth();
+
+        if !Self::is_operator_approved(env.clone(), operator.clone()) {
+            return Err(Error::OperatorNotApproved);
+        }
+
+        let now = env.ledger().timestamp();
+        let registration = match Self::get_registration(env.clone(), instance.clone()) {
+            Some(existing) if existing.operator != operator => {
+                return Err(Error::OperatorMismatch);
+            }
+            Some(existing) => Registration {
+                version: version.clone(),
+                updated_at: now,
+                ..existing
+            },
+            None => {
+                let mut instances = Self::get_instances(env.clone(), operator.clone());
+                instances.push_back(instance.clone());
+                let key = operator_key(&operator);
+                env.storage().persistent().set(&key, &instances);
+                extend_persistent(&env, &key);
+
+                Registration {
+                    instance: instance.clone(),
+                    operator: operator.clone(),
+                    version: version.clone(),
+                    registered_at: now,
+                    updated_at: now,
+                }
+            }
+        };
+
+        let key = registration_key(&instance);
+        env.storage().persistent().set(&key, &registration);
+        extend_persistent(&env, &key);
+        env.events()
+            .publish((symbol_short!("register"), instance), (operator, version));
+        Ok(())
+    }
+
+    /// Removes `instance`'s entry (admin only), e.g. after it is found to be compromised.
+    ///
+    /// # Errors
+    /// * `NotInitialized` - If the registry was not initialized
+    /// * `NotRegistered` - If `instance` has no entry
+    pub fn deregister(env: Env, instance: Address) -> Result<(), Error> {
+        require_admin(&env)?;
+
+        let registration =
+            Self::get_registration(env.clone(), instance.clone()).ok_or(Error::NotRegistered)?;
+        env.storage()
+            .persistent()
+            .remove(&registration_key(&instance));
+
+        let mut instances = Self::get_instances(env.clone(), registration.operator.clone());
+        if let Some(index) = instances.first_index_of(&instance) {
+            instances.remove(index);
+        }
+        env.storage()
+            .persistent()
+            .set(&operator_key(&registration.operator), &instances);
+
+        env.events()
+            .publish((symbol_short!("deregist"), instance), ());
+        Ok(())
+    }
+
+    /// Returns whether `instance` is registered under a currently approved operator.
+    pub fn is_registered(env: Env, instance: Address) -> bool {
+        match Self::get_registration(env.clone(), instance) {
+            Some(registration) => Self::is_operator_approved(env, registration.operator),
+            None => false,
+        }
+    }
+
+    /// Returns the entry recorded for `instance`, regardless of its operator's standing.
+    pub fn get_registration(env: Env, instance: Address) -> Option<Registration> {
+        env.storage().persistent().get(&registration_key(&instance))
+    }
+
+    /// Instances registered under `operator`, oldest first.
+    pub fn get_instances(env: Env, operator: Address) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&operator_key(&operator))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+}
+
+fn require_admin(env: &Env) -> Result<(), Error> {
+    let admin: Address = env
+        .storage()
+        .instance()
+        .get(&admin_key())
+        .ok_or(Error::NotInitialized)?;
+    admin.require_auth();
+    extend_instance(env);
+    Ok(())
+}
+
+fn admin_key() -> Symbol {
+    symbol_short!("admin")
+}
+
+fn approved_key(operator: &Address) -> (Symbol, Address) {
+    (symbol_short!("approved"), operator.clone())
+}
+
+fn registration_key(instance: &Address) -> (Symbol, Address) {
+    (symbol_short!("instance"), instance.clone())
+}
+
+fn operator_key(operator: &Address) -> (Symbol, Address) {
+    (symbol_short!("operator"), operator.clone())
+}
+
+fn extend_instance(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+fn extend_persistent(env: &Env, key: &(Symbol, Address)) {
+    env.storage()
+        .persistent()
+        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+#[cfg(test)]
+mod test;
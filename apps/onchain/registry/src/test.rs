@@ -0,0 +1,57 @@
+use super::*;
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, String};
+
+mod escrow {
+    soroban_sdk::contractimport!(file = "../target/wasm32v1-none/release/onchain.wasm");
+}
+
+#[test]
+fn test_instance_registers_itself_under_approved_operator() {
+    let env = Env::default();
+    env.mock_all_auths();
+    // Instantiating the full escrow WASM exceeds the default test budget
+    env.budget().reset_unlimited();
+
+    let registry_id = env.register_contract(None, VaultixRegistry);
+    let registry = VaultixRegistryClient::new(&env, &registry_id);
+    registry.init(&Address::generate(&env));
+
+    let operator = Address::generate(&env);
+    let instance = env.register_contract_wasm(None, escrow::WASM);
+    let escrow = escrow::Client::new(&env, &instance);
+    escrow.init(&operator);
+
+    // Listing is refused until the registry admin approves the operator
+    assert!(escrow
+        .try_register_with_registry(&operator, &registry_id)
+        .is_err());
+    assert!(!registry.is_registered(&instance));
+
+    registry.approve_operator(&operator);
+    escrow.register_with_registry(&operator, &registry_id);
+    assert!(registry.is_registered(&instance));
+
+    let registration = registry.get_registration(&instance).unwrap();
+    assert_eq!(registration.operator, operator);
+    assert_eq!(registration.version, escrow.version().version);
+    assert_eq!(
+        registry.get_instances(&operator),
+        vec![&env, instance.clone()]
+    );
+
+    // Another operator cannot take over an existing entry
+    let other = Address::generate(&env);
+    registry.approve_operator(&other);
+    assert_eq!(
+        registry.try_register(&instance, &other, &String::from_str(&env, "9.9.9")),
+        Err(Ok(Error::OperatorMismatch))
+    );
+
+    registry.revoke_operator(&operator);
+    assert!(!registry.is_registered(&instance));
+    assert!(registry.get_registration(&instance).is_some());
+
+    registry.deregister(&instance);
+    assert_eq!(registry.get_registration(&instance), None);
+    assert_eq!(registry.get_instances(&operator), vec![&env]);
+}
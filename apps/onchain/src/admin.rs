@@ -0,0 +1,793 @@
+//! Deployment setup, global configuration and access policy.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin_log,
+    errors::Error,
+    escrow::{save_escrow, BPS_DENOMINATOR},
+    rbac::{grant, require_role},
+    recovery::MIN_DORMANCY_PERIOD,
+    state_machine::is_terminal,
+    storage::{
+        admin_storage_key, allowed_tokens_key, allowlist_key, amount_limits_key, blocked_key,
+        creation_quota_key, extend_instance, get_admin, get_storage_key, load_config,
+        migrate_singletons, milestone_bit, pair_escrows_key, record_schema, record_schema_if_fresh,
+        save_config, save_milestone, stored_schema_version, ttl_policy, write_persistent, DataKey,
+        DEFAULT_FEE_BPS, STORAGE_SCHEMA_VERSION,
+    },
+    types::{
+        AmountLimits, AttestorClient, Config, CreationQuota, Escrow, LegacyEscrow, Milestone,
+        MilestoneStatus, PriceOracleClient, ReceiptConfig, RegistryClient, Role, ScreenerClient,
+        TtlPolicy, ValueFloor, Version,
+    },
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{
+    contractimpl, symbol_short, xdr::ToXdr, Address, BytesN, Env, String, Symbol, Vec,
+};
+
+pub(crate) const MAX_MILESTONES_LIMIT: u32 = 64;
+
+// Finer than any token in practice; keeps `10^(decimals - precision)` meaningful
+const MAX_DUST_PRECISION: u32 = 18;
+
+/// Highest open-escrow cap per depositor/recipient pair, so the pair's index stays cheap to prune
+const MAX_PAIR_ESCROWS: u32 = 20;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Initializes the contract with treasury address and optional fee configuration.
+    ///
+    /// # Arguments
+    /// * `treasury` - Address that will receive platform fees
+    /// * `fee_bps` - Optional fee in basis points (default: 50 bps = 0.5%)
+    ///
+    /// Deprecated: use `setup`, which also configures the admin atomically.
+    ///
+    /// # Errors
+    /// * `AlreadyInitialized` - If the treasury is already set
+    /// * `InvalidFeeConfiguration` - If fee_bps exceeds 10000 (100%)
+    pub fn initialize(env: Env, treasury: Address, fee_bps: Option<i128>) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::treasury) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        // Verify treasury address authorization
+        treasury.require_auth();
+        configure_treasury(&env, &treasury, fee_bps)?;
+        admin_log::record(&env, "initialize", &treasury, None);
+        Ok(())
+    }
+
+    /// Updates the platform fee (FeeManager role).
+    ///
+    /// # Arguments
+    /// * `caller` - Account holding the FeeManager role
+    /// * `new_fee_bps` - New fee in basis points
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller lacks the FeeManager role
+    /// * `InvalidFeeConfiguration` - If fee exceeds 100%
+    pub fn update_fee(env: Env, caller: Address, new_fee_bps: i128) -> Result<(), Error> {
+        require_role(&env, &caller, Role::FeeManager)?;
+        admin_log::record(&env, "update_fee", &caller, None);
+
+        if !(0..=BPS_DENOMINATOR).contains(&new_fee_bps) {
+            return Err(Error::InvalidFeeConfiguration);
+        }
+
+        let mut config = load_config(&env);
+        config.fee_bps = new_fee_bps;
+        save_config(&env, &config);
+
+        Ok(())
+    }
+
+    /// Returns the treasury address that receives platform fees.
+    pub fn get_treasury(env: Env) -> Result<Address, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::treasury)
+            .ok_or(Error::TreasuryNotInitialized)
+    }
+
+    /// Returns the global configuration, with defaults for anything never set.
+    pub fn get_config(env: Env) -> Config {
+        load_config(&env)
+    }
+
+    /// Replaces the global configuration (Admin role).
+    ///
+    /// # Errors
+    /// * `InvalidFeeConfiguration` - If fee_bps exceeds 10000 (100%)
+    /// * `InvalidConfiguration` - If the milestone cap is outside 1..=64, an amount is out of range,
+    ///   the dormancy period is shorter than a year, the escalation window doesn't outlast the
+    ///   review window, the dust precision exceeds 18 decimals or the pair cap exceeds 20
+    pub fn set_config(env: Env, caller: Address, config: Config) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "set_config", &caller, None);
+
+        if !(0..=BPS_DENOMINATOR).contains(&config.fee_bps) {
+            return Err(Error::InvalidFeeConfiguration);
+        }
+        if !(1..=MAX_MILESTONES_LIMIT).contains(&config.max_milestones)
+            || config.min_milestone_amount < 1
+            || config.dispute_bond < 0
+            || (config.creation_quota > 0 && config.quota_window == 0)
+            || (config.dormancy_period > 0 && config.dormancy_period < MIN_DORMANCY_PERIOD)
+            || (config.escalation_window > 0 && config.escalation_window <= config.review_window)
+            || config.dust_precision > MAX_DUST_PRECISION
+            || config.max_pair_escrows > MAX_PAIR_ESCROWS
+        {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        save_config(&env, &config);
+        Ok(())
+    }
+
+    /// Returns the TTL policy storage entries are bumped under.
+    pub fn get_ttl_policy(env: Env) -> TtlPolicy {
+        ttl_policy(&env)
+    }
+
+    /// Sets when storage entries have their TTL bumped (Admin role). Every entry the contract
+    /// reads or writes through its storage helpers, the instance included, is bumped to
+    /// `extend_to` ledgers once it has fewer than `threshold` left.
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller lacks the Admin role
+    /// * `InvalidConfiguration` - If the threshold is zero or not below `extend_to`, or
+    ///   `extend_to` exceeds the network's maximum TTL
+    pub fn set_ttl_policy(env: Env, caller: Address, policy: TtlPolicy) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "set_ttl_policy", &caller, None);
+
+        if policy.threshold == 0
+            || policy.threshold >= policy.extend_to
+            || policy.extend_to > env.storage().max_ttl()
+        {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        env.storage().instance().set(&DataKey::ttl, &policy);
+        extend_instance(&env);
+        Ok(())
+    }
+
+    /// Sets the accepted range of escrow totals for a token (Admin role).
+    ///
+    /// # Errors
+    /// * `InvalidConfiguration` - If min is negative or exceeds max
+    pub fn set_amount_limits(
+        env: Env,
+        caller: Address,
+        token_address: Address,
+        limits: AmountLimits,
+    ) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "set_amount_limits", &caller, None);
+
+        if limits.min < 0 || limits.min > limits.max {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        let key = amount_limits_key(&token_address);
+        write_persistent(&env, &key, &limits);
+        Ok(())
+    }
+
+    /// Returns the escrow total limits for a token, if any were set.
+    pub fn get_amount_limits(env: Env, token_address: Address) -> Option<AmountLimits> {
+        env.storage()
+            .persistent()
+            .get(&amount_limits_key(&token_address))
+    }
+
+    /// Restricts new escrows to the given tokens (Admin role). An empty list accepts any token.
+    pub fn set_allowed_tokens(
+        env: Env,
+        caller: Address,
+        tokens: Vec<Address>,
+    ) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "set_allowed_tokens", &caller, None);
+
+        env.storage().instance().set(&allowed_tokens_key(), &tokens);
+        extend_instance(&env);
+        env.events().publish((symbol_short!("tokens"),), tokens);
+        Ok(())
+    }
+
+    /// Tokens new escrows are restricted to; empty when any token is accepted.
+    pub fn get_allowed_tokens(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&allowed_tokens_key())
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Returns how many more escrows `depositor` may create in the current quota window.
+    /// `u32::MAX` when no creation quota is configured.
+    pub fn get_remaining_quota(env: Env, depositor: Address) -> u32 {
+        let config = load_config(&env);
+        if config.creation_quota == 0 {
+            return u32::MAX;
+        }
+        let usage = current_quota_usage(&env, &depositor, &config);
+        config.creation_quota.saturating_sub(usage.used)
+    }
+
+    /// Bars an address from creating, funding or receiving payouts from escrows (Admin role).
+    pub fn block_address(env: Env, caller: Address, address: Address) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "block_address", &caller, None);
+
+        let key = blocked_key(&address);
+        write_persistent(&env, &key, &true);
+
+        env.events()
+            .publish((symbol_short!("blocked"), address), ());
+        Ok(())
+    }
+
+    /// Lifts a block placed with `block_address` (Admin role).
+    pub fn unblock_address(env: Env, caller: Address, address: Address) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "unblock_address", &caller, None);
+
+        env.storage().persistent().remove(&blocked_key(&address));
+
+        env.events()
+            .publish((symbol_short!("unblocked"), address), ());
+        Ok(())
+    }
+
+    /// Returns whether an address is on the denylist or flagged by the screening contract.
+    pub fn is_blocked(env: Env, address: Address) -> bool {
+        if env.storage().persistent().has(&blocked_key(&address)) {
+            return true;
+        }
+        match Self::get_screener(env.clone()) {
+            Some(screener) => ScreenerClient::new(&env, &screener).is_blocked(&address),
+            None => false,
+        }
+    }
+
+    /// Registers (or clears) a sanctions-screening contract whose `is_blocked` is checked,
+    /// alongside the on-chain denylist, wherever blocked addresses are refused (Admin role).
+    pub fn set_screener(env: Env, caller: Address, screener: Option<Address>) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "set_screener", &caller, None);
+
+        match screener {
+            Some(screener) => env.storage().instance().set(&DataKey::screener, &screener),
+            None => env.storage().instance().remove(&DataKey::screener),
+        }
+        extend_instance(&env);
+        Ok(())
+    }
+
+    /// Returns the registered screening contract, if any.
+    pub fn get_screener(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::screener)
+    }
+
+    /// Adds an address to the on-chain allowlist used when `allowlist_only` is set (Admin role).
+    pub fn allow_address(env: Env, caller: Address, address: Address) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "allow_address", &caller, None);
+
+        let key = allowlist_key(&address);
+        write_persistent(&env, &key, &true);
+        Ok(())
+    }
+
+    /// Removes an address from the on-chain allowlist (Admin role).
+    pub fn disallow_address(env: Env, caller: Address, address: Address) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "disallow_address", &caller, None);
+
+        env.storage().persistent().remove(&allowlist_key(&address));
+        Ok(())
+    }
+
+    /// Registers (or clears) the KYC attestor contract consulted for non-allowlisted
+    /// addresses when `allowlist_only` is set (Admin role).
+    pub fn set_kyc_attestor(
+        env: Env,
+        caller: Address,
+        attestor: Option<Address>,
+    ) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "set_kyc_attestor", &caller, None);
+
+        match attestor {
+            Some(attestor) => env.storage().instance().set(&DataKey::kyc_att, &attestor),
+            None => env.storage().instance().remove(&DataKey::kyc_att),
+        }
+        extend_instance(&env);
+        Ok(())
+    }
+
+    /// Returns the registered KYC attestor contract, if any.
+    pub fn get_kyc_attestor(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::kyc_att)
+    }
+
+    /// Requires every milestone of a new escrow to be worth at least `min_milestone_usd`
+    /// according to the price feed at creation, or drops the requirement with `None` (Admin
+    /// role). Keeps dust escrows, and dust disputes, away from arbitrators.
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller lacks the Admin role
+    /// * `InvalidConfiguration` - If the minimum isn't positive
+    pub fn set_value_floor(
+        env: Env,
+        caller: Address,
+        floor: Option<ValueFloor>,
+    ) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "set_value_floor", &caller, None);
+
+        match floor {
+            Some(floor) => {
+                if floor.min_milestone_usd <= 0 {
+                    return Err(Error::InvalidConfiguration);
+                }
+                env.storage().instance().set(&DataKey::val_floor, &floor)
+            }
+            None => env.storage().instance().remove(&DataKey::val_floor),
+        }
+        extend_instance(&env);
+        Ok(())
+    }
+
+    /// Returns the USD value floor for milestones, if one is set.
+    pub fn get_value_floor(env: Env) -> Option<ValueFloor> {
+        env.storage().instance().get(&DataKey::val_floor)
+    }
+
+    /// Returns whether an address may act as depositor or recipient under the current mode.
+    pub fn is_allowed(env: Env, address: Address) -> bool {
+        !load_config(&env).allowlist_only || is_verified_party(&env, &address)
+    }
+
+    /// Sets the share of each collected fee that is diverted into the insurance pool
+    /// (FeeManager role).
+    ///
+    /// # Arguments
+    /// * `caller` - Account holding the FeeManager role
+    /// * `insurance_bps` - Share of the fee in basis points (0 disables the pool, 10000 sends the whole fee)
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller lacks the FeeManager role
+    /// * `InvalidFeeConfiguration` - If the share exceeds 100%
+    pub fn set_insurance_share(
+        env: Env,
+        caller: Address,
+        insurance_bps: i128,
+    ) -> Result<(), Error> {
+        require_role(&env, &caller, Role::FeeManager)?;
+        admin_log::record(&env, "set_insurance_share", &caller, None);
+
+        if !(0..=BPS_DENOMINATOR).contains(&insurance_bps) {
+            return Err(Error::InvalidFeeConfiguration);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::ins_bps, &insurance_bps);
+        extend_instance(&env);
+
+        Ok(())
+    }
+
+    /// Configures the companion NFT contract used to mint escrow receipts (treasury only).
+    ///
+    /// # Arguments
+    /// * `receipt_contract` - Address of the NFT contract implementing `ReceiptInterface`
+    /// * `transferable` - Whether minted receipts may be transferred by their holder
+    pub fn set_receipt_contract(
+        env: Env,
+        receipt_contract: Address,
+        transferable: bool,
+    ) -> Result<(), Error> {
+        let treasury: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::treasury)
+            .ok_or(Error::TreasuryNotInitialized)?;
+        treasury.require_auth();
+        admin_log::record(&env, "set_receipt_contract", &treasury, None);
+
+        let config = ReceiptConfig {
+            contract: receipt_contract,
+            transferable,
+        };
+        env.storage().instance().set(&DataKey::receipt, &config);
+        extend_instance(&env);
+
+        Ok(())
+    }
+
+    /// Returns the receipt NFT configuration, if one has been set.
+    pub fn get_receipt_config(env: Env) -> Option<ReceiptConfig> {
+        env.storage().instance().get(&DataKey::receipt)
+    }
+
+    /// Sets the global paused state (Pauser role).
+    /// When paused, state-changing user operations are blocked.
+    /// Read-only functions remain accessible.
+    pub fn set_paused(env: Env, caller: Address, paused: bool) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Pauser)?;
+        admin_log::record(&env, "set_paused", &caller, None);
+
+        let mut config = load_config(&env);
+        config.paused = paused;
+        save_config(&env, &config);
+
+        Ok(())
+    }
+
+    /// Configures admin, treasury and fee in a single call, so a deployment is never left
+    /// half-initialized for someone else to claim. Deploy and call this in the same transaction.
+    ///
+    /// soroban-sdk 20 has no deploy-time constructors; this becomes `__constructor` once the
+    /// contract moves to an SDK release that supports them.
+    ///
+    /// # Errors
+    /// * `AlreadyInitialized` - If either the admin or the treasury is already set
+    /// * `InvalidFeeConfiguration` - If fee_bps exceeds 10000 (100%)
+    pub fn setup(
+        env: Env,
+        admin: Address,
+        treasury: Address,
+        fee_bps: Option<i128>,
+    ) -> Result<(), Error> {
+        if get_admin(&env).is_ok() || env.storage().instance().has(&DataKey::treasury) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        admin.require_auth();
+        treasury.require_auth();
+        configure_treasury(&env, &treasury, fee_bps)?;
+        env.storage().instance().set(&admin_storage_key(), &admin);
+        admin_log::record(&env, "setup", &admin, None);
+        Ok(())
+    }
+
+    /// Initializes the contract with an admin address responsible for dispute resolution.
+    ///
+    /// Deprecated: use `setup`, which also configures the treasury atomically.
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        if get_admin(&env).is_ok() {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        admin.require_auth();
+        env.storage().instance().set(&admin_storage_key(), &admin);
+        admin_log::record(&env, "init", &admin, None);
+        record_schema_if_fresh(&env);
+        extend_instance(&env);
+        Ok(())
+    }
+
+    /// Returns the admin address set by `init`.
+    pub fn get_admin(env: Env) -> Result<Address, Error> {
+        get_admin(&env)
+    }
+
+    /// Returns whether both the admin and the treasury have been configured.
+    /// A deployment reporting `false` cannot resolve disputes or collect fees yet.
+    pub fn is_initialized(env: Env) -> bool {
+        get_admin(&env).is_ok() && env.storage().instance().has(&DataKey::treasury)
+    }
+
+    /// Returns the contract's semantic version and the storage schema version it expects.
+    pub fn version(env: Env) -> Version {
+        Version {
+            version: String::from_str(&env, env!("CARGO_PKG_VERSION")),
+            schema: STORAGE_SCHEMA_VERSION,
+        }
+    }
+
+    /// Lists this deployment in a Vaultix registry under `caller` as operator (Admin role),
+    /// reporting the running contract version. Call again after an upgrade to refresh it.
+    pub fn register_with_registry(
+        env: Env,
+        caller: Address,
+        registry: Address,
+    ) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "register_with_registry", &caller, None);
+
+        let version = Self::version(env.clone()).version;
+        RegistryClient::new(&env, &registry).register(
+            &env.current_contract_address(),
+            &caller,
+            &version,
+        );
+        Ok(())
+    }
+
+    /// Returns the storage schema version recorded for this deployment.
+    pub fn get_schema_version(env: Env) -> u32 {
+        stored_schema_version(&env)
+    }
+
+    /// Replaces the contract's code with an uploaded WASM (Upgrader role). Call `migrate`
+    /// afterwards if the new code expects a newer storage schema.
+    pub fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Upgrader)?;
+        admin_log::record(&env, "upgrade", &caller, None);
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        env.events()
+            .publish((symbol_short!("upgraded"), caller), new_wasm_hash);
+        Ok(())
+    }
+
+    /// Brings instance-level storage up to the current schema after a WASM upgrade
+    /// (Upgrader role): folds the legacy fee and pause keys into `Config` and moves singletons
+    /// kept under bare symbols to their `DataKey`. Schema 1 escrows are then converted with
+    /// `migrate_escrow`.
+    ///
+    /// # Returns
+    /// The schema version now recorded
+    ///
+    /// # Errors
+    /// * `IncompatibleSchema` - If storage was written by a newer schema than this code
+    pub fn migrate(env: Env, caller: Address) -> Result<u32, Error> {
+        require_role(&env, &caller, Role::Upgrader)?;
+        admin_log::record(&env, "migrate", &caller, None);
+
+        let stored = stored_schema_version(&env);
+        if stored > STORAGE_SCHEMA_VERSION {
+            return Err(Error::IncompatibleSchema);
+        }
+        if stored < STORAGE_SCHEMA_VERSION {
+            save_config(&env, &load_config(&env));
+            migrate_singletons(&env);
+            if let Ok(treasury) = Self::get_treasury(env.clone()) {
+                grant_treasury_roles(&env, &treasury);
+            }
+            record_schema(&env);
+            extend_instance(&env);
+        }
+        Ok(STORAGE_SCHEMA_VERSION)
+    }
+
+    /// Rewrites an escrow stored by the previous contract version, converting its
+    /// `Symbol` milestone descriptions to `String` and moving its milestones into
+    /// per-index entries. Requires the Upgrader role.
+    ///
+    /// Migration plan: upgrade the contract WASM, then call this once for every escrow
+    /// created before the upgrade. Entries created after the upgrade are already in the
+    /// new layout and must not be passed here.
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller lacks the Upgrader role
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    pub fn migrate_escrow(env: Env, caller: Address, escrow_id: u64) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Upgrader)?;
+        admin_log::record(&env, "migrate_escrow", &caller, Some(escrow_id));
+
+        let storage_key = get_storage_key(escrow_id);
+        let legacy: LegacyEscrow = env
+            .storage()
+            .persistent()
+            .get(&storage_key)
+            .ok_or(Error::EscrowNotFound)?;
+
+        let mut released_mask: u64 = 0;
+        let mut disputed_mask: u64 = 0;
+        for (index, milestone) in legacy.milestones.iter().enumerate() {
+            match milestone.status {
+                MilestoneStatus::Released => released_mask |= milestone_bit(index as u32),
+                MilestoneStatus::Disputed => disputed_mask |= milestone_bit(index as u32),
+                MilestoneStatus::Pending | MilestoneStatus::Unfunded => {}
+            }
+            let converted = Milestone {
+                amount: milestone.amount,
+                status: MilestoneStatus::Pending,
+                description: symbol_to_string(&env, &milestone.description),
+            };
+            save_milestone(&env, escrow_id, index as u32, &converted);
+        }
+
+        let escrow = Escrow {
+            depositor: legacy.depositor,
+            recipient: legacy.recipient,
+            token_address: legacy.token_address,
+            total_amount: legacy.total_amount,
+            total_released: legacy.total_released,
+            milestone_count: legacy.milestones.len(),
+            released_mask,
+            disputed_mask,
+            status: legacy.status,
+            deadline: legacy.deadline,
+            resolution: legacy.resolution,
+            assignable: legacy.assignable,
+        };
+
+        save_escrow(&env, escrow_id, &escrow);
+
+        Ok(())
+    }
+}
+
+pub(crate) fn ensure_not_blocked(env: &Env, address: &Address) -> Result<(), Error> {
+    if VaultixEscrow::is_blocked(env.clone(), address.clone()) {
+        return Err(Error::AddressBlocked);
+    }
+    Ok(())
+}
+
+/// Allowlisted on-chain, or vouched for by the registered KYC attestor.
+pub(crate) fn is_verified_party(env: &Env, address: &Address) -> bool {
+    if env.storage().persistent().has(&allowlist_key(address)) {
+        return true;
+    }
+    match VaultixEscrow::get_kyc_attestor(env.clone()) {
+        Some(attestor) => AttestorClient::new(env, &attestor).is_verified(address),
+        None => false,
+    }
+}
+
+pub(crate) fn ensure_allowed(env: &Env, address: &Address) -> Result<(), Error> {
+    if load_config(env).allowlist_only && !is_verified_party(env, address) {
+        return Err(Error::NotAllowlisted);
+    }
+    Ok(())
+}
+
+pub(crate) fn ensure_token_allowed(env: &Env, token_address: &Address) -> Result<(), Error> {
+    let tokens = VaultixEscrow::get_allowed_tokens(env.clone());
+    if !tokens.is_empty() && !tokens.contains(token_address) {
+        return Err(Error::NotAllowlisted);
+    }
+    Ok(())
+}
+
+/// Fails with `AmountOutOfRange` if the price feed values any milestone below the floor.
+pub(crate) fn ensure_min_value(
+    env: &Env,
+    token_address: &Address,
+    milestones: &Vec<Milestone>,
+) -> Result<(), Error> {
+    let Some(floor) = VaultixEscrow::get_value_floor(env.clone()) else {
+        return Ok(());
+    };
+    let oracle = PriceOracleClient::new(env, &floor.oracle);
+    for milestone in milestones.iter() {
+        if oracle.usd_value(token_address, &milestone.amount) < floor.min_milestone_usd {
+            return Err(Error::AmountOutOfRange);
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn ensure_not_paused(env: &Env) -> Result<(), Error> {
+    if load_config(env).paused {
+        return Err(Error::ContractPaused);
+    }
+
+    extend_instance(env);
+    Ok(())
+}
+
+/// Quota usage of `depositor` in the window containing the current ledger time.
+pub(crate) fn current_quota_usage(
+    env: &Env,
+    depositor: &Address,
+    config: &Config,
+) -> CreationQuota {
+    let now = env.ledger().timestamp();
+    match env
+        .storage()
+        .persistent()
+        .get::<_, CreationQuota>(&creation_quota_key(depositor))
+    {
+        Some(usage) if now < usage.window_start.saturating_add(config.quota_window) => usage,
+        _ => CreationQuota {
+            window_start: now,
+            used: 0,
+        },
+    }
+}
+
+/// Counts one escrow creation against the depositor's quota, if a quota is configured.
+pub(crate) fn consume_creation_quota(env: &Env, depositor: &Address) -> Result<(), Error> {
+    let config = load_config(env);
+    if config.creation_quota == 0 {
+        return Ok(());
+    }
+
+    let mut usage = current_quota_usage(env, depositor, &config);
+    if usage.used >= config.creation_quota {
+        return Err(Error::RateLimited);
+    }
+    usage.used += 1;
+
+    let key = creation_quota_key(depositor);
+    write_persistent(env, &key, &usage);
+    Ok(())
+}
+
+/// Records a new escrow between `escrow.depositor` and `escrow.recipient` against the pair's cap
+/// on open escrows, if one is configured. Escrows that have since reached a terminal state or
+/// been closed are dropped from the pair's index first.
+pub(crate) fn claim_pair_slot(env: &Env, escrow_id: u64, escrow: &Escrow) -> Result<(), Error> {
+    let cap = load_config(env).max_pair_escrows;
+    if cap == 0 {
+        return Ok(());
+    }
+
+    let key = pair_escrows_key(&escrow.depositor, &escrow.recipient);
+    let mut open = Vec::new(env);
+    for id in env
+        .storage()
+        .persistent()
+        .get::<_, Vec<u64>>(&key)
+        .unwrap_or_else(|| Vec::new(env))
+        .iter()
+    {
+        if let Ok(state) = VaultixEscrow::get_status(env.clone(), id) {
+            if !is_terminal(state.status) {
+                open.push_back(id);
+            }
+        }
+    }
+    if open.len() >= cap {
+        return Err(Error::RateLimited);
+    }
+    open.push_back(escrow_id);
+
+    write_persistent(env, &key, &open);
+    Ok(())
+}
+
+/// Converts a `Symbol` to a `String` by reading the symbol bytes out of its XDR encoding
+/// (4-byte type tag, 4-byte big-endian length, then the characters).
+pub(crate) fn symbol_to_string(env: &Env, symbol: &Symbol) -> String {
+    let xdr = symbol.clone().to_xdr(env);
+    let mut len_bytes = [0u8; 4];
+    xdr.slice(4..8).copy_into_slice(&mut len_bytes);
+    let len = u32::from_be_bytes(len_bytes) as usize;
+
+    // Symbols are at most 32 characters
+    let mut buf = [0u8; 32];
+    xdr.slice(8..8 + len as u32)
+        .copy_into_slice(&mut buf[..len]);
+    String::from_bytes(env, &buf[..len])
+}
+
+/// Stores the treasury and the platform fee, once the fee has been validated.
+fn configure_treasury(env: &Env, treasury: &Address, fee_bps: Option<i128>) -> Result<(), Error> {
+    let fee = fee_bps.unwrap_or(DEFAULT_FEE_BPS);
+
+    // Validate fee is reasonable (max 100%)
+    if !(0..=BPS_DENOMINATOR).contains(&fee) {
+        return Err(Error::InvalidFeeConfiguration);
+    }
+
+    // Store treasury address
+    env.storage().instance().set(&DataKey::treasury, treasury);
+    grant_treasury_roles(env, treasury);
+    record_schema_if_fresh(env);
+
+    // Store fee configuration
+    let mut config = load_config(env);
+    config.fee_bps = fee;
+    save_config(env, &config);
+    Ok(())
+}
+
+/// Grants the treasury the roles it held implicitly before schema 4.
+fn grant_treasury_roles(env: &Env, treasury: &Address) {
+    grant(env, Role::Pauser, treasury);
+    grant(env, Role::FeeManager, treasury);
+}
@@ -0,0 +1,55 @@
+//! Log of privileged actions.
+//!
+//! Every action taken under a privileged role (configuration changes, role grants, pauses,
+//! upgrades and migrations, recoveries, write-offs and dispute rulings) is appended to one
+//! deployment-wide log with the account that took it and the ledger it happened in. The log is
+//! never pruned, so whoever runs a deployment can be held to account for everything done with
+//! its powers. Entries are stored in pages of `ADMIN_LOG_PAGE_SIZE`, oldest first.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    storage::{admin_log_len_key, admin_log_page_key, write_persistent},
+    types::AdminAction,
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, Address, Env, Symbol, Vec};
+
+pub(crate) const ADMIN_LOG_PAGE_SIZE: u32 = 20;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Returns one page of the privileged action log, oldest entry first. Page `n` holds
+    /// entries `n * 20` to `n * 20 + 19`; pages past the end are empty.
+    pub fn get_admin_actions(env: Env, page: u32) -> Vec<AdminAction> {
+        env.storage()
+            .persistent()
+            .get(&admin_log_page_key(page))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Total number of privileged actions ever logged.
+    pub fn get_admin_actions_len(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&admin_log_len_key())
+            .unwrap_or(0)
+    }
+}
+
+/// Appends a privileged action, named after the entrypoint that took it, to the log.
+pub(crate) fn record(env: &Env, action: &str, actor: &Address, escrow_id: Option<u64>) {
+    let len = VaultixEscrow::get_admin_actions_len(env.clone());
+    let page = len / ADMIN_LOG_PAGE_SIZE;
+    let mut entries = VaultixEscrow::get_admin_actions(env.clone(), page);
+    entries.push_back(AdminAction {
+        action: Symbol::new(env, action),
+        actor: actor.clone(),
+        escrow_id,
+        ledger: env.ledger().sequence(),
+        timestamp: env.ledger().timestamp(),
+    });
+
+    write_persistent(env, &admin_log_page_key(page), &entries);
+    write_persistent(env, &admin_log_len_key(), &(len + 1));
+}
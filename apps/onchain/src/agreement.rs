@@ -0,0 +1,110 @@
+//! Standing arbitration agreements.
+//!
+//! Two addresses that work together repeatedly can agree once on how their disputes are
+//! handled: a dedicated arbiter, the dispute bond and the evidence window. Every escrow created
+//! between them afterwards, in either direction, takes those terms as they stood at creation,
+//! instead of the deployment-wide defaults. Either side may revoke the agreement; escrows already
+//! created keep their terms.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    errors::Error,
+    storage::{
+        agreed_terms_key, arbiter_storage_key, load_config, standing_agreement_key,
+        write_persistent,
+    },
+    types::{Escrow, StandingAgreement},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Records the standing agreement between `party_a` and `party_b`, replacing any earlier
+    /// one. Both must authorize it.
+    ///
+    /// # Errors
+    /// * `SelfDealing` - If the parties are the same address
+    /// * `InvalidConfiguration` - If the arbiter is one of the parties or the bond is negative
+    pub fn set_standing_agreement(
+        env: Env,
+        party_a: Address,
+        party_b: Address,
+        agreement: StandingAgreement,
+    ) -> Result<(), Error> {
+        party_a.require_auth();
+        party_b.require_auth();
+
+        if party_a == party_b {
+            return Err(Error::SelfDealing);
+        }
+        if agreement.arbiter == party_a || agreement.arbiter == party_b || agreement.bond < 0 {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        let key = standing_agreement_key(&party_a, &party_b);
+        write_persistent(&env, &key, &agreement);
+        env.events()
+            .publish((symbol_short!("agreed"), party_a, party_b), agreement);
+        Ok(())
+    }
+
+    /// Ends the standing agreement between `caller` and `counterparty`. Either side may call
+    /// this.
+    pub fn revoke_standing_agreement(env: Env, caller: Address, counterparty: Address) {
+        caller.require_auth();
+        env.storage()
+            .persistent()
+            .remove(&standing_agreement_key(&caller, &counterparty));
+        env.events()
+            .publish((symbol_short!("agree_rm"), caller, counterparty), ());
+    }
+
+    /// The standing agreement between two addresses, if any. The order doesn't matter.
+    pub fn get_standing_agreement(
+        env: Env,
+        party_a: Address,
+        party_b: Address,
+    ) -> Option<StandingAgreement> {
+        env.storage()
+            .persistent()
+            .get(&standing_agreement_key(&party_a, &party_b))
+    }
+
+    /// The standing agreement terms the escrow was created under, if any.
+    pub fn get_agreed_terms(env: Env, escrow_id: u64) -> Option<StandingAgreement> {
+        env.storage().persistent().get(&agreed_terms_key(escrow_id))
+    }
+}
+
+/// Applies the parties' standing agreement, if any, to a newly created escrow: its arbiter
+/// becomes the escrow's dedicated arbiter and its terms are kept with the escrow.
+pub(crate) fn apply(env: &Env, escrow_id: u64, escrow: &Escrow) {
+    let Some(agreement) = VaultixEscrow::get_standing_agreement(
+        env.clone(),
+        escrow.depositor.clone(),
+        escrow.recipient.clone(),
+    ) else {
+        return;
+    };
+
+    let arbiter_key = arbiter_storage_key(escrow_id);
+    write_persistent(env, &arbiter_key, &agreement.arbiter);
+    let key = agreed_terms_key(escrow_id);
+    write_persistent(env, &key, &agreement);
+}
+
+/// Dispute bond for the escrow: the agreed one, or the configured default.
+pub(crate) fn dispute_bond(env: &Env, escrow_id: u64) -> i128 {
+    VaultixEscrow::get_agreed_terms(env.clone(), escrow_id)
+        .map_or_else(|| load_config(env).dispute_bond, |terms| terms.bond)
+}
+
+/// Evidence window for the escrow's disputes: the agreed one, or the configured default.
+pub(crate) fn evidence_window(env: &Env, escrow_id: u64) -> u64 {
+    VaultixEscrow::get_agreed_terms(env.clone(), escrow_id).map_or_else(
+        || load_config(env).evidence_window,
+        |terms| terms.evidence_window,
+    )
+}
@@ -0,0 +1,234 @@
+//! Multi-party release approval.
+//!
+//! Before funding, a depositor can hand release authority to a quorum of approvers, e.g. 2-of-3
+//! between the client, a project manager and QA. Each approver signs off per milestone; the
+//! approval that reaches the threshold pays the milestone out, and the depositor-driven release
+//! paths are closed for the escrow.
+//!
+//! Approvals can be made to expire, so a stale sign-off from weeks ago does not combine with a
+//! fresh one to release work that has since changed.
+//!
+//! `create_escrow_with_approvers` covers the common org split where finance funds and delivery
+//! managers sign off: any one named approver releases. Approvers are not parties, so they can
+//! never cancel or dispute the escrow.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin::ensure_not_paused,
+    errors::Error,
+    escrow::{load_live_escrow, new_escrow, pay_milestone, release_guard, store_new_escrow},
+    history,
+    storage::{approval_policy_key, approvals_key, next_escrow_id, write_persistent},
+    types::{ApprovalPolicy, Escrow, EscrowStatus, Milestone},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, Map, Vec};
+
+/// Upper bound on approvers per escrow, keeping quorum checks cheap.
+pub(crate) const MAX_APPROVERS: u32 = 10;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Requires `threshold` of `approvers` to sign off on each milestone before it is released.
+    /// Depositor only, before funding. The recipient cannot be an approver.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `EscrowAlreadyFunded` - If the escrow has left the Created state
+    /// * `InvalidConfiguration` - If the threshold is zero or above the approver count, there
+    ///   are more than `MAX_APPROVERS`, an approver is repeated or is the recipient
+    pub fn set_approval_policy(
+        env: Env,
+        escrow_id: u64,
+        policy: ApprovalPolicy,
+    ) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if escrow.status != EscrowStatus::Created {
+            return Err(Error::EscrowAlreadyFunded);
+        }
+        store_policy(&env, escrow_id, &escrow, &policy)
+    }
+
+    /// Creates an escrow whose milestones are released by any one of `approvers` rather than
+    /// the depositor, who keeps the right to fund, cancel and dispute.
+    ///
+    /// # Returns
+    /// The identifier assigned to the new escrow
+    ///
+    /// # Errors
+    /// Those of `create_escrow`, plus `InvalidConfiguration` if the approver set is empty, larger
+    /// than `MAX_APPROVERS`, repeats an address or includes the recipient.
+    pub fn create_escrow_with_approvers(
+        env: Env,
+        depositor: Address,
+        recipient: Address,
+        token_address: Address,
+        milestones: Vec<Milestone>,
+        deadline: u64,
+        approvers: Vec<Address>,
+    ) -> Result<u64, Error> {
+        depositor.require_auth();
+
+        ensure_not_paused(&env)?;
+
+        let (escrow, milestones) = new_escrow(
+            &env,
+            depositor,
+            recipient,
+            token_address,
+            milestones,
+            deadline,
+        )?;
+        let policy = ApprovalPolicy {
+            approvers,
+            threshold: 1,
+            approval_ttl: 0,
+        };
+        let escrow_id = next_escrow_id(&env);
+        store_new_escrow(&env, escrow_id, &escrow, &milestones)?;
+        store_policy(&env, escrow_id, &escrow, &policy)?;
+        Ok(escrow_id)
+    }
+
+    /// Returns the escrow's approval policy, if releases go through a quorum.
+    pub fn get_approval_policy(env: Env, escrow_id: u64) -> Option<ApprovalPolicy> {
+        env.storage()
+            .persistent()
+            .get(&approval_policy_key(escrow_id))
+    }
+
+    /// Records `approver`'s sign-off on a milestone. Returns true when this approval completed
+    /// the quorum and the milestone was released.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `UnauthorizedAccess` - If `approver` is not in the escrow's approval policy
+    /// * `EscrowNotActive` - If the escrow is not funded or is disputed
+    /// * `MilestoneNotFound` - If the index is out of bounds
+    /// * `MilestoneAlreadyReleased` - If the milestone was already paid
+    pub fn approve_milestone(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+        approver: Address,
+    ) -> Result<bool, Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        approver.require_auth();
+        approve(&env, escrow_id, &mut escrow, milestone_index, &approver)
+    }
+
+    /// Approvers whose sign-off on the milestone still counts towards the quorum.
+    pub fn get_approvals(env: Env, escrow_id: u64, milestone_index: u32) -> Vec<Address> {
+        match Self::get_approval_policy(env.clone(), escrow_id) {
+            Some(policy) => live_approvals(&env, escrow_id, milestone_index, &policy).keys(),
+            None => Vec::new(&env),
+        }
+    }
+}
+
+/// Records the sign-off of `approver`, who has already been authorized. Returns true when it
+/// completed the quorum and the milestone was released.
+pub(crate) fn approve(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &mut Escrow,
+    milestone_index: u32,
+    approver: &Address,
+) -> Result<bool, Error> {
+    let policy = VaultixEscrow::get_approval_policy(env.clone(), escrow_id)
+        .ok_or(Error::UnauthorizedAccess)?;
+    if !policy.approvers.contains(approver) {
+        return Err(Error::UnauthorizedAccess);
+    }
+    release_guard(env, escrow_id, escrow, milestone_index)?;
+
+    let mut approvals = live_approvals(env, escrow_id, milestone_index, &policy);
+    approvals.set(approver.clone(), env.ledger().timestamp());
+
+    history::record(env, escrow_id, symbol_short!("approved"), approver, 0);
+    env.events().publish(
+        (symbol_short!("approved"), escrow_id, milestone_index),
+        (approver.clone(), approvals.len()),
+    );
+
+    let key = approvals_key(escrow_id, milestone_index);
+    if approvals.len() < policy.threshold {
+        write_persistent(env, &key, &approvals);
+        return Ok(false);
+    }
+
+    env.storage().persistent().remove(&key);
+    pay_milestone(env, escrow_id, escrow, milestone_index, approver)?;
+    Ok(true)
+}
+
+fn store_policy(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &Escrow,
+    policy: &ApprovalPolicy,
+) -> Result<(), Error> {
+    let count = policy.approvers.len();
+    if policy.threshold == 0 || policy.threshold > count || count > MAX_APPROVERS {
+        return Err(Error::InvalidConfiguration);
+    }
+    for (i, approver) in policy.approvers.iter().enumerate() {
+        if approver == escrow.recipient
+            || policy.approvers.first_index_of(&approver) != Some(i as u32)
+        {
+            return Err(Error::InvalidConfiguration);
+        }
+    }
+
+    let key = approval_policy_key(escrow_id);
+    write_persistent(env, &key, policy);
+
+    env.events().publish(
+        (symbol_short!("appr_pol"), escrow_id),
+        (policy.threshold, count),
+    );
+    Ok(())
+}
+
+/// Guard for the depositor-driven release paths, which a quorum replaces.
+pub(crate) fn ensure_no_approval_policy(env: &Env, escrow_id: u64) -> Result<(), Error> {
+    if env
+        .storage()
+        .persistent()
+        .has(&approval_policy_key(escrow_id))
+    {
+        return Err(Error::ApprovalRequired);
+    }
+    Ok(())
+}
+
+/// Stored approvals for the milestone, minus those older than the policy's `approval_ttl`.
+fn live_approvals(
+    env: &Env,
+    escrow_id: u64,
+    milestone_index: u32,
+    policy: &ApprovalPolicy,
+) -> Map<Address, u64> {
+    let approvals: Map<Address, u64> = env
+        .storage()
+        .persistent()
+        .get(&approvals_key(escrow_id, milestone_index))
+        .unwrap_or_else(|| Map::new(env));
+    if policy.approval_ttl == 0 {
+        return approvals;
+    }
+
+    let now = env.ledger().timestamp();
+    let mut live = Map::new(env);
+    for (approver, approved_at) in approvals.iter() {
+        if now < approved_at.saturating_add(policy.approval_ttl) {
+            live.set(approver, approved_at);
+        }
+    }
+    live
+}
@@ -0,0 +1,525 @@
+//! Arbitrator registry and the queue of disputes waiting for one.
+//!
+//! When a dispute is raised on an escrow without a dedicated arbiter, one is drawn at random
+//! from the registered arbitrators who are not party to it, so neither side can pick a friendly
+//! judge. If nobody is eligible the dispute is queued instead: a registered arbitrator takes it
+//! with `claim_dispute`, becoming its arbiter, after which only they can resolve it. Queued
+//! disputes nobody has claimed remain resolvable by the admin.
+//!
+//! Once an `ArbitrationConfig` is set, arbitrators join the pool by staking at least
+//! `min_stake`, and an arbitrator who lets an assigned dispute run past its resolution deadline
+//! loses `slash_bps` of their stake to the insurance pool and leaves the pool. Arbitrators who
+//! resolve a dispute earn `fee_bps` of its dispute bond, credited to a claimable balance.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin_log,
+    dispute::add_to_insurance_pool,
+    errors::{fail, Error, ExtendedError},
+    escrow::{calculate_fee, load_live_escrow},
+    history, operator, panel,
+    rbac::require_role,
+    storage::{
+        arbiter_storage_key, arbitration_config_key, arbitrator_fees_key, arbitrators_key,
+        assignment_key, dispute_queue_key, open_assignments_key, stake_key, write_persistent,
+    },
+    types::{ArbitrationConfig, Escrow, EscrowStatus, Role},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, token, Address, Bytes, Env, Vec};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Registers an address as an arbitrator allowed to claim queued disputes (Admin role).
+    /// Same as granting it the Arbitrator role.
+    pub fn add_arbitrator(env: Env, caller: Address, arbitrator: Address) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "add_arbitrator", &caller, None);
+        join_pool(&env, &arbitrator);
+        Ok(())
+    }
+
+    /// Removes an arbitrator from the registry (Admin role). Disputes they already claimed
+    /// stay assigned to them. Same as revoking the Arbitrator role.
+    pub fn remove_arbitrator(env: Env, caller: Address, arbitrator: Address) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "remove_arbitrator", &caller, None);
+        leave_pool(&env, &arbitrator);
+        env.events()
+            .publish((symbol_short!("arb_rm"), arbitrator), ());
+        Ok(())
+    }
+
+    /// Returns every registered arbitrator in registration order.
+    pub fn get_arbitrators(env: Env) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&arbitrators_key())
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Returns whether an address is a registered arbitrator holding the required stake.
+    pub fn is_arbitrator(env: Env, address: Address) -> bool {
+        if let Some(config) = Self::get_arbitration_config(env.clone()) {
+            if Self::get_stake(env.clone(), address.clone()) < config.min_stake {
+                return false;
+            }
+        }
+        Self::get_arbitrators(env).contains(&address)
+    }
+
+    /// Sets the staking rules for the arbitrator pool (Admin role). The stake token cannot be
+    /// changed once set, since existing stakes are held in it.
+    ///
+    /// # Errors
+    /// * `InvalidConfiguration` - If a value is out of range or the stake token changes
+    pub fn set_arbitration_config(
+        env: Env,
+        caller: Address,
+        config: ArbitrationConfig,
+    ) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "set_arbitration_config", &caller, None);
+
+        if config.min_stake < 0 || config.slash_bps > 10_000 || config.fee_bps > 10_000 {
+            return Err(Error::InvalidConfiguration);
+        }
+        if let Some(current) = Self::get_arbitration_config(env.clone()) {
+            if current.stake_token != config.stake_token {
+                return Err(Error::InvalidConfiguration);
+            }
+        }
+
+        env.storage()
+            .instance()
+            .set(&arbitration_config_key(), &config);
+        Ok(())
+    }
+
+    /// Returns the arbitrator staking rules, if staking is enabled.
+    pub fn get_arbitration_config(env: Env) -> Option<ArbitrationConfig> {
+        env.storage().instance().get(&arbitration_config_key())
+    }
+
+    /// Returns the amount an arbitrator has staked.
+    pub fn get_stake(env: Env, arbitrator: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&stake_key(&arbitrator))
+            .unwrap_or(0)
+    }
+
+    /// Stakes `amount` of the stake token. Reaching `min_stake` adds the arbitrator to the pool.
+    ///
+    /// # Errors
+    /// * `InvalidConfiguration` - If staking is not enabled
+    /// * `ZeroAmount` - If amount is zero or negative
+    pub fn stake(env: Env, arbitrator: Address, amount: i128) -> Result<(), Error> {
+        arbitrator.require_auth();
+
+        let config =
+            Self::get_arbitration_config(env.clone()).ok_or(Error::InvalidConfiguration)?;
+        if amount <= 0 {
+            return Err(Error::ZeroAmount);
+        }
+
+        let staked = Self::get_stake(env.clone(), arbitrator.clone())
+            .checked_add(amount)
+            .ok_or(Error::InvalidMilestoneAmount)?;
+        save_stake(&env, &arbitrator, staked);
+
+        if staked >= config.min_stake {
+            let mut arbitrators = Self::get_arbitrators(env.clone());
+            if !arbitrators.contains(&arbitrator) {
+                arbitrators.push_back(arbitrator.clone());
+                save_arbitrators(&env, &arbitrators);
+            }
+        }
+        token::Client::new(&env, &config.stake_token).transfer(
+            &arbitrator,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        env.events()
+            .publish((symbol_short!("staked"), arbitrator), staked);
+        Ok(())
+    }
+
+    /// Withdraws `amount` of an arbitrator's stake. Dropping below `min_stake` removes them
+    /// from the pool.
+    ///
+    /// # Errors
+    /// * `InvalidConfiguration` - If staking is not enabled
+    /// * `ZeroAmount` - If amount is zero or negative
+    /// * `InsufficientBalance` - If amount exceeds the stake
+    /// * `StakeLocked` - If the arbitrator still has assigned disputes open
+    pub fn unstake(env: Env, arbitrator: Address, amount: i128) -> Result<(), Error> {
+        arbitrator.require_auth();
+
+        let config =
+            Self::get_arbitration_config(env.clone()).ok_or(Error::InvalidConfiguration)?;
+        if amount <= 0 {
+            return Err(Error::ZeroAmount);
+        }
+        let staked = Self::get_stake(env.clone(), arbitrator.clone());
+        if amount > staked {
+            return Err(Error::InsufficientBalance);
+        }
+        if open_assignments(&env, &arbitrator) > 0 {
+            return Err(Error::StakeLocked);
+        }
+
+        let remaining = staked - amount;
+        save_stake(&env, &arbitrator, remaining);
+        if remaining < config.min_stake {
+            leave_pool(&env, &arbitrator);
+        }
+
+        token::Client::new(&env, &config.stake_token).transfer(
+            &env.current_contract_address(),
+            &arbitrator,
+            &amount,
+        );
+
+        env.events()
+            .publish((symbol_short!("unstaked"), arbitrator), remaining);
+        Ok(())
+    }
+
+    /// Returns the resolution fees an arbitrator has earned in `token_address` and not yet claimed.
+    pub fn get_claimable_fees(env: Env, arbitrator: Address, token_address: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&arbitrator_fees_key(&arbitrator, &token_address))
+            .unwrap_or(0)
+    }
+
+    /// Pays out an arbitrator's accumulated resolution fees in `token_address`.
+    ///
+    /// # Errors
+    /// * `ZeroAmount` - If there is nothing to claim
+    pub fn claim_arbitration_fees(
+        env: Env,
+        arbitrator: Address,
+        token_address: Address,
+    ) -> Result<i128, Error> {
+        arbitrator.require_auth();
+
+        let amount =
+            Self::get_claimable_fees(env.clone(), arbitrator.clone(), token_address.clone());
+        if amount <= 0 {
+            return Err(Error::ZeroAmount);
+        }
+        env.storage()
+            .persistent()
+            .remove(&arbitrator_fees_key(&arbitrator, &token_address));
+
+        token::Client::new(&env, &token_address).transfer(
+            &env.current_contract_address(),
+            &arbitrator,
+            &amount,
+        );
+
+        env.events().publish(
+            (symbol_short!("arb_paid"), arbitrator),
+            (token_address, amount),
+        );
+        Ok(amount)
+    }
+
+    /// Returns when the arbitrator assigned to a dispute must have resolved it, if a
+    /// resolution window applies.
+    pub fn get_resolution_deadline(env: Env, escrow_id: u64) -> Option<u64> {
+        let (_, deadline): (Address, u64) =
+            env.storage().persistent().get(&assignment_key(escrow_id))?;
+        if deadline == 0 {
+            None
+        } else {
+            Some(deadline)
+        }
+    }
+
+    /// Slashes the arbitrator of a dispute left unresolved past its deadline and hands the
+    /// dispute to another arbitrator, or back to the queue. Callable by anyone.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow is not disputed by an assigned arbitrator
+    /// * `InvalidDeadline` - If the resolution deadline has not passed yet
+    pub fn slash_missed_deadline(env: Env, escrow_id: u64) -> Result<i128, Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        if escrow.status != EscrowStatus::Disputed {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        let deadline = Self::get_resolution_deadline(env.clone(), escrow_id)
+            .ok_or(Error::InvalidEscrowStatus)?;
+        if env.ledger().timestamp() <= deadline {
+            return Err(Error::InvalidDeadline);
+        }
+
+        let arbitrator = release_assignment(&env, escrow_id).ok_or(Error::InvalidEscrowStatus)?;
+        let slashed = slash(&env, &arbitrator)?;
+        leave_pool(&env, &arbitrator);
+
+        env.storage()
+            .persistent()
+            .remove(&arbiter_storage_key(escrow_id));
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("slashed"),
+            &arbitrator,
+            slashed,
+        );
+        assign_or_enqueue(&env, escrow_id, &escrow);
+
+        Ok(slashed)
+    }
+
+    /// Returns the ids of disputed escrows waiting for an arbitrator, oldest first.
+    pub fn get_dispute_queue(env: Env) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&dispute_queue_key())
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Takes ownership of a queued dispute. The arbitrator becomes the escrow's arbiter and the
+    /// only address able to resolve it.
+    ///
+    /// # Errors
+    /// * `NotArbitrator` - If `arbitrator` is not registered
+    /// * `DisputeNotQueued` - If the escrow has no dispute waiting in the queue
+    pub fn claim_dispute(env: Env, escrow_id: u64, arbitrator: Address) -> Result<(), Error> {
+        arbitrator.require_auth();
+
+        if !Self::is_arbitrator(env.clone(), arbitrator.clone()) {
+            return Err(Error::NotArbitrator);
+        }
+        if !dequeue(&env, escrow_id) {
+            return Err(Error::DisputeNotQueued);
+        }
+
+        assign(&env, escrow_id, &arbitrator);
+
+        history::record(&env, escrow_id, symbol_short!("claimed"), &arbitrator, 0);
+        env.events()
+            .publish((symbol_short!("d_claimed"), escrow_id), arbitrator);
+        Ok(())
+    }
+}
+
+fn save_arbitrators(env: &Env, arbitrators: &Vec<Address>) {
+    let key = arbitrators_key();
+    write_persistent(env, &key, arbitrators);
+}
+
+/// Adds an arbitrator to the pool, announcing it with an `arb_add` event.
+pub(crate) fn join_pool(env: &Env, arbitrator: &Address) {
+    let mut arbitrators = VaultixEscrow::get_arbitrators(env.clone());
+    if !arbitrators.contains(arbitrator) {
+        arbitrators.push_back(arbitrator.clone());
+        save_arbitrators(env, &arbitrators);
+    }
+
+    env.events()
+        .publish((symbol_short!("arb_add"), arbitrator.clone()), ());
+}
+
+pub(crate) fn leave_pool(env: &Env, arbitrator: &Address) {
+    let mut arbitrators = VaultixEscrow::get_arbitrators(env.clone());
+    if let Some(index) = arbitrators.first_index_of(arbitrator) {
+        arbitrators.remove(index);
+        save_arbitrators(env, &arbitrators);
+    }
+}
+
+fn save_stake(env: &Env, arbitrator: &Address, amount: i128) {
+    let key = stake_key(arbitrator);
+    write_persistent(env, &key, &amount);
+}
+
+pub(crate) fn open_assignments(env: &Env, arbitrator: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&open_assignments_key(arbitrator))
+        .unwrap_or(0)
+}
+
+pub(crate) fn set_open_assignments(env: &Env, arbitrator: &Address, count: u32) {
+    let key = open_assignments_key(arbitrator);
+    if count == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        write_persistent(env, &key, &count);
+    }
+}
+
+/// Makes `arbitrator` the arbiter of a dispute and starts its resolution window, if any.
+pub(crate) fn assign(env: &Env, escrow_id: u64, arbitrator: &Address) {
+    let arbiter_key = arbiter_storage_key(escrow_id);
+    write_persistent(env, &arbiter_key, arbitrator);
+
+    // The resolution clock starts once evidence has closed, since rulings wait for it
+    let start = VaultixEscrow::get_evidence_deadline(env.clone(), escrow_id)
+        .unwrap_or(0)
+        .max(env.ledger().timestamp());
+    let deadline = match VaultixEscrow::get_arbitration_config(env.clone()) {
+        Some(config) if config.resolution_window > 0 => {
+            start.saturating_add(config.resolution_window)
+        }
+        _ => 0,
+    };
+    let key = assignment_key(escrow_id);
+    write_persistent(env, &key, &(arbitrator.clone(), deadline));
+    set_open_assignments(env, arbitrator, open_assignments(env, arbitrator) + 1);
+}
+
+/// Ends the assignment of a dispute, returning the arbitrator it was assigned to.
+pub(crate) fn release_assignment(env: &Env, escrow_id: u64) -> Option<Address> {
+    let key = assignment_key(escrow_id);
+    let (arbitrator, _): (Address, u64) = env.storage().persistent().get(&key)?;
+    env.storage().persistent().remove(&key);
+    set_open_assignments(
+        env,
+        &arbitrator,
+        open_assignments(env, &arbitrator).saturating_sub(1),
+    );
+    Some(arbitrator)
+}
+
+/// The arbiter's, or the deciding panel's, `fee_bps` cut of a dispute bond being settled.
+/// Disputes resolved by the admin pay no fee.
+pub(crate) fn arbitrator_fee(env: &Env, escrow_id: u64, bond: i128) -> Result<i128, Error> {
+    let Some(config) = VaultixEscrow::get_arbitration_config(env.clone()) else {
+        return Ok(0);
+    };
+    if VaultixEscrow::get_arbiter(env.clone(), escrow_id).is_none()
+        && VaultixEscrow::get_panel_vote(env.clone(), escrow_id).is_none()
+    {
+        return Ok(0);
+    }
+    Ok(calculate_fee(bond, config.fee_bps as i128)
+        .unwrap_or_else(|| fail(env, ExtendedError::FeeOverflow)))
+}
+
+/// Credits the escrow's arbiter, or the panel majority, with its fee from a dispute bond being
+/// settled and returns the fee.
+pub(crate) fn compensate(
+    env: &Env,
+    escrow_id: u64,
+    token_address: &Address,
+    bond: i128,
+) -> Result<i128, Error> {
+    let fee = arbitrator_fee(env, escrow_id, bond)?;
+    if let Some(arbitrator) = VaultixEscrow::get_arbiter(env.clone(), escrow_id).filter(|_| fee > 0)
+    {
+        credit_fee(env, &arbitrator, token_address, fee)?;
+    } else if fee > 0 {
+        panel::share_fee(env, escrow_id, token_address, fee)?;
+    }
+    Ok(fee)
+}
+
+/// Adds `fee` to what an arbitrator can claim in `token_address`.
+pub(crate) fn credit_fee(
+    env: &Env,
+    arbitrator: &Address,
+    token_address: &Address,
+    fee: i128,
+) -> Result<(), Error> {
+    let key = arbitrator_fees_key(arbitrator, token_address);
+    let owed =
+        VaultixEscrow::get_claimable_fees(env.clone(), arbitrator.clone(), token_address.clone())
+            .checked_add(fee)
+            .unwrap_or_else(|| fail(env, ExtendedError::FeeOverflow));
+    write_persistent(env, &key, &owed);
+    Ok(())
+}
+
+/// Moves `slash_bps` of an arbitrator's stake into the insurance pool of the stake token.
+pub(crate) fn slash(env: &Env, arbitrator: &Address) -> Result<i128, Error> {
+    let config =
+        VaultixEscrow::get_arbitration_config(env.clone()).ok_or(Error::InvalidConfiguration)?;
+    let staked = VaultixEscrow::get_stake(env.clone(), arbitrator.clone());
+    let slashed = calculate_fee(staked, config.slash_bps as i128)
+        .unwrap_or_else(|| fail(env, ExtendedError::FeeOverflow));
+    if slashed > 0 {
+        let remaining = staked
+            .checked_sub(slashed)
+            .unwrap_or_else(|| fail(env, ExtendedError::BondUnderflow));
+        save_stake(env, arbitrator, remaining);
+        add_to_insurance_pool(env, &config.stake_token, slashed)?;
+    }
+
+    env.events()
+        .publish((symbol_short!("slashed"), arbitrator.clone()), slashed);
+    Ok(slashed)
+}
+
+/// Gives a newly disputed escrow without a dedicated arbiter a randomly selected one, or
+/// queues it when no registered arbitrator is eligible.
+pub(crate) fn assign_or_enqueue(env: &Env, escrow_id: u64, escrow: &Escrow) {
+    let arbiter_key = arbiter_storage_key(escrow_id);
+    if env.storage().persistent().has(&arbiter_key) {
+        return;
+    }
+
+    if let Some(arbitrator) = select_arbitrator(env, escrow_id, escrow) {
+        assign(env, escrow_id, &arbitrator);
+        env.events()
+            .publish((symbol_short!("d_assign"), escrow_id), arbitrator);
+        return;
+    }
+
+    let key = dispute_queue_key();
+    let mut queue = VaultixEscrow::get_dispute_queue(env.clone());
+    queue.push_back(escrow_id);
+    write_persistent(env, &key, &queue);
+}
+
+/// Removes an escrow from the dispute queue, returning whether it was queued.
+pub(crate) fn dequeue(env: &Env, escrow_id: u64) -> bool {
+    let mut queue = VaultixEscrow::get_dispute_queue(env.clone());
+    match queue.first_index_of(escrow_id) {
+        Some(index) => {
+            queue.remove(index);
+            env.storage().persistent().set(&dispute_queue_key(), &queue);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Draws one registered arbitrator who is neither party to the escrow, from the operator's
+/// arbiter set when the escrow's operator has one. The PRNG is reseeded
+/// from the ledger sequence, timestamp and escrow id, so the draw is reproducible but not
+/// chosen by either party.
+pub(crate) fn select_arbitrator(env: &Env, escrow_id: u64, escrow: &Escrow) -> Option<Address> {
+    let candidates = operator::config(env, escrow_id)
+        .map(|config| config.arbiters)
+        .filter(|arbiters| !arbiters.is_empty())
+        .unwrap_or_else(|| VaultixEscrow::get_arbitrators(env.clone()));
+    let mut eligible = Vec::new(env);
+    for arbitrator in candidates.iter() {
+        if arbitrator != escrow.depositor
+            && arbitrator != escrow.recipient
+            && VaultixEscrow::is_arbitrator(env.clone(), arbitrator.clone())
+        {
+            eligible.push_back(arbitrator);
+        }
+    }
+    if eligible.is_empty() {
+        return None;
+    }
+
+    let mut seed = Bytes::from_array(env, &env.ledger().sequence().to_be_bytes());
+    seed.extend_from_array(&env.ledger().timestamp().to_be_bytes());
+    seed.extend_from_array(&escrow_id.to_be_bytes());
+    env.prng().seed(env.crypto().sha256(&seed).into());
+
+    let index = env.prng().gen_range::<u64>(0..eligible.len() as u64) as u32;
+    eligible.get(index)
+}
@@ -0,0 +1,119 @@
+//! Open bounties: escrows posted without a recipient.
+//!
+//! `create_open_escrow` locks the reward straight away and leaves the escrow `Open`, with the
+//! contract itself standing in as recipient. Any address may then take the job with
+//! `claim_open_escrow`, or only the addresses the depositor listed, and always subject to the
+//! contract-wide blocklist and allowlist. The claimant becomes the recipient and the escrow
+//! turns `Active`, after which milestones are released as usual. The depositor can withdraw
+//! an unclaimed bounty with `cancel_escrow`, which refunds the reward.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin::{ensure_allowed, ensure_not_blocked, ensure_not_paused},
+    errors::Error,
+    escrow::{
+        collect_deposit, ensure_caller_chosen_id, load_live_escrow, mint_receipt, new_escrow,
+        save_escrow, stamp_created_at, store_new_escrow,
+    },
+    history, state_machine,
+    storage::{open_claimants_key, write_persistent},
+    types::{EscrowStatus, Milestone},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, Vec};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Posts a bounty anyone can claim as recipient, or only `claimants` when given, and funds
+    /// it from the depositor, who must have approved the total. Fails with the same errors as
+    /// `create_escrow`.
+    pub fn create_open_escrow(
+        env: Env,
+        escrow_id: u64,
+        depositor: Address,
+        token_address: Address,
+        milestones: Vec<Milestone>,
+        deadline: u64,
+        claimants: Option<Vec<Address>>,
+    ) -> Result<(), Error> {
+        depositor.require_auth();
+
+        ensure_not_paused(&env)?;
+        ensure_caller_chosen_id(escrow_id)?;
+
+        let (mut escrow, milestones) = new_escrow(
+            &env,
+            depositor,
+            env.current_contract_address(),
+            token_address,
+            milestones,
+            deadline,
+        )?;
+        escrow.status = EscrowStatus::Open;
+        store_new_escrow(&env, escrow_id, &escrow, &milestones)?;
+        if let Some(claimants) = claimants {
+            let key = open_claimants_key(escrow_id);
+            write_persistent(&env, &key, &claimants);
+        }
+        collect_deposit(&env, escrow_id, &escrow, escrow.total_amount)?;
+        Ok(())
+    }
+
+    /// Takes an open bounty: `claimant` becomes its recipient and the escrow turns Active.
+    /// The dispute grace period counts from the claim.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow is not an open bounty
+    /// * `SelfDealing` - If the depositor tries to claim their own bounty
+    /// * `NotAllowlisted` - If claimant is not on the bounty's or the contract's allowlist
+    /// * `AddressBlocked` - If claimant is blocked
+    pub fn claim_open_escrow(env: Env, escrow_id: u64, claimant: Address) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        claimant.require_auth();
+
+        if escrow.status != EscrowStatus::Open {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        if claimant == escrow.depositor {
+            return Err(Error::SelfDealing);
+        }
+        if let Some(claimants) = Self::get_open_claimants(env.clone(), escrow_id) {
+            if !claimants.contains(&claimant) {
+                return Err(Error::NotAllowlisted);
+            }
+        }
+        ensure_not_blocked(&env, &claimant)?;
+        ensure_allowed(&env, &claimant)?;
+
+        escrow.recipient = claimant.clone();
+        state_machine::transition(&mut escrow, EscrowStatus::Active)?;
+        save_escrow(&env, escrow_id, &escrow);
+        stamp_created_at(&env, escrow_id);
+        env.storage()
+            .persistent()
+            .remove(&open_claimants_key(escrow_id));
+        mint_receipt(&env, escrow_id, &claimant);
+
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("taken"),
+            &claimant,
+            escrow.total_amount,
+        );
+        env.events()
+            .publish((symbol_short!("taken"), escrow_id), claimant);
+        Ok(())
+    }
+
+    /// Addresses allowed to claim an open bounty, if the depositor restricted it.
+    pub fn get_open_claimants(env: Env, escrow_id: u64) -> Option<Vec<Address>> {
+        env.storage()
+            .persistent()
+            .get(&open_claimants_key(escrow_id))
+    }
+}
@@ -0,0 +1,66 @@
+//! Completion certificates.
+//!
+//! When the admin has registered a certificate contract, every escrow that completes has a
+//! soulbound certificate issued to each party, recording the engagement without its exact
+//! figures: the token, the order of magnitude of the volume released and whether it finished by
+//! its deadline. Recipients can point clients at these as verifiable work history. Issuing is
+//! best effort, so a failing certificate contract never holds up a completion.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin_log,
+    errors::Error,
+    rbac::require_role,
+    storage::{extend_instance, DataKey},
+    types::{Certificate, CertificateClient, Escrow, Role},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, Address, Env};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Registers (or clears) the contract completion certificates are issued through (Admin
+    /// role).
+    pub fn set_certificate_contract(
+        env: Env,
+        caller: Address,
+        contract: Option<Address>,
+    ) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "set_certificate_contract", &caller, None);
+
+        match contract {
+            Some(contract) => env.storage().instance().set(&DataKey::cert, &contract),
+            None => env.storage().instance().remove(&DataKey::cert),
+        }
+        extend_instance(&env);
+        Ok(())
+    }
+
+    /// Returns the registered certificate contract, if any.
+    pub fn get_certificate_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::cert)
+    }
+}
+
+/// Issues a certificate for the just-completed escrow to both parties, if a certificate
+/// contract is registered.
+pub(crate) fn issue(env: &Env, escrow_id: u64, escrow: &Escrow) {
+    let Some(contract) = VaultixEscrow::get_certificate_contract(env.clone()) else {
+        return;
+    };
+    let certificate = Certificate {
+        escrow_id,
+        depositor: escrow.depositor.clone(),
+        recipient: escrow.recipient.clone(),
+        token: escrow.token_address.clone(),
+        volume_band: escrow.total_released.checked_ilog10().unwrap_or(0),
+        on_time: env.ledger().timestamp() <= escrow.deadline,
+        completed_at: env.ledger().timestamp(),
+    };
+    let client = CertificateClient::new(env, &contract);
+    for holder in [&escrow.depositor, &escrow.recipient] {
+        let _ = client.try_issue(holder, &certificate);
+    }
+}
@@ -0,0 +1,76 @@
+//! Chained escrows.
+//!
+//! An escrow can name a parent escrow that must reach Completed before any of its own
+//! milestones are released. Multi-phase programs chain each phase to the one before it, and a
+//! prime contractor can fund subcontractor escrows that only pay out once the prime contract
+//! itself has been completed. The link is set by the depositor before funding and cannot form a
+//! cycle.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    errors::Error,
+    escrow::load_live_escrow,
+    storage::{parent_key, write_persistent},
+    types::EscrowStatus,
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, Env};
+
+// Longest chain of ancestors an escrow may have
+const MAX_CHAIN_DEPTH: u32 = 8;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Makes the escrow's releases wait for `parent_id` to complete. Depositor only, before
+    /// funding.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If either escrow doesn't exist
+    /// * `EscrowAlreadyFunded` - If the escrow has been funded
+    /// * `InvalidConfiguration` - If the link would form a cycle or a chain deeper than 8
+    pub fn set_parent_escrow(env: Env, escrow_id: u64, parent_id: u64) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if !matches!(
+            escrow.status,
+            EscrowStatus::Draft | EscrowStatus::Proposed | EscrowStatus::Created
+        ) {
+            return Err(Error::EscrowAlreadyFunded);
+        }
+        Self::get_escrow(env.clone(), parent_id)?;
+
+        let mut ancestor = Some(parent_id);
+        for _ in 0..MAX_CHAIN_DEPTH {
+            match ancestor {
+                Some(id) if id == escrow_id => return Err(Error::InvalidConfiguration),
+                Some(id) => ancestor = Self::get_parent_escrow(env.clone(), id),
+                None => break,
+            }
+        }
+        if ancestor.is_some() {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        let key = parent_key(escrow_id);
+        write_persistent(&env, &key, &parent_id);
+        Ok(())
+    }
+
+    /// The escrow that must complete before this one can release, if any.
+    pub fn get_parent_escrow(env: Env, escrow_id: u64) -> Option<u64> {
+        env.storage().persistent().get(&parent_key(escrow_id))
+    }
+}
+
+/// Fails unless the escrow has no parent or its parent has completed.
+pub(crate) fn ensure_parent_completed(env: &Env, escrow_id: u64) -> Result<(), Error> {
+    let Some(parent_id) = VaultixEscrow::get_parent_escrow(env.clone(), escrow_id) else {
+        return Ok(());
+    };
+    match VaultixEscrow::get_escrow(env.clone(), parent_id) {
+        Ok(parent) if parent.status == EscrowStatus::Completed => Ok(()),
+        _ => Err(Error::ParentNotCompleted),
+    }
+}
@@ -0,0 +1,300 @@
+//! Payout conversion.
+//!
+//! A depositor can fund an escrow in a volatile asset and still promise the recipient a
+//! stable-value payout: before funding, they name a stablecoin and a slippage tolerance, and
+//! every payout to the recipient side is swapped into that stablecoin through the AMM router
+//! the admin registered, after the platform fee has been taken in the escrow token. If the
+//! router is missing, cannot quote the pair, or the swap would come in below the tolerance,
+//! the payout is made in kind instead, so a release never fails on the conversion.
+//!
+//! Recipients who want an asset with no direct market against the escrow token get a payout
+//! route instead: a path of up to three hops through the AMM pools the admin registered for
+//! each token pair. Every hop is quoted before anything moves, and the route falls back to an
+//! in-kind payout if a pool is missing or can't quote. Once quoted, a hop that fails to fill
+//! aborts the release rather than leaving the contract holding an intermediate asset.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin_log,
+    errors::Error,
+    escrow::{calculate_fee, load_live_escrow, BPS_DENOMINATOR},
+    rbac::require_role,
+    storage::{
+        amm_pool_key, extend_instance, payout_route_key, stable_payout_key, write_persistent,
+        DataKey,
+    },
+    types::{
+        AmmPoolClient, AmmRouterClient, Escrow, EscrowStatus, PayoutRoute, Role, StablePayout,
+    },
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, token, Address, Env, Vec};
+
+/// Most hops a payout route may take
+const MAX_ROUTE_HOPS: u32 = 3;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Registers (or clears) the AMM router payouts are converted through (Admin role).
+    pub fn set_amm_router(env: Env, caller: Address, router: Option<Address>) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "set_amm_router", &caller, None);
+
+        match router {
+            Some(router) => env.storage().instance().set(&DataKey::amm, &router),
+            None => env.storage().instance().remove(&DataKey::amm),
+        }
+        extend_instance(&env);
+        Ok(())
+    }
+
+    /// Returns the registered AMM router, if any.
+    pub fn get_amm_router(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::amm)
+    }
+
+    /// Has the escrow's payouts converted into a stablecoin, or paid in kind again with `None`.
+    /// Depositor only, before funding.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow has been funded
+    /// * `InvalidConfiguration` - If the stablecoin is the escrow token, the tolerance exceeds
+    ///   100% or the escrow has a payout route
+    pub fn set_stable_payout(
+        env: Env,
+        escrow_id: u64,
+        payout: Option<StablePayout>,
+    ) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if escrow.status != EscrowStatus::Created {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        let key = stable_payout_key(escrow_id);
+        let Some(payout) = payout else {
+            env.storage().persistent().remove(&key);
+            return Ok(());
+        };
+        if payout.token == escrow.token_address
+            || payout.max_slippage_bps as i128 > BPS_DENOMINATOR
+            || Self::get_payout_route(env.clone(), escrow_id).is_some()
+        {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        write_persistent(&env, &key, &payout);
+        Ok(())
+    }
+
+    /// Stablecoin the escrow's payouts are converted into, if any.
+    pub fn get_stable_payout(env: Env, escrow_id: u64) -> Option<StablePayout> {
+        env.storage()
+            .persistent()
+            .get(&stable_payout_key(escrow_id))
+    }
+
+    /// Registers (or clears) the AMM pool trading `token_a` against `token_b`, in either
+    /// direction (Admin role).
+    pub fn set_amm_pool(
+        env: Env,
+        caller: Address,
+        token_a: Address,
+        token_b: Address,
+        pool: Option<Address>,
+    ) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "set_amm_pool", &caller, None);
+
+        let key = amm_pool_key(&token_a, &token_b);
+        match pool {
+            Some(pool) => {
+                write_persistent(&env, &key, &pool);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+        Ok(())
+    }
+
+    /// Pool registered for the token pair, if any.
+    pub fn get_amm_pool(env: Env, token_a: Address, token_b: Address) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&amm_pool_key(&token_a, &token_b))
+    }
+
+    /// Routes the escrow's payouts through registered pools into the last token of
+    /// `route.hops`, or pays in kind again with `None`. Depositor only, before funding.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow has been funded
+    /// * `VectorTooLarge` - If the route takes more than 3 hops
+    /// * `InvalidConfiguration` - If the route is empty, a hop repeats the token before it, the
+    ///   tolerance exceeds 100% or the escrow has a stable payout
+    pub fn set_payout_route(
+        env: Env,
+        escrow_id: u64,
+        route: Option<PayoutRoute>,
+    ) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if escrow.status != EscrowStatus::Created {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        let key = payout_route_key(escrow_id);
+        let Some(route) = route else {
+            env.storage().persistent().remove(&key);
+            return Ok(());
+        };
+        if route.hops.len() > MAX_ROUTE_HOPS {
+            return Err(Error::VectorTooLarge);
+        }
+        let mut previous = escrow.token_address;
+        for hop in route.hops.iter() {
+            if hop == previous {
+                return Err(Error::InvalidConfiguration);
+            }
+            previous = hop;
+        }
+        if route.hops.is_empty()
+            || route.max_slippage_bps as i128 > BPS_DENOMINATOR
+            || Self::get_stable_payout(env.clone(), escrow_id).is_some()
+        {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        write_persistent(&env, &key, &route);
+        Ok(())
+    }
+
+    /// Route the escrow's payouts take, if any.
+    pub fn get_payout_route(env: Env, escrow_id: u64) -> Option<PayoutRoute> {
+        env.storage().persistent().get(&payout_route_key(escrow_id))
+    }
+}
+
+/// Pays `amount` of the escrow token out to `payee`, converted along the escrow's payout route
+/// or into its stablecoin when it has one and the conversion can be made within the tolerance.
+pub(crate) fn pay_out(env: &Env, escrow_id: u64, escrow: &Escrow, payee: &Address, amount: i128) {
+    let route = VaultixEscrow::get_payout_route(env.clone(), escrow_id);
+    if let (Some(route), true) = (route, amount > 0) {
+        if let Some(received) = route_through_pools(env, escrow, &route, payee, amount) {
+            env.events().publish(
+                (symbol_short!("converted"), escrow_id),
+                (
+                    route.hops.last().unwrap_or(escrow.token_address.clone()),
+                    received,
+                ),
+            );
+            return;
+        }
+    }
+    let target = VaultixEscrow::get_stable_payout(env.clone(), escrow_id);
+    let router = VaultixEscrow::get_amm_router(env.clone());
+    if let (Some(target), Some(router), true) = (target, router, amount > 0) {
+        if let Some(received) = convert(env, &router, escrow, &target, payee, amount) {
+            env.events().publish(
+                (symbol_short!("converted"), escrow_id),
+                (target.token, received),
+            );
+            return;
+        }
+    }
+    token::Client::new(env, &escrow.token_address).transfer(
+        &env.current_contract_address(),
+        payee,
+        &amount,
+    );
+}
+
+/// Swaps `amount` for the stablecoin, delivered to `payee`. Returns `None`, with nothing
+/// moved, if the router cannot quote the pair or fill the swap within the tolerance.
+fn convert(
+    env: &Env,
+    router: &Address,
+    escrow: &Escrow,
+    target: &StablePayout,
+    payee: &Address,
+    amount: i128,
+) -> Option<i128> {
+    let router = AmmRouterClient::new(env, router);
+    let quoted = match router.try_quote(&escrow.token_address, &target.token, &amount) {
+        Ok(Ok(quoted)) if quoted > 0 => quoted,
+        _ => return None,
+    };
+    let min_out = quoted.checked_sub(calculate_fee(quoted, target.max_slippage_bps as i128)?)?;
+
+    let contract = env.current_contract_address();
+    let asset = token::Client::new(env, &escrow.token_address);
+    asset.approve(
+        &contract,
+        &router.address,
+        &amount,
+        &env.ledger().sequence(),
+    );
+    match router.try_swap(
+        &contract,
+        &escrow.token_address,
+        &target.token,
+        &amount,
+        &min_out,
+        payee,
+    ) {
+        Ok(Ok(received)) => Some(received),
+        _ => {
+            asset.approve(&contract, &router.address, &0, &env.ledger().sequence());
+            None
+        }
+    }
+}
+
+/// Swaps `amount` hop by hop along `route`, delivering the last token to `payee`. Returns
+/// `None`, with nothing moved, if a hop has no pool or can't be quoted.
+fn route_through_pools(
+    env: &Env,
+    escrow: &Escrow,
+    route: &PayoutRoute,
+    payee: &Address,
+    amount: i128,
+) -> Option<i128> {
+    let mut pools = Vec::new(env);
+    let mut token_in = escrow.token_address.clone();
+    let mut expected = amount;
+    for token_out in route.hops.iter() {
+        let pool = VaultixEscrow::get_amm_pool(env.clone(), token_in.clone(), token_out.clone())?;
+        expected = match AmmPoolClient::new(env, &pool).try_quote(&token_in, &expected) {
+            Ok(Ok(quoted)) if quoted > 0 => quoted,
+            _ => return None,
+        };
+        pools.push_back(pool);
+        token_in = token_out;
+    }
+    let min_out = expected.checked_sub(calculate_fee(expected, route.max_slippage_bps as i128)?)?;
+
+    // The final minimum guards the whole route, so intermediate hops accept any fill
+    let contract = env.current_contract_address();
+    let mut token_in = escrow.token_address.clone();
+    let mut amount_in = amount;
+    for (index, pool) in pools.iter().enumerate() {
+        let last = index as u32 + 1 == pools.len();
+        let (hop_min, to) = if last {
+            (min_out, payee.clone())
+        } else {
+            (0, contract.clone())
+        };
+        token::Client::new(env, &token_in).approve(
+            &contract,
+            &pool,
+            &amount_in,
+            &env.ledger().sequence(),
+        );
+        amount_in =
+            AmmPoolClient::new(env, &pool).swap(&contract, &token_in, &amount_in, &hop_min, &to);
+        token_in = route.hops.get(index as u32)?;
+    }
+    Some(amount_in)
+}
@@ -0,0 +1,42 @@
+//! Escrow currency metadata.
+//!
+//! Amounts are stored in the token's base units. So that interfaces and statements can still
+//! render them for people long after creation, the token's symbol and decimals are captured when
+//! the escrow is created and kept with it, and later changes to the token's metadata, or the
+//! token becoming unreachable, don't affect how the escrow displays. A token that can't report
+//! its metadata at creation leaves the escrow without a snapshot.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    storage::{currency_key, write_persistent},
+    types::Currency,
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, token, Address, Env, String};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Symbol and decimals of the escrow's token as they were when the escrow was created, if
+    /// the token reported them.
+    pub fn get_escrow_currency(env: Env, escrow_id: u64) -> Option<Currency> {
+        env.storage().persistent().get(&currency_key(escrow_id))
+    }
+}
+
+/// The escrow's currency snapshot, or an empty symbol with no decimals if it has none.
+pub(crate) fn or_unknown(env: &Env, escrow_id: u64) -> Currency {
+    VaultixEscrow::get_escrow_currency(env.clone(), escrow_id).unwrap_or(Currency {
+        symbol: String::from_str(env, ""),
+        decimals: 0,
+    })
+}
+
+/// Captures the token's current symbol and decimals for a new escrow.
+pub(crate) fn snapshot(env: &Env, escrow_id: u64, token_address: &Address) {
+    let token = token::Client::new(env, token_address);
+    if let (Ok(Ok(symbol)), Ok(Ok(decimals))) = (token.try_symbol(), token.try_decimals()) {
+        let key = currency_key(escrow_id);
+        write_persistent(env, &key, &Currency { symbol, decimals });
+    }
+}
@@ -0,0 +1,230 @@
+//! Token-holder arbitration.
+//!
+//! Instead of an arbitrator, a depositor can have the escrow's disputes decided by the holders
+//! of a governance token. Raising a dispute on such an escrow opens a vote whose weights are read
+//! from the configured voting contract at the ledger before the dispute, so tokens acquired
+//! afterwards carry no weight. Holders back either party until the voting period ends; the side
+//! with more weight wins, provided the votes cast reach the quorum. A side backed by more than
+//! half the snapshot supply can no longer lose, so its ruling executes as soon as that vote is
+//! cast. Votes that miss the quorum or tie hand the dispute to the arbitrator pool instead.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin_log, arbitration,
+    dispute::{apply_ruling, ensure_party},
+    errors::{fail, Error, ExtendedError},
+    escrow::{calculate_fee, load_live_escrow},
+    history,
+    rbac::require_role,
+    storage::{
+        ballot_key, dao_config_key, dao_mode_key, dao_vote_key, extend_instance, ttl_policy,
+        write_persistent,
+    },
+    types::{DaoConfig, DaoVote, EscrowStatus, Role, RulingReason, VotesClient},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Configures token-holder arbitration (Admin role). Votes already open keep the voting
+    /// contract and quorum they were opened with.
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller lacks the Admin role
+    /// * `InvalidConfiguration` - If the quorum exceeds 100% or the period is zero
+    pub fn set_dao_config(env: Env, caller: Address, config: DaoConfig) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "set_dao_config", &caller, None);
+
+        if config.quorum_bps > 10_000 || config.period == 0 {
+            return Err(Error::InvalidConfiguration);
+        }
+        env.storage().instance().set(&dao_config_key(), &config);
+        extend_instance(&env);
+        Ok(())
+    }
+
+    /// Returns the token-holder arbitration settings, if configured.
+    pub fn get_dao_config(env: Env) -> Option<DaoConfig> {
+        env.storage().instance().get(&dao_config_key())
+    }
+
+    /// Sends the escrow's disputes to a token-holder vote instead of an arbitrator. Depositor
+    /// only, before funding.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If the escrow doesn't exist
+    /// * `EscrowAlreadyFunded` - If the escrow has been funded
+    /// * `InvalidConfiguration` - If token-holder arbitration is not configured, or the escrow
+    ///   already uses panel arbitration
+    pub fn use_dao_arbitration(env: Env, escrow_id: u64) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if !matches!(
+            escrow.status,
+            EscrowStatus::Draft | EscrowStatus::Proposed | EscrowStatus::Created
+        ) {
+            return Err(Error::EscrowAlreadyFunded);
+        }
+        if Self::get_dao_config(env.clone()).is_none()
+            || Self::uses_panel_arbitration(env.clone(), escrow_id)
+        {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        let key = dao_mode_key(escrow_id);
+        write_persistent(&env, &key, &true);
+        Ok(())
+    }
+
+    /// Whether the escrow's disputes are decided by token holders.
+    pub fn uses_dao_arbitration(env: Env, escrow_id: u64) -> bool {
+        env.storage().persistent().has(&dao_mode_key(escrow_id))
+    }
+
+    /// The open token-holder vote on the escrow's dispute, if any.
+    pub fn get_dao_vote(env: Env, escrow_id: u64) -> Option<DaoVote> {
+        env.storage().persistent().get(&dao_vote_key(escrow_id))
+    }
+
+    /// Casts `voter`'s snapshot weight for the depositor or, when `for_recipient` is set, the
+    /// recipient. Executes the ruling straight away once one side holds a majority of the
+    /// snapshot supply.
+    ///
+    /// # Returns
+    /// The voter's weight
+    ///
+    /// # Errors
+    /// * `InvalidEscrowStatus` - If no vote is open on the escrow or voting has ended
+    /// * `UnauthorizedAccess` - If voter is a party to the escrow, had no weight at the
+    ///   snapshot, or has already voted
+    pub fn cast_vote(
+        env: Env,
+        escrow_id: u64,
+        voter: Address,
+        for_recipient: bool,
+    ) -> Result<i128, Error> {
+        voter.require_auth();
+        let mut vote = Self::get_dao_vote(env.clone(), escrow_id)
+            .filter(|vote| env.ledger().timestamp() < vote.ends_at)
+            .ok_or(Error::InvalidEscrowStatus)?;
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        if ensure_party(&escrow, &voter).is_ok() {
+            return Err(Error::UnauthorizedAccess);
+        }
+
+        let ballot = ballot_key(escrow_id, &voter);
+        if env.storage().temporary().get::<_, u32>(&ballot) == Some(vote.snapshot) {
+            return Err(Error::UnauthorizedAccess);
+        }
+        let weight = VotesClient::new(&env, &vote.votes).get_past_votes(&voter, &vote.snapshot);
+        if weight <= 0 {
+            return Err(Error::UnauthorizedAccess);
+        }
+        env.storage().temporary().set(&ballot, &vote.snapshot);
+        // A ballot lives as long as the vote entry it was cast on
+        let extend_to = ttl_policy(&env).extend_to;
+        env.storage()
+            .temporary()
+            .extend_ttl(&ballot, extend_to, extend_to);
+
+        let tally = if for_recipient {
+            &mut vote.for_recipient
+        } else {
+            &mut vote.for_depositor
+        };
+        *tally = tally
+            .checked_add(weight)
+            .ok_or(Error::InvalidMilestoneAmount)?;
+        let decided = *tally > vote.supply / 2;
+        history::record(&env, escrow_id, symbol_short!("voted"), &voter, weight);
+
+        if decided {
+            close_vote(&env, escrow_id, &vote)?;
+        } else {
+            let key = dao_vote_key(escrow_id);
+            write_persistent(&env, &key, &vote);
+        }
+        Ok(weight)
+    }
+
+    /// Closes a vote whose period has ended: the ruling executes for the side with more
+    /// weight if the quorum was reached, otherwise the dispute goes to the arbitrator pool.
+    /// Anyone may call this.
+    ///
+    /// # Returns
+    /// The winning party, or `None` if the vote was undecided
+    ///
+    /// # Errors
+    /// * `InvalidEscrowStatus` - If no vote is open on the escrow
+    /// * `RulingNotReady` - If voting is still open
+    pub fn finalize_vote(env: Env, escrow_id: u64) -> Result<Option<Address>, Error> {
+        let vote = Self::get_dao_vote(env.clone(), escrow_id).ok_or(Error::InvalidEscrowStatus)?;
+        if env.ledger().timestamp() < vote.ends_at {
+            return Err(Error::RulingNotReady);
+        }
+        close_vote(&env, escrow_id, &vote)
+    }
+}
+
+/// Opens a token-holder vote on a newly disputed escrow that uses token-holder arbitration,
+/// returning whether it does.
+pub(crate) fn open_vote(env: &Env, escrow_id: u64) -> Result<bool, Error> {
+    if !VaultixEscrow::uses_dao_arbitration(env.clone(), escrow_id) {
+        return Ok(false);
+    }
+    let config = VaultixEscrow::get_dao_config(env.clone()).ok_or(Error::InvalidConfiguration)?;
+
+    let snapshot = env.ledger().sequence().saturating_sub(1);
+    let supply = VotesClient::new(env, &config.votes).get_past_total_supply(&snapshot);
+    let vote = DaoVote {
+        votes: config.votes,
+        snapshot,
+        ends_at: env.ledger().timestamp().saturating_add(config.period),
+        supply,
+        quorum: calculate_fee(supply, config.quorum_bps as i128)
+            .unwrap_or_else(|| fail(env, ExtendedError::FeeOverflow)),
+        for_depositor: 0,
+        for_recipient: 0,
+    };
+    let key = dao_vote_key(escrow_id);
+    write_persistent(env, &key, &vote);
+    Ok(true)
+}
+
+/// Ends the vote, executing the ruling it decided or handing the dispute to the arbitrator pool.
+fn close_vote(env: &Env, escrow_id: u64, vote: &DaoVote) -> Result<Option<Address>, Error> {
+    env.storage().persistent().remove(&dao_vote_key(escrow_id));
+    let escrow = load_live_escrow(env, escrow_id)?;
+
+    let cast = vote.for_depositor + vote.for_recipient;
+    if cast < vote.quorum || vote.for_depositor == vote.for_recipient {
+        history::record(
+            env,
+            escrow_id,
+            symbol_short!("undecided"),
+            &vote.votes,
+            cast,
+        );
+        arbitration::assign_or_enqueue(env, escrow_id, &escrow);
+        return Ok(None);
+    }
+
+    let winner = if vote.for_recipient > vote.for_depositor {
+        escrow.recipient
+    } else {
+        escrow.depositor
+    };
+    apply_ruling(
+        env.clone(),
+        escrow_id,
+        vote.votes.clone(),
+        winner.clone(),
+        RulingReason::Other,
+        None,
+    )?;
+    Ok(Some(winner))
+}
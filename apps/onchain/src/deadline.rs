@@ -0,0 +1,80 @@
+//! Deadline extensions.
+//!
+//! Timelines slip. Rather than cancel and recreate an escrow, the parties can agree a later
+//! deadline: one proposes it with `extend_deadline` and the change takes effect once the other
+//! party calls `extend_deadline` with the same date. A proposal stays open until it is accepted
+//! or replaced by a new one from either side; only extensions can be agreed this way.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    dispute::ensure_party,
+    errors::Error,
+    escrow::{load_live_escrow, save_escrow},
+    history, state_machine,
+    storage::{deadline_proposal_key, write_persistent},
+    types::DeadlineProposal,
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Proposes moving the escrow's deadline to `new_deadline`, or accepts the other party's
+    /// proposal of that same deadline. Depositor or recipient only.
+    ///
+    /// # Returns
+    /// Whether the new deadline took effect
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `UnauthorizedAccess` - If caller is not a party to the escrow
+    /// * `InvalidEscrowStatus` - If the escrow has finished
+    /// * `InvalidDeadline` - If the new deadline is not later than both the current one and now
+    pub fn extend_deadline(
+        env: Env,
+        escrow_id: u64,
+        caller: Address,
+        new_deadline: u64,
+    ) -> Result<bool, Error> {
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        ensure_party(&escrow, &caller)?;
+        caller.require_auth();
+
+        if state_machine::is_terminal(escrow.status) {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        if new_deadline <= escrow.deadline || new_deadline <= env.ledger().timestamp() {
+            return Err(Error::InvalidDeadline);
+        }
+
+        let key = deadline_proposal_key(escrow_id);
+        let agreed = Self::get_deadline_proposal(env.clone(), escrow_id).is_some_and(|proposal| {
+            proposal.proposer != caller && proposal.deadline == new_deadline
+        });
+        if !agreed {
+            write_persistent(
+                &env,
+                &key,
+                &DeadlineProposal {
+                    proposer: caller,
+                    deadline: new_deadline,
+                },
+            );
+            return Ok(false);
+        }
+
+        escrow.deadline = new_deadline;
+        save_escrow(&env, escrow_id, &escrow);
+        env.storage().persistent().remove(&key);
+        history::record(&env, escrow_id, symbol_short!("extended"), &caller, 0);
+        Ok(true)
+    }
+
+    /// The open deadline proposal on the escrow, if any.
+    pub fn get_deadline_proposal(env: Env, escrow_id: u64) -> Option<DeadlineProposal> {
+        env.storage()
+            .persistent()
+            .get(&deadline_proposal_key(escrow_id))
+    }
+}
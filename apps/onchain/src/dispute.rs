@@ -0,0 +1,1155 @@
+//! Disputes, their resolution and the insurance pool backing them.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin::ensure_not_paused,
+    admin_log, agreement, arbitration, dao,
+    errors::{fail, Error, ExtendedError},
+    escrow::{
+        burn_receipt, finish_funded, load_live_escrow, payout_address, save_escrow,
+        unreleased_amount, update_reputation,
+    },
+    funding::{ensure_funded, funding, held_amount},
+    history, hooks, nft,
+    org::acts_for_org,
+    panel,
+    rbac::require_role,
+    recovery::depositor_authority,
+    state_machine,
+    statement::tally,
+    stats,
+    storage::{
+        all_milestones_mask, arbiter_storage_key, claim_storage_key, claimed_key, created_at_key,
+        dao_vote_key, dispute_bond_key, dispute_cooldown_key, dispute_grace_key,
+        dispute_ledger_key, dispute_origin_key, dispute_raiser_key, dispute_reason_key,
+        evidence_commit_key, evidence_deadline_key, evidence_key, extend_persistent, get_admin,
+        get_storage_key, insurance_pool_key, load_config, load_milestone, milestone_bit,
+        panel_vote_key, pending_ruling_key, ruling_key, ruling_rationale_key, settlement_key,
+        write_persistent, DataKey,
+    },
+    swap,
+    timelock::queue_ruling,
+    types::{
+        ClaimStatus, DisputeFeePolicy, DisputeReason, Escrow, EscrowStatus, Evidence,
+        InsuranceClaim, QueuedRuling, Resolution, Role, Ruling, RulingReason,
+    },
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, token, xdr::ToXdr, Address, BytesN, Env, Vec};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Raises a dispute on an active escrow. Either party (depositor or recipient) may invoke this.
+    /// The reason is kept alongside the escrow and published with the `disputed` event.
+    /// Fails with `DisputeTooEarly` until the escrow's grace period has elapsed, and with
+    /// `DisputeCooldown` while the caller is cooling down from a dispute the escrow resumed from.
+    pub fn raise_dispute(
+        env: Env,
+        escrow_id: u64,
+        caller: Address,
+        reason: DisputeReason,
+    ) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+
+        caller.require_auth();
+        let party = acting_party(&env, escrow_id, &escrow, &caller);
+        ensure_party(&escrow, &party)?;
+        ensure_grace_elapsed(&env, escrow_id)?;
+        ensure_cooldown_elapsed(&env, escrow_id, &party)?;
+
+        let disputed_mask = all_milestones_mask(escrow.milestone_count) & !escrow.released_mask;
+        open_dispute(&env, escrow_id, &mut escrow, disputed_mask, reason)?;
+        let raiser_key = dispute_raiser_key(escrow_id);
+        write_persistent(&env, &raiser_key, &party);
+
+        // A backup raising for the party pays the bond and is refunded it, but the ruling is
+        // weighed against the party
+        let bond = agreement::dispute_bond(&env, escrow_id);
+        if bond > 0 {
+            let bond_key = dispute_bond_key(escrow_id);
+            write_persistent(&env, &bond_key, &(caller.clone(), bond));
+            token::Client::new(&env, &escrow.token_address).transfer(
+                &caller,
+                &env.current_contract_address(),
+                &bond,
+            );
+        }
+
+        history::record(&env, escrow_id, symbol_short!("disputed"), &caller, bond);
+
+        update_reputation(&env, &caller, |rep| {
+            rep.disputes_raised += 1;
+            Ok(())
+        })?;
+
+        env.events()
+            .publish((symbol_short!("disputed"), escrow_id), (caller, reason));
+        Ok(())
+    }
+
+    /// Records a party's evidence for the open dispute, replacing anything it submitted before.
+    /// Accepted until the evidence window set when the dispute was raised closes, unless either
+    /// party has sealed its evidence with `commit_evidence`.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow is not disputed or evidence has been sealed
+    /// * `UnauthorizedAccess` - If caller is neither depositor nor recipient
+    /// * `EvidenceWindowClosed` - If the evidence cutoff has passed
+    pub fn submit_evidence(
+        env: Env,
+        escrow_id: u64,
+        caller: Address,
+        hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        ensure_party(&escrow, &caller)?;
+        caller.require_auth();
+
+        if escrow.status != EscrowStatus::Disputed || evidence_sealed(&env, escrow_id, &escrow) {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        ensure_evidence_open(&env, escrow_id)?;
+
+        record_evidence(&env, escrow_id, caller, hash);
+        Ok(())
+    }
+
+    /// The commitment a party submits to seal evidence `hash` behind a secret `salt`.
+    pub fn evidence_commitment(
+        env: Env,
+        escrow_id: u64,
+        party: Address,
+        hash: BytesN<32>,
+        salt: BytesN<32>,
+    ) -> BytesN<32> {
+        let preimage = (escrow_id, party, hash, salt).to_xdr(&env);
+        env.crypto().sha256(&preimage)
+    }
+
+    /// Seals a party's evidence for the open dispute as a commitment, to be revealed with
+    /// `reveal_evidence` once the other party has committed too, so neither side can tailor its
+    /// evidence to the other's. Once either party commits, plain `submit_evidence` is closed to
+    /// both. Each party commits once per dispute, before the evidence window closes.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow is not disputed
+    /// * `UnauthorizedAccess` - If caller is not a party or has already committed
+    /// * `EvidenceWindowClosed` - If the evidence cutoff has passed
+    pub fn commit_evidence(
+        env: Env,
+        escrow_id: u64,
+        caller: Address,
+        commitment: BytesN<32>,
+    ) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        ensure_party(&escrow, &caller)?;
+        caller.require_auth();
+
+        if escrow.status != EscrowStatus::Disputed {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        ensure_evidence_open(&env, escrow_id)?;
+        let key = evidence_commit_key(escrow_id, &caller);
+        if env.storage().persistent().has(&key) {
+            return Err(Error::UnauthorizedAccess);
+        }
+
+        write_persistent(&env, &key, &commitment);
+        env.events().publish(
+            (symbol_short!("ev_commit"), escrow_id),
+            (caller, commitment),
+        );
+        Ok(())
+    }
+
+    /// Reveals the evidence behind a party's commitment, recording it as with `submit_evidence`.
+    /// Possible once both parties have committed, or once the evidence window has closed.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow is not disputed
+    /// * `UnauthorizedAccess` - If caller is not a party or the reveal doesn't match its
+    ///   commitment
+    /// * `EvidenceWindowOpen` - If the other party can still commit
+    pub fn reveal_evidence(
+        env: Env,
+        escrow_id: u64,
+        caller: Address,
+        hash: BytesN<32>,
+        salt: BytesN<32>,
+    ) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        ensure_party(&escrow, &caller)?;
+        caller.require_auth();
+
+        if escrow.status != EscrowStatus::Disputed {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        let commitment =
+            Self::evidence_commitment(env.clone(), escrow_id, caller.clone(), hash.clone(), salt);
+        if Self::get_evidence_commitment(env.clone(), escrow_id, caller.clone()) != Some(commitment)
+        {
+            return Err(Error::UnauthorizedAccess);
+        }
+        let both_committed = [&escrow.depositor, &escrow.recipient].iter().all(|party| {
+            env.storage()
+                .persistent()
+                .has(&evidence_commit_key(escrow_id, party))
+        });
+        if !both_committed && ensure_evidence_open(&env, escrow_id).is_ok() {
+            return Err(Error::EvidenceWindowOpen);
+        }
+
+        record_evidence(&env, escrow_id, caller, hash);
+        Ok(())
+    }
+
+    /// Evidence commitment `party` has sealed in the escrow's dispute, if any.
+    pub fn get_evidence_commitment(env: Env, escrow_id: u64, party: Address) -> Option<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&evidence_commit_key(escrow_id, &party))
+    }
+
+    /// Latest evidence submitted by `party` in the escrow's dispute, if any.
+    pub fn get_evidence(env: Env, escrow_id: u64, party: Address) -> Option<Evidence> {
+        env.storage()
+            .persistent()
+            .get(&evidence_key(escrow_id, &party))
+    }
+
+    /// When the evidence window of the escrow's dispute closes, if one applies.
+    pub fn get_evidence_deadline(env: Env, escrow_id: u64) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&evidence_deadline_key(escrow_id))
+    }
+
+    /// Reason given for the escrow's current or most recent dispute, if any.
+    pub fn get_dispute_reason(env: Env, escrow_id: u64) -> Option<DisputeReason> {
+        env.storage()
+            .persistent()
+            .get(&dispute_reason_key(escrow_id))
+    }
+
+    /// Sets how long after creation the parties must wait before raising a dispute. Only the
+    /// depositor may change it, and only before the escrow is funded.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `EscrowAlreadyFunded` - If the escrow has left the Created state
+    pub fn set_dispute_grace(env: Env, escrow_id: u64, grace: u64) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if escrow.status != EscrowStatus::Created {
+            return Err(Error::EscrowAlreadyFunded);
+        }
+
+        let key = dispute_grace_key(escrow_id);
+        write_persistent(&env, &key, &grace);
+        Ok(())
+    }
+
+    /// Earliest timestamp at which a party may raise a dispute on the escrow. Escrows created
+    /// before grace periods existed return 0.
+    pub fn get_dispute_opens_at(env: Env, escrow_id: u64) -> u64 {
+        let created_at: Option<u64> = env.storage().persistent().get(&created_at_key(escrow_id));
+        let grace: u64 = env
+            .storage()
+            .persistent()
+            .get(&dispute_grace_key(escrow_id))
+            .unwrap_or(0);
+        created_at.map_or(0, |created_at| created_at.saturating_add(grace))
+    }
+
+    /// Evaluates the guards of `raise_dispute` for `caller` without changing state.
+    /// Returns `None` if the dispute could be raised, otherwise the code of the `Error`
+    /// raising it would fail with.
+    pub fn can_dispute(env: Env, escrow_id: u64, caller: Address) -> Option<u32> {
+        let check = || -> Result<(), Error> {
+            ensure_not_paused(&env)?;
+            let mut escrow = load_live_escrow(&env, escrow_id)?;
+            let party = acting_party(&env, escrow_id, &escrow, &caller);
+            ensure_party(&escrow, &party)?;
+            ensure_grace_elapsed(&env, escrow_id)?;
+            ensure_cooldown_elapsed(&env, escrow_id, &party)?;
+            state_machine::transition(&mut escrow, EscrowStatus::Disputed)
+        };
+        check().err().map(|error| error as u32)
+    }
+
+    /// Resolves an active dispute by directing funds to the chosen party.
+    /// Only the escrow's arbiter (dedicated, or the arbitrator who claimed it from the dispute
+    /// queue), or the admin when there is none, may call this. The ruling is recorded with its
+    /// `reason` and, when given, `rationale`, the hash of the written reasoning, so it can be
+    /// audited or appealed later. Admin rulings on escrows above the ruling timelock's
+    /// threshold are queued instead; see `set_ruling_timelock`.
+    pub fn resolve_dispute(
+        env: Env,
+        escrow_id: u64,
+        winner: Address,
+        reason: RulingReason,
+        rationale: Option<BytesN<32>>,
+    ) -> Result<(), Error> {
+        let resolver = get_resolver(&env, escrow_id)?;
+        resolver.require_auth();
+        ensure_evidence_closed(&env, escrow_id)?;
+        admin_log::record(&env, "resolve_dispute", &resolver, Some(escrow_id));
+
+        let ruling = QueuedRuling::Full(winner.clone(), reason);
+        if queue_ruling(&env, escrow_id, &resolver, ruling, rationale.clone())? {
+            return Ok(());
+        }
+        apply_ruling(env, escrow_id, resolver, winner, reason, rationale)
+    }
+
+    /// The ruling that resolved the escrow's dispute, if an arbitrator resolved one.
+    pub fn get_ruling(env: Env, escrow_id: u64) -> Option<Ruling> {
+        env.storage().persistent().get(&ruling_key(escrow_id))
+    }
+
+    /// Hash of the written reasoning published with the escrow's ruling, if any.
+    pub fn get_ruling_rationale(env: Env, escrow_id: u64) -> Option<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&ruling_rationale_key(escrow_id))
+    }
+
+    /// Resolves a dispute over part of the work and lets the engagement continue: milestones in
+    /// `awarded` are paid to the recipient and the escrow returns to Active with every other
+    /// milestone pending again. The dispute bond goes back to whoever posted it, less the
+    /// arbitrator's fee. Same authorization and ruling timelock as
+    /// `resolve_dispute`.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If escrow is not disputed, or was disputed before funding
+    /// * `MilestoneNotFound` - If an awarded index is out of bounds
+    /// * `MilestoneAlreadyReleased` - If an awarded milestone was already paid
+    /// * `InsufficientBalance` - If an awarded milestone was never funded
+    /// * `EvidenceWindowOpen` - If the parties can still submit evidence
+    pub fn resolve_dispute_partial(
+        env: Env,
+        escrow_id: u64,
+        awarded: Vec<u32>,
+    ) -> Result<(), Error> {
+        let resolver = get_resolver(&env, escrow_id)?;
+        resolver.require_auth();
+        ensure_evidence_closed(&env, escrow_id)?;
+        admin_log::record(&env, "resolve_dispute_partial", &resolver, Some(escrow_id));
+
+        let ruling = QueuedRuling::Partial(awarded.clone());
+        if queue_ruling(&env, escrow_id, &resolver, ruling, None)? {
+            return Ok(());
+        }
+        apply_partial_ruling(env, escrow_id, resolver, awarded)
+    }
+
+    /// Proposes settling a dispute by paying `recipient_amount` of the outstanding funds to the
+    /// recipient and refunding the rest. Either party may propose; a new proposal replaces the
+    /// previous one.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `UnauthorizedAccess` - If proposer is not a party to the escrow
+    /// * `InvalidEscrowStatus` - If escrow is not disputed
+    /// * `AmountOutOfRange` - If recipient_amount is negative or exceeds the outstanding funds
+    pub fn propose_settlement(
+        env: Env,
+        escrow_id: u64,
+        proposer: Address,
+        recipient_amount: i128,
+    ) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        ensure_party(&escrow, &proposer)?;
+        proposer.require_auth();
+
+        if escrow.status != EscrowStatus::Disputed {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        let outstanding = held_amount(&env, escrow_id, &escrow)?;
+        if recipient_amount < 0 || recipient_amount > outstanding {
+            return Err(Error::AmountOutOfRange);
+        }
+
+        let key = settlement_key(escrow_id);
+        write_persistent(&env, &key, &(proposer.clone(), recipient_amount));
+
+        env.events().publish(
+            (symbol_short!("settle_pr"), escrow_id),
+            (proposer, recipient_amount),
+        );
+        Ok(())
+    }
+
+    /// Returns the pending settlement proposal of a dispute as `(proposer, recipient_amount)`.
+    pub fn get_settlement(env: Env, escrow_id: u64) -> Option<(Address, i128)> {
+        env.storage().persistent().get(&settlement_key(escrow_id))
+    }
+
+    /// Accepts the other party's settlement proposal and executes it immediately, without
+    /// involving an arbitrator. `expected_recipient_amount` must match the pending proposal, so a
+    /// proposal replaced after the accepter read it is not accepted by mistake. Each side's dispute bond, if any, is returned to it. A held NFT
+    /// deliverable or swap leg goes to the depositor if the recipient is paid anything, else
+    /// back to the recipient.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `NoSettlementProposed` - If there is no proposal to accept
+    /// * `UnauthorizedAccess` - If accepter is not the counterparty of the proposer
+    /// * `InvalidEscrowStatus` - If escrow is not disputed
+    /// * `SettlementMismatch` - If the pending proposal is not `expected_recipient_amount`
+    pub fn accept_settlement(
+        env: Env,
+        escrow_id: u64,
+        accepter: Address,
+        expected_recipient_amount: i128,
+    ) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        let (proposer, recipient_amount) =
+            Self::get_settlement(env.clone(), escrow_id).ok_or(Error::NoSettlementProposed)?;
+
+        ensure_party(&escrow, &accepter)?;
+        if accepter == proposer {
+            return Err(Error::UnauthorizedAccess);
+        }
+        accepter.require_auth();
+        if recipient_amount != expected_recipient_amount {
+            fail(&env, ExtendedError::SettlementMismatch);
+        }
+
+        state_machine::transition(&mut escrow, EscrowStatus::Resolved)?;
+
+        let outstanding = held_amount(&env, escrow_id, &escrow)?;
+        let refund = outstanding
+            .checked_sub(recipient_amount)
+            .unwrap_or_else(|| fail(&env, ExtendedError::SplitMismatch));
+
+        escrow.total_released = escrow
+            .total_released
+            .checked_add(recipient_amount)
+            .ok_or(Error::InvalidMilestoneAmount)?;
+        escrow.resolution = Resolution::Settled;
+
+        let bond_key = dispute_bond_key(escrow_id);
+        let bond = env
+            .storage()
+            .persistent()
+            .get::<_, (Address, i128)>(&bond_key);
+        env.storage().persistent().remove(&bond_key);
+        env.storage()
+            .persistent()
+            .remove(&settlement_key(escrow_id));
+        env.storage()
+            .persistent()
+            .remove(&pending_ruling_key(escrow_id));
+        env.storage().persistent().remove(&dao_vote_key(escrow_id));
+        panel::discard(&env, escrow_id);
+        save_escrow(&env, escrow_id, &escrow);
+
+        let token_client = token::Client::new(&env, &escrow.token_address);
+        if recipient_amount > 0 {
+            token_client.transfer(
+                &env.current_contract_address(),
+                &payout_address(&env, escrow_id, &escrow),
+                &recipient_amount,
+            );
+        }
+        if refund > 0 {
+            token_client.transfer(&env.current_contract_address(), &escrow.depositor, &refund);
+        }
+        if let Some((raiser, bond)) = bond {
+            token_client.transfer(&env.current_contract_address(), &raiser, &bond);
+        }
+        nft::settle_ruling(&env, escrow_id, &escrow, recipient_amount > 0)?;
+        swap::settle_ruling(&env, escrow_id, &escrow, recipient_amount > 0)?;
+
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("settled"),
+            &accepter,
+            recipient_amount,
+        );
+        stats::funds_released(&env, &escrow.token_address, recipient_amount);
+        stats::funds_refunded(&env, &escrow.token_address, refund);
+        stats::dispute_closed(&env, escrow_id);
+        arbitration::dequeue(&env, escrow_id);
+        arbitration::release_assignment(&env, escrow_id);
+        hooks::on_resolve(&env, escrow_id, escrow.resolution);
+        finish_funded(&env, escrow_id, &escrow)?;
+
+        burn_receipt(&env, escrow_id);
+
+        for party in [&escrow.depositor, &escrow.recipient] {
+            update_reputation(&env, party, |rep| {
+                rep.volume = rep
+                    .volume
+                    .checked_add(escrow.total_released)
+                    .ok_or(Error::InvalidMilestoneAmount)?;
+                Ok(())
+            })?;
+        }
+
+        env.events().publish(
+            (symbol_short!("settled"), escrow_id),
+            (recipient_amount, refund),
+        );
+        Ok(())
+    }
+
+    /// Returns the dedicated arbiter of an escrow, if one was assigned.
+    pub fn get_arbiter(env: Env, escrow_id: u64) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&arbiter_storage_key(escrow_id))
+    }
+
+    /// Returns the insurance pool balance held for a given token.
+    pub fn get_insurance_pool(env: Env, token_address: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&insurance_pool_key(&token_address))
+            .unwrap_or(0)
+    }
+
+    /// Files an insurance claim against a resolved escrow. Only the party the ruling favoured may
+    /// file, and its pending and approved claims together may not exceed what the ruling awarded
+    /// it: the refund for the depositor, the released total for the recipient.
+    ///
+    /// # Arguments
+    /// * `claimant` - Party requesting compensation
+    /// * `escrow_id` - Escrow whose dispute outcome could not be fully enforced
+    /// * `amount` - Requested payout
+    ///
+    /// # Returns
+    /// The identifier of the new claim
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `UnauthorizedAccess` - If claimant is not the party the ruling favoured
+    /// * `InvalidEscrowStatus` - If the escrow's dispute was not resolved by a ruling
+    /// * `ZeroAmount` - If amount is zero or negative
+    /// * `InvalidMilestoneAmount` - If the escrow's claims would exceed the awarded amount
+    pub fn file_claim(
+        env: Env,
+        claimant: Address,
+        escrow_id: u64,
+        amount: i128,
+    ) -> Result<u64, Error> {
+        claimant.require_auth();
+
+        ensure_not_paused(&env)?;
+
+        let escrow = Self::get_escrow(env.clone(), escrow_id)?;
+
+        if escrow.status != EscrowStatus::Resolved {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        let awarded = match escrow.resolution {
+            Resolution::Depositor if claimant == escrow.depositor => {
+                escrow.total_amount.saturating_sub(escrow.total_released)
+            }
+            Resolution::Recipient if claimant == escrow.recipient => escrow.total_released,
+            Resolution::Depositor | Resolution::Recipient => return Err(Error::UnauthorizedAccess),
+            Resolution::None | Resolution::Settled => return Err(Error::InvalidEscrowStatus),
+        };
+
+        if amount <= 0 {
+            return Err(Error::ZeroAmount);
+        }
+        let claimed_key = claimed_key(escrow_id);
+        let claimed: i128 = env.storage().persistent().get(&claimed_key).unwrap_or(0);
+        let claimed = claimed
+            .checked_add(amount)
+            .filter(|claimed| *claimed <= awarded)
+            .ok_or(Error::InvalidMilestoneAmount)?;
+        write_persistent(&env, &claimed_key, &claimed);
+
+        let claim_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::claim_ctr)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::claim_ctr, &(claim_id + 1));
+
+        let claim = InsuranceClaim {
+            claimant: claimant.clone(),
+            escrow_id,
+            token_address: escrow.token_address,
+            amount,
+            status: ClaimStatus::Pending,
+        };
+
+        let storage_key = claim_storage_key(claim_id);
+        write_persistent(&env, &storage_key, &claim);
+
+        env.events().publish(
+            (symbol_short!("ins_claim"), claim_id, escrow_id),
+            (claimant, amount),
+        );
+
+        Ok(claim_id)
+    }
+
+    /// Approves a pending insurance claim and pays it out of the pool (Admin role).
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller lacks the Admin role
+    /// * `ClaimNotFound` - If the claim doesn't exist
+    /// * `ClaimAlreadyProcessed` - If the claim was already approved or rejected
+    /// * `InsufficientBalance` - If the pool cannot cover the claim
+    pub fn approve_claim(env: Env, caller: Address, claim_id: u64) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "approve_claim", &caller, None);
+
+        let storage_key = claim_storage_key(claim_id);
+        let mut claim: InsuranceClaim = env
+            .storage()
+            .persistent()
+            .get(&storage_key)
+            .ok_or(Error::ClaimNotFound)?;
+
+        if claim.status != ClaimStatus::Pending {
+            return Err(Error::ClaimAlreadyProcessed);
+        }
+
+        let pool_key = insurance_pool_key(&claim.token_address);
+        let pool: i128 = env.storage().persistent().get(&pool_key).unwrap_or(0);
+        if pool < claim.amount {
+            return Err(Error::InsufficientBalance);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&pool_key, &(pool - claim.amount));
+
+        claim.status = ClaimStatus::Approved;
+        write_persistent(&env, &storage_key, &claim);
+
+        let token_client = token::Client::new(&env, &claim.token_address);
+        token_client.transfer(
+            &env.current_contract_address(),
+            &claim.claimant,
+            &claim.amount,
+        );
+
+        env.events().publish(
+            (symbol_short!("ins_paid"), claim_id, claim.escrow_id),
+            (claim.claimant, claim.amount),
+        );
+
+        Ok(())
+    }
+
+    /// Rejects a pending insurance claim (Admin role).
+    pub fn reject_claim(env: Env, caller: Address, claim_id: u64) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "reject_claim", &caller, None);
+
+        let storage_key = claim_storage_key(claim_id);
+        let mut claim: InsuranceClaim = env
+            .storage()
+            .persistent()
+            .get(&storage_key)
+            .ok_or(Error::ClaimNotFound)?;
+
+        if claim.status != ClaimStatus::Pending {
+            return Err(Error::ClaimAlreadyProcessed);
+        }
+
+        claim.status = ClaimStatus::Rejected;
+        write_persistent(&env, &storage_key, &claim);
+
+        // A rejected claim no longer counts against the escrow's cap
+        let claimed_key = claimed_key(claim.escrow_id);
+        if let Some(claimed) = env.storage().persistent().get::<_, i128>(&claimed_key) {
+            write_persistent(&env, &claimed_key, &claimed.saturating_sub(claim.amount));
+        }
+
+        Ok(())
+    }
+
+    /// Retrieves an insurance claim (read-only)
+    pub fn get_claim(env: Env, claim_id: u64) -> Result<InsuranceClaim, Error> {
+        env.storage()
+            .persistent()
+            .get(&claim_storage_key(claim_id))
+            .ok_or(Error::ClaimNotFound)
+    }
+}
+
+/// Address allowed to resolve disputes on an escrow: its dedicated arbiter, else the admin.
+/// Nobody may while token holders or the arbitrator panel are voting on the dispute.
+pub(crate) fn get_resolver(env: &Env, escrow_id: u64) -> Result<Address, Error> {
+    if env.storage().persistent().has(&dao_vote_key(escrow_id))
+        || env.storage().persistent().has(&panel_vote_key(escrow_id))
+    {
+        return Err(Error::UnauthorizedAccess);
+    }
+    match env
+        .storage()
+        .persistent()
+        .get(&arbiter_storage_key(escrow_id))
+    {
+        Some(arbiter) => Ok(arbiter),
+        None => get_admin(env),
+    }
+}
+
+/// Carries out a full ruling for `winner`: pays out or refunds the outstanding funds, settles
+/// the dispute bond and records the ruling.
+pub(crate) fn apply_ruling(
+    env: Env,
+    escrow_id: u64,
+    resolver: Address,
+    winner: Address,
+    reason: RulingReason,
+    rationale: Option<BytesN<32>>,
+) -> Result<(), Error> {
+    let mut escrow = load_live_escrow(&env, escrow_id)?;
+
+    state_machine::transition(&mut escrow, EscrowStatus::Resolved)?;
+
+    if winner != escrow.depositor && winner != escrow.recipient {
+        return Err(Error::InvalidWinner);
+    }
+
+    let outstanding = held_amount(&env, escrow_id, &escrow)?;
+
+    let payee = if winner == escrow.recipient {
+        // Only funded milestones can be awarded; the rest were never deposited
+        let (funded, funded_amount) = funding(&env, escrow_id, &escrow);
+        escrow.released_mask |= all_milestones_mask(funded);
+        escrow.disputed_mask = 0;
+        escrow.total_released = funded_amount;
+        escrow.resolution = Resolution::Recipient;
+        payout_address(&env, escrow_id, &escrow)
+    } else {
+        escrow.disputed_mask = all_milestones_mask(escrow.milestone_count) & !escrow.released_mask;
+        escrow.resolution = Resolution::Depositor;
+        escrow.depositor.clone()
+    };
+
+    // The dispute bond goes to the winner: returned to whoever paid it if the winner raised the
+    // dispute, forfeited otherwise. The resolving arbitrator's fee comes out of it first, unless
+    // the loser is to pay it.
+    let bond_key = dispute_bond_key(escrow_id);
+    let mut bond_payout = None;
+    let mut fee_payer = None;
+    if let Some((raiser, payer, bond)) = held_bond(&env, escrow_id) {
+        let fee = arbitration::compensate(&env, escrow_id, &escrow.token_address, bond)?;
+        fee_payer =
+            loser_paying_fee(&env, &escrow, &raiser, &winner, fee).map(|loser| (loser, fee));
+        let amount = if fee_payer.is_some() {
+            bond
+        } else {
+            bond.checked_sub(fee)
+                .unwrap_or_else(|| fail(&env, ExtendedError::BondUnderflow))
+        };
+        let payee = if raiser == winner {
+            payer
+        } else {
+            winner.clone()
+        };
+        bond_payout = Some((payee, amount));
+        if raiser != winner {
+            tally(&env, escrow_id, |totals| {
+                totals.forfeited = totals.forfeited.saturating_add(bond)
+            });
+        }
+        env.storage().persistent().remove(&bond_key);
+    }
+    env.storage()
+        .persistent()
+        .remove(&settlement_key(escrow_id));
+    env.storage()
+        .persistent()
+        .remove(&pending_ruling_key(escrow_id));
+    env.storage().persistent().remove(&dao_vote_key(escrow_id));
+    panel::discard(&env, escrow_id);
+    save_escrow(&env, escrow_id, &escrow);
+
+    let token_client = token::Client::new(&env, &escrow.token_address);
+    if let Some((loser, fee)) = fee_payer {
+        let contract = env.current_contract_address();
+        token_client.transfer_from(&contract, &loser, &contract, &fee);
+    }
+    if outstanding > 0 {
+        token_client.transfer(&env.current_contract_address(), &payee, &outstanding);
+    }
+    if let Some((payee, amount)) = bond_payout {
+        token_client.transfer(&env.current_contract_address(), &payee, &amount);
+    }
+    nft::settle_ruling(&env, escrow_id, &escrow, winner == escrow.recipient)?;
+    swap::settle_ruling(&env, escrow_id, &escrow, winner == escrow.recipient)?;
+
+    let ruling = Ruling {
+        winner: winner.clone(),
+        reason,
+        resolver: resolver.clone(),
+        ledger: env.ledger().sequence(),
+    };
+    env.storage()
+        .persistent()
+        .set(&ruling_key(escrow_id), &ruling);
+    extend_persistent(&env, &ruling_key(escrow_id));
+    if let Some(rationale) = &rationale {
+        let rationale_key = ruling_rationale_key(escrow_id);
+        write_persistent(&env, &rationale_key, rationale);
+    }
+    env.events()
+        .publish((symbol_short!("ruling"), escrow_id), (reason, rationale));
+    history::record(
+        &env,
+        escrow_id,
+        symbol_short!("resolved"),
+        &resolver,
+        outstanding,
+    );
+    if winner == escrow.recipient {
+        stats::funds_released(&env, &escrow.token_address, outstanding);
+    } else {
+        stats::funds_refunded(&env, &escrow.token_address, outstanding);
+    }
+    stats::dispute_closed(&env, escrow_id);
+    arbitration::dequeue(&env, escrow_id);
+    arbitration::release_assignment(&env, escrow_id);
+    hooks::on_resolve(&env, escrow_id, escrow.resolution);
+    finish_funded(&env, escrow_id, &escrow)?;
+
+    burn_receipt(&env, escrow_id);
+
+    let loser = if winner == escrow.recipient {
+        escrow.depositor.clone()
+    } else {
+        escrow.recipient.clone()
+    };
+    update_reputation(&env, &loser, |rep| {
+        rep.disputes_lost += 1;
+        Ok(())
+    })?;
+    for party in [&escrow.depositor, &escrow.recipient] {
+        update_reputation(&env, party, |rep| {
+            rep.volume = rep
+                .volume
+                .checked_add(escrow.total_released)
+                .ok_or(Error::InvalidMilestoneAmount)?;
+            Ok(())
+        })?;
+    }
+
+    Ok(())
+}
+
+/// The held dispute bond as `(raiser, payer, bond)`: the party that raised the dispute, and the
+/// account the bond was taken from and is refunded to, which is a backup when one acted for it.
+pub(crate) fn held_bond(env: &Env, escrow_id: u64) -> Option<(Address, Address, i128)> {
+    let (payer, bond) = env
+        .storage()
+        .persistent()
+        .get::<_, (Address, i128)>(&dispute_bond_key(escrow_id))?;
+    let raiser = env
+        .storage()
+        .persistent()
+        .get(&dispute_raiser_key(escrow_id))
+        .unwrap_or_else(|| payer.clone());
+    Some((raiser, payer, bond))
+}
+
+/// The party to charge the arbitrator's `fee` on a full ruling for `winner`, if anyone: under the
+/// loser-pays policy, the loser when the raiser won, provided they have approved the contract to
+/// collect it and hold enough. Otherwise the fee comes out of the bond.
+pub(crate) fn loser_paying_fee(
+    env: &Env,
+    escrow: &Escrow,
+    raiser: &Address,
+    winner: &Address,
+    fee: i128,
+) -> Option<Address> {
+    if fee <= 0
+        || raiser != winner
+        || load_config(env).dispute_fee_policy != DisputeFeePolicy::LoserPays
+    {
+        return None;
+    }
+    let loser = if *winner == escrow.recipient {
+        escrow.depositor.clone()
+    } else {
+        escrow.recipient.clone()
+    };
+    let token_client = token::Client::new(env, &escrow.token_address);
+    let covered = token_client.allowance(&loser, &env.current_contract_address()) >= fee
+        && token_client.balance(&loser) >= fee;
+    covered.then_some(loser)
+}
+
+/// Carries out a partial ruling: pays the `awarded` milestones and returns the escrow to Active.
+pub(crate) fn apply_partial_ruling(
+    env: Env,
+    escrow_id: u64,
+    resolver: Address,
+    awarded: Vec<u32>,
+) -> Result<(), Error> {
+    let mut escrow = load_live_escrow(&env, escrow_id)?;
+
+    // Only escrows disputed while funded have anything to resume
+    let origin_key = dispute_origin_key(escrow_id);
+    let disputed_from: Option<EscrowStatus> = env.storage().persistent().get(&origin_key);
+    if escrow.status == EscrowStatus::Disputed && disputed_from != Some(EscrowStatus::Active) {
+        return Err(Error::InvalidEscrowStatus);
+    }
+    state_machine::transition(&mut escrow, EscrowStatus::Active)
+        .map_err(|_| Error::InvalidEscrowStatus)?;
+
+    let mut awarded_amount: i128 = 0;
+    for index in awarded.iter() {
+        if index >= escrow.milestone_count {
+            return Err(Error::MilestoneNotFound);
+        }
+        if escrow.released_mask & milestone_bit(index) != 0 {
+            return Err(Error::MilestoneAlreadyReleased);
+        }
+        ensure_funded(&env, escrow_id, &escrow, index)?;
+        let milestone = load_milestone(&env, escrow_id, index)?;
+        awarded_amount = awarded_amount
+            .checked_add(unreleased_amount(&env, escrow_id, index, &milestone)?)
+            .ok_or(Error::InvalidMilestoneAmount)?;
+        escrow.released_mask |= milestone_bit(index);
+    }
+    escrow.total_released = escrow
+        .total_released
+        .checked_add(awarded_amount)
+        .ok_or(Error::InvalidMilestoneAmount)?;
+    escrow.disputed_mask = 0;
+
+    let bond_key = dispute_bond_key(escrow_id);
+    let mut bond_refund = None;
+    if let Some((raiser, bond)) = env
+        .storage()
+        .persistent()
+        .get::<_, (Address, i128)>(&bond_key)
+    {
+        let fee = arbitration::compensate(&env, escrow_id, &escrow.token_address, bond)?;
+        let refund = bond
+            .checked_sub(fee)
+            .unwrap_or_else(|| fail(&env, ExtendedError::BondUnderflow));
+        bond_refund = Some((raiser, refund));
+        env.storage().persistent().remove(&bond_key);
+    }
+
+    // Whoever raised the dispute waits out the cooldown before raising another
+    let raiser_key = dispute_raiser_key(escrow_id);
+    let cooldown = load_config(&env).dispute_cooldown;
+    if let Some(raiser) = env.storage().persistent().get::<_, Address>(&raiser_key) {
+        if cooldown > 0 {
+            let cooldown_key = dispute_cooldown_key(escrow_id, &raiser);
+            let until = env.ledger().timestamp().saturating_add(cooldown);
+            write_persistent(&env, &cooldown_key, &until);
+        }
+    }
+
+    for key in [
+        raiser_key,
+        settlement_key(escrow_id),
+        pending_ruling_key(escrow_id),
+        dao_vote_key(escrow_id),
+        dispute_ledger_key(escrow_id),
+        dispute_reason_key(escrow_id),
+        evidence_deadline_key(escrow_id),
+        origin_key,
+    ] {
+        env.storage().persistent().remove(&key);
+    }
+    panel::discard(&env, escrow_id);
+    for party in [&escrow.depositor, &escrow.recipient] {
+        env.storage()
+            .persistent()
+            .remove(&evidence_key(escrow_id, party));
+        env.storage()
+            .persistent()
+            .remove(&evidence_commit_key(escrow_id, party));
+    }
+    save_escrow(&env, escrow_id, &escrow);
+
+    let token_client = token::Client::new(&env, &escrow.token_address);
+    if awarded_amount > 0 {
+        token_client.transfer(
+            &env.current_contract_address(),
+            &payout_address(&env, escrow_id, &escrow),
+            &awarded_amount,
+        );
+    }
+    if let Some((raiser, refund)) = bond_refund {
+        token_client.transfer(&env.current_contract_address(), &raiser, &refund);
+    }
+
+    history::record(
+        &env,
+        escrow_id,
+        symbol_short!("resumed"),
+        &resolver,
+        awarded_amount,
+    );
+    stats::funds_released(&env, &escrow.token_address, awarded_amount);
+    stats::dispute_closed(&env, escrow_id);
+    arbitration::dequeue(&env, escrow_id);
+    arbitration::release_assignment(&env, escrow_id);
+    hooks::on_resolve(&env, escrow_id, escrow.resolution);
+
+    env.events()
+        .publish((symbol_short!("resumed"), escrow_id), awarded_amount);
+    Ok(())
+}
+
+pub(crate) fn add_to_insurance_pool(
+    env: &Env,
+    token_address: &Address,
+    amount: i128,
+) -> Result<(), Error> {
+    let pool_key = insurance_pool_key(token_address);
+    let pool: i128 = env.storage().persistent().get(&pool_key).unwrap_or(0);
+    let updated = pool
+        .checked_add(amount)
+        .unwrap_or_else(|| fail(env, ExtendedError::FeeOverflow));
+
+    write_persistent(env, &pool_key, &updated);
+
+    Ok(())
+}
+
+/// The party `caller` acts for: the depositor when `caller` is their active backup or a member
+/// of the organization owning the escrow, otherwise `caller` itself.
+fn acting_party(env: &Env, escrow_id: u64, escrow: &Escrow, caller: &Address) -> Address {
+    if *caller != escrow.depositor
+        && (depositor_authority(env, escrow_id, escrow) == *caller
+            || acts_for_org(env, escrow_id, caller))
+    {
+        escrow.depositor.clone()
+    } else {
+        caller.clone()
+    }
+}
+
+/// Only the depositor and the recipient may dispute an escrow.
+pub(crate) fn ensure_party(escrow: &Escrow, caller: &Address) -> Result<(), Error> {
+    if *caller != escrow.depositor && *caller != escrow.recipient {
+        return Err(Error::UnauthorizedAccess);
+    }
+    Ok(())
+}
+
+/// Moves `escrow` into dispute over the milestones in `disputed_mask` and saves it: records the
+/// reason and pre-dispute status, starts the evidence window, freezes streaming and routes the
+/// dispute to an arbitrator. Bonds, history and events are left to the caller.
+pub(crate) fn open_dispute(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &mut Escrow,
+    disputed_mask: u64,
+    reason: DisputeReason,
+) -> Result<(), Error> {
+    let disputed_from = escrow.status;
+    state_machine::transition(escrow, EscrowStatus::Disputed)?;
+    let origin_key = dispute_origin_key(escrow_id);
+    write_persistent(env, &origin_key, &disputed_from);
+    let reason_key = dispute_reason_key(escrow_id);
+    write_persistent(env, &reason_key, &reason);
+
+    let evidence_window = agreement::evidence_window(env, escrow_id);
+    if evidence_window > 0 {
+        let due_key = evidence_deadline_key(escrow_id);
+        let due = env.ledger().timestamp().saturating_add(evidence_window);
+        write_persistent(env, &due_key, &due);
+    }
+
+    escrow.disputed_mask = disputed_mask;
+    escrow.resolution = Resolution::None;
+    let storage_key = get_storage_key(escrow_id);
+    env.storage().persistent().set(&storage_key, escrow);
+
+    // Streaming milestones stop vesting at the ledger the dispute was raised
+    let dispute_key = dispute_ledger_key(escrow_id);
+    write_persistent(env, &dispute_key, &env.ledger().sequence());
+
+    stats::dispute_opened(env, escrow_id, reason);
+    if !dao::open_vote(env, escrow_id)? && !panel::open_vote(env, escrow_id)? {
+        arbitration::assign_or_enqueue(env, escrow_id, escrow);
+    }
+    Ok(())
+}
+
+/// Keeps a party from freezing the counterparty's funds straight after creation.
+fn ensure_grace_elapsed(env: &Env, escrow_id: u64) -> Result<(), Error> {
+    if env.ledger().timestamp() < VaultixEscrow::get_dispute_opens_at(env.clone(), escrow_id) {
+        return Err(Error::DisputeTooEarly);
+    }
+    Ok(())
+}
+
+/// Limits serial disputes: a party whose dispute ended with the escrow resuming must wait.
+fn ensure_cooldown_elapsed(env: &Env, escrow_id: u64, party: &Address) -> Result<(), Error> {
+    let until: u64 = env
+        .storage()
+        .persistent()
+        .get(&dispute_cooldown_key(escrow_id, party))
+        .unwrap_or(0);
+    if env.ledger().timestamp() < until {
+        return Err(Error::DisputeCooldown);
+    }
+    Ok(())
+}
+
+/// Fails once the evidence window of the escrow's dispute has closed.
+fn ensure_evidence_open(env: &Env, escrow_id: u64) -> Result<(), Error> {
+    match VaultixEscrow::get_evidence_deadline(env.clone(), escrow_id) {
+        Some(due) if env.ledger().timestamp() > due => Err(Error::EvidenceWindowClosed),
+        _ => Ok(()),
+    }
+}
+
+/// Whether either party has sealed its evidence in the escrow's dispute.
+fn evidence_sealed(env: &Env, escrow_id: u64, escrow: &Escrow) -> bool {
+    [&escrow.depositor, &escrow.recipient].iter().any(|party| {
+        env.storage()
+            .persistent()
+            .has(&evidence_commit_key(escrow_id, party))
+    })
+}
+
+/// Stores `hash` as `party`'s latest evidence and publishes it.
+fn record_evidence(env: &Env, escrow_id: u64, party: Address, hash: BytesN<32>) {
+    let key = evidence_key(escrow_id, &party);
+    let evidence = Evidence {
+        hash: hash.clone(),
+        submitted_at: env.ledger().timestamp(),
+    };
+    write_persistent(env, &key, &evidence);
+
+    env.events()
+        .publish((symbol_short!("evidence"), escrow_id), (party, hash));
+}
+
+/// Rulings wait until both parties have had the full evidence window to make their case.
+pub(crate) fn ensure_evidence_closed(env: &Env, escrow_id: u64) -> Result<(), Error> {
+    let due: Option<u64> = env
+        .storage()
+        .persistent()
+        .get(&evidence_deadline_key(escrow_id));
+    match due {
+        Some(due) if env.ledger().timestamp() <= due => Err(Error::EvidenceWindowOpen),
+        _ => Ok(()),
+    }
+}
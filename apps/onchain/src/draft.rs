@@ -0,0 +1,117 @@
+//! Draft escrows, for negotiating terms before anything is locked in.
+//!
+//! A draft is stored like any other escrow but starts in `Draft`, where the depositor can
+//! replace its milestones as often as needed. `activate_draft` moves it to `Created`, after
+//! which funding proceeds as usual and the milestones can no longer be edited. A draft the
+//! parties walk away from is discarded with `cancel_escrow`.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin::ensure_not_paused,
+    errors::Error,
+    escrow::{
+        ensure_caller_chosen_id, load_live_escrow, new_escrow, replace_terms, save_escrow,
+        stamp_created_at, store_new_escrow,
+    },
+    history, state_machine,
+    types::{EscrowStatus, Milestone},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, Vec};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Creates an escrow in the `Draft` state. Takes the same arguments and fails with the same
+    /// errors as `create_escrow`, but the milestones stay editable through `update_draft`
+    /// until the depositor calls `activate_draft`.
+    pub fn create_draft(
+        env: Env,
+        escrow_id: u64,
+        depositor: Address,
+        recipient: Address,
+        token_address: Address,
+        milestones: Vec<Milestone>,
+        deadline: u64,
+    ) -> Result<(), Error> {
+        depositor.require_auth();
+
+        ensure_not_paused(&env)?;
+        ensure_caller_chosen_id(escrow_id)?;
+
+        let (mut escrow, milestones) = new_escrow(
+            &env,
+            depositor,
+            recipient,
+            token_address,
+            milestones,
+            deadline,
+        )?;
+        escrow.status = EscrowStatus::Draft;
+        store_new_escrow(&env, escrow_id, &escrow, &milestones)
+    }
+
+    /// Replaces every milestone of a draft, recomputing its total. Only the depositor may
+    /// edit, and only while the escrow is still a draft.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow is no longer a draft
+    /// * `VectorTooLarge` - If more milestones are provided than the configured cap
+    /// * `InvalidMilestoneAmount` - If any milestone amount is zero or negative
+    /// * `AmountOutOfRange` - If the new total is outside the token's limits
+    pub fn update_draft(env: Env, escrow_id: u64, milestones: Vec<Milestone>) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if escrow.status != EscrowStatus::Draft {
+            return Err(Error::InvalidEscrowStatus);
+        }
+
+        let deadline = escrow.deadline;
+        replace_terms(&env, escrow_id, &mut escrow, milestones, deadline)?;
+
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("redraft"),
+            &escrow.depositor,
+            escrow.total_amount,
+        );
+        env.events().publish(
+            (symbol_short!("redraft"), escrow_id),
+            (escrow.milestone_count, escrow.total_amount),
+        );
+        Ok(())
+    }
+
+    /// Freezes a draft's terms and moves it to `Created`, ready to be funded. The dispute
+    /// grace period counts from activation rather than from when the draft was opened.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow is not a draft
+    pub fn activate_draft(env: Env, escrow_id: u64) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        state_machine::transition(&mut escrow, EscrowStatus::Created)?;
+        save_escrow(&env, escrow_id, &escrow);
+        stamp_created_at(&env, escrow_id);
+
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("activated"),
+            &escrow.depositor,
+            escrow.total_amount,
+        );
+        env.events()
+            .publish((symbol_short!("activated"), escrow_id), escrow.total_amount);
+        Ok(())
+    }
+}
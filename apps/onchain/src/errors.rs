@@ -0,0 +1,9 @@
+//! Contract error codes, defined in `vaultix-interface` so callers can match on them.
+
+use soroban_sdk::{panic_with_error, Env};
+pub use vaultix_interface::{Error, ExtendedError};
+
+/// Aborts the call with an `ExtendedError`, which `Error` results have no room for.
+pub(crate) fn fail(env: &Env, error: ExtendedError) -> ! {
+    panic_with_error!(env, error)
+}
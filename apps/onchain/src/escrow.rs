@@ -0,0 +1,2525 @@
+//! Escrow lifecycle: creation, funding, payouts, settlement and related views.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin::{
+        claim_pair_slot, consume_creation_quota, ensure_allowed, ensure_min_value,
+        ensure_not_blocked, ensure_not_paused, ensure_token_allowed,
+    },
+    agreement,
+    approvals::ensure_no_approval_policy,
+    arbitration, certificate,
+    chain::ensure_parent_completed,
+    convert, currency,
+    dispute::add_to_insurance_pool,
+    errors::{fail, Error, ExtendedError},
+    events,
+    export::{index_escrow, unindex_escrow},
+    fees::fee_split,
+    funding::{ensure_funded, funding, held_amount},
+    history, hooks, nft,
+    payout::emit_payout_memo,
+    recovery::depositor_authority,
+    shortfall, spending, state_machine,
+    statement::tally,
+    stats,
+    storage::{
+        all_milestones_mask, approvals_key, arbiter_storage_key, assignee_storage_key,
+        created_at_key, deliverable_key, dispute_cooldown_key, dispute_grace_key,
+        dispute_ledger_key, escrow_exists, escrow_keys, escrow_nonce_key, evidence_commit_key,
+        evidence_key, excess_key, extend_instance, extend_persistent, external_ref_index_key,
+        external_ref_key, get_storage_key, is_archived, legacy_accrued_yield_key, load_config,
+        load_milestone, milestone_bit, milestone_status, milestone_storage_key, next_escrow_id,
+        nonce_counter_key, nonce_index_key, rating_storage_key, rating_summary_key,
+        read_persistent, recurring_key, recurring_start_key, rejection_reason_key, reputation_key,
+        review_key, review_window_key, save_milestone, shipment_key, stream_storage_key,
+        template_arbiter_key, template_storage_key, trigger_key, vesting_key, write_persistent,
+        DataKey, ARCHIVE_TTL,
+    },
+    tags,
+    triggers::fire_triggers,
+    types::{
+        Escrow, EscrowState, EscrowStatus, EscrowTemplate, Milestone, MilestoneStatus, Rating,
+        RatingSummary, ReceiptClient, ReceiptConfig, RecurringSchedule, Reputation, Resolution,
+        StreamConfig, TemplateMilestone, VestingSchedule,
+    },
+    yields::settle_yield,
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{
+    contractimpl, symbol_short, token, xdr::ToXdr, Address, BytesN, Env, Map, String, Symbol, Vec,
+};
+
+pub(crate) const BPS_DENOMINATOR: i128 = 10000;
+
+// Salted escrow IDs have the top bit set; caller-chosen IDs may not use that range
+pub(crate) const SALTED_ID_FLAG: u64 = 1 << 63;
+
+// Maximum milestone description length in bytes
+pub(crate) const MAX_DESCRIPTION_LEN: u32 = 256;
+
+// Maximum external reference length in bytes
+pub(crate) const MAX_EXTERNAL_REF_LEN: u32 = 64;
+
+// Maximum escrows returned by one `get_escrows` call
+pub(crate) const MAX_BATCH_ESCROWS: u32 = 50;
+
+// Ratings are whole stars from 1 to 5
+pub(crate) const MIN_RATING: u32 = 1;
+pub(crate) const MAX_RATING: u32 = 5;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Creates a new escrow with milestone-based payment releases.
+    /// NOTE: This only creates the escrow structure. Funds must be deposited separately via deposit_funds().
+    ///
+    /// # Arguments
+    /// * `escrow_id` - Unique identifier for the escrow
+    /// * `depositor` - Address funding the escrow
+    /// * `recipient` - Address receiving milestone payments
+    /// * `token_address` - Address of the token contract (e.g., XLM, USDC)
+    /// * `milestones` - Vector of milestones defining payment schedule
+    /// * `deadline` - Unix timestamp deadline for escrow completion
+    ///
+    /// # Errors
+    /// * `EscrowAlreadyExists` - If escrow_id is already in use
+    /// * `VectorTooLarge` - If more milestones are provided than the configured cap
+    /// * `InvalidMilestoneAmount` - If any milestone amount is zero or negative
+    /// * `SelfDealing` - If depositor and recipient are the same
+    /// * `AmountOutOfRange` - If the total is outside the token's limits
+    /// * `RateLimited` - If the depositor's creation quota or pair cap is used up
+    /// * `ReservedEscrowId` - If escrow_id is in the salted-ID range
+    pub fn create_escrow(
+        env: Env,
+        escrow_id: u64,
+        depositor: Address,
+        recipient: Address,
+        token_address: Address,
+        milestones: Vec<Milestone>,
+        deadline: u64,
+    ) -> Result<(), Error> {
+        depositor.require_auth();
+
+        ensure_not_paused(&env)?;
+        ensure_caller_chosen_id(escrow_id)?;
+
+        let (escrow, milestones) = new_escrow(
+            &env,
+            depositor,
+            recipient,
+            token_address,
+            milestones,
+            deadline,
+        )?;
+        store_new_escrow(&env, escrow_id, &escrow, &milestones)
+    }
+
+    /// Creates an escrow whose ID is derived from `hash(depositor, recipient, salt)`.
+    /// Derived IDs live in a range `create_escrow` cannot claim, so nobody can squat them.
+    ///
+    /// # Returns
+    /// The derived escrow ID
+    pub fn create_escrow_salted(
+        env: Env,
+        depositor: Address,
+        recipient: Address,
+        token_address: Address,
+        milestones: Vec<Milestone>,
+        deadline: u64,
+        salt: BytesN<32>,
+    ) -> Result<u64, Error> {
+        depositor.require_auth();
+
+        ensure_not_paused(&env)?;
+
+        let escrow_id = salted_escrow_id(&env, &depositor, &recipient, &salt);
+        let (escrow, milestones) = new_escrow(
+            &env,
+            depositor,
+            recipient,
+            token_address,
+            milestones,
+            deadline,
+        )?;
+        store_new_escrow(&env, escrow_id, &escrow, &milestones)?;
+        Ok(escrow_id)
+    }
+
+    /// Computes the ID `create_escrow_salted` would assign, so clients can know it up front.
+    pub fn get_salted_escrow_id(
+        env: Env,
+        depositor: Address,
+        recipient: Address,
+        salt: BytesN<32>,
+    ) -> u64 {
+        salted_escrow_id(&env, &depositor, &recipient, &salt)
+    }
+
+    /// Creates an escrow under the depositor's next nonce, so clients can address it as
+    /// `(depositor, nonce)` instead of coordinating global IDs. A global ID is still assigned.
+    ///
+    /// # Returns
+    /// The identifier assigned to the new escrow
+    pub fn create_escrow_with_nonce(
+        env: Env,
+        depositor: Address,
+        recipient: Address,
+        token_address: Address,
+        milestones: Vec<Milestone>,
+        deadline: u64,
+    ) -> Result<u64, Error> {
+        depositor.require_auth();
+
+        ensure_not_paused(&env)?;
+
+        let nonce = Self::get_next_nonce(env.clone(), depositor.clone());
+        let (escrow, milestones) = new_escrow(
+            &env,
+            depositor.clone(),
+            recipient,
+            token_address,
+            milestones,
+            deadline,
+        )?;
+        let escrow_id = next_escrow_id(&env);
+        store_new_escrow(&env, escrow_id, &escrow, &milestones)?;
+
+        let counter_key = nonce_counter_key(&depositor);
+        write_persistent(&env, &counter_key, &(nonce + 1));
+
+        let index_key = nonce_index_key(&depositor, nonce);
+        write_persistent(&env, &index_key, &escrow_id);
+
+        let reverse_key = escrow_nonce_key(escrow_id);
+        write_persistent(&env, &reverse_key, &nonce);
+
+        Ok(escrow_id)
+    }
+
+    /// Returns the nonce the depositor's next `create_escrow_with_nonce` call will use.
+    pub fn get_next_nonce(env: Env, depositor: Address) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&nonce_counter_key(&depositor))
+            .unwrap_or(0)
+    }
+
+    /// Resolves a `(depositor, nonce)` pair to the escrow ID it was assigned.
+    pub fn get_escrow_id_by_nonce(env: Env, depositor: Address, nonce: u64) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&nonce_index_key(&depositor, nonce))
+    }
+
+    /// Deposits funds into an escrow, transitioning it from Created to Active.
+    /// The depositor must have approved this contract to spend the required amount.
+    ///
+    /// # Arguments
+    /// * `escrow_id` - Identifier of the escrow to fund
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `UnauthorizedAccess` - If caller is not the depositor
+    /// * `EscrowAlreadyFunded` - If escrow is already in Active state
+    /// * `InvalidEscrowStatus` - If escrow is a draft or a proposal the recipient has not
+    ///   accepted
+    /// * `TokenTransferFailed` - If token transfer fails
+    /// * `FundingShortfall` - If the token delivered less than was pulled, as fee-on-transfer
+    ///   tokens do
+    pub fn deposit_funds(env: Env, escrow_id: u64) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        // Load escrow from storage
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+
+        // Verify authorization - only depositor can fund
+        escrow.depositor.require_auth();
+        fund(&env, escrow_id, &mut escrow)
+    }
+
+    /// Retrieves escrow details, bumping the header's TTL. Falls back to the archived copy.
+    pub fn get_escrow(env: Env, escrow_id: u64) -> Result<Escrow, Error> {
+        let storage_key = get_storage_key(escrow_id);
+        if let Some(escrow) = read_persistent(&env, &storage_key) {
+            return Ok(escrow);
+        }
+        env.storage()
+            .temporary()
+            .get(&storage_key)
+            .ok_or(Error::EscrowNotFound)
+    }
+
+    /// Retrieves several escrows in one call, keyed by ID. IDs that do not exist (or whose
+    /// storage was closed) are left out rather than failing the batch.
+    ///
+    /// # Errors
+    /// * `VectorTooLarge` - If more than 50 IDs are requested
+    pub fn get_escrows(env: Env, escrow_ids: Vec<u64>) -> Result<Map<u64, Escrow>, Error> {
+        if escrow_ids.len() > MAX_BATCH_ESCROWS {
+            return Err(Error::VectorTooLarge);
+        }
+
+        let mut escrows = Map::new(&env);
+        for escrow_id in escrow_ids.iter() {
+            if let Ok(escrow) = Self::get_escrow(env.clone(), escrow_id) {
+                escrows.set(escrow_id, escrow);
+            }
+        }
+        Ok(escrows)
+    }
+
+    /// Status and totals of an escrow, for callers polling for changes. Reads only the header
+    /// and the history counter: no milestones, and no TTL bump. `changes` grows with every
+    /// recorded action, so an unchanged value means nothing happened since the last poll.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    pub fn get_status(env: Env, escrow_id: u64) -> Result<EscrowState, Error> {
+        let storage_key = get_storage_key(escrow_id);
+        let escrow: Escrow = match env.storage().persistent().get(&storage_key) {
+            Some(escrow) => escrow,
+            None => env
+                .storage()
+                .temporary()
+                .get(&storage_key)
+                .ok_or(Error::EscrowNotFound)?,
+        };
+        Ok(EscrowState {
+            status: escrow.status,
+            total_amount: escrow.total_amount,
+            total_released: escrow.total_released,
+            released_mask: escrow.released_mask,
+            disputed_mask: escrow.disputed_mask,
+            changes: Self::get_history_len(env, escrow_id),
+        })
+    }
+
+    /// Sends the depositor whatever the escrow received beyond its total when it was funded.
+    /// Available in any state, since the overage never belongs to the recipient.
+    ///
+    /// # Returns
+    /// The amount refunded, 0 if there was no overage
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    pub fn refund_excess(env: Env, escrow_id: u64) -> Result<i128, Error> {
+        let escrow = Self::get_escrow(env.clone(), escrow_id)?;
+        escrow.depositor.require_auth();
+
+        Ok(refund_excess(&env, escrow_id, &escrow))
+    }
+
+    /// Overage received when the escrow was funded that the depositor has not withdrawn yet.
+    pub fn get_excess(env: Env, escrow_id: u64) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&excess_key(escrow_id))
+            .unwrap_or(0)
+    }
+
+    /// Moves a finished escrow's header and milestones into temporary storage so it stops
+    /// accruing persistent rent. It stays readable until the temporary entries expire.
+    pub fn archive_escrow(env: Env, escrow_id: u64) -> Result<(), Error> {
+        let storage_key = get_storage_key(escrow_id);
+        let escrow = load_live_escrow(&env, escrow_id)?;
+
+        escrow.depositor.require_auth();
+
+        if !state_machine::is_terminal(escrow.status) {
+            return Err(Error::InvalidEscrowStatus);
+        }
+
+        for index in 0..escrow.milestone_count {
+            let milestone_key = milestone_storage_key(escrow_id, index);
+            let milestone = load_milestone(&env, escrow_id, index)?;
+            env.storage().persistent().remove(&milestone_key);
+            env.storage().temporary().set(&milestone_key, &milestone);
+            env.storage()
+                .temporary()
+                .extend_ttl(&milestone_key, ARCHIVE_TTL, ARCHIVE_TTL);
+        }
+
+        env.storage().persistent().remove(&storage_key);
+        env.storage().temporary().set(&storage_key, &escrow);
+        env.storage()
+            .temporary()
+            .extend_ttl(&storage_key, ARCHIVE_TTL, ARCHIVE_TTL);
+
+        events::emit(
+            &env,
+            symbol_short!("archived"),
+            escrow_id,
+            &escrow.depositor,
+            0,
+        );
+        env.events()
+            .publish((symbol_short!("archived"), escrow_id), escrow.status);
+        Ok(())
+    }
+
+    /// Removes every ledger entry of a finished escrow, including its external reference index.
+    /// Terminal escrows hold no funds beyond any funding overage, which is refunded to the
+    /// depositor first. The id becomes reusable.
+    pub fn close_escrow(env: Env, escrow_id: u64) -> Result<(), Error> {
+        let escrow = Self::get_escrow(env.clone(), escrow_id)?;
+
+        escrow.depositor.require_auth();
+
+        if !state_machine::is_terminal(escrow.status) {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        refund_excess(&env, escrow_id, &escrow);
+
+        let persistent = env.storage().persistent();
+        let temporary = env.storage().temporary();
+
+        for index in 0..escrow.milestone_count {
+            let milestone_key = milestone_storage_key(escrow_id, index);
+            persistent.remove(&milestone_key);
+            temporary.remove(&milestone_key);
+            persistent.remove(&stream_storage_key(escrow_id, index));
+            persistent.remove(&review_key(escrow_id, index));
+            persistent.remove(&deliverable_key(escrow_id, index));
+            persistent.remove(&rejection_reason_key(escrow_id, index));
+            persistent.remove(&approvals_key(escrow_id, index));
+            persistent.remove(&trigger_key(escrow_id, index));
+            persistent.remove(&shipment_key(escrow_id, index));
+        }
+
+        if let Some(external_ref) = persistent.get::<_, String>(&external_ref_key(escrow_id)) {
+            persistent.remove(&external_ref_index_key(&external_ref));
+        }
+        unindex_escrow(&env, escrow_id);
+        tags::untag(&env, escrow_id);
+        arbitration::release_assignment(&env, escrow_id);
+        for key in escrow_keys(escrow_id).iter() {
+            persistent.remove(key);
+        }
+        persistent.remove(&legacy_accrued_yield_key(escrow_id));
+        for party in [&escrow.depositor, &escrow.recipient] {
+            persistent.remove(&rating_storage_key(escrow_id, party));
+            persistent.remove(&evidence_key(escrow_id, party));
+            persistent.remove(&evidence_commit_key(escrow_id, party));
+            persistent.remove(&dispute_cooldown_key(escrow_id, party));
+        }
+        if let Some(nonce) = persistent.get::<_, u64>(&escrow_nonce_key(escrow_id)) {
+            persistent.remove(&nonce_index_key(&escrow.depositor, nonce));
+            persistent.remove(&escrow_nonce_key(escrow_id));
+        }
+        history::clear(&env, escrow_id);
+
+        let storage_key = get_storage_key(escrow_id);
+        persistent.remove(&storage_key);
+        temporary.remove(&storage_key);
+
+        events::emit(
+            &env,
+            symbol_short!("closed"),
+            escrow_id,
+            &escrow.depositor,
+            0,
+        );
+        env.events()
+            .publish((symbol_short!("closed"), escrow_id), ());
+        Ok(())
+    }
+
+    /// Extends the TTL of every ledger entry belonging to an escrow. Callable by anyone.
+    pub fn extend_escrow_ttl(env: Env, escrow_id: u64) -> Result<(), Error> {
+        let escrow = Self::get_escrow(env.clone(), escrow_id)?;
+        if is_archived(&env, escrow_id) {
+            return Err(Error::InvalidEscrowStatus);
+        }
+
+        for index in 0..escrow.milestone_count {
+            extend_persistent(&env, &milestone_storage_key(escrow_id, index));
+            for key in [
+                stream_storage_key(escrow_id, index),
+                review_key(escrow_id, index),
+                deliverable_key(escrow_id, index),
+                rejection_reason_key(escrow_id, index),
+                approvals_key(escrow_id, index),
+                trigger_key(escrow_id, index),
+                shipment_key(escrow_id, index),
+            ] {
+                if env.storage().persistent().has(&key) {
+                    extend_persistent(&env, &key);
+                }
+            }
+        }
+
+        for key in escrow_keys(escrow_id).iter() {
+            if env.storage().persistent().has(key) {
+                extend_persistent(&env, key);
+            }
+        }
+        let legacy_yield_key = legacy_accrued_yield_key(escrow_id);
+        if env.storage().persistent().has(&legacy_yield_key) {
+            extend_persistent(&env, &legacy_yield_key);
+        }
+        for party in [&escrow.depositor, &escrow.recipient] {
+            for key in [
+                evidence_key(escrow_id, party),
+                evidence_commit_key(escrow_id, party),
+                dispute_cooldown_key(escrow_id, party),
+            ] {
+                if env.storage().persistent().has(&key) {
+                    extend_persistent(&env, &key);
+                }
+            }
+        }
+
+        if let Some(external_ref) = env
+            .storage()
+            .persistent()
+            .get::<_, String>(&external_ref_key(escrow_id))
+        {
+            extend_persistent(&env, &external_ref_index_key(&external_ref));
+        }
+        if let Some(nonce) = env
+            .storage()
+            .persistent()
+            .get::<_, u64>(&escrow_nonce_key(escrow_id))
+        {
+            extend_persistent(&env, &escrow_nonce_key(escrow_id));
+            extend_persistent(&env, &nonce_index_key(&escrow.depositor, nonce));
+        }
+        history::extend(&env, escrow_id);
+
+        extend_instance(&env);
+        Ok(())
+    }
+
+    /// Retrieves all milestones of an escrow in index order (read-only)
+    pub fn get_milestones(env: Env, escrow_id: u64) -> Result<Vec<Milestone>, Error> {
+        Self::get_milestones_page(env, escrow_id, 0, u32::MAX)
+    }
+
+    /// Retrieves up to `limit` milestones of an escrow starting at index `start`, in index
+    /// order (read-only). Pages past the last milestone are empty, so clients can walk large
+    /// escrows without loading every milestone in one call.
+    pub fn get_milestones_page(
+        env: Env,
+        escrow_id: u64,
+        start: u32,
+        limit: u32,
+    ) -> Result<Vec<Milestone>, Error> {
+        let escrow = Self::get_escrow(env.clone(), escrow_id)?;
+        let funded = reported_funding(&env, escrow_id, &escrow);
+        let end = start.saturating_add(limit).min(escrow.milestone_count);
+        let mut milestones = Vec::new(&env);
+        for index in start..end {
+            let mut milestone = load_milestone(&env, escrow_id, index)?;
+            milestone.status = reported_status(&escrow, funded, index);
+            milestones.push_back(milestone);
+        }
+        Ok(milestones)
+    }
+
+    /// Retrieves a single milestone of an escrow (read-only)
+    pub fn get_milestone(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+    ) -> Result<Milestone, Error> {
+        let escrow = Self::get_escrow(env.clone(), escrow_id)?;
+        let mut milestone = load_milestone(&env, escrow_id, milestone_index)?;
+        let funded = reported_funding(&env, escrow_id, &escrow);
+        milestone.status = reported_status(&escrow, funded, milestone_index);
+        Ok(milestone)
+    }
+
+    /// Amount still held by the contract for an escrow: zero before funding and once the
+    /// escrow is finished, otherwise the amount deposited minus everything paid out so far.
+    pub fn get_remaining_amount(env: Env, escrow_id: u64) -> Result<i128, Error> {
+        let escrow = Self::get_escrow(env.clone(), escrow_id)?;
+        if matches!(
+            escrow.status,
+            EscrowStatus::Draft | EscrowStatus::Proposed | EscrowStatus::Created
+        ) || state_machine::is_terminal(escrow.status)
+        {
+            return Ok(0);
+        }
+        held_amount(&env, escrow_id, &escrow)
+    }
+
+    /// Indices of the milestones that can still be released, in index order. Disputed and
+    /// unfunded milestones and every milestone of a finished escrow are excluded.
+    pub fn get_pending_milestones(env: Env, escrow_id: u64) -> Result<Vec<u32>, Error> {
+        let escrow = Self::get_escrow(env.clone(), escrow_id)?;
+        let mut pending = Vec::new(&env);
+        if state_machine::is_terminal(escrow.status) {
+            return Ok(pending);
+        }
+        let funded = reported_funding(&env, escrow_id, &escrow);
+        for index in 0..escrow.milestone_count {
+            if reported_status(&escrow, funded, index) == MilestoneStatus::Pending {
+                pending.push_back(index);
+            }
+        }
+        Ok(pending)
+    }
+
+    /// Read-only helper to fetch escrow status
+    pub fn get_state(env: Env, escrow_id: u64) -> Result<EscrowStatus, Error> {
+        let escrow = Self::get_escrow(env, escrow_id)?;
+        Ok(escrow.status)
+    }
+
+    /// Evaluates the guards of `release_milestone` for `caller` without changing state.
+    /// Returns `None` if the release would go through, otherwise the code of the `Error` it
+    /// would fail with.
+    pub fn can_release(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+        caller: Address,
+    ) -> Option<u32> {
+        let check = || -> Result<(), Error> {
+            ensure_not_paused(&env)?;
+            let escrow = load_live_escrow(&env, escrow_id)?;
+            if caller != depositor_authority(&env, escrow_id, &escrow) {
+                return Err(Error::UnauthorizedAccess);
+            }
+            release_guard(&env, escrow_id, &escrow, milestone_index)?;
+            VaultixEscrow::get_treasury(env.clone())?;
+            ensure_not_blocked(&env, &payout_address(&env, escrow_id, &escrow))
+        };
+        check().err().map(|error| error as u32)
+    }
+
+    /// Evaluates the guards of `cancel_escrow` for `caller` without changing state.
+    /// Returns `None` if the cancellation would go through, otherwise the code of the `Error`
+    /// it would fail with.
+    pub fn can_cancel(env: Env, escrow_id: u64, caller: Address) -> Option<u32> {
+        let check = || -> Result<(), Error> {
+            ensure_not_paused(&env)?;
+            let escrow = load_live_escrow(&env, escrow_id)?;
+            if caller != depositor_authority(&env, escrow_id, &escrow) {
+                return Err(Error::UnauthorizedAccess);
+            }
+            cancel_guard(&env, escrow_id, &escrow)
+        };
+        check().err().map(|error| error as u32)
+    }
+
+    /// Allows the recipient to assign future payouts of this escrow to a third party.
+    /// Only the depositor may opt in, and only before the escrow is funded.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `EscrowAlreadyFunded` - If escrow is no longer in Created state
+    pub fn set_assignable(env: Env, escrow_id: u64, assignable: bool) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+
+        escrow.depositor.require_auth();
+
+        if escrow.status != EscrowStatus::Created {
+            return Err(Error::EscrowAlreadyFunded);
+        }
+
+        escrow.assignable = assignable;
+        save_escrow(&env, escrow_id, &escrow);
+
+        Ok(())
+    }
+
+    /// Assigns the right to receive subsequent payouts to `assignee` (e.g. an invoice factor).
+    /// The current claim holder must authorize: the recipient, or the previous assignee once assigned.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `UnauthorizedAccess` - If the depositor did not opt in to assignment
+    /// * `InvalidEscrowStatus` - If escrow is not Created or Active
+    pub fn assign_claim(env: Env, escrow_id: u64, assignee: Address) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let escrow = load_live_escrow(&env, escrow_id)?;
+
+        let holder = payout_address(&env, escrow_id, &escrow);
+        holder.require_auth();
+
+        if !escrow.assignable {
+            return Err(Error::UnauthorizedAccess);
+        }
+
+        if escrow.status != EscrowStatus::Active && escrow.status != EscrowStatus::Created {
+            return Err(Error::InvalidEscrowStatus);
+        }
+
+        ensure_not_blocked(&env, &assignee)?;
+
+        let assignee_key = assignee_storage_key(escrow_id);
+        write_persistent(&env, &assignee_key, &assignee);
+
+        history::record(&env, escrow_id, symbol_short!("assigned"), &holder, 0);
+        env.events()
+            .publish((symbol_short!("assigned"), escrow_id), (holder, assignee));
+
+        Ok(())
+    }
+
+    /// Attaches an external reference (invoice number, marketplace order id, ...) to an escrow
+    /// so off-chain systems can reconcile it. Only the depositor may set it. Setting a new
+    /// reference replaces the previous one.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `DescriptionTooLong` - If the reference exceeds 64 bytes
+    /// * `EscrowAlreadyExists` - If another escrow already uses this reference
+    pub fn set_external_ref(env: Env, escrow_id: u64, external_ref: String) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let escrow = Self::get_escrow(env.clone(), escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if external_ref.len() > MAX_EXTERNAL_REF_LEN {
+            return Err(Error::DescriptionTooLong);
+        }
+
+        let index_key = external_ref_index_key(&external_ref);
+        if let Some(existing) = env.storage().persistent().get::<_, u64>(&index_key) {
+            if existing != escrow_id {
+                return Err(Error::EscrowAlreadyExists);
+            }
+        }
+
+        let ref_key = external_ref_key(escrow_id);
+        if let Some(previous) = env.storage().persistent().get::<_, String>(&ref_key) {
+            env.storage()
+                .persistent()
+                .remove(&external_ref_index_key(&previous));
+        }
+
+        env.storage().persistent().set(&ref_key, &external_ref);
+        env.storage().persistent().set(&index_key, &escrow_id);
+        extend_persistent(&env, &ref_key);
+        extend_persistent(&env, &index_key);
+
+        Ok(())
+    }
+
+    /// Returns the external reference attached to an escrow, if any.
+    pub fn get_external_ref(env: Env, escrow_id: u64) -> Option<String> {
+        env.storage().persistent().get(&external_ref_key(escrow_id))
+    }
+
+    /// Looks up an escrow by its external reference.
+    pub fn get_escrow_by_ref(env: Env, external_ref: String) -> Result<(u64, Escrow), Error> {
+        let escrow_id: u64 = env
+            .storage()
+            .persistent()
+            .get(&external_ref_index_key(&external_ref))
+            .ok_or(Error::EscrowNotFound)?;
+        let escrow = Self::get_escrow(env, escrow_id)?;
+        Ok((escrow_id, escrow))
+    }
+
+    /// ID of the escrow carrying an external reference, without loading the escrow. Cheaper
+    /// than `get_escrow_by_ref` for support tools that only need to jump to the record.
+    pub fn find_by_ref(env: Env, external_ref: String) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&external_ref_index_key(&external_ref))
+    }
+
+    /// Returns the current assignee of an escrow's payouts, if the claim was assigned.
+    pub fn get_assignee(env: Env, escrow_id: u64) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&assignee_storage_key(escrow_id))
+    }
+
+    /// Releases a specific milestone payment to the recipient with platform fee deduction.
+    ///
+    /// # Arguments
+    /// * `escrow_id` - Identifier of the escrow
+    /// * `milestone_index` - Index of the milestone to release
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `UnauthorizedAccess` - If caller is not the depositor
+    /// * `EscrowNotActive` - If escrow is not in Active state
+    /// * `MilestoneNotFound` - If index is out of bounds
+    /// * `MilestoneAlreadyReleased` - If milestone was already released
+    /// * `InsufficientBalance` - If the milestone has not been funded yet
+    /// * `TreasuryNotInitialized` - If contract not initialized
+    /// * `ApprovalRequired` - If the escrow releases through an approver quorum
+    /// * `ParentNotCompleted` - If the escrow's parent escrow has not completed
+    /// * `RateLimited` - If a backup releasing would exceed its spending limit
+    ///
+    /// The platform fee is deducted from the payout, or charged in the asset's fee token.
+    pub fn release_milestone(env: Env, escrow_id: u64, milestone_index: u32) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+
+        // Verify authorization - only the depositor, or their backup once active, can release
+        let authority = depositor_authority(&env, escrow_id, &escrow);
+        authority.require_auth();
+        release(&env, escrow_id, &mut escrow, milestone_index, &authority)
+    }
+
+    /// Buyer confirms delivery and releases a milestone to the recipient (buyer-driven path).
+    /// The payout goes through the same fee, spending limits and hooks as `release_milestone`,
+    /// so like it this needs the treasury configured, even when the fee is zero.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `UnauthorizedAccess` - If buyer does not hold the depositor's authority
+    /// * `MilestoneAlreadyReleased` - If milestone was already released
+    /// * `TreasuryNotInitialized` - If contract not initialized
+    /// * `ApprovalRequired` - If the escrow releases through an approver quorum
+    /// * `RateLimited` - If a backup confirming would exceed its spending limit
+    pub fn confirm_delivery(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+        buyer: Address,
+    ) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+
+        buyer.require_auth();
+
+        if depositor_authority(&env, escrow_id, &escrow) != buyer {
+            return Err(Error::UnauthorizedAccess);
+        }
+        ensure_no_approval_policy(&env, escrow_id)?;
+
+        release_guard(&env, escrow_id, &escrow, milestone_index)?;
+
+        pay_milestone_recorded(
+            &env,
+            escrow_id,
+            &mut escrow,
+            milestone_index,
+            &buyer,
+            symbol_short!("confirmed"),
+        )
+    }
+
+    /// Cancels an escrow before any milestones are released.
+    /// Returns all funds to the depositor.
+    ///
+    /// # Arguments
+    /// * `escrow_id` - Identifier of the escrow
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `UnauthorizedAccess` - If caller is not the depositor
+    /// * `MilestoneAlreadyReleased` - If any milestone has been released
+    pub fn cancel_escrow(env: Env, escrow_id: u64) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+
+        let authority = depositor_authority(&env, escrow_id, &escrow);
+        authority.require_auth();
+
+        cancel(&env, escrow_id, &mut escrow, &authority)
+    }
+
+    /// Marks an escrow as completed after all milestones are released.
+    pub fn complete_escrow(env: Env, escrow_id: u64) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+
+        let authority = depositor_authority(&env, escrow_id, &escrow);
+        authority.require_auth();
+        complete(&env, escrow_id, &mut escrow, &authority)
+    }
+
+    /// Submits a one-time 1-5 rating about the counterparty once an escrow has finished.
+    ///
+    /// # Arguments
+    /// * `escrow_id` - Escrow in Completed or Resolved state
+    /// * `rater` - Depositor or recipient leaving the rating
+    /// * `score` - Whole-star score between 1 and 5
+    /// * `comment_hash` - Hash of the off-chain review text
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `UnauthorizedAccess` - If rater is not a party to the escrow
+    /// * `InvalidEscrowStatus` - If escrow has not finished
+    /// * `InvalidRating` - If score is outside 1-5
+    /// * `AlreadyRated` - If rater already rated this escrow
+    pub fn submit_rating(
+        env: Env,
+        escrow_id: u64,
+        rater: Address,
+        score: u32,
+        comment_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        rater.require_auth();
+
+        ensure_not_paused(&env)?;
+
+        let escrow = Self::get_escrow(env.clone(), escrow_id)?;
+
+        let ratee = if rater == escrow.depositor {
+            escrow.recipient
+        } else if rater == escrow.recipient {
+            escrow.depositor
+        } else {
+            return Err(Error::UnauthorizedAccess);
+        };
+
+        if escrow.status != EscrowStatus::Completed && escrow.status != EscrowStatus::Resolved {
+            return Err(Error::InvalidEscrowStatus);
+        }
+
+        if !(MIN_RATING..=MAX_RATING).contains(&score) {
+            return Err(Error::InvalidRating);
+        }
+
+        let rating_key = rating_storage_key(escrow_id, &rater);
+        if env.storage().persistent().has(&rating_key) {
+            return Err(Error::AlreadyRated);
+        }
+
+        let rating = Rating {
+            rater: rater.clone(),
+            ratee: ratee.clone(),
+            score,
+            comment_hash,
+        };
+        write_persistent(&env, &rating_key, &rating);
+
+        let summary_key = rating_summary_key(&ratee);
+        let mut summary: RatingSummary = env
+            .storage()
+            .persistent()
+            .get(&summary_key)
+            .unwrap_or_default();
+        summary.count += 1;
+        summary.total_score += score;
+        write_persistent(&env, &summary_key, &summary);
+
+        env.events()
+            .publish((symbol_short!("rated"), escrow_id, rater), (ratee, score));
+
+        Ok(())
+    }
+
+    /// Returns the rating a party left on a given escrow, if any.
+    pub fn get_rating(env: Env, escrow_id: u64, rater: Address) -> Option<Rating> {
+        env.storage()
+            .persistent()
+            .get(&rating_storage_key(escrow_id, &rater))
+    }
+
+    /// Returns the aggregate of all ratings received by an address.
+    pub fn get_rating_summary(env: Env, address: Address) -> RatingSummary {
+        env.storage()
+            .persistent()
+            .get(&rating_summary_key(&address))
+            .unwrap_or_default()
+    }
+
+    /// Registers a reusable escrow template.
+    ///
+    /// # Arguments
+    /// * `owner` - Organization registering the template
+    /// * `token_address` - Token escrows created from the template are denominated in
+    /// * `milestones` - Milestone layout with each share in basis points (must sum to 10000)
+    /// * `duration` - Seconds from creation until the escrow deadline
+    /// * `review_window` - Seconds a depositor has to review a delivered milestone (0 uses the
+    ///   configured default)
+    /// * `arbiter` - Optional address that resolves disputes instead of the admin
+    ///
+    /// # Returns
+    /// The identifier of the new template
+    ///
+    /// # Errors
+    /// * `VectorTooLarge` - If more milestones are provided than the configured cap
+    /// * `TotalAmountMismatch` - If milestone shares do not sum to 100%
+    /// * `DescriptionTooLong` - If a milestone description exceeds the cap
+    pub fn register_template(
+        env: Env,
+        owner: Address,
+        token_address: Address,
+        milestones: Vec<TemplateMilestone>,
+        duration: u64,
+        review_window: u64,
+        arbiter: Option<Address>,
+    ) -> Result<u64, Error> {
+        owner.require_auth();
+
+        ensure_not_paused(&env)?;
+
+        if milestones.len() > load_config(&env).max_milestones {
+            return Err(Error::VectorTooLarge);
+        }
+
+        let mut total_bps: u32 = 0;
+        for milestone in milestones.iter() {
+            if milestone.bps == 0 {
+                return Err(Error::ZeroAmount);
+            }
+            if milestone.description.len() > MAX_DESCRIPTION_LEN {
+                return Err(Error::DescriptionTooLong);
+            }
+            total_bps = total_bps
+                .checked_add(milestone.bps)
+                .ok_or(Error::TotalAmountMismatch)?;
+        }
+        if total_bps as i128 != BPS_DENOMINATOR {
+            return Err(Error::TotalAmountMismatch);
+        }
+
+        let template_id: u64 = env.storage().instance().get(&DataKey::tpl_ctr).unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::tpl_ctr, &(template_id + 1));
+
+        let template = EscrowTemplate {
+            owner: owner.clone(),
+            token_address,
+            milestones,
+            duration,
+            review_window,
+        };
+        let storage_key = template_storage_key(template_id);
+        write_persistent(&env, &storage_key, &template);
+
+        if let Some(arbiter) = arbiter {
+            let arbiter_key = template_arbiter_key(template_id);
+            write_persistent(&env, &arbiter_key, &arbiter);
+        }
+
+        env.events()
+            .publish((symbol_short!("template"), template_id), owner);
+
+        Ok(template_id)
+    }
+
+    /// Retrieves a registered template (read-only)
+    pub fn get_template(env: Env, template_id: u64) -> Result<EscrowTemplate, Error> {
+        env.storage()
+            .persistent()
+            .get(&template_storage_key(template_id))
+            .ok_or(Error::TemplateNotFound)
+    }
+
+    /// Creates an escrow from a template, expanding milestone shares into concrete amounts.
+    /// Any rounding remainder is added to the last milestone so the amounts sum to `total_amount`.
+    /// The escrow takes the template's arbiter and review window.
+    ///
+    /// # Returns
+    /// The identifier assigned to the new escrow
+    ///
+    /// # Errors
+    /// * `TemplateNotFound` - If the template doesn't exist
+    /// * `ZeroAmount` - If a milestone would receive a zero amount
+    /// * `SelfDealing` - If depositor and recipient are the same
+    pub fn create_from_template(
+        env: Env,
+        template_id: u64,
+        depositor: Address,
+        recipient: Address,
+        total_amount: i128,
+    ) -> Result<u64, Error> {
+        depositor.require_auth();
+
+        ensure_not_paused(&env)?;
+
+        let template = Self::get_template(env.clone(), template_id)?;
+
+        let mut milestones = Vec::new(&env);
+        let mut allocated: i128 = 0;
+        let last_index = template.milestones.len().saturating_sub(1);
+        for (index, slot) in template.milestones.iter().enumerate() {
+            let amount = if index as u32 == last_index {
+                total_amount
+                    .checked_sub(allocated)
+                    .unwrap_or_else(|| fail(&env, ExtendedError::SplitMismatch))
+            } else {
+                calculate_fee(total_amount, slot.bps as i128)
+                    .unwrap_or_else(|| fail(&env, ExtendedError::SplitMismatch))
+            };
+            allocated = allocated
+                .checked_add(amount)
+                .unwrap_or_else(|| fail(&env, ExtendedError::SplitMismatch));
+
+            milestones.push_back(Milestone {
+                amount,
+                status: MilestoneStatus::Pending,
+                description: slot.description,
+            });
+        }
+
+        let deadline = env
+            .ledger()
+            .timestamp()
+            .checked_add(template.duration)
+            .ok_or(Error::InvalidDeadline)?;
+
+        let (escrow, milestones) = new_escrow(
+            &env,
+            depositor,
+            recipient,
+            template.token_address,
+            milestones,
+            deadline,
+        )?;
+        let escrow_id = next_escrow_id(&env);
+        store_new_escrow(&env, escrow_id, &escrow, &milestones)?;
+
+        let template_arbiter: Option<Address> = env
+            .storage()
+            .persistent()
+            .get(&template_arbiter_key(template_id));
+        if let Some(arbiter) = template_arbiter {
+            let arbiter_key = arbiter_storage_key(escrow_id);
+            write_persistent(&env, &arbiter_key, &arbiter);
+        }
+        if template.review_window > 0 {
+            let window_key = review_window_key(escrow_id);
+            write_persistent(&env, &window_key, &template.review_window);
+        }
+
+        Ok(escrow_id)
+    }
+
+    /// Creates a fresh escrow for a repeat engagement by copying the milestone structure,
+    /// token and arbiter of an existing escrow. Milestone statuses and released totals are
+    /// reset; only the source escrow's depositor may clone it.
+    ///
+    /// # Arguments
+    /// * `source_id` - Escrow to copy
+    /// * `new_recipient` - Recipient of the new escrow
+    /// * `deadline` - Deadline of the new escrow
+    ///
+    /// # Returns
+    /// The identifier assigned to the new escrow
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If the source escrow doesn't exist
+    /// * `SelfDealing` - If the depositor and new recipient are the same
+    pub fn clone_escrow(
+        env: Env,
+        source_id: u64,
+        new_recipient: Address,
+        deadline: u64,
+    ) -> Result<u64, Error> {
+        ensure_not_paused(&env)?;
+
+        let source = Self::get_escrow(env.clone(), source_id)?;
+        source.depositor.require_auth();
+
+        let milestones = Self::get_milestones(env.clone(), source_id)?;
+        let (escrow, milestones) = new_escrow(
+            &env,
+            source.depositor,
+            new_recipient,
+            source.token_address,
+            milestones,
+            deadline,
+        )?;
+        let escrow_id = next_escrow_id(&env);
+        store_new_escrow(&env, escrow_id, &escrow, &milestones)?;
+
+        if let Some(arbiter) = Self::get_arbiter(env.clone(), source_id) {
+            let arbiter_key = arbiter_storage_key(escrow_id);
+            write_persistent(&env, &arbiter_key, &arbiter);
+        }
+
+        Ok(escrow_id)
+    }
+
+    /// Creates a recurring escrow that pays `schedule.amount` every `schedule.interval` seconds,
+    /// `schedule.count` times, once funded. The full `amount * count` is deposited upfront via
+    /// `deposit_funds`, and each elapsed period becomes a claimable milestone.
+    ///
+    /// # Errors
+    /// * `EscrowAlreadyExists` - If escrow_id is already in use
+    /// * `ZeroAmount` - If amount, interval or count is zero
+    /// * `VectorTooLarge` - If more periods are requested than the configured milestone cap
+    /// * `SelfDealing` - If depositor and recipient are the same
+    pub fn create_recurring_escrow(
+        env: Env,
+        escrow_id: u64,
+        depositor: Address,
+        recipient: Address,
+        token_address: Address,
+        schedule: RecurringSchedule,
+    ) -> Result<(), Error> {
+        depositor.require_auth();
+
+        ensure_not_paused(&env)?;
+        ensure_caller_chosen_id(escrow_id)?;
+
+        if schedule.amount <= 0 || schedule.interval == 0 || schedule.count == 0 {
+            return Err(Error::ZeroAmount);
+        }
+        if schedule.count > load_config(&env).max_milestones {
+            return Err(Error::VectorTooLarge);
+        }
+
+        let total_amount = schedule
+            .amount
+            .checked_mul(schedule.count as i128)
+            .ok_or(Error::InvalidMilestoneAmount)?;
+        let duration = schedule
+            .interval
+            .checked_mul(schedule.count as u64)
+            .ok_or(Error::InvalidDeadline)?;
+        let deadline = env
+            .ledger()
+            .timestamp()
+            .checked_add(duration)
+            .ok_or(Error::InvalidDeadline)?;
+
+        // Periods are materialized as milestones while the schedule runs
+        let (mut escrow, milestones) = new_escrow(
+            &env,
+            depositor,
+            recipient,
+            token_address,
+            Vec::new(&env),
+            deadline,
+        )?;
+        escrow.total_amount = total_amount;
+        store_new_escrow(&env, escrow_id, &escrow, &milestones)?;
+
+        let schedule_key = recurring_key(escrow_id);
+        write_persistent(&env, &schedule_key, &schedule);
+
+        Ok(())
+    }
+
+    /// Returns the payment schedule of a recurring escrow, if it is one.
+    pub fn get_recurring_schedule(env: Env, escrow_id: u64) -> Option<RecurringSchedule> {
+        env.storage().persistent().get(&recurring_key(escrow_id))
+    }
+
+    /// Materializes every period that has elapsed since funding as a milestone and pays all
+    /// of them out to the recipient. The escrow completes once the last period is claimed.
+    ///
+    /// # Returns
+    /// The net amount paid out
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If escrow is not a recurring escrow
+    /// * `EscrowNotActive` - If escrow is not funded or is disputed
+    /// * `MilestoneNotFound` - If no period is currently claimable
+    pub fn claim_recurring(env: Env, escrow_id: u64) -> Result<i128, Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+
+        escrow.recipient.require_auth();
+
+        let schedule = Self::get_recurring_schedule(env.clone(), escrow_id)
+            .ok_or(Error::InvalidEscrowStatus)?;
+
+        state_machine::ensure_active(escrow.status)?;
+
+        let due = recurring_periods_due(&env, escrow_id, &schedule, env.ledger().timestamp());
+        while escrow.milestone_count < due {
+            let period = Milestone {
+                amount: schedule.amount,
+                status: MilestoneStatus::Pending,
+                description: String::from_str(&env, "Recurring period"),
+            };
+            save_milestone(&env, escrow_id, escrow.milestone_count, &period);
+            escrow.milestone_count += 1;
+        }
+
+        let mut claimable: i128 = 0;
+        let mut first_index: Option<u32> = None;
+        for index in 0..escrow.milestone_count {
+            if escrow.released_mask & milestone_bit(index) == 0 {
+                let milestone = load_milestone(&env, escrow_id, index)?;
+                claimable = claimable
+                    .checked_add(milestone.amount)
+                    .ok_or(Error::InvalidMilestoneAmount)?;
+                first_index.get_or_insert(index);
+                escrow.released_mask |= milestone_bit(index);
+            }
+        }
+        let first_index = first_index.ok_or(Error::MilestoneNotFound)?;
+
+        escrow.total_released = escrow
+            .total_released
+            .checked_add(claimable)
+            .ok_or(Error::InvalidMilestoneAmount)?;
+        if escrow.total_released == escrow.total_amount {
+            state_machine::transition(&mut escrow, EscrowStatus::Completed)?;
+        }
+
+        save_escrow(&env, escrow_id, &escrow);
+
+        let (payout, payee) = pay_out_with_fee(&env, escrow_id, &escrow, claimable, first_index)?;
+
+        if escrow.status == EscrowStatus::Completed {
+            burn_receipt(&env, escrow_id);
+        }
+
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("rec_claim"),
+            &escrow.recipient,
+            claimable,
+        );
+        stats::funds_released(&env, &escrow.token_address, claimable);
+        if escrow.status == EscrowStatus::Completed {
+            finish_funded(&env, escrow_id, &escrow)?;
+        }
+        env.events().publish(
+            (symbol_short!("rec_claim"), escrow_id, first_index),
+            (payout, payee),
+        );
+
+        Ok(payout)
+    }
+
+    /// Cancels a funded recurring escrow. Periods that fall due before the notice period ends
+    /// remain claimable by the recipient; funds for all later periods are refunded now.
+    ///
+    /// # Returns
+    /// The amount refunded to the depositor
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If escrow is not a recurring escrow
+    /// * `EscrowNotActive` - If escrow is not funded or is disputed
+    pub fn cancel_recurring(env: Env, escrow_id: u64) -> Result<i128, Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+
+        escrow.depositor.require_auth();
+
+        let schedule_key = recurring_key(escrow_id);
+        let mut schedule = Self::get_recurring_schedule(env.clone(), escrow_id)
+            .ok_or(Error::InvalidEscrowStatus)?;
+
+        state_machine::ensure_active(escrow.status)?;
+
+        let notice_end = env
+            .ledger()
+            .timestamp()
+            .saturating_add(schedule.notice_period);
+        let owed_periods = recurring_periods_due(&env, escrow_id, &schedule, notice_end)
+            .max(escrow.milestone_count);
+
+        let refund = schedule
+            .amount
+            .checked_mul((schedule.count - owed_periods) as i128)
+            .ok_or(Error::InvalidMilestoneAmount)?;
+
+        schedule.count = owed_periods;
+        escrow.total_amount = escrow
+            .total_amount
+            .checked_sub(refund)
+            .ok_or(Error::InvalidMilestoneAmount)?;
+        if escrow.total_released == escrow.total_amount {
+            state_machine::transition(&mut escrow, EscrowStatus::Cancelled)?;
+        }
+
+        env.storage().persistent().set(&schedule_key, &schedule);
+        save_escrow(&env, escrow_id, &escrow);
+
+        if refund > 0 {
+            let token_client = token::Client::new(&env, &escrow.token_address);
+            token_client.transfer(&env.current_contract_address(), &escrow.depositor, &refund);
+        }
+
+        if escrow.status == EscrowStatus::Cancelled {
+            burn_receipt(&env, escrow_id);
+        }
+
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("rec_stop"),
+            &escrow.depositor,
+            refund,
+        );
+        stats::funds_refunded(&env, &escrow.token_address, refund);
+        if escrow.status == EscrowStatus::Cancelled {
+            finish_funded(&env, escrow_id, &escrow)?;
+        }
+        env.events().publish(
+            (symbol_short!("rec_stop"), escrow_id),
+            (owed_periods, refund),
+        );
+
+        Ok(refund)
+    }
+
+    /// Turns a milestone into a stream that vests linearly between two ledgers.
+    /// Only the depositor may configure streams, and only before the escrow is funded.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `MilestoneNotFound` - If index is out of bounds
+    /// * `EscrowAlreadyFunded` - If escrow is no longer in Created state
+    /// * `InvalidDeadline` - If the stream does not end after it starts
+    pub fn set_milestone_stream(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+        start_ledger: u32,
+        end_ledger: u32,
+    ) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let escrow = Self::get_escrow(env.clone(), escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if milestone_index >= escrow.milestone_count {
+            return Err(Error::MilestoneNotFound);
+        }
+        if escrow.status != EscrowStatus::Created {
+            return Err(Error::EscrowAlreadyFunded);
+        }
+        if end_ledger <= start_ledger {
+            return Err(Error::InvalidDeadline);
+        }
+
+        let stream = StreamConfig {
+            start_ledger,
+            end_ledger,
+            claimed: 0,
+        };
+        let stream_key = stream_storage_key(escrow_id, milestone_index);
+        write_persistent(&env, &stream_key, &stream);
+
+        Ok(())
+    }
+
+    /// Returns the stream configuration of a milestone, if it is a streaming milestone.
+    pub fn get_milestone_stream(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+    ) -> Option<StreamConfig> {
+        env.storage()
+            .persistent()
+            .get(&stream_storage_key(escrow_id, milestone_index))
+    }
+
+    /// Pays the recipient the portion of a streaming milestone vested so far.
+    /// While the escrow is disputed, vesting is frozen at the ledger the dispute was raised.
+    ///
+    /// # Returns
+    /// The net amount paid out
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `MilestoneNotFound` - If index is out of bounds
+    /// * `InvalidEscrowStatus` - If the milestone is not a stream
+    /// * `EscrowNotActive` - If escrow is not funded, or already finished
+    /// * `MilestoneAlreadyReleased` - If the milestone was already fully paid
+    /// * `ZeroAmount` - If nothing new has vested since the last claim
+    /// * `ParentNotCompleted` - If the escrow's parent escrow has not completed
+    pub fn claim_stream(env: Env, escrow_id: u64, milestone_index: u32) -> Result<i128, Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+
+        escrow.recipient.require_auth();
+
+        if milestone_index >= escrow.milestone_count {
+            return Err(Error::MilestoneNotFound);
+        }
+        ensure_funded(&env, escrow_id, &escrow, milestone_index)?;
+        ensure_parent_completed(&env, escrow_id)?;
+        let milestone = load_milestone(&env, escrow_id, milestone_index)?;
+
+        let stream_key = stream_storage_key(escrow_id, milestone_index);
+        let mut stream: StreamConfig = env
+            .storage()
+            .persistent()
+            .get(&stream_key)
+            .ok_or(Error::InvalidEscrowStatus)?;
+
+        let mut vest_until = env.ledger().sequence();
+        match escrow.status {
+            EscrowStatus::Active => {}
+            EscrowStatus::Disputed => {
+                let frozen_at: u32 = env
+                    .storage()
+                    .persistent()
+                    .get(&dispute_ledger_key(escrow_id))
+                    .unwrap_or(vest_until);
+                vest_until = vest_until.min(frozen_at);
+            }
+            _ => return Err(Error::EscrowNotActive),
+        }
+
+        if escrow.released_mask & milestone_bit(milestone_index) != 0 {
+            return Err(Error::MilestoneAlreadyReleased);
+        }
+
+        let vested = vested_amount(milestone.amount, &stream, vest_until)?;
+        let claimable = vested
+            .checked_sub(stream.claimed)
+            .ok_or(Error::InvalidMilestoneAmount)?;
+        if claimable <= 0 {
+            return Err(Error::ZeroAmount);
+        }
+
+        stream.claimed = vested;
+        if stream.claimed == milestone.amount {
+            escrow.released_mask |= milestone_bit(milestone_index);
+        }
+        escrow.total_released = escrow
+            .total_released
+            .checked_add(claimable)
+            .ok_or(Error::InvalidMilestoneAmount)?;
+
+        env.storage().persistent().set(&stream_key, &stream);
+        save_escrow(&env, escrow_id, &escrow);
+
+        let (payout, payee) =
+            pay_out_with_fee(&env, escrow_id, &escrow, claimable, milestone_index)?;
+
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("streamed"),
+            &escrow.recipient,
+            claimable,
+        );
+        stats::funds_released(&env, &escrow.token_address, claimable);
+        env.events().publish(
+            (symbol_short!("streamed"), escrow_id, milestone_index),
+            (payout, payee),
+        );
+
+        Ok(payout)
+    }
+
+    /// Creates a vesting escrow with no manual approvals: `total_amount` vests on `schedule`
+    /// (cliff, then linear) once funded, and the recipient claims with `claim_vested`.
+    /// The depositor can only intervene through a dispute or `clawback_vesting`.
+    ///
+    /// # Errors
+    /// * `EscrowAlreadyExists` - If escrow_id is already in use
+    /// * `ZeroAmount` - If total_amount is zero or negative
+    /// * `InvalidDeadline` - If the duration is zero or the cliff exceeds it
+    /// * `SelfDealing` - If depositor and recipient are the same
+    pub fn create_vesting_escrow(
+        env: Env,
+        escrow_id: u64,
+        depositor: Address,
+        recipient: Address,
+        token_address: Address,
+        total_amount: i128,
+        schedule: VestingSchedule,
+    ) -> Result<(), Error> {
+        depositor.require_auth();
+
+        ensure_not_paused(&env)?;
+        ensure_caller_chosen_id(escrow_id)?;
+
+        if schedule.duration == 0 || schedule.cliff > schedule.duration {
+            return Err(Error::InvalidDeadline);
+        }
+        let deadline = schedule
+            .start
+            .checked_add(schedule.duration)
+            .ok_or(Error::InvalidDeadline)?;
+
+        let milestones = Vec::from_array(
+            &env,
+            [Milestone {
+                amount: total_amount,
+                status: MilestoneStatus::Pending,
+                description: String::from_str(&env, "Vesting"),
+            }],
+        );
+        let (escrow, milestones) = new_escrow(
+            &env,
+            depositor,
+            recipient,
+            token_address,
+            milestones,
+            deadline,
+        )?;
+        store_new_escrow(&env, escrow_id, &escrow, &milestones)?;
+
+        let schedule_key = vesting_key(escrow_id);
+        write_persistent(&env, &schedule_key, &schedule);
+
+        Ok(())
+    }
+
+    /// Returns the vesting schedule of a vesting escrow, if it is one.
+    pub fn get_vesting_schedule(env: Env, escrow_id: u64) -> Option<VestingSchedule> {
+        env.storage().persistent().get(&vesting_key(escrow_id))
+    }
+
+    /// Pays the recipient everything vested and not yet claimed. The escrow completes once
+    /// the full amount has been claimed.
+    ///
+    /// # Returns
+    /// The net amount paid out
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If escrow is not a vesting escrow
+    /// * `EscrowNotActive` - If escrow is not funded or is disputed
+    /// * `ZeroAmount` - If nothing new has vested since the last claim
+    pub fn claim_vested(env: Env, escrow_id: u64) -> Result<i128, Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+
+        escrow.recipient.require_auth();
+
+        let schedule =
+            Self::get_vesting_schedule(env.clone(), escrow_id).ok_or(Error::InvalidEscrowStatus)?;
+
+        state_machine::ensure_active(escrow.status)?;
+
+        let vested = vested_by_schedule(escrow.total_amount, &schedule, env.ledger().timestamp())?;
+        let claimable = vested
+            .checked_sub(escrow.total_released)
+            .ok_or(Error::InvalidMilestoneAmount)?;
+        if claimable <= 0 {
+            return Err(Error::ZeroAmount);
+        }
+
+        escrow.total_released = vested;
+        if vested == escrow.total_amount {
+            escrow.released_mask = milestone_bit(0);
+            state_machine::transition(&mut escrow, EscrowStatus::Completed)?;
+        }
+
+        save_escrow(&env, escrow_id, &escrow);
+
+        let (payout, payee) = pay_out_with_fee(&env, escrow_id, &escrow, claimable, 0)?;
+
+        if escrow.status == EscrowStatus::Completed {
+            burn_receipt(&env, escrow_id);
+        }
+
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("vested"),
+            &escrow.recipient,
+            claimable,
+        );
+        stats::funds_released(&env, &escrow.token_address, claimable);
+        if escrow.status == EscrowStatus::Completed {
+            finish_funded(&env, escrow_id, &escrow)?;
+        }
+        env.events()
+            .publish((symbol_short!("vested"), escrow_id), (payout, payee));
+
+        Ok(payout)
+    }
+
+    /// Stops a vesting escrow: the recipient is paid everything vested so far and the
+    /// unvested remainder is returned to the depositor. Only the depositor may call this.
+    ///
+    /// # Returns
+    /// The amount returned to the depositor
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If escrow is not a vesting escrow
+    /// * `EscrowNotActive` - If escrow is not funded or is disputed
+    pub fn clawback_vesting(env: Env, escrow_id: u64) -> Result<i128, Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+
+        escrow.depositor.require_auth();
+
+        let schedule =
+            Self::get_vesting_schedule(env.clone(), escrow_id).ok_or(Error::InvalidEscrowStatus)?;
+
+        state_machine::ensure_active(escrow.status)?;
+
+        let vested = vested_by_schedule(escrow.total_amount, &schedule, env.ledger().timestamp())?;
+        let owed = vested
+            .checked_sub(escrow.total_released)
+            .ok_or(Error::InvalidMilestoneAmount)?;
+        let clawback = escrow
+            .total_amount
+            .checked_sub(vested)
+            .ok_or(Error::InvalidMilestoneAmount)?;
+
+        escrow.total_amount = vested;
+        escrow.total_released = vested;
+        state_machine::transition(&mut escrow, EscrowStatus::Cancelled)?;
+        save_escrow(&env, escrow_id, &escrow);
+
+        if owed > 0 {
+            pay_out_with_fee(&env, escrow_id, &escrow, owed, 0)?;
+        }
+        if clawback > 0 {
+            let token_client = token::Client::new(&env, &escrow.token_address);
+            token_client.transfer(
+                &env.current_contract_address(),
+                &escrow.depositor,
+                &clawback,
+            );
+        }
+
+        burn_receipt(&env, escrow_id);
+
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("clawback"),
+            &escrow.depositor,
+            clawback,
+        );
+        stats::funds_released(&env, &escrow.token_address, owed);
+        stats::funds_refunded(&env, &escrow.token_address, clawback);
+        finish_funded(&env, escrow_id, &escrow)?;
+        env.events()
+            .publish((symbol_short!("clawback"), escrow_id), (owed, clawback));
+
+        Ok(clawback)
+    }
+
+    /// Returns the reputation counters for an address (all zero if it has no history).
+    pub fn get_reputation(env: Env, address: Address) -> Reputation {
+        env.storage()
+            .persistent()
+            .get(&reputation_key(&address))
+            .unwrap_or_default()
+    }
+}
+
+/// Validates milestones and builds a new escrow in Created state, returning the escrow
+/// header together with its initialized milestones.
+pub(crate) fn new_escrow(
+    env: &Env,
+    depositor: Address,
+    recipient: Address,
+    token_address: Address,
+    milestones: Vec<Milestone>,
+    deadline: u64,
+) -> Result<(Escrow, Vec<Milestone>), Error> {
+    if depositor == recipient {
+        return Err(Error::SelfDealing);
+    }
+
+    let total_amount = validate_milestones(env, &milestones)?;
+
+    let mut initialized_milestones = Vec::new(env);
+    for mut milestone in milestones.iter() {
+        milestone.status = MilestoneStatus::Pending;
+        initialized_milestones.push_back(milestone);
+    }
+
+    let escrow = Escrow {
+        depositor,
+        recipient,
+        token_address,
+        total_amount,
+        total_released: 0,
+        milestone_count: initialized_milestones.len(),
+        released_mask: 0,
+        disputed_mask: 0,
+        status: EscrowStatus::Created, // Initially Created, becomes Active after deposit
+        deadline,
+        resolution: Resolution::None,
+        assignable: false,
+    };
+    Ok((escrow, initialized_milestones))
+}
+
+/// Records now as the escrow's creation time, which the dispute grace period counts from.
+pub(crate) fn stamp_created_at(env: &Env, escrow_id: u64) {
+    let created_key = created_at_key(escrow_id);
+    write_persistent(env, &created_key, &env.ledger().timestamp());
+}
+
+/// Checks an escrow's total against the limits configured for its token, if any.
+pub(crate) fn ensure_within_amount_limits(env: &Env, escrow: &Escrow) -> Result<(), Error> {
+    if let Some(limits) =
+        VaultixEscrow::get_amount_limits(env.clone(), escrow.token_address.clone())
+    {
+        if escrow.total_amount < limits.min || escrow.total_amount > limits.max {
+            return Err(Error::AmountOutOfRange);
+        }
+    }
+    Ok(())
+}
+
+/// With a `dust_precision` configured, rejects milestones worth less than one unit at that many
+/// decimal places of the token, e.g. below 0.01 of a 7-decimal token at precision 2. The token's
+/// decimals are only queried when the check is enabled.
+pub(crate) fn ensure_not_dust(
+    env: &Env,
+    token_address: &Address,
+    milestones: &Vec<Milestone>,
+) -> Result<(), Error> {
+    let precision = load_config(env).dust_precision;
+    if precision == 0 {
+        return Ok(());
+    }
+    let decimals = token::Client::new(env, token_address).decimals();
+    let smallest = 10i128
+        .checked_pow(decimals.saturating_sub(precision))
+        .unwrap_or(i128::MAX);
+    if milestones
+        .iter()
+        .any(|milestone| milestone.amount < smallest)
+    {
+        return Err(Error::InvalidMilestoneAmount);
+    }
+    Ok(())
+}
+
+/// Replaces the milestones and deadline of an escrow that has not been funded yet, validating
+/// them as creation would and dropping milestone entries past the new count. Returns the
+/// milestones as stored.
+pub(crate) fn replace_terms(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &mut Escrow,
+    milestones: Vec<Milestone>,
+    deadline: u64,
+) -> Result<Vec<Milestone>, Error> {
+    let (revised, milestones) = new_escrow(
+        env,
+        escrow.depositor.clone(),
+        escrow.recipient.clone(),
+        escrow.token_address.clone(),
+        milestones,
+        deadline,
+    )?;
+    ensure_within_amount_limits(env, &revised)?;
+    ensure_not_dust(env, &revised.token_address, &milestones)?;
+    ensure_min_value(env, &revised.token_address, &milestones)?;
+
+    for index in revised.milestone_count..escrow.milestone_count {
+        env.storage()
+            .persistent()
+            .remove(&milestone_storage_key(escrow_id, index));
+    }
+    for (index, milestone) in milestones.iter().enumerate() {
+        save_milestone(env, escrow_id, index as u32, &milestone);
+    }
+    escrow.total_amount = revised.total_amount;
+    escrow.milestone_count = revised.milestone_count;
+    escrow.deadline = deadline;
+    save_escrow(env, escrow_id, escrow);
+    Ok(milestones)
+}
+
+/// Persists a freshly built escrow, minting its receipt and emitting the `create` event.
+/// Callers are responsible for authorization.
+pub(crate) fn store_new_escrow(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &Escrow,
+    milestones: &Vec<Milestone>,
+) -> Result<(), Error> {
+    let storage_key = get_storage_key(escrow_id);
+    if escrow_exists(env, escrow_id) {
+        return Err(Error::EscrowAlreadyExists);
+    }
+
+    // Open bounties have no recipient to vet until someone claims them
+    let has_recipient = escrow.status != EscrowStatus::Open;
+    ensure_not_blocked(env, &escrow.depositor)?;
+    ensure_allowed(env, &escrow.depositor)?;
+    if has_recipient {
+        ensure_not_blocked(env, &escrow.recipient)?;
+        ensure_allowed(env, &escrow.recipient)?;
+    }
+    ensure_token_allowed(env, &escrow.token_address)?;
+    consume_creation_quota(env, &escrow.depositor)?;
+    if has_recipient {
+        claim_pair_slot(env, escrow_id, escrow)?;
+    }
+
+    ensure_within_amount_limits(env, escrow)?;
+    ensure_not_dust(env, &escrow.token_address, milestones)?;
+    ensure_min_value(env, &escrow.token_address, milestones)?;
+
+    env.storage().persistent().set(&storage_key, escrow);
+    for (index, milestone) in milestones.iter().enumerate() {
+        save_milestone(env, escrow_id, index as u32, &milestone);
+    }
+
+    stamp_created_at(env, escrow_id);
+    currency::snapshot(env, escrow_id, &escrow.token_address);
+    index_escrow(env, escrow_id);
+    if has_recipient {
+        agreement::apply(env, escrow_id, escrow);
+    }
+    let grace = load_config(env).dispute_grace;
+    if grace > 0 {
+        let grace_key = dispute_grace_key(escrow_id);
+        write_persistent(env, &grace_key, &grace);
+    }
+
+    // Extend TTL for long-term storage
+    extend_persistent(env, &storage_key);
+
+    if has_recipient {
+        mint_receipt(env, escrow_id, &escrow.recipient);
+    }
+
+    history::record(
+        env,
+        escrow_id,
+        symbol_short!("create"),
+        &escrow.depositor,
+        escrow.total_amount,
+    );
+    stats::escrow_created(env);
+
+    // Emit event
+    env.events().publish(
+        (
+            symbol_short!("create"),
+            escrow_id,
+            escrow.depositor.clone(),
+            escrow.recipient.clone(),
+        ),
+        escrow.total_amount,
+    );
+
+    Ok(())
+}
+
+/// Milestones reported as funded: all of them until the escrow is funded at all, so
+/// unfunded escrows keep reporting `Pending`.
+fn reported_funding(env: &Env, escrow_id: u64, escrow: &Escrow) -> u32 {
+    match escrow.status {
+        EscrowStatus::Draft | EscrowStatus::Proposed | EscrowStatus::Created => {
+            escrow.milestone_count
+        }
+        _ => funding(env, escrow_id, escrow).0,
+    }
+}
+
+/// Status of a milestone as reported to callers, given the first `funded` milestones are
+/// backed by deposits.
+fn reported_status(escrow: &Escrow, funded: u32, milestone_index: u32) -> MilestoneStatus {
+    match milestone_status(escrow, milestone_index) {
+        MilestoneStatus::Pending if milestone_index >= funded => MilestoneStatus::Unfunded,
+        status => status,
+    }
+}
+
+/// Loads a live (non-archived) escrow header.
+pub(crate) fn load_live_escrow(env: &Env, escrow_id: u64) -> Result<Escrow, Error> {
+    read_persistent(env, &get_storage_key(escrow_id)).ok_or(Error::EscrowNotFound)
+}
+
+/// Writes a live escrow header back and bumps its TTL.
+pub(crate) fn save_escrow(env: &Env, escrow_id: u64, escrow: &Escrow) {
+    let storage_key = get_storage_key(escrow_id);
+    write_persistent(env, &storage_key, escrow);
+}
+
+/// Funds an escrow whose depositor has already been authorized, moving it to Active.
+pub(crate) fn fund(env: &Env, escrow_id: u64, escrow: &mut Escrow) -> Result<(), Error> {
+    ensure_not_blocked(env, &escrow.depositor)?;
+
+    // Drafts must be activated and proposals accepted first; otherwise check escrow
+    // hasn't already been funded, and move it to Active
+    if matches!(escrow.status, EscrowStatus::Draft | EscrowStatus::Proposed) {
+        return Err(Error::InvalidEscrowStatus);
+    }
+    if escrow.status != EscrowStatus::Created {
+        return Err(Error::EscrowAlreadyFunded);
+    }
+    state_machine::transition(escrow, EscrowStatus::Active)?;
+
+    // Recurring escrows start accruing periods from the moment they are funded
+    if env.storage().persistent().has(&recurring_key(escrow_id)) {
+        let start_key = recurring_start_key(escrow_id);
+        write_persistent(env, &start_key, &env.ledger().timestamp());
+    }
+
+    // Save updated escrow
+    write_persistent(env, &get_storage_key(escrow_id), escrow);
+
+    collect_deposit(env, escrow_id, escrow, escrow.total_amount)?;
+    nft::swap(env, escrow_id)?;
+    Ok(())
+}
+
+/// Releases a milestone on behalf of `authority`, which has already been authorized.
+pub(crate) fn release(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &mut Escrow,
+    milestone_index: u32,
+    authority: &Address,
+) -> Result<(), Error> {
+    ensure_no_approval_policy(env, escrow_id)?;
+
+    // CHECK IF ALREADY RELEASED FIRST - BEFORE ANY TOKEN OPERATIONS
+    release_guard(env, escrow_id, escrow, milestone_index)?;
+
+    pay_milestone(env, escrow_id, escrow, milestone_index, authority)
+}
+
+/// Completes a fully released escrow on behalf of `authority`, which has already been
+/// authorized.
+pub(crate) fn complete(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &mut Escrow,
+    authority: &Address,
+) -> Result<(), Error> {
+    state_machine::transition(escrow, EscrowStatus::Completed)?;
+
+    if !verify_all_released(escrow) || escrow.total_released != escrow.total_amount {
+        return Err(Error::EscrowNotActive);
+    }
+    save_escrow(env, escrow_id, escrow);
+    history::record(env, escrow_id, symbol_short!("completed"), authority, 0);
+    finish_funded(env, escrow_id, escrow)?;
+
+    burn_receipt(env, escrow_id);
+    credit_completion(env, escrow_id, escrow)?;
+
+    // Extend TTL
+    extend_persistent(env, &get_storage_key(escrow_id));
+
+    Ok(())
+}
+
+/// Cancels an escrow whose canceller `actor` has already been authorized, refunding any
+/// deposit.
+pub(crate) fn cancel(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &mut Escrow,
+    actor: &Address,
+) -> Result<(), Error> {
+    let storage_key = get_storage_key(escrow_id);
+
+    cancel_guard(env, escrow_id, escrow)?;
+    let funded = matches!(escrow.status, EscrowStatus::Active | EscrowStatus::Open);
+    // Unclaimed bounties never had a receipt minted
+    let has_receipt = escrow.status != EscrowStatus::Open;
+    let refunded = if funded {
+        held_amount(env, escrow_id, escrow)?
+    } else {
+        0
+    };
+    state_machine::transition(escrow, EscrowStatus::Cancelled)?;
+    env.storage().persistent().set(&storage_key, escrow);
+
+    // If escrow was funded (Active status), refund everything deposited to the depositor
+    if refunded > 0 {
+        shortfall::ensure_covered(env, &escrow.token_address)?;
+        token::Client::new(env, &escrow.token_address).transfer(
+            &env.current_contract_address(),
+            &escrow.depositor,
+            &refunded,
+        );
+    }
+
+    history::record(env, escrow_id, symbol_short!("cancelled"), actor, refunded);
+    if funded {
+        stats::funds_refunded(env, &escrow.token_address, refunded);
+        finish_funded(env, escrow_id, escrow)?;
+    }
+
+    if has_receipt {
+        burn_receipt(env, escrow_id);
+    }
+
+    // Extend TTL
+    extend_persistent(env, &storage_key);
+
+    Ok(())
+}
+
+/// Pays out a milestone that has passed `release_guard`, recording `actor` as its releaser.
+pub(crate) fn pay_milestone(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &mut Escrow,
+    milestone_index: u32,
+    actor: &Address,
+) -> Result<(), Error> {
+    let released = symbol_short!("released");
+    pay_milestone_recorded(env, escrow_id, escrow, milestone_index, actor, released)
+}
+
+/// `pay_milestone`, recording the payout in the history as `action`.
+fn pay_milestone_recorded(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &mut Escrow,
+    milestone_index: u32,
+    actor: &Address,
+    action: Symbol,
+) -> Result<(), Error> {
+    let milestone = load_milestone(env, escrow_id, milestone_index)?;
+
+    // Releasing a streaming milestone pays out whatever has not been streamed yet
+    let amount = unreleased_amount(env, escrow_id, milestone_index, &milestone)?;
+    spending::charge(env, escrow_id, actor, amount)?;
+
+    // The release is saved before any token moves, so a token calling back in finds the
+    // milestone already paid
+    escrow.released_mask |= milestone_bit(milestone_index);
+
+    escrow.total_released = escrow
+        .total_released
+        .checked_add(amount)
+        .ok_or(Error::InvalidMilestoneAmount)?;
+
+    save_escrow(env, escrow_id, escrow);
+    let (payout, payee) = pay_out_with_fee(env, escrow_id, escrow, amount, milestone_index)?;
+
+    history::record(env, escrow_id, action, actor, amount);
+    stats::funds_released(env, &escrow.token_address, amount);
+
+    // Emit event for milestone release
+    #[allow(deprecated)]
+    env.events().publish(
+        (symbol_short!("released"), escrow_id, milestone_index),
+        (payout, payee),
+    );
+    hooks::on_release(env, escrow_id, milestone_index, amount);
+
+    fire_triggers(env, escrow_id, milestone_index)
+}
+
+/// Checks shared by every path that releases a single milestone.
+pub(crate) fn release_guard(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &Escrow,
+    milestone_index: u32,
+) -> Result<(), Error> {
+    state_machine::ensure_active(escrow.status)?;
+
+    // Vesting escrows only pay out on schedule
+    if is_vesting(env, escrow_id) {
+        return Err(Error::InvalidEscrowStatus);
+    }
+
+    if milestone_index >= escrow.milestone_count {
+        return Err(Error::MilestoneNotFound);
+    }
+
+    if escrow.released_mask & milestone_bit(milestone_index) != 0 {
+        return Err(Error::MilestoneAlreadyReleased);
+    }
+    ensure_funded(env, escrow_id, escrow, milestone_index)?;
+    ensure_parent_completed(env, escrow_id)
+}
+
+/// Checks `cancel_escrow` runs before refunding anything.
+pub(crate) fn cancel_guard(env: &Env, escrow_id: u64, escrow: &Escrow) -> Result<(), Error> {
+    // Frozen escrows are wound down through `write_off_shortfall`
+    if escrow.status == EscrowStatus::Frozen
+        || !state_machine::can_transition(escrow.status, EscrowStatus::Cancelled)
+    {
+        return Err(Error::InvalidEscrowStatus);
+    }
+
+    if escrow.total_released > 0 {
+        return Err(Error::MilestoneAlreadyReleased);
+    }
+
+    // Funded recurring escrows must honour their notice period via cancel_recurring,
+    // and funded vesting escrows can only be clawed back via clawback_vesting
+    if escrow.status == EscrowStatus::Active
+        && (env.storage().persistent().has(&recurring_key(escrow_id)) || is_vesting(env, escrow_id))
+    {
+        return Err(Error::InvalidEscrowStatus);
+    }
+    Ok(())
+}
+
+pub(crate) fn ensure_caller_chosen_id(escrow_id: u64) -> Result<(), Error> {
+    if escrow_id & SALTED_ID_FLAG != 0 {
+        return Err(Error::ReservedEscrowId);
+    }
+    Ok(())
+}
+
+/// Escrow ID derived from the parties and a creator-chosen salt, tagged with `SALTED_ID_FLAG`.
+pub(crate) fn salted_escrow_id(
+    env: &Env,
+    depositor: &Address,
+    recipient: &Address,
+    salt: &BytesN<32>,
+) -> u64 {
+    let mut preimage = depositor.clone().to_xdr(env);
+    preimage.append(&recipient.clone().to_xdr(env));
+    preimage.append(&salt.clone().into());
+    let digest = env.crypto().sha256(&preimage).to_array();
+
+    let mut id_bytes = [0u8; 8];
+    id_bytes.copy_from_slice(&digest[..8]);
+    u64::from_be_bytes(id_bytes) | SALTED_ID_FLAG
+}
+
+/// Number of periods of a funded recurring escrow that have fallen due by `at`, capped at the
+/// schedule count. Period `n` (1-based) falls due `n * interval` seconds after funding.
+pub(crate) fn recurring_periods_due(
+    env: &Env,
+    escrow_id: u64,
+    schedule: &RecurringSchedule,
+    at: u64,
+) -> u32 {
+    let start: u64 = env
+        .storage()
+        .persistent()
+        .get(&recurring_start_key(escrow_id))
+        .unwrap_or(at);
+    let elapsed = at.saturating_sub(start) / schedule.interval;
+    elapsed.min(schedule.count as u64) as u32
+}
+
+/// Portion of `amount` vested by ledger `at` under a linear stream.
+pub(crate) fn vested_amount(amount: i128, stream: &StreamConfig, at: u32) -> Result<i128, Error> {
+    if at <= stream.start_ledger {
+        return Ok(0);
+    }
+    if at >= stream.end_ledger {
+        return Ok(amount);
+    }
+
+    let elapsed = (at - stream.start_ledger) as i128;
+    let duration = (stream.end_ledger - stream.start_ledger) as i128;
+    amount
+        .checked_mul(elapsed)
+        .and_then(|v| v.checked_div(duration))
+        .ok_or(Error::InvalidMilestoneAmount)
+}
+
+/// Amount of a milestone not yet paid out: the full amount, minus anything already streamed.
+pub(crate) fn unreleased_amount(
+    env: &Env,
+    escrow_id: u64,
+    milestone_index: u32,
+    milestone: &Milestone,
+) -> Result<i128, Error> {
+    let stream: Option<StreamConfig> = env
+        .storage()
+        .persistent()
+        .get(&stream_storage_key(escrow_id, milestone_index));
+    match stream {
+        Some(stream) => milestone
+            .amount
+            .checked_sub(stream.claimed)
+            .ok_or(Error::InvalidMilestoneAmount),
+        None => Ok(milestone.amount),
+    }
+}
+
+pub(crate) fn is_vesting(env: &Env, escrow_id: u64) -> bool {
+    env.storage().persistent().has(&vesting_key(escrow_id))
+}
+
+/// Portion of `total` vested at timestamp `at` under a cliff + linear schedule.
+pub(crate) fn vested_by_schedule(
+    total: i128,
+    schedule: &VestingSchedule,
+    at: u64,
+) -> Result<i128, Error> {
+    let elapsed = at.saturating_sub(schedule.start);
+    if elapsed < schedule.cliff {
+        return Ok(0);
+    }
+    if elapsed >= schedule.duration {
+        return Ok(total);
+    }
+
+    total
+        .checked_mul(elapsed as i128)
+        .and_then(|v| v.checked_div(schedule.duration as i128))
+        .ok_or(Error::InvalidMilestoneAmount)
+}
+
+pub(crate) fn payout_address(env: &Env, escrow_id: u64, escrow: &Escrow) -> Address {
+    env.storage()
+        .persistent()
+        .get(&assignee_storage_key(escrow_id))
+        .unwrap_or_else(|| escrow.recipient.clone())
+}
+
+/// Mints the recipient's receipt NFT, if a receipt contract is configured.
+pub(crate) fn mint_receipt(env: &Env, escrow_id: u64, recipient: &Address) {
+    let config: Option<ReceiptConfig> = env.storage().instance().get(&DataKey::receipt);
+    if let Some(config) = config {
+        ReceiptClient::new(env, &config.contract).mint(recipient, &escrow_id, &config.transferable);
+    }
+}
+
+/// Pulls an escrow's total from the depositor into the contract and records the deposit.
+/// The depositor must have approved this contract to spend their tokens.
+///
+/// The contract's balance is measured around the single pull. Tokens that charge a fee on
+/// transfer deliver less than was pulled and are rejected rather than leaving the escrow
+/// underfunded.
+///
+/// # Errors
+/// * `FundingShortfall` - If the contract received less than `amount`
+pub(crate) fn collect_deposit(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &Escrow,
+    amount: i128,
+) -> Result<(), Error> {
+    let token_client = token::Client::new(env, &escrow.token_address);
+    let contract = env.current_contract_address();
+    let balance_before = token_client.balance(&contract);
+    token_client.transfer_from(&contract, &escrow.depositor, &contract, &amount);
+
+    let received = token_client.balance(&contract) - balance_before;
+    if received < amount {
+        fail(env, ExtendedError::FundingShortfall);
+    }
+
+    // Tokens that round transfers up can deliver more than was asked for; the overage stays
+    // claimable by the depositor instead of sitting unaccounted in the contract
+    let excess = received - amount;
+    if excess > 0 {
+        let key = excess_key(escrow_id);
+        let earlier: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        let owed = earlier
+            .checked_add(excess)
+            .ok_or(Error::InvalidMilestoneAmount)?;
+        write_persistent(env, &key, &owed);
+    }
+    history::record(
+        env,
+        escrow_id,
+        symbol_short!("deposit"),
+        &escrow.depositor,
+        amount,
+    );
+    stats::escrow_funded(env, &escrow.token_address, amount);
+    tally(env, escrow_id, |totals| {
+        totals.deposited = totals.deposited.saturating_add(amount)
+    });
+    Ok(())
+}
+
+/// Returns any overage received when the escrow was funded to the depositor.
+///
+/// # Returns
+/// The amount refunded, 0 if there was none
+pub(crate) fn refund_excess(env: &Env, escrow_id: u64, escrow: &Escrow) -> i128 {
+    let key = excess_key(escrow_id);
+    let excess: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+    if excess > 0 {
+        env.storage().persistent().remove(&key);
+        token::Client::new(env, &escrow.token_address).transfer(
+            &env.current_contract_address(),
+            &escrow.depositor,
+            &excess,
+        );
+        env.events()
+            .publish((symbol_short!("excess"), escrow_id), excess);
+    }
+    excess
+}
+
+/// Releases every milestone still held to the recipient and completes the escrow, as the
+/// payment leg of a swap the depositor agreed to upfront.
+pub(crate) fn release_all(env: &Env, escrow_id: u64, escrow: &mut Escrow) -> Result<(), Error> {
+    let depositor = escrow.depositor.clone();
+    for index in 0..escrow.milestone_count {
+        if escrow.released_mask & milestone_bit(index) == 0 {
+            release_guard(env, escrow_id, escrow, index)?;
+            pay_milestone(env, escrow_id, escrow, index, &depositor)?;
+        }
+    }
+
+    state_machine::transition(escrow, EscrowStatus::Completed)?;
+    save_escrow(env, escrow_id, escrow);
+    history::record(env, escrow_id, symbol_short!("completed"), &depositor, 0);
+    finish_funded(env, escrow_id, escrow)?;
+    burn_receipt(env, escrow_id);
+    credit_completion(env, escrow_id, escrow)
+}
+
+/// Bookkeeping for a funded escrow that reached a terminal state: pays out its accrued yield
+/// and drops it from the active count.
+pub(crate) fn finish_funded(env: &Env, escrow_id: u64, escrow: &Escrow) -> Result<(), Error> {
+    settle_yield(env, escrow_id, escrow)?;
+    stats::escrow_finished(env);
+    Ok(())
+}
+
+/// Credits both parties' reputations with a completed escrow and its volume, and issues them
+/// their completion certificates.
+pub(crate) fn credit_completion(env: &Env, escrow_id: u64, escrow: &Escrow) -> Result<(), Error> {
+    for party in [&escrow.depositor, &escrow.recipient] {
+        update_reputation(env, party, |rep| {
+            rep.escrows_completed += 1;
+            rep.volume = rep
+                .volume
+                .checked_add(escrow.total_released)
+                .ok_or(Error::InvalidMilestoneAmount)?;
+            Ok(())
+        })?;
+    }
+    certificate::issue(env, escrow_id, escrow);
+    Ok(())
+}
+
+/// Burns the receipt NFT for an escrow that reached a terminal state, if receipts are enabled.
+pub(crate) fn burn_receipt(env: &Env, escrow_id: u64) {
+    let config: Option<ReceiptConfig> = env.storage().instance().get(&DataKey::receipt);
+    if let Some(config) = config {
+        ReceiptClient::new(env, &config.contract).burn(&escrow_id);
+    }
+}
+
+/// Pays `amount` out of escrow to the recipient side, deducting the platform fee and
+/// splitting it between the treasury and the insurance pool.
+///
+/// # Returns
+/// The net payout and the address that received it
+///
+/// # Fee Calculation
+/// Platform fee is calculated using basis points: fee = (amount * fee_bps) / 10000, where
+/// fee_bps is the configured (or operator) fee less any volume-tier discount
+/// The recipient receives: amount - fee
+/// The treasury receives: fee minus the insurance share
+///
+/// If the asset has a fee token, the recipient receives the full amount and the fee is
+/// converted and pulled from the depositor in that token instead. The payout itself is
+/// converted into the escrow's stablecoin, if it has one.
+pub(crate) fn pay_out_with_fee(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &Escrow,
+    amount: i128,
+    milestone_index: u32,
+) -> Result<(i128, Address), Error> {
+    shortfall::ensure_covered(env, &escrow.token_address)?;
+    let treasury = VaultixEscrow::get_treasury(env.clone())?;
+    let split = fee_split(env, escrow_id, escrow, amount)?;
+    let contract = env.current_contract_address();
+
+    // Transfer payout to recipient (seller), or to the assignee if the claim was assigned
+    let payee = payout_address(env, escrow_id, escrow);
+    ensure_not_blocked(env, &payee)?;
+
+    // The fee is booked before any token moves
+    let treasury_fee = split
+        .fee
+        .checked_sub(split.insurance_cut)
+        .unwrap_or_else(|| fail(env, ExtendedError::FeeOverflow));
+    if split.fee > 0 && !split.from_depositor {
+        tally(env, escrow_id, |totals| {
+            totals.fees = totals.fees.saturating_add(split.fee)
+        });
+    }
+    if split.insurance_cut > 0 {
+        add_to_insurance_pool(env, &split.fee_asset, split.insurance_cut)?;
+    }
+
+    convert::pay_out(env, escrow_id, escrow, &payee, split.payout);
+    emit_payout_memo(env, escrow_id, milestone_index, &payee, split.payout);
+
+    // Transfer fee to treasury (only if fee > 0), keeping the insurance share in the pool
+    if split.fee > 0 {
+        let fee_client = token::Client::new(env, &split.fee_asset);
+        if split.from_depositor {
+            fee_client.transfer_from(&contract, &escrow.depositor, &contract, &split.fee);
+        }
+        if treasury_fee > 0 {
+            fee_client.transfer(&contract, &treasury, &treasury_fee);
+        }
+
+        // Emit event for fee collection
+        #[allow(deprecated)]
+        env.events().publish(
+            (symbol_short!("fee_coll"), escrow_id, milestone_index),
+            (split.fee, treasury),
+        );
+    }
+
+    Ok((split.payout, payee))
+}
+
+pub(crate) fn get_insurance_bps(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::ins_bps).unwrap_or(0)
+}
+
+/// Applies `update` to the stored reputation of `address` and persists the result.
+pub(crate) fn update_reputation<F>(env: &Env, address: &Address, update: F) -> Result<(), Error>
+where
+    F: FnOnce(&mut Reputation) -> Result<(), Error>,
+{
+    let key = reputation_key(address);
+    let mut reputation: Reputation = env.storage().persistent().get(&key).unwrap_or_default();
+    update(&mut reputation)?;
+
+    write_persistent(env, &key, &reputation);
+
+    Ok(())
+}
+
+pub(crate) fn validate_milestones(env: &Env, milestones: &Vec<Milestone>) -> Result<i128, Error> {
+    let config = load_config(env);
+    if milestones.len() > config.max_milestones {
+        return Err(Error::VectorTooLarge);
+    }
+
+    let mut total: i128 = 0;
+
+    for milestone in milestones.iter() {
+        if milestone.amount <= 0 {
+            return Err(Error::ZeroAmount);
+        }
+        if milestone.amount < config.min_milestone_amount {
+            return Err(Error::InvalidMilestoneAmount);
+        }
+
+        if milestone.description.len() > MAX_DESCRIPTION_LEN {
+            return Err(Error::DescriptionTooLong);
+        }
+
+        total = total
+            .checked_add(milestone.amount)
+            .ok_or(Error::InvalidMilestoneAmount)?;
+    }
+
+    Ok(total)
+}
+
+pub(crate) fn verify_all_released(escrow: &Escrow) -> bool {
+    escrow.released_mask == all_milestones_mask(escrow.milestone_count)
+}
+
+/// Calculates platform fee using basis points with integer math.
+///
+/// # Arguments
+/// * `amount` - The milestone amount
+/// * `fee_bps` - Fee in basis points (1 bps = 0.01%)
+///
+/// # Returns
+/// The calculated fee amount, or `None` if the calculation overflows
+///
+/// Rounds down, so a fractional unit always stays with the party paying out of the amount.
+/// Every basis-point share in the contract (fees, insurance cuts, bond and slashing shares)
+/// goes through here so they all round the same way.
+///
+/// # Example
+/// For amount = 10000 and fee_bps = 50 (0.5%):
+/// fee = (10000 * 50) / 10000 = 50
+pub(crate) fn calculate_fee(amount: i128, fee_bps: i128) -> Option<i128> {
+    // Calculate: (amount * fee_bps) / BPS_DENOMINATOR
+    amount.checked_mul(fee_bps)?.checked_div(BPS_DENOMINATOR)
+}
@@ -0,0 +1,89 @@
+//! Versioned event schema for indexers.
+//!
+//! Every action recorded in an escrow's history, plus archiving and closing, is published as
+//!
+//! ```text
+//! topics: ("escrow_v1", action: Symbol, escrow_id: u64)
+//! data:   (actor: Address, amount: i128)
+//! ```
+//!
+//! `actor` is the address whose authority the action ran under and `amount` the token amount it
+//! moved, or 0. Within `escrow_v1` the layout never changes; new actions may be added, so
+//! indexers should ignore names they do not know. Actions:
+//!
+//! | action      | emitted when                                    | amount            |
+//! |-------------|-------------------------------------------------|-------------------|
+//! | `create`    | an escrow is created                            | total             |
+//! | `redraft`   | a draft's milestones are replaced               | new total         |
+//! | `activated` | a draft is activated                            | total             |
+//! | `countered` | a party counters a proposal                     | offered total     |
+//! | `accepted`  | a proposal is accepted                          | total             |
+//! | `expired`   | an unanswered proposal expires                  | 0                 |
+//! | `taken`     | an open bounty is claimed by its recipient      | total             |
+//! | `deposit`   | the escrow is funded, in full or in part        | deposited         |
+//! | `released`  | a milestone is paid out                         | milestone amount  |
+//! | `confirmed` | the buyer confirms delivery of a milestone      | milestone amount  |
+//! | `streamed`  | streamed funds are withdrawn                    | withdrawn         |
+//! | `vested`    | vested funds are claimed                        | claimed           |
+//! | `rec_claim` | recurring periods are claimed                   | claimed           |
+//! | `rec_stop`  | a recurring escrow is stopped                   | refunded          |
+//! | `clawback`  | unvested funds are clawed back                  | clawed back       |
+//! | `netted`    | an opposing escrow offsets milestones           | offset            |
+//! | `nft_in`    | the recipient deposits the NFT the escrow buys  | 0                 |
+//! | `nft_out`   | the recipient withdraws that NFT before a swap  | 0                 |
+//! | `leg_in`    | the recipient deposits a swap's counter leg     | counter amount    |
+//! | `leg_out`   | the recipient withdraws it before the swap      | counter amount    |
+//! | `completed` | the escrow is completed                         | 0                 |
+//! | `cancelled` | the escrow is cancelled                         | refunded          |
+//! | `disputed`  | a dispute is raised                             | bond              |
+//! | `escalated` | rejections or a stale submission open a dispute | 0                 |
+//! | `claimed`   | an arbitrator takes a queued dispute            | 0                 |
+//! | `slashed`   | an arbitrator's stake is slashed                | slashed           |
+//! | `voted`     | a token holder or panel arbitrator votes        | vote weight       |
+//! | `revealed`  | a panel arbitrator reveals a committed vote     | vote weight       |
+//! | `undecided` | a dispute vote misses quorum or ties            | weight cast       |
+//! | `rul_queue` | an admin ruling is queued behind the timelock   | 0                 |
+//! | `appealed`  | a party appeals a queued admin ruling           | 0                 |
+//! | `resolved`  | an arbitrator resolves the dispute              | outstanding       |
+//! | `resumed`   | a partial resolution reactivates the escrow     | awarded           |
+//! | `settled`   | the parties' settlement executes                | to recipient      |
+//! | `submitted` | the recipient submits a milestone for review    | 0                 |
+//! | `rejected`  | the depositor rejects a submitted milestone     | 0                 |
+//! | `shipped`   | a logistics oracle attests a shipment           | 0                 |
+//! | `delivered` | a logistics oracle attests delivery             | 0                 |
+//! | `returned`  | a logistics oracle attests a return             | 0                 |
+//! | `contested` | the buyer contests an attested delivery         | 0                 |
+//! | `approved`  | an approver signs off on a milestone            | 0                 |
+//! | `assigned`  | the recipient's payout is assigned              | 0                 |
+//! | `extended`  | the parties agree a later deadline              | 0                 |
+//! | `noted`     | the parties co-sign new escrow notes            | 0                 |
+//! | `rcv_done`  | a recipient recovery is finalized               | 0                 |
+//! | `recovered` | a dormant escrow is refunded by the admin       | refunded          |
+//! | `frozen`    | a clawback shortfall freezes the escrow         | shortfall         |
+//! | `reconcile` | the shortfall is topped up and the escrow thaws | topped up         |
+//! | `writeoff`  | the admin cancels a frozen escrow               | refunded          |
+//! | `org`       | an organization member creates the escrow       | 0                 |
+//! | `operator`  | the escrow is created in an operator namespace  | 0                 |
+//! | `project`   | the escrow is added to a project                | 0                 |
+//! | `archived`  | a finished escrow is archived                   | 0                 |
+//! | `closed`    | a finished escrow's storage is deleted          | 0                 |
+//!
+//! The older per-action events are still published alongside for existing consumers. Payouts to
+//! recipients with a payout memo also publish a `memo` event; see the `payout` module. Parties
+//! that registered a notifier contract are also called with every event; see the `notify` module.
+
+use crate::notify::notify;
+use soroban_sdk::{symbol_short, Address, Env, Symbol};
+
+/// First topic of every event in the current schema.
+pub(crate) const EVENT_SCHEMA: Symbol = symbol_short!("escrow_v1");
+
+/// Publishes `action` on the escrow in the `escrow_v1` layout and passes it on to the parties'
+/// notifiers.
+pub(crate) fn emit(env: &Env, action: Symbol, escrow_id: u64, actor: &Address, amount: i128) {
+    env.events().publish(
+        (EVENT_SCHEMA, action.clone(), escrow_id),
+        (actor.clone(), amount),
+    );
+    notify(env, &action, escrow_id, actor, amount);
+}
@@ -0,0 +1,250 @@
+//! Audit exports.
+//!
+//! Escrow IDs are chosen by their creators, so they can't be walked by counting. Every new
+//! escrow is appended to a creation-order index instead, and `export_escrows` pages through it,
+//! giving auditors and backups a full copy of the escrow records that doesn't depend on how
+//! long the RPC keeps events around.
+//!
+//! `snapshot` walks the same index for migrations: each chunk carries the headers and stored
+//! milestones of live escrows under a layout version, so a redeployed instance can rebuild
+//! them and an upgraded one can be checked against what it held before.
+//!
+//! `import_escrows` is the other half: it replays snapshot chunks into a fresh deployment, and
+//! only until the first escrow is created there natively. Only headers and milestones carry
+//! over, so escrows in dispute or frozen must be settled before migrating, and per-escrow
+//! extras (funding plans, payout routes, tags and the like) set up again afterwards. The
+//! tokens backing funded escrows are moved over separately.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin_log, currency,
+    errors::Error,
+    escrow::{load_live_escrow, save_escrow, MAX_BATCH_ESCROWS},
+    rbac::require_role,
+    stats,
+    storage::{
+        created_at_key, escrow_exists, escrow_index_key, escrow_index_len_key, escrow_position_key,
+        extend_instance, load_milestone, read_persistent, save_milestone, write_persistent,
+        DataKey,
+    },
+    types::{EscrowExport, EscrowRecord, EscrowStatus, Role, Snapshot, SnapshotEntry},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, Address, Env, Vec};
+
+/// Layout version of `Snapshot::entries`
+pub(crate) const SNAPSHOT_VERSION: u32 = 1;
+
+// Escrows per snapshot chunk; each can carry up to 64 milestones
+const MAX_SNAPSHOT_ESCROWS: u32 = 10;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Exports up to `limit` escrow records in creation order, starting at position `cursor`
+    /// of the index. Escrows whose storage has since been closed are skipped, so a page can
+    /// hold fewer records than `limit` without the export being finished.
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller doesn't hold the Admin role
+    /// * `VectorTooLarge` - If `limit` is above 50
+    pub fn export_escrows(
+        env: Env,
+        caller: Address,
+        cursor: u64,
+        limit: u32,
+    ) -> Result<EscrowExport, Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        if limit > MAX_BATCH_ESCROWS {
+            return Err(Error::VectorTooLarge);
+        }
+
+        let len = indexed_escrows(&env);
+        let end = cursor.saturating_add(limit as u64).min(len);
+        let mut records = Vec::new(&env);
+        for position in cursor..end {
+            let key = escrow_index_key(position);
+            let Some(escrow_id) = read_persistent::<_, u64>(&env, &key) else {
+                continue;
+            };
+            if let Ok(escrow) = Self::get_escrow(env.clone(), escrow_id) {
+                records.push_back(EscrowRecord {
+                    escrow_id,
+                    created_at: env
+                        .storage()
+                        .persistent()
+                        .get(&created_at_key(escrow_id))
+                        .unwrap_or(0),
+                    escrow,
+                    currency: currency::or_unknown(&env, escrow_id),
+                });
+            }
+        }
+
+        Ok(EscrowExport {
+            records,
+            next_cursor: end.max(cursor),
+            complete: end >= len,
+        })
+    }
+
+    /// Snapshots up to `limit` escrows in creation order, starting at position `cursor` of
+    /// the index, with every milestone as stored. Escrows that have been archived or closed
+    /// are skipped, so a chunk can hold fewer entries than `limit`.
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller doesn't hold the Admin role
+    /// * `VectorTooLarge` - If `limit` is above 10
+    pub fn snapshot(env: Env, caller: Address, cursor: u64, limit: u32) -> Result<Snapshot, Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        if limit > MAX_SNAPSHOT_ESCROWS {
+            return Err(Error::VectorTooLarge);
+        }
+
+        let len = indexed_escrows(&env);
+        let end = cursor.saturating_add(limit as u64).min(len);
+        let mut entries = Vec::new(&env);
+        for position in cursor..end {
+            let Some(escrow_id) = env
+                .storage()
+                .persistent()
+                .get::<_, u64>(&escrow_index_key(position))
+            else {
+                continue;
+            };
+            let Ok(escrow) = load_live_escrow(&env, escrow_id) else {
+                continue;
+            };
+            let mut milestones = Vec::new(&env);
+            for index in 0..escrow.milestone_count {
+                milestones.push_back(load_milestone(&env, escrow_id, index)?);
+            }
+            entries.push_back(SnapshotEntry {
+                escrow_id,
+                created_at: env
+                    .storage()
+                    .persistent()
+                    .get(&created_at_key(escrow_id))
+                    .unwrap_or(0),
+                escrow,
+                milestones,
+            });
+        }
+
+        Ok(Snapshot {
+            version: SNAPSHOT_VERSION,
+            entries,
+            next_cursor: end.max(cursor),
+            complete: end >= len,
+        })
+    }
+
+    /// Recreates the escrows of a `snapshot` chunk taken from another deployment (Admin role).
+    /// Only possible until the first escrow is created here natively.
+    ///
+    /// # Returns
+    /// The number of escrows imported
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller doesn't hold the Admin role
+    /// * `AlreadyInitialized` - If an escrow has been created on this deployment
+    /// * `VectorTooLarge` - If the chunk holds more than 10 escrows
+    /// * `InvalidConfiguration` - If the chunk has a different layout version
+    /// * `EscrowAlreadyExists` - If an escrow ID is already taken
+    /// * `InvalidEscrowStatus` - If an escrow is disputed or frozen
+    /// * `TotalAmountMismatch` - If an escrow's milestones don't match its header
+    pub fn import_escrows(env: Env, caller: Address, chunk: Snapshot) -> Result<u32, Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "import_escrows", &caller, None);
+        let imported: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::imported)
+            .unwrap_or(0);
+        if indexed_escrows(&env) != imported {
+            return Err(Error::AlreadyInitialized);
+        }
+        if chunk.entries.len() > MAX_SNAPSHOT_ESCROWS {
+            return Err(Error::VectorTooLarge);
+        }
+        if chunk.version != SNAPSHOT_VERSION {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        for entry in chunk.entries.iter() {
+            let escrow = entry.escrow;
+            if escrow_exists(&env, entry.escrow_id) {
+                return Err(Error::EscrowAlreadyExists);
+            }
+            if matches!(escrow.status, EscrowStatus::Disputed | EscrowStatus::Frozen) {
+                return Err(Error::InvalidEscrowStatus);
+            }
+            let mut total: i128 = 0;
+            for milestone in entry.milestones.iter() {
+                total = total
+                    .checked_add(milestone.amount)
+                    .ok_or(Error::InvalidMilestoneAmount)?;
+            }
+            if entry.milestones.len() != escrow.milestone_count || total != escrow.total_amount {
+                return Err(Error::TotalAmountMismatch);
+            }
+
+            save_escrow(&env, entry.escrow_id, &escrow);
+            for (index, milestone) in entry.milestones.iter().enumerate() {
+                save_milestone(&env, entry.escrow_id, index as u32, &milestone);
+            }
+            if entry.created_at > 0 {
+                let key = created_at_key(entry.escrow_id);
+                write_persistent(&env, &key, &entry.created_at);
+            }
+            currency::snapshot(&env, entry.escrow_id, &escrow.token_address);
+            index_escrow(&env, entry.escrow_id);
+            stats::escrow_created(&env);
+            if escrow.status == EscrowStatus::Active {
+                stats::escrow_funded(
+                    &env,
+                    &escrow.token_address,
+                    escrow.total_amount.saturating_sub(escrow.total_released),
+                );
+            }
+        }
+
+        let count = chunk.entries.len();
+        env.storage()
+            .instance()
+            .set(&DataKey::imported, &(imported + count as u64));
+        extend_instance(&env);
+        Ok(count)
+    }
+}
+
+fn indexed_escrows(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&escrow_index_len_key())
+        .unwrap_or(0)
+}
+
+/// Appends a newly created escrow to the export index.
+pub(crate) fn index_escrow(env: &Env, escrow_id: u64) {
+    let position = indexed_escrows(env);
+    let key = escrow_index_key(position);
+    write_persistent(env, &key, &escrow_id);
+    write_persistent(env, &escrow_position_key(escrow_id), &position);
+    env.storage()
+        .instance()
+        .set(&escrow_index_len_key(), &(position + 1));
+    extend_instance(env);
+}
+
+/// Drops a closed escrow from the export index, so that its ID, once reused, is only listed at
+/// the new escrow's position.
+pub(crate) fn unindex_escrow(env: &Env, escrow_id: u64) {
+    let key = escrow_position_key(escrow_id);
+    if let Some(position) = env.storage().persistent().get::<_, u64>(&key) {
+        env.storage()
+            .persistent()
+            .remove(&escrow_index_key(position));
+        env.storage().persistent().remove(&key);
+    }
+}
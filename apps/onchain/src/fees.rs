@@ -0,0 +1,220 @@
+//! Protocol fee adjustments.
+//!
+//! The platform fee starts from `Config::fee_bps`, or the operator's rate for escrows created in
+//! an operator namespace. Fee tiers lower it for parties with a
+//! track record: once the depositor or the recipient has settled at least a tier's
+//! `min_volume` through finished escrows (see `Reputation::volume`), releases pay that tier's
+//! rate instead. Tiers are evaluated at release time, so a discount applies as soon as it is
+//! earned. Addresses the admin marks fee-exempt pay nothing on escrows they are a party to.
+//!
+//! An asset can also have its fee charged in a separate fee token. Releases then pay the
+//! recipient in full and pull the converted fee from the depositor, who must have approved the
+//! contract to spend the fee token.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin_log,
+    errors::{fail, Error, ExtendedError},
+    escrow::{calculate_fee, get_insurance_bps, load_live_escrow, BPS_DENOMINATOR},
+    operator,
+    rbac::require_role,
+    storage::{fee_exempt_key, fee_tiers_key, fee_token_key, load_config, write_persistent},
+    types::{Escrow, FeeTier, FeeToken, Role},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, Vec};
+
+/// Fixed-point scale of `FeeToken::rate`
+pub(crate) const FEE_RATE_SCALE: i128 = 10_000_000;
+
+// Fee tiers are scanned on every release, so keep the table short
+const MAX_FEE_TIERS: u32 = 10;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Replaces the volume-based fee tiers (FeeManager role). Tiers must be ordered by
+    /// increasing `min_volume` with non-increasing rates; an empty list removes all discounts.
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller lacks the FeeManager role
+    /// * `VectorTooLarge` - If there are more than 10 tiers
+    /// * `InvalidFeeConfiguration` - If the tiers are out of order or a rate exceeds 100%
+    pub fn set_fee_tiers(env: Env, caller: Address, tiers: Vec<FeeTier>) -> Result<(), Error> {
+        require_role(&env, &caller, Role::FeeManager)?;
+        admin_log::record(&env, "set_fee_tiers", &caller, None);
+
+        if tiers.len() > MAX_FEE_TIERS {
+            return Err(Error::VectorTooLarge);
+        }
+        let mut previous: Option<FeeTier> = None;
+        for tier in tiers.iter() {
+            if tier.min_volume < 0 || !(0..=BPS_DENOMINATOR).contains(&tier.fee_bps) {
+                return Err(Error::InvalidFeeConfiguration);
+            }
+            if let Some(previous) = previous {
+                if tier.min_volume <= previous.min_volume || tier.fee_bps > previous.fee_bps {
+                    return Err(Error::InvalidFeeConfiguration);
+                }
+            }
+            previous = Some(tier);
+        }
+
+        if tiers.is_empty() {
+            env.storage().instance().remove(&fee_tiers_key());
+        } else {
+            env.storage().instance().set(&fee_tiers_key(), &tiers);
+        }
+        Ok(())
+    }
+
+    /// The volume-based fee tiers, lowest volume first.
+    pub fn get_fee_tiers(env: Env) -> Vec<FeeTier> {
+        env.storage()
+            .instance()
+            .get(&fee_tiers_key())
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Exempts an address from the platform fee, or revokes the exemption (Admin role). Releases
+    /// from escrows where the address is depositor or recipient pay no fee.
+    pub fn set_fee_exempt(
+        env: Env,
+        caller: Address,
+        address: Address,
+        exempt: bool,
+    ) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "set_fee_exempt", &caller, None);
+
+        let key = fee_exempt_key(&address);
+        if exempt {
+            write_persistent(&env, &key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        env.events()
+            .publish((symbol_short!("fee_free"), address), exempt);
+        Ok(())
+    }
+
+    /// Returns whether an address is exempt from the platform fee.
+    pub fn is_fee_exempt(env: Env, address: Address) -> bool {
+        env.storage().persistent().has(&fee_exempt_key(&address))
+    }
+
+    /// Charges the platform fee on `asset` in a separate token, or back in the asset itself when
+    /// `fee_token` is `None` (FeeManager role).
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller lacks the FeeManager role
+    /// * `InvalidFeeConfiguration` - If the rate is not positive or the fee token is the asset
+    pub fn set_fee_token(
+        env: Env,
+        caller: Address,
+        asset: Address,
+        fee_token: Option<FeeToken>,
+    ) -> Result<(), Error> {
+        require_role(&env, &caller, Role::FeeManager)?;
+        admin_log::record(&env, "set_fee_token", &caller, None);
+
+        let key = fee_token_key(&asset);
+        match fee_token {
+            Some(fee_token) => {
+                if fee_token.rate <= 0 || fee_token.token == asset {
+                    return Err(Error::InvalidFeeConfiguration);
+                }
+                write_persistent(&env, &key, &fee_token);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+        Ok(())
+    }
+
+    /// The token the platform fee on `asset` is charged in, if not the asset itself.
+    pub fn get_fee_token(env: Env, asset: Address) -> Option<FeeToken> {
+        env.storage().persistent().get(&fee_token_key(&asset))
+    }
+
+    /// Fee in basis points a release from the escrow would pay right now.
+    pub fn get_fee_bps(env: Env, escrow_id: u64) -> Result<i128, Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        Ok(fee_bps(&env, escrow_id, &escrow))
+    }
+}
+
+/// Platform fee rate for releases from `escrow`: zero if either party is fee-exempt, otherwise
+/// the configured rate, or the best tier either party's settled volume qualifies for if lower.
+pub(crate) fn fee_bps(env: &Env, escrow_id: u64, escrow: &Escrow) -> i128 {
+    if VaultixEscrow::is_fee_exempt(env.clone(), escrow.depositor.clone())
+        || VaultixEscrow::is_fee_exempt(env.clone(), escrow.recipient.clone())
+    {
+        return 0;
+    }
+
+    let base = operator::config(env, escrow_id).map_or(load_config(env).fee_bps, |c| c.fee_bps);
+    let tiers = VaultixEscrow::get_fee_tiers(env.clone());
+    if tiers.is_empty() {
+        return base;
+    }
+
+    let volume = [&escrow.depositor, &escrow.recipient]
+        .into_iter()
+        .map(|party| VaultixEscrow::get_reputation(env.clone(), party.clone()).volume)
+        .max()
+        .unwrap_or(0);
+    tiers
+        .iter()
+        .filter(|tier| tier.min_volume <= volume)
+        .last()
+        .map_or(base, |tier| tier.fee_bps.min(base))
+}
+
+/// How a release of `amount` from an escrow is divided. `fee` and `insurance_cut` are in
+/// `fee_asset`; when that is a separate fee token, the fee is owed by the depositor on top of
+/// the release instead of being deducted from the payout.
+pub(crate) struct FeeSplit {
+    pub payout: i128,
+    pub fee_asset: Address,
+    pub fee: i128,
+    pub insurance_cut: i128,
+    pub from_depositor: bool,
+}
+
+/// Computes the payout and fee for releasing `amount` from `escrow`.
+pub(crate) fn fee_split(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &Escrow,
+    amount: i128,
+) -> Result<FeeSplit, Error> {
+    // fee = (amount * fee_bps) / 10000, in the escrowed asset
+    let fee = calculate_fee(amount, fee_bps(env, escrow_id, escrow))
+        .unwrap_or_else(|| fail(env, ExtendedError::FeeOverflow));
+    let fee_token = VaultixEscrow::get_fee_token(env.clone(), escrow.token_address.clone());
+    let (payout, fee_asset, fee, from_depositor) = match fee_token {
+        Some(fee_token) => {
+            let converted = fee
+                .checked_mul(fee_token.rate)
+                .unwrap_or_else(|| fail(env, ExtendedError::FeeOverflow))
+                / FEE_RATE_SCALE;
+            (amount, fee_token.token, converted, true)
+        }
+        None => {
+            let payout = amount
+                .checked_sub(fee)
+                .unwrap_or_else(|| fail(env, ExtendedError::FeeOverflow));
+            (payout, escrow.token_address.clone(), fee, false)
+        }
+    };
+
+    Ok(FeeSplit {
+        payout,
+        fee_asset,
+        fee,
+        insurance_cut: calculate_fee(fee, get_insurance_bps(env))
+            .unwrap_or_else(|| fail(env, ExtendedError::FeeOverflow)),
+        from_depositor,
+    })
+}
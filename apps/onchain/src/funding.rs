@@ -0,0 +1,294 @@
+//! Progressive funding: backing an escrow a few milestones at a time.
+//!
+//! Instead of depositing the full total with `deposit_funds`, the depositor may call
+//! `fund_milestones` to fund the next `count` milestones in index order. The first call
+//! activates the escrow; milestones past the funded prefix report `Unfunded` and cannot be
+//! submitted, released or streamed until a later call covers them. Once every milestone is
+//! funded the escrow is indistinguishable from one funded in a single deposit.
+//!
+//! Anything that refunds or settles a partly funded escrow works from the amount actually
+//! deposited, never from the escrow's total.
+//!
+//! A depositor can also commit to a schedule up front with `set_funding_plan`: installments
+//! with amounts and due ledgers, each ending on a milestone boundary. `fund_installment` pays
+//! the next one, and `get_funding_arrears` shows the recipient how far behind the depositor is.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin::{ensure_not_blocked, ensure_not_paused},
+    errors::Error,
+    escrow::{collect_deposit, is_vesting, load_live_escrow, save_escrow},
+    nft, state_machine,
+    storage::{funding_key, funding_plan_key, load_milestone, recurring_key, write_persistent},
+    types::{Escrow, EscrowStatus, Installment},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Env, Vec};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Funds the next `count` unfunded milestones, pulling their amounts from the depositor,
+    /// who must have approved them. The first call moves the escrow from Created to Active.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `ZeroAmount` - If `count` is zero
+    /// * `MilestoneNotFound` - If fewer than `count` milestones are left unfunded
+    /// * `EscrowAlreadyFunded` - If the escrow was funded in full
+    /// * `InvalidEscrowStatus` - If the escrow is a draft, proposal or open bounty, or pays
+    ///   out on a recurring or vesting schedule
+    /// * `EscrowNotActive` - If the escrow is disputed or finished
+    pub fn fund_milestones(env: Env, escrow_id: u64, count: u32) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+        ensure_not_blocked(&env, &escrow.depositor)?;
+
+        let (funded, funded_amount) = staged_funding(&env, escrow_id, &escrow)?;
+        if count == 0 {
+            return Err(Error::ZeroAmount);
+        }
+        let end = funded
+            .checked_add(count)
+            .filter(|end| *end <= escrow.milestone_count)
+            .ok_or(Error::MilestoneNotFound)?;
+
+        let mut amount: i128 = 0;
+        for index in funded..end {
+            amount = amount
+                .checked_add(load_milestone(&env, escrow_id, index)?.amount)
+                .ok_or(Error::InvalidMilestoneAmount)?;
+        }
+        fund_through(&env, escrow_id, &mut escrow, end, funded_amount, amount)
+    }
+
+    /// Commits the escrow to a funding schedule. Installments must fall due in increasing
+    /// ledger order, and each must bring the running total to the end of a milestone, with the
+    /// last one reaching the escrow's total. Replaces any earlier plan; only the depositor may
+    /// set it, and only before the escrow is funded.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `EscrowAlreadyFunded` - If the escrow has left the Created state
+    /// * `InvalidEscrowStatus` - If the escrow pays out on a recurring or vesting schedule
+    /// * `ZeroAmount` - If the plan is empty or an installment is not positive
+    /// * `InvalidDeadline` - If due ledgers do not increase
+    /// * `TotalAmountMismatch` - If an installment ends mid-milestone or the plan does not add
+    ///   up to the total
+    pub fn set_funding_plan(env: Env, escrow_id: u64, plan: Vec<Installment>) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if escrow.status != EscrowStatus::Created {
+            return Err(Error::EscrowAlreadyFunded);
+        }
+        staged_funding(&env, escrow_id, &escrow)?;
+        if plan.is_empty() {
+            return Err(Error::ZeroAmount);
+        }
+
+        // Walk the milestones alongside the plan so every installment ends on a boundary
+        let mut planned: i128 = 0;
+        let mut covered: i128 = 0;
+        let mut next_milestone = 0;
+        let mut last_due: Option<u32> = None;
+        for installment in plan.iter() {
+            if installment.amount <= 0 {
+                return Err(Error::ZeroAmount);
+            }
+            if last_due.is_some_and(|due| installment.due_ledger <= due) {
+                return Err(Error::InvalidDeadline);
+            }
+            last_due = Some(installment.due_ledger);
+            planned = planned
+                .checked_add(installment.amount)
+                .ok_or(Error::InvalidMilestoneAmount)?;
+            while covered < planned && next_milestone < escrow.milestone_count {
+                covered += load_milestone(&env, escrow_id, next_milestone)?.amount;
+                next_milestone += 1;
+            }
+            if covered != planned {
+                return Err(Error::TotalAmountMismatch);
+            }
+        }
+        if planned != escrow.total_amount {
+            return Err(Error::TotalAmountMismatch);
+        }
+
+        let key = funding_plan_key(escrow_id);
+        write_persistent(&env, &key, &plan);
+        Ok(())
+    }
+
+    /// Pays the next installment of the escrow's funding plan, or what is left of it if the
+    /// depositor funded ahead with `fund_milestones`. The first payment activates the escrow.
+    ///
+    /// # Returns
+    /// Index of the installment paid
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidConfiguration` - If the escrow has no funding plan
+    /// * Otherwise the same errors as `fund_milestones`
+    pub fn fund_installment(env: Env, escrow_id: u64) -> Result<u32, Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+        ensure_not_blocked(&env, &escrow.depositor)?;
+
+        let (funded, funded_amount) = staged_funding(&env, escrow_id, &escrow)?;
+        let plan =
+            Self::get_funding_plan(env.clone(), escrow_id).ok_or(Error::InvalidConfiguration)?;
+
+        // The first installment that takes the running total past what is already deposited
+        let mut planned: i128 = 0;
+        let mut next = None;
+        for (index, installment) in plan.iter().enumerate() {
+            planned += installment.amount;
+            if planned > funded_amount {
+                next = Some(index as u32);
+                break;
+            }
+        }
+        let next = next.ok_or(Error::EscrowAlreadyFunded)?;
+
+        let mut end = funded;
+        let mut amount: i128 = 0;
+        while funded_amount + amount < planned {
+            amount += load_milestone(&env, escrow_id, end)?.amount;
+            end += 1;
+        }
+        fund_through(&env, escrow_id, &mut escrow, end, funded_amount, amount)?;
+        Ok(next)
+    }
+
+    /// The escrow's funding plan, if the depositor set one.
+    pub fn get_funding_plan(env: Env, escrow_id: u64) -> Option<Vec<Installment>> {
+        env.storage().persistent().get(&funding_plan_key(escrow_id))
+    }
+
+    /// Amount of the funding plan that has fallen due but not been deposited yet. Zero for
+    /// escrows without a plan and for finished ones.
+    pub fn get_funding_arrears(env: Env, escrow_id: u64) -> Result<i128, Error> {
+        let escrow = Self::get_escrow(env.clone(), escrow_id)?;
+        let Some(plan) = Self::get_funding_plan(env.clone(), escrow_id) else {
+            return Ok(0);
+        };
+        if state_machine::is_terminal(escrow.status) {
+            return Ok(0);
+        }
+
+        let (_, deposited) = Self::get_funding(env.clone(), escrow_id)?;
+        let now = env.ledger().sequence();
+        let mut due: i128 = 0;
+        for installment in plan.iter() {
+            if installment.due_ledger <= now {
+                due += installment.amount;
+            }
+        }
+        Ok((due - deposited).max(0))
+    }
+
+    /// How many leading milestones are funded and the amount deposited for them. Escrows
+    /// funded in one deposit report every milestone; unfunded ones report nothing.
+    pub fn get_funding(env: Env, escrow_id: u64) -> Result<(u32, i128), Error> {
+        let escrow = Self::get_escrow(env.clone(), escrow_id)?;
+        if matches!(
+            escrow.status,
+            EscrowStatus::Draft | EscrowStatus::Proposed | EscrowStatus::Created
+        ) {
+            return Ok((0, 0));
+        }
+        Ok(funding(&env, escrow_id, &escrow))
+    }
+}
+
+/// Funding progress of an escrow that can still take a staged deposit, as `(count, amount)`
+/// like [`funding`]; fails with the error a deposit attempt should see otherwise.
+fn staged_funding(env: &Env, escrow_id: u64, escrow: &Escrow) -> Result<(u32, i128), Error> {
+    match escrow.status {
+        EscrowStatus::Created => {
+            if env.storage().persistent().has(&recurring_key(escrow_id))
+                || is_vesting(env, escrow_id)
+            {
+                return Err(Error::InvalidEscrowStatus);
+            }
+            Ok((0, 0))
+        }
+        EscrowStatus::Active => env
+            .storage()
+            .persistent()
+            .get(&funding_key(escrow_id))
+            .ok_or(Error::EscrowAlreadyFunded),
+        EscrowStatus::Draft | EscrowStatus::Proposed | EscrowStatus::Open => {
+            Err(Error::InvalidEscrowStatus)
+        }
+        _ => Err(Error::EscrowNotActive),
+    }
+}
+
+/// Deposits `amount`, which funds the milestones up to `end`, on top of `funded_amount`,
+/// activating the escrow on its first deposit.
+fn fund_through(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &mut Escrow,
+    end: u32,
+    funded_amount: i128,
+    amount: i128,
+) -> Result<(), Error> {
+    if escrow.status == EscrowStatus::Created {
+        state_machine::transition(escrow, EscrowStatus::Active)?;
+        save_escrow(env, escrow_id, escrow);
+    }
+    let key = funding_key(escrow_id);
+    let deposited = funded_amount
+        .checked_add(amount)
+        .ok_or(Error::InvalidMilestoneAmount)?;
+    if end == escrow.milestone_count {
+        env.storage().persistent().remove(&key);
+    } else {
+        write_persistent(env, &key, &(end, deposited));
+    }
+    collect_deposit(env, escrow_id, escrow, amount)?;
+
+    env.events()
+        .publish((symbol_short!("funded"), escrow_id), (end, amount));
+    nft::swap(env, escrow_id)?;
+    Ok(())
+}
+
+/// Milestones `0..count` backed by deposits, and the amount deposited, as `(count, amount)`.
+/// Only meaningful for funded escrows.
+pub(crate) fn funding(env: &Env, escrow_id: u64, escrow: &Escrow) -> (u32, i128) {
+    env.storage()
+        .persistent()
+        .get(&funding_key(escrow_id))
+        .unwrap_or((escrow.milestone_count, escrow.total_amount))
+}
+
+/// Deposited funds of a funded escrow that have not been paid out yet.
+pub(crate) fn held_amount(env: &Env, escrow_id: u64, escrow: &Escrow) -> Result<i128, Error> {
+    funding(env, escrow_id, escrow)
+        .1
+        .checked_sub(escrow.total_released)
+        .ok_or(Error::InvalidMilestoneAmount)
+}
+
+/// Fails with `InsufficientBalance` unless the milestone is covered by a deposit.
+pub(crate) fn ensure_funded(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &Escrow,
+    milestone_index: u32,
+) -> Result<(), Error> {
+    if milestone_index >= funding(env, escrow_id, escrow).0 {
+        return Err(Error::InsufficientBalance);
+    }
+    Ok(())
+}
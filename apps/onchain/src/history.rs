@@ -0,0 +1,132 @@
+//! Per-escrow audit log of state-changing actions.
+//!
+//! Entries are stored in fixed-size pages so a read never loads more than one page. Only the
+//! most recent `MAX_HISTORY_PAGES` pages are kept; older pages are dropped as new ones start.
+//! Recording an action also stamps the actor's last-seen time, which backup addresses use to
+//! tell whether a depositor has gone silent, and publishes it as an `escrow_v1` event.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    events,
+    storage::{
+        extend_persistent, history_len_key, history_page_key, last_seen_key, read_persistent,
+        write_persistent,
+    },
+    types::HistoryEntry,
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, Address, Env, Symbol, Vec};
+
+pub(crate) const HISTORY_PAGE_SIZE: u32 = 20;
+pub(crate) const MAX_HISTORY_PAGES: u32 = 10;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Returns one page of an escrow's audit log, oldest entry first. Page `n` holds entries
+    /// `n * 20` to `n * 20 + 19`. Pages that were never written or have been pruned are empty.
+    pub fn get_history(env: Env, escrow_id: u64, page: u32) -> Vec<HistoryEntry> {
+        let key = history_page_key(escrow_id, page);
+        read_persistent(&env, &key).unwrap_or(Vec::new(&env))
+    }
+
+    /// Total number of entries ever recorded for an escrow, including pruned ones.
+    pub fn get_history_len(env: Env, escrow_id: u64) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&history_len_key(escrow_id))
+            .unwrap_or(0)
+    }
+}
+
+/// Appends an entry to the escrow's audit log, pruning the oldest page when a new one starts.
+pub(crate) fn record(env: &Env, escrow_id: u64, action: Symbol, actor: &Address, amount: i128) {
+    let len_key = history_len_key(escrow_id);
+    let len = VaultixEscrow::get_history_len(env.clone(), escrow_id);
+    let page = len / HISTORY_PAGE_SIZE;
+    let page_key = history_page_key(escrow_id, page);
+
+    let mut entries: Vec<HistoryEntry> = if len.is_multiple_of(HISTORY_PAGE_SIZE) {
+        if page >= MAX_HISTORY_PAGES {
+            env.storage()
+                .persistent()
+                .remove(&history_page_key(escrow_id, page - MAX_HISTORY_PAGES));
+        }
+        Vec::new(env)
+    } else {
+        env.storage()
+            .persistent()
+            .get(&page_key)
+            .unwrap_or_else(|| Vec::new(env))
+    };
+
+    entries.push_back(HistoryEntry {
+        action: action.clone(),
+        actor: actor.clone(),
+        amount,
+        ledger: env.ledger().sequence(),
+        timestamp: env.ledger().timestamp(),
+    });
+
+    env.storage().persistent().set(&page_key, &entries);
+    env.storage().persistent().set(&len_key, &(len + 1));
+    extend_persistent(env, &page_key);
+    extend_persistent(env, &len_key);
+    touch(env, actor);
+    events::emit(env, action, escrow_id, actor, amount);
+}
+
+/// Stamps `address` as active now.
+pub(crate) fn touch(env: &Env, address: &Address) {
+    let key = last_seen_key(address);
+    write_persistent(env, &key, &env.ledger().timestamp());
+}
+
+/// When `address` last performed a recorded action on any escrow, or 0 if never.
+pub(crate) fn last_seen(env: &Env, address: &Address) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&last_seen_key(address))
+        .unwrap_or(0)
+}
+
+/// The most recent entry of the escrow's audit log, if any.
+pub(crate) fn last_entry(env: &Env, escrow_id: u64) -> Option<HistoryEntry> {
+    let len = VaultixEscrow::get_history_len(env.clone(), escrow_id);
+    if len == 0 {
+        return None;
+    }
+    let entries: Vec<HistoryEntry> = env
+        .storage()
+        .persistent()
+        .get(&history_page_key(escrow_id, (len - 1) / HISTORY_PAGE_SIZE))?;
+    entries.last()
+}
+
+/// Removes every stored page of an escrow's audit log.
+pub(crate) fn clear(env: &Env, escrow_id: u64) {
+    let len = VaultixEscrow::get_history_len(env.clone(), escrow_id);
+    let pages = len.div_ceil(HISTORY_PAGE_SIZE);
+    for page in pages.saturating_sub(MAX_HISTORY_PAGES)..pages {
+        env.storage()
+            .persistent()
+            .remove(&history_page_key(escrow_id, page));
+    }
+    env.storage()
+        .persistent()
+        .remove(&history_len_key(escrow_id));
+}
+
+/// Extends the TTL of an escrow's audit log entries.
+pub(crate) fn extend(env: &Env, escrow_id: u64) {
+    let len_key = history_len_key(escrow_id);
+    if !env.storage().persistent().has(&len_key) {
+        return;
+    }
+    let len = VaultixEscrow::get_history_len(env.clone(), escrow_id);
+    let pages = len.div_ceil(HISTORY_PAGE_SIZE);
+    for page in pages.saturating_sub(MAX_HISTORY_PAGES)..pages {
+        extend_persistent(env, &history_page_key(escrow_id, page));
+    }
+    extend_persistent(env, &len_key);
+}
@@ -0,0 +1,81 @@
+//! Lifecycle callbacks into other contracts.
+//!
+//! The depositor can register a hook contract implementing `EscrowHookInterface`, which the
+//! escrow calls after a milestone is paid and after a dispute is resolved, so payroll,
+//! accounting or notification contracts can react on-chain. Calls happen once the escrow's own
+//! state is saved, and a failing hook is ignored rather than blocking the payout.
+//!
+//! Since a hook runs on every payout, either party can detach it.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    errors::Error,
+    escrow::load_live_escrow,
+    state_machine,
+    storage::{hook_key, write_persistent},
+    types::{HookClient, Resolution},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Registers `hook` to be notified of the escrow's releases and resolutions, replacing any
+    /// earlier hook. Depositor only.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow is finished
+    pub fn set_hook(env: Env, escrow_id: u64, hook: Address) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if state_machine::is_terminal(escrow.status) {
+            return Err(Error::InvalidEscrowStatus);
+        }
+
+        let key = hook_key(escrow_id);
+        write_persistent(&env, &key, &hook);
+
+        env.events()
+            .publish((symbol_short!("hook"), escrow_id), hook);
+        Ok(())
+    }
+
+    /// Detaches the escrow's hook. Either party may call this.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `UnauthorizedAccess` - If caller is not a party to the escrow
+    pub fn remove_hook(env: Env, escrow_id: u64, caller: Address) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        caller.require_auth();
+
+        if caller != escrow.depositor && caller != escrow.recipient {
+            return Err(Error::UnauthorizedAccess);
+        }
+
+        env.storage().persistent().remove(&hook_key(escrow_id));
+        env.events()
+            .publish((symbol_short!("hook_rm"), escrow_id), caller);
+        Ok(())
+    }
+
+    /// Returns the escrow's hook contract, if any.
+    pub fn get_hook(env: Env, escrow_id: u64) -> Option<Address> {
+        env.storage().persistent().get(&hook_key(escrow_id))
+    }
+}
+
+pub(crate) fn on_release(env: &Env, escrow_id: u64, milestone_index: u32, amount: i128) {
+    if let Some(hook) = VaultixEscrow::get_hook(env.clone(), escrow_id) {
+        let _ = HookClient::new(env, &hook).try_on_release(&escrow_id, &milestone_index, &amount);
+    }
+}
+
+pub(crate) fn on_resolve(env: &Env, escrow_id: u64, resolution: Resolution) {
+    if let Some(hook) = VaultixEscrow::get_hook(env.clone(), escrow_id) {
+        let _ = HookClient::new(env, &hook).try_on_resolve(&escrow_id, &resolution);
+    }
+}
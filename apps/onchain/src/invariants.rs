@@ -0,0 +1,95 @@
+//! Escrow consistency checks for tests and fuzzing.
+//!
+//! Only compiled into test and `testutils` builds; integrators reach it through
+//! [`crate::testutils::Vaultix::check_invariants`].
+
+use soroban_sdk::Env;
+
+use crate::{
+    escrow::{is_vesting, unreleased_amount},
+    storage::{all_milestones_mask, load_milestone, milestone_bit},
+    Escrow, EscrowStatus, Resolution,
+};
+
+/// Checks that `escrow`'s bookkeeping is self-consistent and agrees with its stored
+/// milestones, returning the first violated invariant:
+///
+/// * `total_released` stays within `0..=total_amount`, and milestones never add up to more
+///   than the total
+/// * released and disputed bits only cover existing milestones
+/// * what milestones have paid out (fully released, or streamed so far) equals
+///   `total_released`, except for vesting escrows and negotiated settlements, which pay
+///   outside the milestones
+/// * escrows that have not started (`Draft`, `Proposed`, `Created`, `Open`) have paid
+///   nothing, `Completed` ones have paid everything, and a resolution is recorded exactly when
+///   the escrow is `Resolved`
+/// * disputed milestones only exist while the escrow is `Disputed` or `Resolved`
+pub(crate) fn check_invariants(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &Escrow,
+) -> Result<(), &'static str> {
+    if escrow.total_released < 0 || escrow.total_released > escrow.total_amount {
+        return Err("total_released outside 0..=total_amount");
+    }
+
+    let existing = all_milestones_mask(escrow.milestone_count);
+    if escrow.released_mask & !existing != 0 || escrow.disputed_mask & !existing != 0 {
+        return Err("milestone bits beyond milestone_count");
+    }
+
+    let mut milestone_total: i128 = 0;
+    let mut paid: i128 = 0;
+    for index in 0..escrow.milestone_count {
+        let milestone =
+            load_milestone(env, escrow_id, index).map_err(|_| "milestone missing from storage")?;
+        milestone_total = milestone_total
+            .checked_add(milestone.amount)
+            .ok_or("milestone amounts overflow")?;
+        let paid_out = if escrow.released_mask & milestone_bit(index) != 0 {
+            milestone.amount
+        } else {
+            let unreleased = unreleased_amount(env, escrow_id, index, &milestone)
+                .map_err(|_| "streamed more than the milestone amount")?;
+            milestone.amount - unreleased
+        };
+        paid = paid.checked_add(paid_out).ok_or("payouts overflow")?;
+    }
+    if milestone_total > escrow.total_amount {
+        return Err("milestones exceed total_amount");
+    }
+    if !is_vesting(env, escrow_id)
+        && escrow.resolution != Resolution::Settled
+        && paid != escrow.total_released
+    {
+        return Err("released milestones do not sum to total_released");
+    }
+
+    match escrow.status {
+        EscrowStatus::Draft
+        | EscrowStatus::Proposed
+        | EscrowStatus::Created
+        | EscrowStatus::Open
+            if escrow.total_released != 0 || escrow.released_mask != 0 =>
+        {
+            return Err("unfunded escrow has released funds");
+        }
+        EscrowStatus::Completed if escrow.total_released != escrow.total_amount => {
+            return Err("completed escrow still holds funds");
+        }
+        _ => {}
+    }
+    if (escrow.status == EscrowStatus::Resolved) != (escrow.resolution != Resolution::None) {
+        return Err("resolution recorded outside the Resolved status");
+    }
+    if escrow.disputed_mask != 0
+        && !matches!(
+            escrow.status,
+            EscrowStatus::Disputed | EscrowStatus::Resolved
+        )
+    {
+        return Err("disputed milestones on an undisputed escrow");
+    }
+
+    Ok(())
+}
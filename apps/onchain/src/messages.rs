@@ -0,0 +1,74 @@
+//! Message threads.
+//!
+//! The parties to an escrow keep talking off-chain, but the exchanges that matter later ("please
+//! redo the logo", "approved as delivered") can be anchored here: either party posts the hash of
+//! a message and the thread records who posted it and when. The content never touches the
+//! chain; in a dispute, a party produces the original and anyone can check it against the
+//! anchored hash. Threads are capped so they stay readable in a single call.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    dispute::ensure_party,
+    errors::Error,
+    escrow::load_live_escrow,
+    storage::{messages_key, write_persistent},
+    types::Message,
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, BytesN, Env, Vec};
+
+/// Most messages one escrow's thread may hold
+const MAX_MESSAGES: u32 = 50;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Appends the hash of an off-chain message to the escrow's thread. Depositor or recipient
+    /// only.
+    ///
+    /// # Returns
+    /// The message's position in the thread
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `UnauthorizedAccess` - If caller is not a party to the escrow
+    /// * `VectorTooLarge` - If the thread already holds 50 messages
+    pub fn post_message(
+        env: Env,
+        escrow_id: u64,
+        caller: Address,
+        message_hash: BytesN<32>,
+    ) -> Result<u32, Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        ensure_party(&escrow, &caller)?;
+        caller.require_auth();
+
+        let mut thread = Self::get_messages(env.clone(), escrow_id);
+        if thread.len() >= MAX_MESSAGES {
+            return Err(Error::VectorTooLarge);
+        }
+
+        let position = thread.len();
+        thread.push_back(Message {
+            author: caller.clone(),
+            message_hash: message_hash.clone(),
+            ledger: env.ledger().sequence(),
+            timestamp: env.ledger().timestamp(),
+        });
+        let key = messages_key(escrow_id);
+        write_persistent(&env, &key, &thread);
+        env.events().publish(
+            (symbol_short!("message"), escrow_id, position),
+            (caller, message_hash),
+        );
+        Ok(position)
+    }
+
+    /// The escrow's message thread, oldest first.
+    pub fn get_messages(env: Env, escrow_id: u64) -> Vec<Message> {
+        env.storage()
+            .persistent()
+            .get(&messages_key(escrow_id))
+            .unwrap_or(Vec::new(&env))
+    }
+}
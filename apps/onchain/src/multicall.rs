@@ -0,0 +1,119 @@
+//! Atomic batches of escrow operations.
+//!
+//! `multicall` runs a short list of fundings, releases, reviews and approvals as one
+//! transaction: either every operation succeeds or the whole batch fails and nothing changes. A
+//! depositor wrapping up a project can pay out several milestones across escrows with a single
+//! signature, and a recipient can hand in several deliverables at once.
+//!
+//! Each operation runs the same checks as its entrypoint, with `caller` authorized once for the
+//! whole batch. Operations must be ones `caller` could perform directly; the batch never borrows
+//! another party's authority.
+//!
+//! Because the actor is named explicitly, a batch is also how a platform relayer submits
+//! actions on a party's behalf: the party signs a Soroban authorization entry for
+//! `multicall(caller, ops)` and the relayer sends the transaction and pays its fee. That covers
+//! the entrypoints that otherwise only derive their actor from the stored escrow, so end users
+//! never need XLM of their own.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin::ensure_not_paused,
+    approvals,
+    errors::Error,
+    escrow::{self, load_live_escrow, release_guard},
+    recovery::depositor_authority,
+    review,
+    types::{Escrow, EscrowOp},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, Address, Env, String, Vec};
+
+// Maximum operations per batch, keeping a batch within one transaction's resource limits
+pub(crate) const MAX_MULTICALL_OPS: u32 = 10;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Executes `ops` in order under `caller`'s authorization, all or nothing. Anyone may
+    /// submit the call, a relayer paying the fee for instance, as long as `caller` signed it.
+    ///
+    /// # Errors
+    /// * `VectorTooLarge` - If there are more than 10 operations
+    /// * `UnauthorizedAccess` - If an operation is not one `caller` may perform
+    /// * Any error of the underlying entrypoint, which aborts the whole batch
+    pub fn multicall(env: Env, caller: Address, ops: Vec<EscrowOp>) -> Result<(), Error> {
+        caller.require_auth();
+        if ops.len() > MAX_MULTICALL_OPS {
+            return Err(Error::VectorTooLarge);
+        }
+
+        for op in ops.iter() {
+            match op {
+                EscrowOp::Release(escrow_id, milestone_index) => {
+                    ensure_not_paused(&env)?;
+                    let mut escrow = as_depositor_authority(&env, escrow_id, &caller)?;
+                    escrow::release(&env, escrow_id, &mut escrow, milestone_index, &caller)?;
+                }
+                EscrowOp::Submit(escrow_id, milestone_index, deliverable_hash) => {
+                    let escrow = load_live_escrow(&env, escrow_id)?;
+                    if escrow.recipient != caller {
+                        return Err(Error::UnauthorizedAccess);
+                    }
+                    release_guard(&env, escrow_id, &escrow, milestone_index)?;
+                    review::record_submission(
+                        &env,
+                        escrow_id,
+                        &escrow,
+                        milestone_index,
+                        deliverable_hash,
+                        String::from_str(&env, ""),
+                    )?;
+                }
+                EscrowOp::Approve(escrow_id, milestone_index) => {
+                    ensure_not_paused(&env)?;
+                    let mut escrow = load_live_escrow(&env, escrow_id)?;
+                    approvals::approve(&env, escrow_id, &mut escrow, milestone_index, &caller)?;
+                }
+                EscrowOp::Fund(escrow_id) => {
+                    ensure_not_paused(&env)?;
+                    let mut escrow = as_depositor(&env, escrow_id, &caller)?;
+                    escrow::fund(&env, escrow_id, &mut escrow)?;
+                }
+                EscrowOp::Reject(escrow_id, milestone_index, reason_hash) => {
+                    let mut escrow = as_depositor(&env, escrow_id, &caller)?;
+                    review::reject(&env, escrow_id, &mut escrow, milestone_index, reason_hash)?;
+                }
+                EscrowOp::Complete(escrow_id) => {
+                    ensure_not_paused(&env)?;
+                    let mut escrow = as_depositor_authority(&env, escrow_id, &caller)?;
+                    escrow::complete(&env, escrow_id, &mut escrow, &caller)?;
+                }
+                EscrowOp::Cancel(escrow_id) => {
+                    ensure_not_paused(&env)?;
+                    let mut escrow = as_depositor_authority(&env, escrow_id, &caller)?;
+                    escrow::cancel(&env, escrow_id, &mut escrow, &caller)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Loads the escrow, failing with `UnauthorizedAccess` unless `caller` is its depositor.
+fn as_depositor(env: &Env, escrow_id: u64, caller: &Address) -> Result<Escrow, Error> {
+    let escrow = load_live_escrow(env, escrow_id)?;
+    if escrow.depositor != *caller {
+        return Err(Error::UnauthorizedAccess);
+    }
+    Ok(escrow)
+}
+
+/// Loads the escrow, failing with `UnauthorizedAccess` unless `caller` currently acts for its
+/// depositor.
+fn as_depositor_authority(env: &Env, escrow_id: u64, caller: &Address) -> Result<Escrow, Error> {
+    let escrow = load_live_escrow(env, escrow_id)?;
+    if depositor_authority(env, escrow_id, &escrow) != *caller {
+        return Err(Error::UnauthorizedAccess);
+    }
+    Ok(escrow)
+}
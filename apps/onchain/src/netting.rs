@@ -0,0 +1,158 @@
+//! Netting between counterparties.
+//!
+//! Two parties who owe each other through opposing escrows in the same token (A pays B in one,
+//! B pays A in the other) would otherwise lock and move funds that largely cancel out. With
+//! both parties' consent, `net_escrows` offsets the smaller escrow's total against the larger
+//! one before either is funded: the smaller escrow completes without any deposit, and the
+//! larger one's leading milestones worth the same amount count as funded and released. The
+//! larger escrow becomes Active with only the difference left to fund through
+//! `fund_milestones`; when the totals match, both escrows complete.
+//!
+//! Milestones settle whole, so the offset must end on a milestone boundary of the larger
+//! escrow.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin::ensure_not_paused,
+    chain::ensure_parent_completed,
+    errors::Error,
+    escrow::{
+        burn_receipt, credit_completion, finish_funded, is_vesting, load_live_escrow, save_escrow,
+    },
+    history, state_machine,
+    statement::tally,
+    stats,
+    storage::{
+        all_milestones_mask, funding_key, funding_plan_key, load_milestone, recurring_key,
+        write_persistent,
+    },
+    types::{Escrow, EscrowStatus},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Env};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Offsets two unfunded escrows running in opposite directions between the same parties,
+    /// with both parties' authorization. Returns the amount offset.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If either escrow doesn't exist
+    /// * `InvalidConfiguration` - If the escrows are the same, not between the same two parties
+    ///   in opposite roles, in different tokens, or either has a funding plan
+    /// * `EscrowAlreadyFunded` - If either escrow has been funded
+    /// * `InvalidEscrowStatus` - If either escrow is a draft, proposal or open bounty, or pays
+    ///   out on a recurring or vesting schedule
+    /// * `ParentNotCompleted` - If either escrow is waiting on an incomplete parent
+    /// * `TotalAmountMismatch` - If the offset ends mid-milestone in the larger escrow
+    pub fn net_escrows(env: Env, escrow_id: u64, other_id: u64) -> Result<i128, Error> {
+        ensure_not_paused(&env)?;
+
+        let first = load_live_escrow(&env, escrow_id)?;
+        let second = load_live_escrow(&env, other_id)?;
+        if escrow_id == other_id
+            || first.depositor != second.recipient
+            || first.recipient != second.depositor
+            || first.token_address != second.token_address
+        {
+            return Err(Error::InvalidConfiguration);
+        }
+        first.depositor.require_auth();
+        first.recipient.require_auth();
+        ensure_nettable(&env, escrow_id, &first)?;
+        ensure_nettable(&env, other_id, &second)?;
+
+        let ((small_id, mut small), (large_id, mut large)) =
+            if first.total_amount <= second.total_amount {
+                ((escrow_id, first), (other_id, second))
+            } else {
+                ((other_id, second), (escrow_id, first))
+            };
+        let offset = small.total_amount;
+
+        let mut count = 0;
+        let mut covered: i128 = 0;
+        while covered < offset && count < large.milestone_count {
+            covered = covered
+                .checked_add(load_milestone(&env, large_id, count)?.amount)
+                .ok_or(Error::InvalidMilestoneAmount)?;
+            count += 1;
+        }
+        if covered != offset {
+            return Err(Error::TotalAmountMismatch);
+        }
+
+        let small_count = small.milestone_count;
+        settle_netted(&env, small_id, &mut small, small_count, offset)?;
+        settle_netted(&env, large_id, &mut large, count, offset)?;
+
+        env.events()
+            .publish((symbol_short!("netted"), escrow_id, other_id), offset);
+        Ok(offset)
+    }
+}
+
+/// Fails with the error netting should report unless the escrow is unfunded and pays out
+/// through plain milestones.
+fn ensure_nettable(env: &Env, escrow_id: u64, escrow: &Escrow) -> Result<(), Error> {
+    match escrow.status {
+        EscrowStatus::Created => {}
+        EscrowStatus::Draft | EscrowStatus::Proposed | EscrowStatus::Open => {
+            return Err(Error::InvalidEscrowStatus)
+        }
+        _ => return Err(Error::EscrowAlreadyFunded),
+    }
+    if env.storage().persistent().has(&recurring_key(escrow_id)) || is_vesting(env, escrow_id) {
+        return Err(Error::InvalidEscrowStatus);
+    }
+    if env.storage().persistent().has(&funding_plan_key(escrow_id)) {
+        return Err(Error::InvalidConfiguration);
+    }
+    ensure_parent_completed(env, escrow_id)
+}
+
+/// Marks milestones `0..count`, worth `amount`, as funded and released by the offset, completing
+/// the escrow if that covers all of it.
+fn settle_netted(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &mut Escrow,
+    count: u32,
+    amount: i128,
+) -> Result<(), Error> {
+    state_machine::transition(escrow, EscrowStatus::Active)?;
+    stats::escrow_funded(env, &escrow.token_address, 0);
+    escrow.released_mask = all_milestones_mask(count);
+    escrow.total_released = amount;
+    tally(env, escrow_id, |totals| {
+        totals.netted = totals.netted.saturating_add(amount)
+    });
+    history::record(
+        env,
+        escrow_id,
+        symbol_short!("netted"),
+        &escrow.depositor,
+        amount,
+    );
+
+    if count < escrow.milestone_count {
+        save_escrow(env, escrow_id, escrow);
+        let key = funding_key(escrow_id);
+        write_persistent(env, &key, &(count, amount));
+        return Ok(());
+    }
+
+    state_machine::transition(escrow, EscrowStatus::Completed)?;
+    save_escrow(env, escrow_id, escrow);
+    history::record(
+        env,
+        escrow_id,
+        symbol_short!("completed"),
+        &escrow.depositor,
+        0,
+    );
+    finish_funded(env, escrow_id, escrow)?;
+    burn_receipt(env, escrow_id);
+    credit_completion(env, escrow_id, escrow)
+}
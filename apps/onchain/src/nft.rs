@@ -0,0 +1,188 @@
+//! NFT deliverables.
+//!
+//! An escrow can pay for a specific NFT: the depositor names the collection and token ID before
+//! funding, and the recipient deposits that token into the contract's custody. As soon as the
+//! escrow is fully funded with the token held, the two legs swap atomically: the token goes to
+//! the depositor, every milestone is released to the recipient and the escrow completes. Until
+//! then the recipient can take the token back, except while a dispute is open, in which case
+//! the ruling decides who receives it.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    errors::Error,
+    escrow::{load_live_escrow, release_all},
+    funding::funding,
+    history,
+    storage::{nft_key, write_persistent},
+    types::{Escrow, EscrowStatus, NftClient, NftDeliverable},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Makes the escrow pay for NFT `token_id` of `collection`, delivered through the
+    /// contract. Depositor only, before funding.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If the escrow doesn't exist
+    /// * `EscrowAlreadyFunded` - If the escrow has been funded
+    pub fn require_nft(
+        env: Env,
+        escrow_id: u64,
+        collection: Address,
+        token_id: u32,
+    ) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if !matches!(
+            escrow.status,
+            EscrowStatus::Draft | EscrowStatus::Proposed | EscrowStatus::Created
+        ) {
+            return Err(Error::EscrowAlreadyFunded);
+        }
+
+        save_deliverable(
+            &env,
+            escrow_id,
+            &NftDeliverable {
+                collection,
+                token_id,
+                held: false,
+            },
+        );
+        Ok(())
+    }
+
+    /// The NFT the escrow pays for, if any.
+    pub fn get_nft_deliverable(env: Env, escrow_id: u64) -> Option<NftDeliverable> {
+        env.storage().persistent().get(&nft_key(escrow_id))
+    }
+
+    /// Moves the escrow's NFT from the recipient into the contract, swapping it for the
+    /// payment right away if the escrow is fully funded.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If the escrow doesn't exist
+    /// * `InvalidConfiguration` - If the escrow doesn't pay for an NFT
+    /// * `InvalidEscrowStatus` - If the NFT is already held, or the escrow is neither created
+    ///   nor active
+    pub fn deposit_nft(env: Env, escrow_id: u64) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.recipient.require_auth();
+
+        let mut deliverable =
+            Self::get_nft_deliverable(env.clone(), escrow_id).ok_or(Error::InvalidConfiguration)?;
+        if deliverable.held
+            || !matches!(escrow.status, EscrowStatus::Created | EscrowStatus::Active)
+        {
+            return Err(Error::InvalidEscrowStatus);
+        }
+
+        deliverable.held = true;
+        save_deliverable(&env, escrow_id, &deliverable);
+        NftClient::new(&env, &deliverable.collection).transfer(
+            &escrow.recipient,
+            &env.current_contract_address(),
+            &deliverable.token_id,
+        );
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("nft_in"),
+            &escrow.recipient,
+            0,
+        );
+
+        swap(&env, escrow_id)?;
+        Ok(())
+    }
+
+    /// Returns the escrow's NFT from the contract to the recipient before it is swapped.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If the escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the contract doesn't hold the NFT, or the escrow is
+    ///   disputed
+    pub fn withdraw_nft(env: Env, escrow_id: u64) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.recipient.require_auth();
+
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        hand_over(&env, escrow_id, &escrow.recipient)?;
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("nft_out"),
+            &escrow.recipient,
+            0,
+        );
+        Ok(())
+    }
+}
+
+fn save_deliverable(env: &Env, escrow_id: u64, deliverable: &NftDeliverable) {
+    let key = nft_key(escrow_id);
+    write_persistent(env, &key, deliverable);
+}
+
+/// Transfers the NFT held for the escrow to `to`.
+fn hand_over(env: &Env, escrow_id: u64, to: &Address) -> Result<(), Error> {
+    let mut deliverable = VaultixEscrow::get_nft_deliverable(env.clone(), escrow_id)
+        .filter(|deliverable| deliverable.held)
+        .ok_or(Error::InvalidEscrowStatus)?;
+
+    deliverable.held = false;
+    save_deliverable(env, escrow_id, &deliverable);
+    NftClient::new(env, &deliverable.collection).transfer(
+        &env.current_contract_address(),
+        to,
+        &deliverable.token_id,
+    );
+    Ok(())
+}
+
+/// Swaps the NFT held for the escrow against its payment once the escrow is fully funded:
+/// the NFT goes to the depositor, the remaining milestones to the recipient, and the escrow
+/// completes. Returns whether the swap happened.
+pub(crate) fn swap(env: &Env, escrow_id: u64) -> Result<bool, Error> {
+    let held = VaultixEscrow::get_nft_deliverable(env.clone(), escrow_id)
+        .is_some_and(|deliverable| deliverable.held);
+    let mut escrow = load_live_escrow(env, escrow_id)?;
+    if !held
+        || escrow.status != EscrowStatus::Active
+        || funding(env, escrow_id, &escrow).0 < escrow.milestone_count
+    {
+        return Ok(false);
+    }
+
+    let depositor = escrow.depositor.clone();
+    hand_over(env, escrow_id, &depositor)?;
+    release_all(env, escrow_id, &mut escrow)?;
+    Ok(true)
+}
+
+/// Settles an NFT held for a disputed escrow on its ruling: the depositor receives it if the
+/// recipient was paid, otherwise it goes back to the recipient.
+pub(crate) fn settle_ruling(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &Escrow,
+    recipient_won: bool,
+) -> Result<(), Error> {
+    let held = VaultixEscrow::get_nft_deliverable(env.clone(), escrow_id)
+        .is_some_and(|deliverable| deliverable.held);
+    if !held {
+        return Ok(());
+    }
+    let to = if recipient_won {
+        &escrow.depositor
+    } else {
+        &escrow.recipient
+    };
+    hand_over(env, escrow_id, to)
+}
@@ -0,0 +1,61 @@
+//! Escrow notes.
+//!
+//! Scope questions get settled along the way: a clarified requirement, an agreed substitution,
+//! a change to the hand-over format. Rather than amend the escrow for each, the parties can keep
+//! a hash of their shared notes on it, which `set_escrow_notes` replaces only when both sign the
+//! same call. Either side can then prove what was agreed without trusting the other's copy.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    errors::Error,
+    escrow::load_live_escrow,
+    history, state_machine,
+    storage::{notes_key, write_persistent},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, BytesN, Env};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Replaces the escrow's notes hash, or clears it with `None`, with both parties'
+    /// authorization.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow has finished
+    pub fn set_escrow_notes(
+        env: Env,
+        escrow_id: u64,
+        notes_hash: Option<BytesN<32>>,
+    ) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+        escrow.recipient.require_auth();
+
+        if state_machine::is_terminal(escrow.status) {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        let key = notes_key(escrow_id);
+        match &notes_hash {
+            Some(notes_hash) => write_persistent(&env, &key, notes_hash),
+            None => env.storage().persistent().remove(&key),
+        }
+
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("noted"),
+            &escrow.depositor,
+            0,
+        );
+        env.events()
+            .publish((symbol_short!("noted"), escrow_id), notes_hash);
+        Ok(())
+    }
+
+    /// Hash of the notes both parties last agreed on the escrow, if any.
+    pub fn get_escrow_notes(env: Env, escrow_id: u64) -> Option<BytesN<32>> {
+        env.storage().persistent().get(&notes_key(escrow_id))
+    }
+}
@@ -0,0 +1,61 @@
+//! Per-address notifier contracts.
+//!
+//! Any address can register a notifier contract implementing `NotifierInterface`. Whenever an
+//! `escrow_v1` event is published for an escrow the address is a party to, the notifier is
+//! called with the same action, actor and amount, so bots and agents can react on-chain without
+//! polling. Calls are best-effort: a notifier that fails is ignored. Notifiers run on other
+//! people's transactions, so they should stay light; one that exhausts the budget still fails
+//! the transaction.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    storage::{get_storage_key, notifier_key, write_persistent},
+    types::{Escrow, NotifierClient},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, Symbol};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Registers `notifier` to be called on every event of escrows `account` is a party to, or
+    /// removes the registration when `None`.
+    pub fn set_notifier(env: Env, account: Address, notifier: Option<Address>) {
+        account.require_auth();
+
+        let key = notifier_key(&account);
+        match &notifier {
+            Some(notifier) => {
+                write_persistent(&env, &key, notifier);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+        env.events()
+            .publish((symbol_short!("notifier"), account), notifier);
+    }
+
+    /// The notifier contract registered by `account`, if any.
+    pub fn get_notifier(env: Env, account: Address) -> Option<Address> {
+        env.storage().persistent().get(&notifier_key(&account))
+    }
+}
+
+/// Passes an escrow event on to the notifiers of the escrow's parties, ignoring failures.
+pub(crate) fn notify(env: &Env, action: &Symbol, escrow_id: u64, actor: &Address, amount: i128) {
+    let storage_key = get_storage_key(escrow_id);
+    let escrow: Option<Escrow> = env
+        .storage()
+        .persistent()
+        .get(&storage_key)
+        .or_else(|| env.storage().temporary().get(&storage_key));
+    let Some(escrow) = escrow else {
+        return;
+    };
+
+    for party in [escrow.depositor, escrow.recipient] {
+        if let Some(notifier) = VaultixEscrow::get_notifier(env.clone(), party) {
+            let _ = NotifierClient::new(env, &notifier)
+                .try_on_escrow_event(&escrow_id, action, actor, &amount);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Operator namespaces.
+//!
+//! One deployment can serve several platforms, each with its own policy: the fee it charges,
+//! the arbitrators its disputes go to, the tokens it accepts and the escrow sizes it allows.
+//! An escrow joins an operator's namespace when it is created through
+//! `create_operator_escrow` and follows that operator's policy for its whole life; the
+//! deployment-wide checks still apply on top.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin::ensure_not_paused,
+    admin_log,
+    errors::Error,
+    escrow::{new_escrow, store_new_escrow, BPS_DENOMINATOR},
+    history,
+    rbac::require_role,
+    storage::{escrow_operator_key, next_escrow_id, operator_key, write_persistent},
+    types::{Milestone, OperatorConfig, Role},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, Symbol, Vec};
+
+/// Most arbitrators or tokens an operator policy may list
+const MAX_OPERATOR_ENTRIES: u32 = 20;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Sets the policy of `operator` (Admin role), or removes it with `None`. Escrows already
+    /// created under the operator follow the new policy from then on.
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller lacks the Admin role
+    /// * `InvalidFeeConfiguration` - If the fee is outside 0..=10000 bps
+    /// * `VectorTooLarge` - If more than 20 arbitrators or tokens are listed
+    /// * `InvalidConfiguration` - If the amount bounds are negative or inverted
+    pub fn set_operator_config(
+        env: Env,
+        caller: Address,
+        operator: Symbol,
+        config: Option<OperatorConfig>,
+    ) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "set_operator_config", &caller, None);
+
+        let key = operator_key(&operator);
+        let Some(config) = config else {
+            env.storage().persistent().remove(&key);
+            return Ok(());
+        };
+        if !(0..=BPS_DENOMINATOR).contains(&config.fee_bps) {
+            return Err(Error::InvalidFeeConfiguration);
+        }
+        if config.arbiters.len() > MAX_OPERATOR_ENTRIES
+            || config.tokens.len() > MAX_OPERATOR_ENTRIES
+        {
+            return Err(Error::VectorTooLarge);
+        }
+        if config.min_amount < 0
+            || config.max_amount < 0
+            || (config.max_amount > 0 && config.max_amount < config.min_amount)
+        {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        write_persistent(&env, &key, &config);
+        Ok(())
+    }
+
+    /// Policy of `operator`, if one is set.
+    pub fn get_operator_config(env: Env, operator: Symbol) -> Option<OperatorConfig> {
+        env.storage().persistent().get(&operator_key(&operator))
+    }
+
+    /// Creates an escrow in the namespace of `operator`, as `create_escrow` would, after
+    /// checking it against the operator's token allowlist and amount bounds.
+    ///
+    /// # Returns
+    /// The identifier assigned to the new escrow
+    ///
+    /// # Errors
+    /// Those of `create_escrow`, plus:
+    /// * `InvalidConfiguration` - If the operator has no policy
+    /// * `NotAllowlisted` - If the operator does not accept the token
+    /// * `AmountOutOfRange` - If the total is outside the operator's bounds
+    pub fn create_operator_escrow(
+        env: Env,
+        operator: Symbol,
+        depositor: Address,
+        recipient: Address,
+        token_address: Address,
+        milestones: Vec<Milestone>,
+        deadline: u64,
+    ) -> Result<u64, Error> {
+        depositor.require_auth();
+
+        ensure_not_paused(&env)?;
+
+        let config = Self::get_operator_config(env.clone(), operator.clone())
+            .ok_or(Error::InvalidConfiguration)?;
+        if !config.tokens.is_empty() && !config.tokens.contains(&token_address) {
+            return Err(Error::NotAllowlisted);
+        }
+
+        let (escrow, milestones) = new_escrow(
+            &env,
+            depositor.clone(),
+            recipient,
+            token_address,
+            milestones,
+            deadline,
+        )?;
+        if escrow.total_amount < config.min_amount
+            || (config.max_amount > 0 && escrow.total_amount > config.max_amount)
+        {
+            return Err(Error::AmountOutOfRange);
+        }
+        let escrow_id = next_escrow_id(&env);
+        store_new_escrow(&env, escrow_id, &escrow, &milestones)?;
+
+        let key = escrow_operator_key(escrow_id);
+        write_persistent(&env, &key, &operator);
+        history::record(&env, escrow_id, symbol_short!("operator"), &depositor, 0);
+        Ok(escrow_id)
+    }
+
+    /// Operator whose namespace the escrow was created in, if any.
+    pub fn get_escrow_operator(env: Env, escrow_id: u64) -> Option<Symbol> {
+        env.storage()
+            .persistent()
+            .get(&escrow_operator_key(escrow_id))
+    }
+}
+
+/// Policy the escrow follows, if it belongs to an operator that still has one.
+pub(crate) fn config(env: &Env, escrow_id: u64) -> Option<OperatorConfig> {
+    let operator = VaultixEscrow::get_escrow_operator(env.clone(), escrow_id)?;
+    VaultixEscrow::get_operator_config(env.clone(), operator)
+}
@@ -0,0 +1,226 @@
+//! Organization accounts.
+//!
+//! A company registers its funding account as an organization and adds employees as members.
+//! Members create escrows "as the org" — the org account is the depositor and funds them — and
+//! any member can release, cancel or dispute the org's escrows with their own key. Rotating an
+//! employee out is a single membership change instead of a role transfer on every escrow.
+//!
+//! The org account itself always counts as an admin, so the org cannot lock itself out by
+//! removing its last admin member.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin::ensure_not_paused,
+    approvals::ensure_no_approval_policy,
+    errors::Error,
+    escrow::{
+        cancel, load_live_escrow, new_escrow, pay_milestone, release_guard, store_new_escrow,
+    },
+    history,
+    storage::{
+        escrow_org_key, next_escrow_id, next_org_id, org_key, org_member_key, write_persistent,
+    },
+    types::{Milestone, OrgRole, Organization},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, Vec};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Registers `account` as an organization with `admin` as its first admin member. The
+    /// account must authorize, since it will fund and own the org's escrows.
+    ///
+    /// # Returns
+    /// The identifier assigned to the organization
+    pub fn register_org(env: Env, account: Address, admin: Address) -> u64 {
+        account.require_auth();
+
+        let org_id = next_org_id(&env);
+        let key = org_key(org_id);
+        write_persistent(
+            &env,
+            &key,
+            &Organization {
+                account: account.clone(),
+            },
+        );
+        save_member(&env, org_id, &admin, OrgRole::Admin);
+
+        env.events()
+            .publish((symbol_short!("org_new"), org_id), (account, admin));
+        org_id
+    }
+
+    /// Adds `member` to the organization or changes their role. Org admins only.
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If `caller` is not an admin of the organization
+    pub fn set_org_member(
+        env: Env,
+        org_id: u64,
+        caller: Address,
+        member: Address,
+        role: OrgRole,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        ensure_org_admin(&env, org_id, &caller)?;
+
+        save_member(&env, org_id, &member, role);
+        env.events()
+            .publish((symbol_short!("org_mem"), org_id), (member, role));
+        Ok(())
+    }
+
+    /// Removes `member` from the organization, revoking their access to all its escrows at once.
+    /// Org admins only.
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If `caller` is not an admin of the organization
+    pub fn remove_org_member(
+        env: Env,
+        org_id: u64,
+        caller: Address,
+        member: Address,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        ensure_org_admin(&env, org_id, &caller)?;
+
+        env.storage()
+            .persistent()
+            .remove(&org_member_key(org_id, &member));
+        env.events()
+            .publish((symbol_short!("org_rm"), org_id), member);
+        Ok(())
+    }
+
+    /// Returns the organization, if registered.
+    pub fn get_org(env: Env, org_id: u64) -> Option<Organization> {
+        env.storage().persistent().get(&org_key(org_id))
+    }
+
+    /// Role `member` holds in the organization; the org account itself reports Admin.
+    pub fn get_org_role(env: Env, org_id: u64, member: Address) -> Option<OrgRole> {
+        org_role(&env, org_id, &member)
+    }
+
+    /// Creates an escrow on behalf of the organization, with the org account as depositor.
+    /// Funding still goes through `deposit_funds`, authorized by the org account.
+    ///
+    /// # Returns
+    /// The identifier assigned to the new escrow
+    ///
+    /// # Errors
+    /// Those of `create_escrow`, plus `UnauthorizedAccess` if `member` does not belong to the
+    /// organization.
+    pub fn create_org_escrow(
+        env: Env,
+        org_id: u64,
+        member: Address,
+        recipient: Address,
+        token_address: Address,
+        milestones: Vec<Milestone>,
+        deadline: u64,
+    ) -> Result<u64, Error> {
+        member.require_auth();
+
+        ensure_not_paused(&env)?;
+
+        let org = Self::get_org(env.clone(), org_id).ok_or(Error::UnauthorizedAccess)?;
+        if org_role(&env, org_id, &member).is_none() {
+            return Err(Error::UnauthorizedAccess);
+        }
+
+        let (escrow, milestones) = new_escrow(
+            &env,
+            org.account,
+            recipient,
+            token_address,
+            milestones,
+            deadline,
+        )?;
+        let escrow_id = next_escrow_id(&env);
+        store_new_escrow(&env, escrow_id, &escrow, &milestones)?;
+
+        let key = escrow_org_key(escrow_id);
+        write_persistent(&env, &key, &org_id);
+        history::record(&env, escrow_id, symbol_short!("org"), &member, 0);
+        Ok(escrow_id)
+    }
+
+    /// Organization that owns the escrow, if it was created through `create_org_escrow`.
+    pub fn get_escrow_org(env: Env, escrow_id: u64) -> Option<u64> {
+        env.storage().persistent().get(&escrow_org_key(escrow_id))
+    }
+
+    /// Releases a milestone of an org-owned escrow on the member's own authority.
+    ///
+    /// # Errors
+    /// Those of `release_milestone`, with `UnauthorizedAccess` if `member` does not belong to
+    /// the organization owning the escrow.
+    pub fn org_release_milestone(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+        member: Address,
+    ) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        member.require_auth();
+        if !acts_for_org(&env, escrow_id, &member) {
+            return Err(Error::UnauthorizedAccess);
+        }
+        ensure_no_approval_policy(&env, escrow_id)?;
+        release_guard(&env, escrow_id, &escrow, milestone_index)?;
+
+        pay_milestone(&env, escrow_id, &mut escrow, milestone_index, &member)
+    }
+
+    /// Cancels an org-owned escrow on the member's own authority, refunding the org account.
+    ///
+    /// # Errors
+    /// Those of `cancel_escrow`, with `UnauthorizedAccess` if `member` does not belong to the
+    /// organization owning the escrow.
+    pub fn org_cancel_escrow(env: Env, escrow_id: u64, member: Address) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        member.require_auth();
+        if !acts_for_org(&env, escrow_id, &member) {
+            return Err(Error::UnauthorizedAccess);
+        }
+
+        cancel(&env, escrow_id, &mut escrow, &member)
+    }
+}
+
+/// Whether `address` is a member of the organization owning the escrow.
+pub(crate) fn acts_for_org(env: &Env, escrow_id: u64, address: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .get::<_, u64>(&escrow_org_key(escrow_id))
+        .is_some_and(|org_id| org_role(env, org_id, address).is_some())
+}
+
+fn org_role(env: &Env, org_id: u64, address: &Address) -> Option<OrgRole> {
+    let org: Organization = env.storage().persistent().get(&org_key(org_id))?;
+    if org.account == *address {
+        return Some(OrgRole::Admin);
+    }
+    env.storage()
+        .persistent()
+        .get(&org_member_key(org_id, address))
+}
+
+fn ensure_org_admin(env: &Env, org_id: u64, caller: &Address) -> Result<(), Error> {
+    if org_role(env, org_id, caller) != Some(OrgRole::Admin) {
+        return Err(Error::UnauthorizedAccess);
+    }
+    Ok(())
+}
+
+fn save_member(env: &Env, org_id: u64, member: &Address, role: OrgRole) {
+    let key = org_member_key(org_id, member);
+    write_persistent(env, &key, &role);
+}
@@ -0,0 +1,332 @@
+//! Panel arbitration.
+//!
+//! Instead of a single arbitrator, a depositor can have the escrow's disputes decided by a vote
+//! of the arbitrator pool. Once evidence has closed, every pool arbitrator who is not a party to
+//! the escrow may commit to a hidden vote for either side until the arbitration config's
+//! resolution window runs out, then has another resolution window to reveal it. Keeping votes
+//! sealed until everyone has committed stops arbitrators from copying each other and leaves
+//! nothing to target with a bribe while voting is open.
+//!
+//! Votes weigh as much as the voter's stake, which stays locked until the vote closes. Voters who
+//! never reveal are slashed and left out of the count. The side with more revealed stake behind
+//! it wins, and the arbitration fee out of the dispute bond is shared among the arbitrators who
+//! voted with the majority, in proportion to their stake. A vote that nobody reveals, or that
+//! ties, hands the dispute to a single arbitrator as usual.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    arbitration::{self, credit_fee, open_assignments, set_open_assignments},
+    dispute::{apply_ruling, ensure_evidence_closed, ensure_party},
+    errors::{fail, Error, ExtendedError},
+    escrow::load_live_escrow,
+    history,
+    storage::{panel_mode_key, panel_vote_key, write_persistent},
+    types::{EscrowStatus, PanelBallot, PanelVote, RulingReason},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, xdr::ToXdr, Address, BytesN, Env, Map, Vec};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Sends the escrow's disputes to a stake-weighted vote of the arbitrator pool. Depositor
+    /// only, before funding.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If the escrow doesn't exist
+    /// * `EscrowAlreadyFunded` - If the escrow has been funded
+    /// * `InvalidConfiguration` - If arbitration has no resolution window to vote in, or the
+    ///   escrow already uses token-holder arbitration
+    pub fn use_panel_arbitration(env: Env, escrow_id: u64) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if !matches!(
+            escrow.status,
+            EscrowStatus::Draft | EscrowStatus::Proposed | EscrowStatus::Created
+        ) {
+            return Err(Error::EscrowAlreadyFunded);
+        }
+        let windowed = Self::get_arbitration_config(env.clone())
+            .is_some_and(|config| config.resolution_window > 0);
+        if !windowed || Self::uses_dao_arbitration(env.clone(), escrow_id) {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        let key = panel_mode_key(escrow_id);
+        write_persistent(&env, &key, &true);
+        Ok(())
+    }
+
+    /// Whether the escrow's disputes are decided by a panel of arbitrators.
+    pub fn uses_panel_arbitration(env: Env, escrow_id: u64) -> bool {
+        env.storage().persistent().has(&panel_mode_key(escrow_id))
+    }
+
+    /// The open panel vote on the escrow's dispute, if any.
+    pub fn get_panel_vote(env: Env, escrow_id: u64) -> Option<PanelVote> {
+        env.storage().persistent().get(&panel_vote_key(escrow_id))
+    }
+
+    /// The commitment `arbitrator` submits to vote for the depositor or, when `for_recipient`
+    /// is set, the recipient, sealed with a secret `salt`.
+    pub fn panel_commitment(
+        env: Env,
+        escrow_id: u64,
+        arbitrator: Address,
+        for_recipient: bool,
+        salt: BytesN<32>,
+    ) -> BytesN<32> {
+        let preimage = (escrow_id, arbitrator, for_recipient, salt).to_xdr(&env);
+        env.crypto().sha256(&preimage)
+    }
+
+    /// Commits `arbitrator` to a sealed vote, weighted by their current stake. The stake is
+    /// locked until the vote closes.
+    ///
+    /// # Returns
+    /// The vote's weight
+    ///
+    /// # Errors
+    /// * `InvalidEscrowStatus` - If no panel vote is open on the escrow or voting has ended
+    /// * `EvidenceWindowOpen` - If the parties can still submit evidence
+    /// * `UnauthorizedAccess` - If arbitrator is a party to the escrow or has already voted
+    /// * `NotArbitrator` - If arbitrator is not in the pool or has nothing staked
+    pub fn commit_panel_vote(
+        env: Env,
+        escrow_id: u64,
+        arbitrator: Address,
+        commitment: BytesN<32>,
+    ) -> Result<i128, Error> {
+        arbitrator.require_auth();
+        let mut vote = Self::get_panel_vote(env.clone(), escrow_id)
+            .filter(|vote| env.ledger().timestamp() < vote.ends_at)
+            .ok_or(Error::InvalidEscrowStatus)?;
+        ensure_evidence_closed(&env, escrow_id)?;
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        if ensure_party(&escrow, &arbitrator).is_ok()
+            || vote.ballots.contains_key(arbitrator.clone())
+        {
+            return Err(Error::UnauthorizedAccess);
+        }
+        let weight = Self::get_stake(env.clone(), arbitrator.clone());
+        if weight <= 0 || !Self::is_arbitrator(env.clone(), arbitrator.clone()) {
+            return Err(Error::NotArbitrator);
+        }
+
+        vote.ballots.set(
+            arbitrator.clone(),
+            PanelBallot {
+                commitment,
+                revealed: false,
+                for_recipient: false,
+                weight,
+            },
+        );
+        let key = panel_vote_key(escrow_id);
+        write_persistent(&env, &key, &vote);
+        set_open_assignments(&env, &arbitrator, open_assignments(&env, &arbitrator) + 1);
+        history::record(&env, escrow_id, symbol_short!("voted"), &arbitrator, weight);
+        Ok(weight)
+    }
+
+    /// Reveals `arbitrator`'s committed vote once voting has ended and before the reveal
+    /// window runs out.
+    ///
+    /// # Errors
+    /// * `InvalidEscrowStatus` - If no panel vote is open on the escrow or it is not revealing
+    /// * `UnauthorizedAccess` - If arbitrator has no unrevealed vote matching the reveal
+    pub fn reveal_panel_vote(
+        env: Env,
+        escrow_id: u64,
+        arbitrator: Address,
+        for_recipient: bool,
+        salt: BytesN<32>,
+    ) -> Result<(), Error> {
+        arbitrator.require_auth();
+        let now = env.ledger().timestamp();
+        let mut vote = Self::get_panel_vote(env.clone(), escrow_id)
+            .filter(|vote| now >= vote.ends_at && now < vote.reveal_ends_at)
+            .ok_or(Error::InvalidEscrowStatus)?;
+        let commitment = Self::panel_commitment(
+            env.clone(),
+            escrow_id,
+            arbitrator.clone(),
+            for_recipient,
+            salt,
+        );
+        let mut ballot = vote
+            .ballots
+            .get(arbitrator.clone())
+            .filter(|ballot| !ballot.revealed && ballot.commitment == commitment)
+            .ok_or(Error::UnauthorizedAccess)?;
+
+        ballot.revealed = true;
+        ballot.for_recipient = for_recipient;
+        let weight = ballot.weight;
+        vote.ballots.set(arbitrator.clone(), ballot);
+        let key = panel_vote_key(escrow_id);
+        write_persistent(&env, &key, &vote);
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("revealed"),
+            &arbitrator,
+            weight,
+        );
+        Ok(())
+    }
+
+    /// Closes a panel vote whose reveal window has ended: voters who never revealed are
+    /// slashed, then the ruling executes for the side with more revealed stake behind it, or
+    /// the dispute goes to a single arbitrator if the vote was undecided. Anyone may call this.
+    ///
+    /// # Returns
+    /// The winning party, or `None` if the vote was undecided
+    ///
+    /// # Errors
+    /// * `InvalidEscrowStatus` - If no panel vote is open on the escrow
+    /// * `RulingNotReady` - If votes can still be committed or revealed
+    pub fn finalize_panel_vote(env: Env, escrow_id: u64) -> Result<Option<Address>, Error> {
+        let vote =
+            Self::get_panel_vote(env.clone(), escrow_id).ok_or(Error::InvalidEscrowStatus)?;
+        if env.ledger().timestamp() < vote.reveal_ends_at {
+            return Err(Error::RulingNotReady);
+        }
+        for (voter, ballot) in vote.ballots.iter() {
+            if !ballot.revealed {
+                let slashed = arbitration::slash(&env, &voter)?;
+                history::record(&env, escrow_id, symbol_short!("slashed"), &voter, slashed);
+            }
+        }
+
+        let (for_depositor, for_recipient) = tally(&vote);
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        if for_depositor == for_recipient {
+            discard(&env, escrow_id);
+            history::record(
+                &env,
+                escrow_id,
+                symbol_short!("undecided"),
+                &env.current_contract_address(),
+                for_depositor + for_recipient,
+            );
+            arbitration::assign_or_enqueue(&env, escrow_id, &escrow);
+            return Ok(None);
+        }
+
+        let winner = if for_recipient > for_depositor {
+            escrow.recipient
+        } else {
+            escrow.depositor
+        };
+        let resolver = env.current_contract_address();
+        apply_ruling(
+            env,
+            escrow_id,
+            resolver,
+            winner.clone(),
+            RulingReason::Other,
+            None,
+        )?;
+        Ok(Some(winner))
+    }
+}
+
+/// Total revealed stake behind the depositor and the recipient.
+fn tally(vote: &PanelVote) -> (i128, i128) {
+    let (mut for_depositor, mut for_recipient) = (0, 0);
+    for ballot in vote.ballots.values() {
+        if !ballot.revealed {
+            continue;
+        }
+        if ballot.for_recipient {
+            for_recipient += ballot.weight;
+        } else {
+            for_depositor += ballot.weight;
+        }
+    }
+    (for_depositor, for_recipient)
+}
+
+/// Opens a panel vote on a newly disputed escrow that uses panel arbitration, returning whether
+/// it does. Voting runs for the resolution window once evidence has closed, followed by another
+/// resolution window for revealing.
+pub(crate) fn open_vote(env: &Env, escrow_id: u64) -> Result<bool, Error> {
+    if !VaultixEscrow::uses_panel_arbitration(env.clone(), escrow_id) {
+        return Ok(false);
+    }
+    let config =
+        VaultixEscrow::get_arbitration_config(env.clone()).ok_or(Error::InvalidConfiguration)?;
+
+    let start = VaultixEscrow::get_evidence_deadline(env.clone(), escrow_id)
+        .unwrap_or(0)
+        .max(env.ledger().timestamp());
+    let ends_at = start.saturating_add(config.resolution_window);
+    let vote = PanelVote {
+        ends_at,
+        reveal_ends_at: ends_at.saturating_add(config.resolution_window),
+        ballots: Map::new(env),
+    };
+    let key = panel_vote_key(escrow_id);
+    write_persistent(env, &key, &vote);
+    Ok(true)
+}
+
+/// Drops the escrow's panel vote, if any, unlocking its voters' stakes.
+pub(crate) fn discard(env: &Env, escrow_id: u64) {
+    let Some(vote) = VaultixEscrow::get_panel_vote(env.clone(), escrow_id) else {
+        return;
+    };
+    for voter in vote.ballots.keys() {
+        set_open_assignments(env, &voter, open_assignments(env, &voter).saturating_sub(1));
+    }
+    env.storage()
+        .persistent()
+        .remove(&panel_vote_key(escrow_id));
+}
+
+/// Splits `fee` among the voters on the winning side of the escrow's panel vote in proportion to
+/// their stake. Rounding leftovers go to the first of them.
+pub(crate) fn share_fee(
+    env: &Env,
+    escrow_id: u64,
+    token_address: &Address,
+    fee: i128,
+) -> Result<(), Error> {
+    let Some(vote) = VaultixEscrow::get_panel_vote(env.clone(), escrow_id) else {
+        return Ok(());
+    };
+    let (for_depositor, for_recipient) = tally(&vote);
+    let recipient_won = for_recipient > for_depositor;
+    let majority_weight = for_depositor.max(for_recipient);
+
+    let mut majority = Vec::new(env);
+    let mut shared: i128 = 0;
+    for (voter, ballot) in vote.ballots.iter() {
+        if ballot.revealed && ballot.for_recipient == recipient_won {
+            let share = fee
+                .checked_mul(ballot.weight)
+                .unwrap_or_else(|| fail(env, ExtendedError::FeeOverflow))
+                / majority_weight;
+            shared = shared
+                .checked_add(share)
+                .unwrap_or_else(|| fail(env, ExtendedError::SplitMismatch));
+            majority.push_back((voter, share));
+        }
+    }
+    let leftover = fee
+        .checked_sub(shared)
+        .unwrap_or_else(|| fail(env, ExtendedError::SplitMismatch));
+    for (index, (voter, share)) in majority.iter().enumerate() {
+        let share = if index == 0 {
+            share
+                .checked_add(leftover)
+                .unwrap_or_else(|| fail(env, ExtendedError::SplitMismatch))
+        } else {
+            share
+        };
+        credit_fee(env, &voter, token_address, share)?;
+    }
+    Ok(())
+}
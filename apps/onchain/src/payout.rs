@@ -0,0 +1,72 @@
+//! Per-recipient payout settings.
+//!
+//! Recipients receiving into exchange or custodial accounts can register a payout memo (a
+//! destination tag or text memo, up to Stellar's 28 bytes). Token transfers carry no memo, so
+//! every fee-bearing payout to such an address also publishes
+//!
+//! ```text
+//! topics: ("memo", escrow_id: u64, milestone_index: u32)
+//! data:   (payee: Address, amount: i128, memo: String)
+//! ```
+//!
+//! from the same invocation as the transfer, letting the custodian match the deposit.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    errors::Error,
+    storage::{payout_memo_key, write_persistent},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, String};
+
+// Longest memo a Stellar payment can carry
+const MAX_PAYOUT_MEMO_LEN: u32 = 28;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Sets the memo published with payouts to `recipient`, or clears it with `None`.
+    ///
+    /// # Errors
+    /// * `DescriptionTooLong` - If the memo exceeds 28 bytes
+    pub fn set_payout_memo(
+        env: Env,
+        recipient: Address,
+        memo: Option<String>,
+    ) -> Result<(), Error> {
+        recipient.require_auth();
+
+        let key = payout_memo_key(&recipient);
+        match memo {
+            Some(memo) => {
+                if memo.len() > MAX_PAYOUT_MEMO_LEN {
+                    return Err(Error::DescriptionTooLong);
+                }
+                write_persistent(&env, &key, &memo);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+        Ok(())
+    }
+
+    /// The memo published with payouts to `recipient`, if one is set.
+    pub fn get_payout_memo(env: Env, recipient: Address) -> Option<String> {
+        env.storage().persistent().get(&payout_memo_key(&recipient))
+    }
+}
+
+/// Publishes the payee's memo, if any, for a payout of `amount` just transferred to it.
+pub(crate) fn emit_payout_memo(
+    env: &Env,
+    escrow_id: u64,
+    milestone_index: u32,
+    payee: &Address,
+    amount: i128,
+) {
+    if let Some(memo) = VaultixEscrow::get_payout_memo(env.clone(), payee.clone()) {
+        env.events().publish(
+            (symbol_short!("memo"), escrow_id, milestone_index),
+            (payee.clone(), amount, memo),
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Dry runs of money movements.
+//!
+//! The preview views work out exactly what a release or a dispute ruling would pay each party,
+//! the treasury, the insurance pool and the arbitrator, using the same calculations as the real
+//! paths but without changing any state, so a frontend can show the numbers before asking for a
+//! signature.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    arbitration::arbitrator_fee,
+    dispute::{held_bond, loser_paying_fee},
+    errors::{fail, Error, ExtendedError},
+    escrow::{load_live_escrow, release_guard, unreleased_amount},
+    fees::fee_split,
+    funding::held_amount,
+    state_machine::can_transition,
+    storage::load_milestone,
+    types::{EscrowStatus, PayoutPreview},
+    yields::yield_split,
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, Address, Env};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// What releasing the milestone now would pay out, after fees.
+    ///
+    /// # Errors
+    /// Whatever `release_milestone` would fail with for this milestone
+    pub fn preview_release(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+    ) -> Result<PayoutPreview, Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        release_guard(&env, escrow_id, &escrow, milestone_index)?;
+
+        let milestone = load_milestone(&env, escrow_id, milestone_index)?;
+        let amount = unreleased_amount(&env, escrow_id, milestone_index, &milestone)?;
+        let split = fee_split(&env, escrow_id, &escrow, amount)?;
+        Ok(PayoutPreview {
+            to_recipient: split.payout,
+            to_depositor: 0,
+            to_treasury: split
+                .fee
+                .checked_sub(split.insurance_cut)
+                .unwrap_or_else(|| fail(&env, ExtendedError::FeeOverflow)),
+            to_insurance: split.insurance_cut,
+            to_arbitrator: 0,
+            fee_asset: split.fee_asset,
+        })
+    }
+
+    /// What resolving the dispute in favour of `winner` would pay out, including the dispute
+    /// bond, the arbitrator's cut of it and any accrued yield.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow is not disputed
+    /// * `InvalidWinner` - If `winner` is neither party
+    pub fn preview_resolution(
+        env: Env,
+        escrow_id: u64,
+        winner: Address,
+    ) -> Result<PayoutPreview, Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        if !can_transition(escrow.status, EscrowStatus::Resolved) {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        if winner != escrow.depositor && winner != escrow.recipient {
+            return Err(Error::InvalidWinner);
+        }
+
+        // Funds still held go to the winner, along with the bond less the arbitrator's fee,
+        // unless the loser is to pay that fee. A bond a backup paid for the winner goes back to
+        // the backup instead.
+        let mut award = held_amount(&env, escrow_id, &escrow)?;
+        let mut to_arbitrator = 0;
+        if let Some((raiser, payer, bond)) = held_bond(&env, escrow_id) {
+            to_arbitrator = arbitrator_fee(&env, escrow_id, bond)?;
+            let charged =
+                if loser_paying_fee(&env, &escrow, &raiser, &winner, to_arbitrator).is_some() {
+                    0
+                } else {
+                    to_arbitrator
+                };
+            let returned = bond
+                .checked_sub(charged)
+                .unwrap_or_else(|| fail(&env, ExtendedError::BondUnderflow));
+            if raiser != winner || payer == winner {
+                award = award
+                    .checked_add(returned)
+                    .unwrap_or_else(|| fail(&env, ExtendedError::BondUnderflow));
+            }
+        }
+
+        let (mut to_recipient, mut to_depositor, to_insurance) = yield_split(&env, escrow_id);
+        let share = if winner == escrow.recipient {
+            &mut to_recipient
+        } else {
+            &mut to_depositor
+        };
+        *share = share
+            .checked_add(award)
+            .unwrap_or_else(|| fail(&env, ExtendedError::SplitMismatch));
+
+        Ok(PayoutPreview {
+            to_recipient,
+            to_depositor,
+            to_treasury: 0,
+            to_insurance,
+            to_arbitrator,
+            fee_asset: escrow.token_address,
+        })
+    }
+}
@@ -0,0 +1,111 @@
+//! Projects.
+//!
+//! A client running a program of many escrows can group them under a project and follow it as
+//! one unit: the escrows it contains, the budget committed across them, how much has been paid
+//! out and how many are in dispute. Aggregates are computed when read, so they always reflect
+//! the escrows' current state; escrows closed since they were added drop out of them.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    errors::Error,
+    history,
+    storage::{escrow_project_key, next_project_id, project_key, write_persistent},
+    types::{EscrowStatus, Project, ProjectSummary},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, Vec};
+
+/// Most escrows a project may group, so its summary stays within one call's budget
+const MAX_PROJECT_ESCROWS: u32 = 100;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Opens an empty project owned by `owner`.
+    ///
+    /// # Returns
+    /// The identifier assigned to the project
+    pub fn create_project(env: Env, owner: Address) -> u64 {
+        owner.require_auth();
+
+        let project_id = next_project_id(&env);
+        let key = project_key(project_id);
+        write_persistent(
+            &env,
+            &key,
+            &Project {
+                owner,
+                escrows: Vec::new(&env),
+            },
+        );
+        project_id
+    }
+
+    /// Adds an escrow to a project. The project owner must be the escrow's depositor and
+    /// authorize; an escrow belongs to at most one project.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `UnauthorizedAccess` - If the project doesn't exist or its owner isn't the depositor
+    /// * `InvalidConfiguration` - If the escrow already belongs to a project
+    /// * `VectorTooLarge` - If the project already holds 100 escrows
+    pub fn add_to_project(env: Env, project_id: u64, escrow_id: u64) -> Result<(), Error> {
+        let escrow = Self::get_escrow(env.clone(), escrow_id)?;
+        let mut project =
+            Self::get_project(env.clone(), project_id).ok_or(Error::UnauthorizedAccess)?;
+        if project.owner != escrow.depositor {
+            return Err(Error::UnauthorizedAccess);
+        }
+        project.owner.require_auth();
+
+        let escrow_key = escrow_project_key(escrow_id);
+        if env.storage().persistent().has(&escrow_key) {
+            return Err(Error::InvalidConfiguration);
+        }
+        if project.escrows.len() >= MAX_PROJECT_ESCROWS {
+            return Err(Error::VectorTooLarge);
+        }
+
+        project.escrows.push_back(escrow_id);
+        let key = project_key(project_id);
+        write_persistent(&env, &key, &project);
+        write_persistent(&env, &escrow_key, &project_id);
+        history::record(&env, escrow_id, symbol_short!("project"), &project.owner, 0);
+        Ok(())
+    }
+
+    /// The project, with every escrow ever added to it.
+    pub fn get_project(env: Env, project_id: u64) -> Option<Project> {
+        env.storage().persistent().get(&project_key(project_id))
+    }
+
+    /// Project the escrow belongs to, if any.
+    pub fn get_escrow_project(env: Env, escrow_id: u64) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&escrow_project_key(escrow_id))
+    }
+
+    /// Budget, payouts and open disputes across the project's escrows that still exist.
+    pub fn get_project_summary(env: Env, project_id: u64) -> Option<ProjectSummary> {
+        let project = Self::get_project(env.clone(), project_id)?;
+        let mut summary = ProjectSummary {
+            escrows: 0,
+            budget: 0,
+            released: 0,
+            open_disputes: 0,
+        };
+        for escrow_id in project.escrows.iter() {
+            let Ok(escrow) = Self::get_escrow(env.clone(), escrow_id) else {
+                continue;
+            };
+            summary.escrows += 1;
+            summary.budget = summary.budget.saturating_add(escrow.total_amount);
+            summary.released = summary.released.saturating_add(escrow.total_released);
+            if escrow.status == EscrowStatus::Disputed {
+                summary.open_disputes += 1;
+            }
+        }
+        Some(summary)
+    }
+}
@@ -0,0 +1,280 @@
+//! Proposed escrows, which only take effect once the recipient agrees to the terms.
+//!
+//! `propose_escrow` stores an escrow in `Proposed`. Nothing can be funded or released until
+//! the recipient calls `accept_escrow`, which moves it to `Created` and leaves both parties'
+//! signatures on the same on-chain terms. The depositor withdraws an unaccepted proposal with
+//! `cancel_escrow`.
+//!
+//! Until then the parties can negotiate: whoever did not author the current terms may answer
+//! with `counter_offer`, replacing the milestones and deadline, and it is then the other
+//! party's turn to accept or counter again. Each round is kept in the escrow's offer history,
+//! capped at `MAX_OFFERS`, so the agreed version and how the parties got there stay on-chain.
+//!
+//! Every proposal carries an expiry ledger, `DEFAULT_OFFER_LIFETIME` after it was made unless
+//! the depositor moves it with `set_offer_expiry`; each counter-offer restarts it. Past it the recipient can no longer accept,
+//! and anyone may void the offer with `expire_offer` so stale proposals do not linger.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin::ensure_not_paused,
+    errors::Error,
+    escrow::{
+        burn_receipt, ensure_caller_chosen_id, load_live_escrow, new_escrow, replace_terms,
+        save_escrow, stamp_created_at, store_new_escrow,
+    },
+    history, state_machine,
+    storage::{offer_expiry_key, offers_key, write_persistent},
+    types::{Escrow, EscrowStatus, Milestone, Offer},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, Vec};
+
+/// How long a proposal stays open by default: about a week of 5-second ledgers.
+pub(crate) const DEFAULT_OFFER_LIFETIME: u32 = 7 * 17_280;
+/// Most offers, the original proposal included, one negotiation may go through.
+pub(crate) const MAX_OFFERS: u32 = 10;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Proposes an escrow to `recipient`. Takes the same arguments and fails with the same
+    /// errors as `create_escrow`, but the escrow stays `Proposed` until the recipient accepts,
+    /// which must happen within `DEFAULT_OFFER_LIFETIME` ledgers unless the depositor changes
+    /// the expiry.
+    pub fn propose_escrow(
+        env: Env,
+        escrow_id: u64,
+        depositor: Address,
+        recipient: Address,
+        token_address: Address,
+        milestones: Vec<Milestone>,
+        deadline: u64,
+    ) -> Result<(), Error> {
+        depositor.require_auth();
+
+        ensure_not_paused(&env)?;
+        ensure_caller_chosen_id(escrow_id)?;
+
+        let (mut escrow, milestones) = new_escrow(
+            &env,
+            depositor,
+            recipient,
+            token_address,
+            milestones,
+            deadline,
+        )?;
+        escrow.status = EscrowStatus::Proposed;
+        store_new_escrow(&env, escrow_id, &escrow, &milestones)?;
+        record_offer(&env, escrow_id, &escrow, &escrow.depositor, milestones);
+        Ok(())
+    }
+
+    /// Answers the current terms of a proposal with new milestones and deadline. Only the
+    /// party that did not author the current terms may counter; it is then the other party's
+    /// turn. Returns the offer's position in the negotiation history.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow is not awaiting acceptance
+    /// * `UnauthorizedAccess` - If caller is not a party, or authored the current terms
+    /// * `InvalidDeadline` - If the offer has expired
+    /// * `VectorTooLarge` - If the negotiation has used up `MAX_OFFERS`
+    /// * `InvalidMilestoneAmount` / `AmountOutOfRange` - As for `create_escrow`
+    pub fn counter_offer(
+        env: Env,
+        escrow_id: u64,
+        caller: Address,
+        milestones: Vec<Milestone>,
+        deadline: u64,
+    ) -> Result<u32, Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        caller.require_auth();
+
+        if escrow.status != EscrowStatus::Proposed {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        if caller != awaiting_response_from(&env, escrow_id, &escrow) {
+            return Err(Error::UnauthorizedAccess);
+        }
+        if offer_expired(&env, escrow_id) {
+            return Err(Error::InvalidDeadline);
+        }
+        if Self::get_offers(env.clone(), escrow_id).len() >= MAX_OFFERS {
+            return Err(Error::VectorTooLarge);
+        }
+
+        let milestones = replace_terms(&env, escrow_id, &mut escrow, milestones, deadline)?;
+        let version = record_offer(&env, escrow_id, &escrow, &caller, milestones);
+
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("countered"),
+            &caller,
+            escrow.total_amount,
+        );
+        env.events()
+            .publish((symbol_short!("countered"), escrow_id), (caller, version));
+        Ok(version)
+    }
+
+    /// Every offer made on a proposal in order, the original terms first. The last entry is
+    /// the version in force, and once accepted, the version both parties agreed to.
+    pub fn get_offers(env: Env, escrow_id: u64) -> Vec<Offer> {
+        env.storage()
+            .persistent()
+            .get(&offers_key(escrow_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Moves a proposal's expiry to ledger `expires_at`, extending or shortening the offer.
+    /// Only the depositor may change it, and only while the offer is open.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow is not awaiting acceptance
+    /// * `InvalidDeadline` - If `expires_at` is not a future ledger, or the offer has already
+    ///   expired
+    pub fn set_offer_expiry(env: Env, escrow_id: u64, expires_at: u32) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if escrow.status != EscrowStatus::Proposed {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        if offer_expired(&env, escrow_id) || expires_at <= env.ledger().sequence() {
+            return Err(Error::InvalidDeadline);
+        }
+        save_offer_expiry(&env, escrow_id, expires_at);
+        Ok(())
+    }
+
+    /// Agrees to a proposed escrow's current terms, moving it to `Created` so the depositor can
+    /// fund it. The party that did not author those terms signs: the recipient for the
+    /// original proposal, otherwise whoever the last counter-offer was made to. The dispute
+    /// grace period counts from acceptance.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow is not awaiting acceptance
+    /// * `InvalidDeadline` - If the offer has expired
+    pub fn accept_escrow(env: Env, escrow_id: u64) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        let accepter = awaiting_response_from(&env, escrow_id, &escrow);
+        accepter.require_auth();
+
+        if escrow.status != EscrowStatus::Proposed {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        if offer_expired(&env, escrow_id) {
+            return Err(Error::InvalidDeadline);
+        }
+        state_machine::transition(&mut escrow, EscrowStatus::Created)?;
+        save_escrow(&env, escrow_id, &escrow);
+        stamp_created_at(&env, escrow_id);
+        env.storage()
+            .persistent()
+            .remove(&offer_expiry_key(escrow_id));
+
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("accepted"),
+            &accepter,
+            escrow.total_amount,
+        );
+        env.events()
+            .publish((symbol_short!("accepted"), escrow_id), accepter);
+        Ok(())
+    }
+    /// Voids a proposal whose expiry ledger has passed, cancelling it. Anyone may call this.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow is not awaiting acceptance
+    /// * `InvalidDeadline` - If the offer has not expired yet
+    pub fn expire_offer(env: Env, escrow_id: u64) -> Result<(), Error> {
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        if escrow.status != EscrowStatus::Proposed {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        if !offer_expired(&env, escrow_id) {
+            return Err(Error::InvalidDeadline);
+        }
+
+        state_machine::transition(&mut escrow, EscrowStatus::Cancelled)?;
+        save_escrow(&env, escrow_id, &escrow);
+        env.storage()
+            .persistent()
+            .remove(&offer_expiry_key(escrow_id));
+        burn_receipt(&env, escrow_id);
+
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("expired"),
+            &env.current_contract_address(),
+            0,
+        );
+        env.events()
+            .publish((symbol_short!("expired"), escrow_id), escrow.depositor);
+        Ok(())
+    }
+
+    /// Last ledger at which a proposal can be accepted. Cleared once it is accepted or expires.
+    pub fn get_offer_expiry(env: Env, escrow_id: u64) -> Option<u32> {
+        env.storage().persistent().get(&offer_expiry_key(escrow_id))
+    }
+}
+
+fn offer_expired(env: &Env, escrow_id: u64) -> bool {
+    VaultixEscrow::get_offer_expiry(env.clone(), escrow_id)
+        .is_some_and(|expires_at| env.ledger().sequence() > expires_at)
+}
+
+fn save_offer_expiry(env: &Env, escrow_id: u64, expires_at: u32) {
+    let expiry_key = offer_expiry_key(escrow_id);
+    write_persistent(env, &expiry_key, &expires_at);
+}
+
+/// Party whose turn it is on a proposal: whoever did not author the latest offer.
+fn awaiting_response_from(env: &Env, escrow_id: u64, escrow: &Escrow) -> Address {
+    match VaultixEscrow::get_offers(env.clone(), escrow_id).last() {
+        Some(offer) if offer.proposer == escrow.recipient => escrow.depositor.clone(),
+        _ => escrow.recipient.clone(),
+    }
+}
+
+/// Appends the escrow's current terms to its offer history and restarts the offer expiry.
+/// Returns the new offer's position in the history.
+fn record_offer(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &Escrow,
+    proposer: &Address,
+    milestones: Vec<Milestone>,
+) -> u32 {
+    let mut offers = VaultixEscrow::get_offers(env.clone(), escrow_id);
+    offers.push_back(Offer {
+        proposer: proposer.clone(),
+        milestones,
+        deadline: escrow.deadline,
+        total_amount: escrow.total_amount,
+        ledger: env.ledger().sequence(),
+    });
+    let key = offers_key(escrow_id);
+    write_persistent(env, &key, &offers);
+
+    save_offer_expiry(
+        env,
+        escrow_id,
+        env.ledger()
+            .sequence()
+            .saturating_add(DEFAULT_OFFER_LIFETIME),
+    );
+    offers.len() - 1
+}
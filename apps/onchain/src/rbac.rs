@@ -0,0 +1,149 @@
+//! Role-based access control for privileged operations.
+//!
+//! Each privileged entrypoint takes the acting account and checks it holds the role for that
+//! operation. The admin configured by `setup` or `init` implicitly holds every role and is the
+//! root of trust: its roles cannot be revoked. Accounts holding the Admin role may grant and
+//! revoke roles, including Admin itself. The treasury holds no role of its own: `setup` grants
+//! it Pauser and FeeManager, which the Admin role may revoke like any other grant.
+//!
+//! The Arbitrator role is the arbitrator pool: granting it registers the account as
+//! `add_arbitrator` does, and revoking it removes the account from the pool.
+//!
+//! Explicit grants are also kept in a per-role list so `get_role_members` can show who controls
+//! the deployment. Grants made before the list existed only show up once they are re-granted.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin_log,
+    arbitration::{join_pool, leave_pool},
+    errors::Error,
+    storage::{get_admin, role_key, role_members_key, write_persistent},
+    types::Role,
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, Vec};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Grants `role` to `account` (Admin role).
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller lacks the Admin role
+    pub fn grant_role(
+        env: Env,
+        caller: Address,
+        role: Role,
+        account: Address,
+    ) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "grant_role", &caller, None);
+
+        if role == Role::Arbitrator {
+            join_pool(&env, &account);
+        } else {
+            grant(&env, role, &account);
+        }
+
+        env.events()
+            .publish((symbol_short!("role_add"), role, account), caller);
+        Ok(())
+    }
+
+    /// Revokes `role` from `account` (Admin role). Roles the admin holds implicitly are
+    /// unaffected.
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller lacks the Admin role
+    pub fn revoke_role(
+        env: Env,
+        caller: Address,
+        role: Role,
+        account: Address,
+    ) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "revoke_role", &caller, None);
+
+        if role == Role::Arbitrator {
+            leave_pool(&env, &account);
+        } else {
+            env.storage().persistent().remove(&role_key(role, &account));
+            let mut members = role_members(&env, role);
+            if let Some(index) = members.first_index_of(&account) {
+                members.remove(index);
+                save_role_members(&env, role, &members);
+            }
+        }
+
+        env.events()
+            .publish((symbol_short!("role_rm"), role, account), caller);
+        Ok(())
+    }
+
+    /// Returns whether `account` holds `role`, explicitly or implicitly.
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        if get_admin(&env).is_ok_and(|admin| admin == account) {
+            return true;
+        }
+        match role {
+            Role::Arbitrator => Self::get_arbitrators(env).contains(&account),
+            _ => env.storage().persistent().has(&role_key(role, &account)),
+        }
+    }
+
+    /// Lists up to `limit` holders of `role` starting at position `start`: the admin first, then explicit grants in the order they were made (for the
+    /// Arbitrator role, the arbitrator pool). Pages past the end are empty.
+    pub fn get_role_members(env: Env, role: Role, start: u32, limit: u32) -> Vec<Address> {
+        let mut holders = Vec::new(&env);
+        if let Ok(admin) = get_admin(&env) {
+            holders.push_back(admin);
+        }
+        let granted = match role {
+            Role::Arbitrator => Self::get_arbitrators(env.clone()),
+            _ => role_members(&env, role),
+        };
+        for account in granted.iter() {
+            if !holders.contains(&account) {
+                holders.push_back(account);
+            }
+        }
+
+        let end = start.saturating_add(limit).min(holders.len());
+        if start >= end {
+            return Vec::new(&env);
+        }
+        holders.slice(start..end)
+    }
+}
+
+/// Records an explicit grant of `role`, other than Arbitrator, to `account`.
+pub(crate) fn grant(env: &Env, role: Role, account: &Address) {
+    let key = role_key(role, account);
+    if !env.storage().persistent().has(&key) {
+        let mut members = role_members(env, role);
+        members.push_back(account.clone());
+        save_role_members(env, role, &members);
+    }
+    write_persistent(env, &key, &true);
+}
+
+fn role_members(env: &Env, role: Role) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&role_members_key(role))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn save_role_members(env: &Env, role: Role, members: &Vec<Address>) {
+    let key = role_members_key(role);
+    write_persistent(env, &key, members);
+}
+
+/// Authenticates `caller` and checks it holds `role`.
+pub(crate) fn require_role(env: &Env, caller: &Address, role: Role) -> Result<(), Error> {
+    caller.require_auth();
+    if !VaultixEscrow::has_role(env.clone(), role, caller.clone()) {
+        return Err(Error::UnauthorizedAccess);
+    }
+    Ok(())
+}
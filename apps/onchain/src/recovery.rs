@@ -0,0 +1,361 @@
+//! Recovery paths for escrows whose parties have gone silent.
+//!
+//! A depositor may name a backup address before funding. If the depositor then shows no
+//! activity on the contract for the backup's `inactivity_period`, the backup can approve
+//! releases, cancel and raise disputes in their place. Any recorded action by the depositor,
+//! or a `check_in`, hands authority straight back.
+//!
+//! A recipient may likewise register a recovery address. If the recipient's key is lost, the
+//! recovery address starts a claim that takes effect after `RECIPIENT_RECOVERY_DELAY`, during
+//! which the depositor or the recipient can veto it. Once finalized, the recovery address
+//! replaces the recipient on the escrow and receives all further releases.
+//!
+//! Once an active escrow has seen no activity for the configured `dormancy_period`, the admin
+//! may announce that it will be force-settled back to the depositor. The refund can only be
+//! executed `DORMANT_RECOVERY_DELAY` after the announcement, and any activity recorded on the
+//! escrow in the meantime voids it.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin_log,
+    errors::Error,
+    escrow::{burn_receipt, finish_funded, load_live_escrow, save_escrow},
+    funding::held_amount,
+    history,
+    rbac::require_role,
+    state_machine, stats,
+    storage::{
+        backup_key, dormant_recovery_key, load_config, recipient_recovery_key,
+        recovery_request_key, write_persistent,
+    },
+    types::{Backup, Escrow, EscrowStatus, Role},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, token, Address, Env};
+
+/// Smallest accepted `dormancy_period`, so recovery stays a last resort.
+pub(crate) const MIN_DORMANCY_PERIOD: u64 = 365 * 24 * 60 * 60;
+/// Time between announcing a dormant-escrow recovery and being able to execute it.
+pub(crate) const DORMANT_RECOVERY_DELAY: u64 = 30 * 24 * 60 * 60;
+/// Challenge period during which a recipient recovery can be vetoed.
+pub(crate) const RECIPIENT_RECOVERY_DELAY: u64 = 7 * 24 * 60 * 60;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Registers a backup that inherits the depositor's authority over the escrow once the
+    /// depositor has been inactive for `inactivity_period` seconds. Depositor only, before
+    /// funding.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `EscrowAlreadyFunded` - If the escrow has left the Created state
+    /// * `InvalidConfiguration` - If the period is zero or the backup is a party to the escrow
+    pub fn set_backup(
+        env: Env,
+        escrow_id: u64,
+        backup: Address,
+        inactivity_period: u64,
+    ) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if escrow.status != EscrowStatus::Created {
+            return Err(Error::EscrowAlreadyFunded);
+        }
+        if inactivity_period == 0 || backup == escrow.depositor || backup == escrow.recipient {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        let key = backup_key(escrow_id);
+        write_persistent(
+            &env,
+            &key,
+            &Backup {
+                address: backup.clone(),
+                inactivity_period,
+            },
+        );
+
+        env.events()
+            .publish((symbol_short!("backup"), escrow_id), backup);
+        Ok(())
+    }
+
+    /// Returns the depositor's registered backup for the escrow, if any.
+    pub fn get_backup(env: Env, escrow_id: u64) -> Option<Backup> {
+        env.storage().persistent().get(&backup_key(escrow_id))
+    }
+
+    /// Proves `address` still controls its key, resetting its inactivity clock for backups.
+    pub fn check_in(env: Env, address: Address) {
+        address.require_auth();
+        history::touch(&env, &address);
+    }
+
+    /// When `address` last acted on the contract, or 0 if never.
+    pub fn get_last_seen(env: Env, address: Address) -> u64 {
+        history::last_seen(&env, &address)
+    }
+
+    /// Returns the address currently holding the depositor's authority over the escrow.
+    pub fn get_depositor_authority(env: Env, escrow_id: u64) -> Result<Address, Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        Ok(depositor_authority(&env, escrow_id, &escrow))
+    }
+
+    /// Registers the address allowed to take over as recipient if the recipient's key is lost.
+    /// Recipient only; replacing the address cancels any recovery in progress.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow is finished
+    /// * `InvalidConfiguration` - If the recovery address is a party to the escrow
+    pub fn set_recovery_address(env: Env, escrow_id: u64, recovery: Address) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.recipient.require_auth();
+
+        if state_machine::is_terminal(escrow.status) {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        if recovery == escrow.depositor || recovery == escrow.recipient {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        let key = recipient_recovery_key(escrow_id);
+        write_persistent(&env, &key, &recovery);
+        env.storage()
+            .persistent()
+            .remove(&recovery_request_key(escrow_id));
+        Ok(())
+    }
+
+    /// Returns the recipient's registered recovery address, if any.
+    pub fn get_recovery_address(env: Env, escrow_id: u64) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&recipient_recovery_key(escrow_id))
+    }
+
+    /// Starts the recovery address's claim to the recipient role. It can be finalized once the
+    /// challenge period has passed unless vetoed first.
+    ///
+    /// # Returns
+    /// When the recovery can be finalized
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow is finished
+    /// * `RecoveryNotReady` - If no recovery address is registered or a claim is already running
+    pub fn start_recipient_recovery(env: Env, escrow_id: u64) -> Result<u64, Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        if state_machine::is_terminal(escrow.status) {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        let recovery =
+            Self::get_recovery_address(env.clone(), escrow_id).ok_or(Error::RecoveryNotReady)?;
+        recovery.require_auth();
+
+        let key = recovery_request_key(escrow_id);
+        if env.storage().persistent().has(&key) {
+            return Err(Error::RecoveryNotReady);
+        }
+        let activates_at = env
+            .ledger()
+            .timestamp()
+            .saturating_add(RECIPIENT_RECOVERY_DELAY);
+        write_persistent(&env, &key, &activates_at);
+
+        env.events().publish(
+            (symbol_short!("rcv_start"), escrow_id),
+            (recovery, activates_at),
+        );
+        Ok(activates_at)
+    }
+
+    /// Cancels a pending recipient recovery. `caller` must be the recipient or hold the
+    /// depositor's authority.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `UnauthorizedAccess` - If caller may not veto
+    /// * `RecoveryNotReady` - If no recovery is pending
+    pub fn veto_recipient_recovery(env: Env, escrow_id: u64, caller: Address) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        caller.require_auth();
+        if caller != escrow.recipient && caller != depositor_authority(&env, escrow_id, &escrow) {
+            return Err(Error::UnauthorizedAccess);
+        }
+
+        let key = recovery_request_key(escrow_id);
+        if !env.storage().persistent().has(&key) {
+            return Err(Error::RecoveryNotReady);
+        }
+        env.storage().persistent().remove(&key);
+
+        env.events()
+            .publish((symbol_short!("rcv_veto"), escrow_id), caller);
+        Ok(())
+    }
+
+    /// Makes the recovery address the escrow's recipient once its unvetoed claim has matured.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow is finished
+    /// * `RecoveryNotReady` - If no claim is pending or its challenge period is still running
+    pub fn finalize_recipient_recovery(env: Env, escrow_id: u64) -> Result<(), Error> {
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        if state_machine::is_terminal(escrow.status) {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        let recovery =
+            Self::get_recovery_address(env.clone(), escrow_id).ok_or(Error::RecoveryNotReady)?;
+        recovery.require_auth();
+
+        let request_key = recovery_request_key(escrow_id);
+        let activates_at: u64 = env
+            .storage()
+            .persistent()
+            .get(&request_key)
+            .ok_or(Error::RecoveryNotReady)?;
+        if env.ledger().timestamp() < activates_at {
+            return Err(Error::RecoveryNotReady);
+        }
+        env.storage().persistent().remove(&request_key);
+        env.storage()
+            .persistent()
+            .remove(&recipient_recovery_key(escrow_id));
+
+        let previous = escrow.recipient.clone();
+        escrow.recipient = recovery.clone();
+        save_escrow(&env, escrow_id, &escrow);
+        history::record(&env, escrow_id, symbol_short!("rcv_done"), &recovery, 0);
+
+        env.events()
+            .publish((symbol_short!("rcv_done"), escrow_id), (previous, recovery));
+        Ok(())
+    }
+
+    /// Announces that a dormant escrow will be refunded to its depositor (Admin role). Emits a
+    /// `dormant` event carrying the earliest execution time.
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller lacks the Admin role
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `EscrowNotActive` - If the escrow is not funded or is disputed
+    /// * `RecoveryNotReady` - If dormancy recovery is disabled or the escrow saw recent activity
+    pub fn announce_dormant_recovery(
+        env: Env,
+        caller: Address,
+        escrow_id: u64,
+    ) -> Result<u64, Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "announce_dormant_recovery", &caller, Some(escrow_id));
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        state_machine::ensure_active(escrow.status)?;
+
+        let dormancy_period = load_config(&env).dormancy_period;
+        let now = env.ledger().timestamp();
+        if dormancy_period == 0
+            || now < last_activity(&env, escrow_id).saturating_add(dormancy_period)
+        {
+            return Err(Error::RecoveryNotReady);
+        }
+
+        let key = dormant_recovery_key(escrow_id);
+        write_persistent(&env, &key, &now);
+
+        let executable_at = now.saturating_add(DORMANT_RECOVERY_DELAY);
+        env.events()
+            .publish((symbol_short!("dormant"), escrow_id), executable_at);
+        Ok(executable_at)
+    }
+
+    /// Refunds everything still locked in an announced dormant escrow to its depositor and
+    /// cancels it (Admin role).
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller lacks the Admin role
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `EscrowNotActive` - If the escrow is not funded or is disputed
+    /// * `RecoveryNotReady` - If nothing was announced, the timelock is still running, or the
+    ///   escrow saw activity after the announcement
+    pub fn execute_dormant_recovery(
+        env: Env,
+        caller: Address,
+        escrow_id: u64,
+    ) -> Result<i128, Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "execute_dormant_recovery", &caller, Some(escrow_id));
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        state_machine::ensure_active(escrow.status)?;
+
+        let key = dormant_recovery_key(escrow_id);
+        let announced_at: u64 = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::RecoveryNotReady)?;
+        if env.ledger().timestamp() < announced_at.saturating_add(DORMANT_RECOVERY_DELAY)
+            || last_activity(&env, escrow_id) >= announced_at
+        {
+            return Err(Error::RecoveryNotReady);
+        }
+        env.storage().persistent().remove(&key);
+
+        state_machine::transition(&mut escrow, EscrowStatus::Cancelled)?;
+        let refunded = held_amount(&env, escrow_id, &escrow)?;
+        save_escrow(&env, escrow_id, &escrow);
+        if refunded > 0 {
+            token::Client::new(&env, &escrow.token_address).transfer(
+                &env.current_contract_address(),
+                &escrow.depositor,
+                &refunded,
+            );
+        }
+
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("recovered"),
+            &caller,
+            refunded,
+        );
+        stats::funds_refunded(&env, &escrow.token_address, refunded);
+        finish_funded(&env, escrow_id, &escrow)?;
+        burn_receipt(&env, escrow_id);
+
+        env.events()
+            .publish((symbol_short!("recovered"), escrow_id), refunded);
+        Ok(refunded)
+    }
+
+    /// When an announced dormant recovery of the escrow becomes executable, if one is pending.
+    pub fn get_dormant_recovery(env: Env, escrow_id: u64) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get::<_, u64>(&dormant_recovery_key(escrow_id))
+            .map(|announced_at| announced_at.saturating_add(DORMANT_RECOVERY_DELAY))
+    }
+
+    /// Timestamp of the most recent action recorded on the escrow.
+    pub fn get_last_activity(env: Env, escrow_id: u64) -> u64 {
+        last_activity(&env, escrow_id)
+    }
+}
+
+/// The depositor, or their backup once the depositor's inactivity period has run out.
+pub(crate) fn depositor_authority(env: &Env, escrow_id: u64, escrow: &Escrow) -> Address {
+    if let Some(backup) = VaultixEscrow::get_backup(env.clone(), escrow_id) {
+        let last_seen = history::last_seen(env, &escrow.depositor);
+        if env.ledger().timestamp() >= last_seen.saturating_add(backup.inactivity_period) {
+            return backup.address;
+        }
+    }
+    escrow.depositor.clone()
+}
+
+fn last_activity(env: &Env, escrow_id: u64) -> u64 {
+    history::last_entry(env, escrow_id).map_or(0, |entry| entry.timestamp)
+}
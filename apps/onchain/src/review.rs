@@ -0,0 +1,289 @@
+//! Milestone delivery review: the recipient submits a milestone, the depositor either releases
+//! it as usual or rejects it with a reason, and the recipient may then resubmit.
+//!
+//! A milestone rejected `max_rejections` times is escalated to a dispute instead, so the pair
+//! cannot loop forever. Deployments that prefer a human decision over a silent depositor can also
+//! set an `escalation_window`, longer than the review window, after which anyone may send a
+//! submission that still has not been reviewed to arbitration. Whether the depositor has been
+//! active at all meanwhile is visible through `get_last_seen`.
+//!
+//! Each submission carries a hash of the delivered work, and optionally a URI to fetch it from,
+//! so an arbitrator can later check exactly what was handed over and when.
+//!
+//! Review state lives beside the milestone rather than in the escrow's masks, so releasing a
+//! milestone never depends on it having been submitted first.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    dispute::open_dispute,
+    errors::Error,
+    escrow::{load_live_escrow, release_guard},
+    history,
+    storage::{
+        deliverable_key, load_config, milestone_bit, rejection_reason_key, review_key,
+        review_window, write_persistent,
+    },
+    types::{Deliverable, DisputeReason, Escrow, MilestoneReview},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, BytesN, Env, String};
+
+// Maximum deliverable URI length in bytes
+const MAX_DELIVERABLE_URI_LEN: u32 = 256;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Recipient marks a milestone as delivered and ready for review, committing to the work with
+    /// `deliverable_hash` and optionally pointing at it with `uri`. Also used to resubmit after a
+    /// rejection, replacing the previous deliverable.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `EscrowNotActive` - If the escrow is not funded or is disputed
+    /// * `MilestoneNotFound` - If the index is out of bounds
+    /// * `MilestoneAlreadyReleased` - If the milestone was already paid
+    /// * `MilestoneAlreadySubmitted` - If the milestone is already awaiting review
+    /// * `DescriptionTooLong` - If the URI exceeds 256 bytes
+    pub fn submit_milestone(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+        deliverable_hash: BytesN<32>,
+        uri: Option<String>,
+    ) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.recipient.require_auth();
+        release_guard(&env, escrow_id, &escrow, milestone_index)?;
+        if uri
+            .as_ref()
+            .is_some_and(|uri| uri.len() > MAX_DELIVERABLE_URI_LEN)
+        {
+            return Err(Error::DescriptionTooLong);
+        }
+
+        record_submission(
+            &env,
+            escrow_id,
+            &escrow,
+            milestone_index,
+            deliverable_hash,
+            uri.unwrap_or_else(|| String::from_str(&env, "")),
+        )
+    }
+
+    /// Depositor rejects a submitted milestone, sending it back to Pending so the recipient can
+    /// rework and resubmit it. `reason_hash` commits to the off-chain explanation. The rejection
+    /// that reaches the configured limit disputes the milestone instead.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `EscrowNotActive` - If the escrow is not funded or is disputed
+    /// * `MilestoneNotFound` - If the index is out of bounds
+    /// * `MilestoneAlreadyReleased` - If the milestone was already paid
+    /// * `MilestoneNotSubmitted` - If the milestone is not awaiting review
+    pub fn reject_milestone(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+        reason_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+        reject(&env, escrow_id, &mut escrow, milestone_index, reason_hash)
+    }
+
+    /// Disputes a milestone whose submission has gone unreviewed for the configured escalation
+    /// window, sending it to arbitration. Anyone may call this.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidConfiguration` - If escalation is disabled
+    /// * `EscrowNotActive` - If the escrow is not funded or is disputed
+    /// * `MilestoneNotFound` - If the index is out of bounds
+    /// * `MilestoneAlreadyReleased` - If the milestone was already paid
+    /// * `MilestoneNotSubmitted` - If the milestone is not awaiting review
+    /// * `DisputeTooEarly` - If the escalation window has not elapsed since submission
+    pub fn escalate_unreviewed(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+    ) -> Result<(), Error> {
+        let escalation_window = load_config(&env).escalation_window;
+        if escalation_window == 0 {
+            return Err(Error::InvalidConfiguration);
+        }
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        release_guard(&env, escrow_id, &escrow, milestone_index)?;
+
+        let review = Self::get_milestone_review(env.clone(), escrow_id, milestone_index);
+        if !review.submitted {
+            return Err(Error::MilestoneNotSubmitted);
+        }
+        if env.ledger().timestamp() < review.submitted_at.saturating_add(escalation_window) {
+            return Err(Error::DisputeTooEarly);
+        }
+
+        open_dispute(
+            &env,
+            escrow_id,
+            &mut escrow,
+            milestone_bit(milestone_index),
+            DisputeReason::Other,
+        )?;
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("escalated"),
+            &env.current_contract_address(),
+            0,
+        );
+        env.events().publish(
+            (symbol_short!("escalated"), escrow_id, milestone_index),
+            review.rejections,
+        );
+        Ok(())
+    }
+
+    /// Review state of a milestone; never-submitted milestones report the default.
+    pub fn get_milestone_review(env: Env, escrow_id: u64, milestone_index: u32) -> MilestoneReview {
+        env.storage()
+            .persistent()
+            .get(&review_key(escrow_id, milestone_index))
+            .unwrap_or_default()
+    }
+
+    /// Seconds the depositor has to review a delivered milestone of the escrow: its template's
+    /// window, or the configured default.
+    pub fn get_review_window(env: Env, escrow_id: u64) -> u64 {
+        review_window(&env, escrow_id)
+    }
+
+    /// Deliverable from the milestone's latest submission, if it was ever submitted.
+    pub fn get_deliverable(env: Env, escrow_id: u64, milestone_index: u32) -> Option<Deliverable> {
+        env.storage()
+            .persistent()
+            .get(&deliverable_key(escrow_id, milestone_index))
+    }
+
+    /// Reason hash given with the milestone's most recent rejection, if any.
+    pub fn get_rejection_reason(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+    ) -> Option<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&rejection_reason_key(escrow_id, milestone_index))
+    }
+}
+
+/// Rejects a submitted milestone on behalf of the depositor, who has already been authorized.
+pub(crate) fn reject(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &mut Escrow,
+    milestone_index: u32,
+    reason_hash: BytesN<32>,
+) -> Result<(), Error> {
+    release_guard(env, escrow_id, escrow, milestone_index)?;
+
+    let mut review = VaultixEscrow::get_milestone_review(env.clone(), escrow_id, milestone_index);
+    if !review.submitted {
+        return Err(Error::MilestoneNotSubmitted);
+    }
+    review.submitted = false;
+    review.rejections += 1;
+    save_review(env, escrow_id, milestone_index, &review);
+
+    let reason_key = rejection_reason_key(escrow_id, milestone_index);
+    write_persistent(env, &reason_key, &reason_hash);
+
+    history::record(
+        env,
+        escrow_id,
+        symbol_short!("rejected"),
+        &escrow.depositor,
+        0,
+    );
+    env.events().publish(
+        (symbol_short!("rejected"), escrow_id, milestone_index),
+        (reason_hash, review.rejections),
+    );
+
+    let max_rejections = load_config(env).max_rejections;
+    if max_rejections > 0 && review.rejections >= max_rejections {
+        open_dispute(
+            env,
+            escrow_id,
+            escrow,
+            milestone_bit(milestone_index),
+            DisputeReason::Quality,
+        )?;
+        history::record(
+            env,
+            escrow_id,
+            symbol_short!("escalated"),
+            &escrow.depositor,
+            0,
+        );
+        env.events().publish(
+            (symbol_short!("escalated"), escrow_id, milestone_index),
+            review.rejections,
+        );
+    }
+    Ok(())
+}
+
+/// Sends a submitted milestone back to Pending without counting it as a rejection.
+pub(crate) fn withdraw_submission(env: &Env, escrow_id: u64, milestone_index: u32) {
+    let mut review = VaultixEscrow::get_milestone_review(env.clone(), escrow_id, milestone_index);
+    if review.submitted {
+        review.submitted = false;
+        save_review(env, escrow_id, milestone_index, &review);
+    }
+}
+
+fn save_review(env: &Env, escrow_id: u64, milestone_index: u32, review: &MilestoneReview) {
+    let key = review_key(escrow_id, milestone_index);
+    write_persistent(env, &key, review);
+}
+
+/// Marks a milestone as submitted with its deliverable, on behalf of the recipient.
+pub(crate) fn record_submission(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &Escrow,
+    milestone_index: u32,
+    deliverable_hash: BytesN<32>,
+    uri: String,
+) -> Result<(), Error> {
+    let mut review = VaultixEscrow::get_milestone_review(env.clone(), escrow_id, milestone_index);
+    if review.submitted {
+        return Err(Error::MilestoneAlreadySubmitted);
+    }
+    review.submitted = true;
+    review.submitted_at = env.ledger().timestamp();
+    save_review(env, escrow_id, milestone_index, &review);
+
+    let deliverable = Deliverable {
+        hash: deliverable_hash.clone(),
+        uri,
+        ledger: env.ledger().sequence(),
+    };
+    let key = deliverable_key(escrow_id, milestone_index);
+    write_persistent(env, &key, &deliverable);
+
+    history::record(
+        env,
+        escrow_id,
+        symbol_short!("submitted"),
+        &escrow.recipient,
+        0,
+    );
+    env.events().publish(
+        (symbol_short!("submitted"), escrow_id, milestone_index),
+        (deliverable_hash, review.rejections),
+    );
+    Ok(())
+}
@@ -0,0 +1,241 @@
+//! Goods escrows.
+//!
+//! An escrow for physical goods can hand its delivery tracking to a logistics oracle: a carrier
+//! integration contract the admin has registered. Before funding, the depositor names the oracle,
+//! which from then on attests each milestone's shipment as it moves. A `Shipped` or `Delivered`
+//! attestation submits the milestone for review on the seller's behalf, a `Returned` one
+//! withdraws that submission again. A delivery nobody contests within the configured review
+//! window can then be released by anyone; until then the buyer may contest it, which disputes the
+//! milestone for non-delivery and leaves the decision to arbitration.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin::ensure_not_paused,
+    admin_log,
+    approvals::ensure_no_approval_policy,
+    dispute::open_dispute,
+    errors::Error,
+    escrow::{load_live_escrow, pay_milestone, release_guard},
+    history,
+    rbac::require_role,
+    recovery::depositor_authority,
+    review::{record_submission, withdraw_submission},
+    storage::{
+        goods_oracle_key, logistics_oracle_key, milestone_bit, review_window, shipment_key,
+        write_persistent,
+    },
+    types::{DeliveryEvent, DisputeReason, EscrowStatus, Role, Shipment},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, BytesN, Env, String};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Registers a logistics oracle goods escrows may name, or deregisters it (Admin role).
+    /// A deregistered oracle can no longer attest, even for escrows that already named it.
+    pub fn set_logistics_oracle(
+        env: Env,
+        caller: Address,
+        oracle: Address,
+        registered: bool,
+    ) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "set_logistics_oracle", &caller, None);
+
+        let key = logistics_oracle_key(&oracle);
+        if registered {
+            write_persistent(&env, &key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        Ok(())
+    }
+
+    /// Whether `oracle` is a registered logistics oracle.
+    pub fn is_logistics_oracle(env: Env, oracle: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&logistics_oracle_key(&oracle))
+    }
+
+    /// Makes the escrow a goods escrow whose deliveries `oracle` attests, or an ordinary escrow
+    /// again with `None`. Depositor only, before funding.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow has been funded
+    /// * `NotAllowlisted` - If the oracle isn't registered
+    pub fn set_goods_oracle(
+        env: Env,
+        escrow_id: u64,
+        oracle: Option<Address>,
+    ) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if escrow.status != EscrowStatus::Created {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        let key = goods_oracle_key(escrow_id);
+        let Some(oracle) = oracle else {
+            env.storage().persistent().remove(&key);
+            return Ok(());
+        };
+        if !Self::is_logistics_oracle(env.clone(), oracle.clone()) {
+            return Err(Error::NotAllowlisted);
+        }
+
+        write_persistent(&env, &key, &oracle);
+        Ok(())
+    }
+
+    /// Logistics oracle attesting the escrow's deliveries, if it is a goods escrow.
+    pub fn get_goods_oracle(env: Env, escrow_id: u64) -> Option<Address> {
+        env.storage().persistent().get(&goods_oracle_key(escrow_id))
+    }
+
+    /// Records a delivery event for a milestone of a goods escrow, backed by `proof`, the hash
+    /// of the carrier's tracking record. Only the escrow's oracle may attest. A shipment or
+    /// delivery submits the milestone for review, with `proof` as its deliverable, if it wasn't
+    /// already; a return withdraws the submission.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidConfiguration` - If the escrow isn't a goods escrow
+    /// * `NotAllowlisted` - If the oracle has been deregistered
+    /// * `EscrowNotActive` - If the escrow is not funded or is disputed
+    /// * `MilestoneNotFound` - If the index is out of bounds
+    /// * `MilestoneAlreadyReleased` - If the milestone was already paid
+    pub fn attest_delivery(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+        event: DeliveryEvent,
+        proof: BytesN<32>,
+    ) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        let oracle =
+            Self::get_goods_oracle(env.clone(), escrow_id).ok_or(Error::InvalidConfiguration)?;
+        oracle.require_auth();
+
+        if !Self::is_logistics_oracle(env.clone(), oracle.clone()) {
+            return Err(Error::NotAllowlisted);
+        }
+        release_guard(&env, escrow_id, &escrow, milestone_index)?;
+
+        let submitted =
+            Self::get_milestone_review(env.clone(), escrow_id, milestone_index).submitted;
+        match event {
+            DeliveryEvent::Shipped | DeliveryEvent::Delivered if !submitted => {
+                record_submission(
+                    &env,
+                    escrow_id,
+                    &escrow,
+                    milestone_index,
+                    proof.clone(),
+                    String::from_str(&env, ""),
+                )?;
+            }
+            DeliveryEvent::Returned => withdraw_submission(&env, escrow_id, milestone_index),
+            _ => {}
+        }
+
+        let shipment = Shipment {
+            event,
+            proof: proof.clone(),
+            attested_at: env.ledger().timestamp(),
+        };
+        let key = shipment_key(escrow_id, milestone_index);
+        write_persistent(&env, &key, &shipment);
+
+        let action = match event {
+            DeliveryEvent::Shipped => symbol_short!("shipped"),
+            DeliveryEvent::Delivered => symbol_short!("delivered"),
+            DeliveryEvent::Returned => symbol_short!("returned"),
+        };
+        history::record(&env, escrow_id, action.clone(), &oracle, 0);
+        env.events()
+            .publish((action, escrow_id, milestone_index), proof);
+        Ok(())
+    }
+
+    /// Latest delivery event attested for a milestone, if any.
+    pub fn get_shipment(env: Env, escrow_id: u64, milestone_index: u32) -> Option<Shipment> {
+        env.storage()
+            .persistent()
+            .get(&shipment_key(escrow_id, milestone_index))
+    }
+
+    /// Buyer contests an attested delivery within the review window, disputing the milestone
+    /// for non-delivery.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `EscrowNotActive` - If the escrow is not funded or is disputed
+    /// * `MilestoneNotFound` - If the index is out of bounds
+    /// * `MilestoneAlreadyReleased` - If the milestone was already paid
+    /// * `InvalidEscrowStatus` - If the milestone's latest attestation isn't a delivery
+    /// * `EvidenceWindowClosed` - If the review window has passed since the delivery
+    pub fn contest_delivery(env: Env, escrow_id: u64, milestone_index: u32) -> Result<(), Error> {
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        let buyer = depositor_authority(&env, escrow_id, &escrow);
+        buyer.require_auth();
+        release_guard(&env, escrow_id, &escrow, milestone_index)?;
+
+        if env.ledger().timestamp() > contest_deadline(&env, escrow_id, milestone_index)? {
+            return Err(Error::EvidenceWindowClosed);
+        }
+
+        // The contest replaces the attestation, so the milestone can't also be finalized
+        env.storage()
+            .persistent()
+            .remove(&shipment_key(escrow_id, milestone_index));
+        open_dispute(
+            &env,
+            escrow_id,
+            &mut escrow,
+            milestone_bit(milestone_index),
+            DisputeReason::NonDelivery,
+        )?;
+        history::record(&env, escrow_id, symbol_short!("contested"), &buyer, 0);
+        Ok(())
+    }
+
+    /// Releases a goods milestone whose delivery went uncontested for the review window.
+    /// Anyone may call this.
+    ///
+    /// # Errors
+    /// * `ContractPaused` - If the contract is paused
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `ApprovalRequired` - If the escrow releases through an approval quorum
+    /// * `EscrowNotActive` - If the escrow is not funded or is disputed
+    /// * `MilestoneNotFound` - If the index is out of bounds
+    /// * `MilestoneAlreadyReleased` - If the milestone was already paid
+    /// * `InvalidEscrowStatus` - If the milestone's latest attestation isn't a delivery
+    /// * `EvidenceWindowOpen` - If the buyer may still contest the delivery
+    pub fn finalize_delivery(env: Env, escrow_id: u64, milestone_index: u32) -> Result<(), Error> {
+        ensure_not_paused(&env)?;
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        ensure_no_approval_policy(&env, escrow_id)?;
+        release_guard(&env, escrow_id, &escrow, milestone_index)?;
+
+        if env.ledger().timestamp() <= contest_deadline(&env, escrow_id, milestone_index)? {
+            return Err(Error::EvidenceWindowOpen);
+        }
+        let oracle =
+            Self::get_goods_oracle(env.clone(), escrow_id).ok_or(Error::InvalidConfiguration)?;
+        pay_milestone(&env, escrow_id, &mut escrow, milestone_index, &oracle)
+    }
+}
+
+/// Last moment the buyer may contest the milestone's attested delivery.
+fn contest_deadline(env: &Env, escrow_id: u64, milestone_index: u32) -> Result<u64, Error> {
+    match VaultixEscrow::get_shipment(env.clone(), escrow_id, milestone_index) {
+        Some(shipment) if shipment.event == DeliveryEvent::Delivered => Ok(shipment
+            .attested_at
+            .saturating_add(review_window(env, escrow_id))),
+        _ => Err(Error::InvalidEscrowStatus),
+    }
+}
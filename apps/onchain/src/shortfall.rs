@@ -0,0 +1,151 @@
+//! Clawback shortfalls.
+//!
+//! Regulated assets can let their issuer claw tokens back from any holder, this contract
+//! included. Escrowed funds are pooled per token, so a clawback shows up as the contract holding
+//! less of a token than it owes: the `TokenStats::locked` amount plus the token's insurance
+//! pool. While that is the case, payouts and refunds in the token fail with
+//! `InsufficientBalance` rather than paying one escrow out of another's funds. Anyone can report
+//! the shortfall against an affected escrow, freezing it. A frozen escrow goes back to `Active`
+//! once someone tops the pool back up, or the admin writes it off, refunding the depositor
+//! whatever the contract still holds for it. Held dispute bonds are not counted, so a clawback
+//! they absorb does not register.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin_log,
+    errors::Error,
+    escrow::{burn_receipt, finish_funded, load_live_escrow, save_escrow},
+    funding::held_amount,
+    history,
+    rbac::require_role,
+    state_machine, stats,
+    types::{EscrowStatus, Role},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, token, Address, Env};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Freezes an `Active` escrow whose token the contract holds less of than it owes. Anyone
+    /// may report it.
+    ///
+    /// # Returns
+    /// The token's shortfall
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow isn't `Active` or its token shows no shortfall
+    pub fn report_shortfall(env: Env, escrow_id: u64) -> Result<i128, Error> {
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        let missing = shortfall(&env, &escrow.token_address);
+        if escrow.status != EscrowStatus::Active || missing == 0 {
+            return Err(Error::InvalidEscrowStatus);
+        }
+
+        state_machine::transition(&mut escrow, EscrowStatus::Frozen)?;
+        save_escrow(&env, escrow_id, &escrow);
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("frozen"),
+            &env.current_contract_address(),
+            missing,
+        );
+        Ok(missing)
+    }
+
+    /// Unfreezes an escrow, first paying the token's remaining shortfall, if any, from `payer`.
+    ///
+    /// # Returns
+    /// The amount `payer` contributed
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow isn't frozen
+    pub fn reconcile_shortfall(env: Env, escrow_id: u64, payer: Address) -> Result<i128, Error> {
+        payer.require_auth();
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        if escrow.status != EscrowStatus::Frozen {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        let missing = shortfall(&env, &escrow.token_address);
+        state_machine::transition(&mut escrow, EscrowStatus::Active)?;
+        save_escrow(&env, escrow_id, &escrow);
+
+        if missing > 0 {
+            token::Client::new(&env, &escrow.token_address).transfer(
+                &payer,
+                &env.current_contract_address(),
+                &missing,
+            );
+        }
+        history::record(&env, escrow_id, symbol_short!("reconcile"), &payer, missing);
+        Ok(missing)
+    }
+
+    /// Cancels a frozen escrow (Admin role), refunding the depositor what the contract holds
+    /// beyond the other escrows' claims on the token, up to the escrow's unpaid amount. The
+    /// rest is written off.
+    ///
+    /// # Returns
+    /// The amount refunded
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller lacks the Admin role
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow isn't frozen
+    pub fn write_off_shortfall(env: Env, caller: Address, escrow_id: u64) -> Result<i128, Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "write_off_shortfall", &caller, Some(escrow_id));
+
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        if escrow.status != EscrowStatus::Frozen {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        let held = held_amount(&env, escrow_id, &escrow)?;
+        let covered = held.saturating_sub(shortfall(&env, &escrow.token_address));
+        let refund = covered.max(0);
+
+        state_machine::transition(&mut escrow, EscrowStatus::Cancelled)?;
+        save_escrow(&env, escrow_id, &escrow);
+        if refund > 0 {
+            token::Client::new(&env, &escrow.token_address).transfer(
+                &env.current_contract_address(),
+                &escrow.depositor,
+                &refund,
+            );
+        }
+        history::record(&env, escrow_id, symbol_short!("writeoff"), &caller, refund);
+        stats::funds_refunded(&env, &escrow.token_address, refund);
+        let written_off = held
+            .checked_sub(refund)
+            .ok_or(Error::InvalidMilestoneAmount)?;
+        stats::funds_written_off(&env, &escrow.token_address, written_off);
+        finish_funded(&env, escrow_id, &escrow)?;
+        burn_receipt(&env, escrow_id);
+        Ok(refund)
+    }
+}
+
+/// How much less of `token_address` the contract holds than it owes to escrows and the
+/// insurance pool.
+pub(crate) fn shortfall(env: &Env, token_address: &Address) -> i128 {
+    let owed = VaultixEscrow::get_token_stats(env.clone(), token_address.clone())
+        .locked
+        .saturating_add(VaultixEscrow::get_insurance_pool(
+            env.clone(),
+            token_address.clone(),
+        ));
+    let balance = token::Client::new(env, token_address).balance(&env.current_contract_address());
+    owed.saturating_sub(balance).max(0)
+}
+
+/// Fails with `InsufficientBalance` while `token_address` shows a shortfall.
+pub(crate) fn ensure_covered(env: &Env, token_address: &Address) -> Result<(), Error> {
+    if shortfall(env, token_address) > 0 {
+        return Err(Error::InsufficientBalance);
+    }
+    Ok(())
+}
@@ -0,0 +1,147 @@
+//! Spending caps for delegated release authority.
+//!
+//! Releases can be authorized by someone other than the depositor: a backup, an approver or an
+//! organization member. The depositor can cap how much each such delegate releases from an
+//! escrow per period (e.g. 10k per week), so a compromised operations key cannot drain a large
+//! escrow in one go. Milestones are paid whole, so one larger than the cap needs the depositor.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    errors::Error,
+    escrow::load_live_escrow,
+    state_machine,
+    storage::{delegate_spend_key, spending_limits_key, write_persistent},
+    types::{DelegateSpend, SpendingLimit},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, Map};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Caps what `delegate` may release from the escrow per period. Depositor only; replaces
+    /// any earlier limit for the delegate.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow is finished
+    /// * `InvalidConfiguration` - If the amount or period is not positive, or the delegate is
+    ///   the depositor
+    pub fn set_spending_limit(
+        env: Env,
+        escrow_id: u64,
+        delegate: Address,
+        limit: SpendingLimit,
+    ) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if state_machine::is_terminal(escrow.status) {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        if limit.amount <= 0 || limit.period == 0 || delegate == escrow.depositor {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        let mut limits = load_limits(&env, escrow_id);
+        limits.set(delegate.clone(), limit.clone());
+        save_limits(&env, escrow_id, &limits);
+
+        env.events().publish(
+            (symbol_short!("spend_cap"), escrow_id),
+            (delegate, limit.amount, limit.period),
+        );
+        Ok(())
+    }
+
+    /// Lifts the delegate's spending limit. Depositor only.
+    pub fn remove_spending_limit(env: Env, escrow_id: u64, delegate: Address) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        let mut limits = load_limits(&env, escrow_id);
+        limits.remove(delegate);
+        save_limits(&env, escrow_id, &limits);
+        Ok(())
+    }
+
+    /// Returns the delegate's spending limit on the escrow, if any.
+    pub fn get_spending_limit(
+        env: Env,
+        escrow_id: u64,
+        delegate: Address,
+    ) -> Option<SpendingLimit> {
+        load_limits(&env, escrow_id).get(delegate)
+    }
+
+    /// Amount the delegate has released from the escrow in the current period.
+    pub fn get_delegate_spent(env: Env, escrow_id: u64, delegate: Address) -> i128 {
+        match Self::get_spending_limit(env.clone(), escrow_id, delegate.clone()) {
+            Some(limit) => current_spend(&env, escrow_id, &delegate, &limit).spent,
+            None => 0,
+        }
+    }
+}
+
+/// Counts `amount` against `actor`'s limit on the escrow, if they have one.
+pub(crate) fn charge(
+    env: &Env,
+    escrow_id: u64,
+    actor: &Address,
+    amount: i128,
+) -> Result<(), Error> {
+    let Some(limit) = load_limits(env, escrow_id).get(actor.clone()) else {
+        return Ok(());
+    };
+
+    let mut spend = current_spend(env, escrow_id, actor, &limit);
+    spend.spent = spend
+        .spent
+        .checked_add(amount)
+        .ok_or(Error::InvalidMilestoneAmount)?;
+    if spend.spent > limit.amount {
+        return Err(Error::RateLimited);
+    }
+
+    let key = delegate_spend_key(escrow_id);
+    let mut spends: Map<Address, DelegateSpend> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or_else(|| Map::new(env));
+    spends.set(actor.clone(), spend);
+    write_persistent(env, &key, &spends);
+    Ok(())
+}
+
+fn current_spend(
+    env: &Env,
+    escrow_id: u64,
+    delegate: &Address,
+    limit: &SpendingLimit,
+) -> DelegateSpend {
+    let now = env.ledger().timestamp();
+    let spends: Option<Map<Address, DelegateSpend>> = env
+        .storage()
+        .persistent()
+        .get(&delegate_spend_key(escrow_id));
+    match spends.and_then(|spends| spends.get(delegate.clone())) {
+        Some(spend) if now < spend.window_start.saturating_add(limit.period) => spend,
+        _ => DelegateSpend {
+            window_start: now,
+            spent: 0,
+        },
+    }
+}
+
+fn save_limits(env: &Env, escrow_id: u64, limits: &Map<Address, SpendingLimit>) {
+    let key = spending_limits_key(escrow_id);
+    write_persistent(env, &key, limits);
+}
+
+fn load_limits(env: &Env, escrow_id: u64) -> Map<Address, SpendingLimit> {
+    env.storage()
+        .persistent()
+        .get(&spending_limits_key(escrow_id))
+        .unwrap_or_else(|| Map::new(env))
+}
@@ -0,0 +1,79 @@
+//! Escrow lifecycle state machine.
+//!
+//! Every status change goes through [`transition`], so the set of legal moves lives in one
+//! table instead of being re-derived by each entrypoint's guards.
+//!
+//! ```text
+//! Created ──deposit──▶ Active ──release all / claims──▶ Completed
+//!    │                   │  ▲
+//!    ├──cancel───────────┼──┼─cancel / clawback────────▶ Cancelled
+//!    │                   │  └─partial resolve─┐
+//!    └──dispute──────────┴──dispute──▶ Disputed ──resolve──▶ Resolved
+//! ```
+//!
+//! `Disputed -> Active` is only taken by `resolve_dispute_partial`, and only for escrows that
+//! were funded when the dispute was raised. Drafts sit in front of all this: `Draft -> Created`
+//! when the depositor activates one, `Draft -> Cancelled` when it is discarded. Proposals work the
+//! same way: `Proposed -> Created` once the recipient accepts, `Proposed -> Cancelled` if the
+//! depositor withdraws. Open bounties are funded up front, so they go `Open -> Active` when
+//! claimed and `Open -> Cancelled`, refunding, when withdrawn. An `Active` escrow whose asset
+//! was clawed back goes `Active -> Frozen` until the shortfall is topped up (`Frozen -> Active`)
+//! or written off (`Frozen -> Cancelled`).
+
+use crate::{Error, Escrow, EscrowStatus};
+
+/// Whether the lifecycle allows moving from `from` to `to`.
+pub(crate) fn can_transition(from: EscrowStatus, to: EscrowStatus) -> bool {
+    use EscrowStatus::*;
+
+    matches!(
+        (from, to),
+        (Draft, Created)
+            | (Draft, Cancelled)
+            | (Proposed, Created)
+            | (Proposed, Cancelled)
+            | (Open, Active)
+            | (Open, Cancelled)
+            | (Created, Active)
+            | (Created, Cancelled)
+            | (Created, Disputed)
+            | (Active, Completed)
+            | (Active, Cancelled)
+            | (Active, Disputed)
+            | (Disputed, Active)
+            | (Disputed, Resolved)
+            | (Active, Frozen)
+            | (Frozen, Active)
+            | (Frozen, Cancelled)
+    )
+}
+
+/// Moves `escrow` to `to`, or returns the error a caller attempting that move should see.
+pub(crate) fn transition(escrow: &mut Escrow, to: EscrowStatus) -> Result<(), Error> {
+    if !can_transition(escrow.status, to) {
+        return Err(match (escrow.status, to) {
+            (_, EscrowStatus::Active) => Error::EscrowAlreadyFunded,
+            (EscrowStatus::Disputed, EscrowStatus::Disputed) => Error::AlreadyInDispute,
+            _ => Error::InvalidEscrowStatus,
+        });
+    }
+
+    escrow.status = to;
+    Ok(())
+}
+
+/// Guard for operations that only make sense while funds are locked and undisputed.
+pub(crate) fn ensure_active(status: EscrowStatus) -> Result<(), Error> {
+    if status != EscrowStatus::Active {
+        return Err(Error::EscrowNotActive);
+    }
+    Ok(())
+}
+
+/// Terminal escrows hold no funds and never change status again.
+pub(crate) fn is_terminal(status: EscrowStatus) -> bool {
+    matches!(
+        status,
+        EscrowStatus::Completed | EscrowStatus::Cancelled | EscrowStatus::Resolved
+    )
+}
@@ -0,0 +1,78 @@
+//! Settlement statements.
+//!
+//! Milestones record what was released, but not what came in, what the platform kept or what a
+//! losing dispute raiser forfeited. Those are tallied per escrow as the money moves, so
+//! `get_statement` can lay the whole settlement out like an invoice without replaying history.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    currency,
+    errors::Error,
+    state_machine::is_terminal,
+    storage::{statement_key, write_persistent},
+    types::{Statement, StatementTotals},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, Env};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Settlement summary of an escrow: one line item per milestone, the fees withheld, any
+    /// forfeited dispute bond and the final balance owed to or held for each party (read-only).
+    ///
+    /// Once the escrow is terminal, whatever was funded but not released counts as refunded to
+    /// the depositor; before that it is reported as held.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If the escrow doesn't exist
+    pub fn get_statement(env: Env, escrow_id: u64) -> Result<Statement, Error> {
+        let escrow = Self::get_escrow(env.clone(), escrow_id)?;
+        let lines = Self::get_milestones(env.clone(), escrow_id)?;
+        let totals = load_totals(&env, escrow_id);
+
+        let outstanding = totals
+            .deposited
+            .saturating_add(totals.netted)
+            .saturating_sub(escrow.total_released);
+        let (refunded, held) = if is_terminal(escrow.status) {
+            (outstanding, 0)
+        } else {
+            (0, outstanding)
+        };
+
+        Ok(Statement {
+            escrow_id,
+            depositor: escrow.depositor,
+            recipient: escrow.recipient,
+            token_address: escrow.token_address,
+            status: escrow.status,
+            lines,
+            total_amount: escrow.total_amount,
+            deposited: totals.deposited,
+            netted: totals.netted,
+            released: escrow.total_released,
+            fees: totals.fees,
+            recipient_net: escrow.total_released.saturating_sub(totals.fees),
+            refunded,
+            held,
+            forfeited_bond: totals.forfeited,
+            currency: currency::or_unknown(&env, escrow_id),
+        })
+    }
+}
+
+fn load_totals(env: &Env, escrow_id: u64) -> StatementTotals {
+    env.storage()
+        .persistent()
+        .get(&statement_key(escrow_id))
+        .unwrap_or_default()
+}
+
+/// Applies `update` to the escrow's running statement totals.
+pub(crate) fn tally(env: &Env, escrow_id: u64, update: impl FnOnce(&mut StatementTotals)) {
+    let mut totals = load_totals(env, escrow_id);
+    update(&mut totals);
+    let key = statement_key(escrow_id);
+    write_persistent(env, &key, &totals);
+}
@@ -0,0 +1,189 @@
+//! Protocol-wide counters, updated incrementally by the lifecycle entrypoints.
+//!
+//! Global amounts are summed across tokens; `get_token_stats` keeps the same figures per token
+//! so they can be reconciled against the contract's actual balances. `get_dispute_stats`
+//! summarises the arbitration backlog from running totals and the list of open disputes in the
+//! order they were raised, both kept up to date as disputes open and close.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    storage::{dispute_tally_key, open_disputes_key, stats_key, token_stats_key, write_persistent},
+    types::{DisputeReason, DisputeStats, DisputeTally, OpenDispute, Stats, TokenStats},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, Address, Env, Map, Vec};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Returns the global protocol counters (all zero on a fresh deployment).
+    pub fn get_stats(env: Env) -> Stats {
+        env.storage()
+            .instance()
+            .get(&stats_key())
+            .unwrap_or_default()
+    }
+
+    /// Returns the escrow accounting for a single token. `locked` should never exceed the
+    /// contract's balance of that token; the difference is held dispute bonds and the
+    /// insurance pool.
+    pub fn get_token_stats(env: Env, token_address: Address) -> TokenStats {
+        env.storage()
+            .persistent()
+            .get(&token_stats_key(&token_address))
+            .unwrap_or_default()
+    }
+
+    /// Returns the arbitration backlog: the open disputes, how long they have waited on
+    /// average in seconds, the escrow disputed longest ago and the open disputes per reason.
+    pub fn get_dispute_stats(env: Env) -> DisputeStats {
+        let tally = dispute_tally(&env);
+        let average_age = match tally.open {
+            0 => 0,
+            open => env
+                .ledger()
+                .timestamp()
+                .saturating_sub(tally.opened_at_sum / open),
+        };
+        DisputeStats {
+            open: tally.open,
+            average_age,
+            oldest_escrow: open_disputes(&env).first().map(|dispute| dispute.escrow_id),
+            by_reason: tally.by_reason,
+        }
+    }
+}
+
+fn dispute_tally(env: &Env) -> DisputeTally {
+    env.storage()
+        .instance()
+        .get(&dispute_tally_key())
+        .unwrap_or(DisputeTally {
+            open: 0,
+            opened_at_sum: 0,
+            by_reason: Map::new(env),
+        })
+}
+
+fn open_disputes(env: &Env) -> Vec<OpenDispute> {
+    env.storage()
+        .persistent()
+        .get(&open_disputes_key())
+        .unwrap_or(Vec::new(env))
+}
+
+fn update(env: &Env, apply: impl FnOnce(&mut Stats)) {
+    let mut stats = VaultixEscrow::get_stats(env.clone());
+    apply(&mut stats);
+    env.storage().instance().set(&stats_key(), &stats);
+}
+
+fn update_token(env: &Env, token_address: &Address, apply: impl FnOnce(&mut TokenStats)) {
+    let key = token_stats_key(token_address);
+    let mut stats = VaultixEscrow::get_token_stats(env.clone(), token_address.clone());
+    apply(&mut stats);
+    write_persistent(env, &key, &stats);
+}
+
+pub(crate) fn escrow_created(env: &Env) {
+    update(env, |stats| stats.escrows_created += 1);
+}
+
+pub(crate) fn escrow_funded(env: &Env, token_address: &Address, amount: i128) {
+    update(env, |stats| {
+        stats.active_escrows += 1;
+        stats.total_locked = stats.total_locked.saturating_add(amount);
+    });
+    update_token(env, token_address, |stats| {
+        stats.locked = stats.locked.saturating_add(amount);
+    });
+}
+
+/// Funds paid out of the contract to the recipient side (payee, fee and insurance share).
+pub(crate) fn funds_released(env: &Env, token_address: &Address, amount: i128) {
+    update(env, |stats| {
+        stats.total_locked = stats.total_locked.saturating_sub(amount);
+        stats.total_released = stats.total_released.saturating_add(amount);
+    });
+    update_token(env, token_address, |stats| {
+        stats.locked = stats.locked.saturating_sub(amount);
+        stats.released = stats.released.saturating_add(amount);
+    });
+}
+
+/// Escrowed funds the contract no longer holds, taken off the books without moving anything.
+pub(crate) fn funds_written_off(env: &Env, token_address: &Address, amount: i128) {
+    update(env, |stats| {
+        stats.total_locked = stats.total_locked.saturating_sub(amount);
+    });
+    update_token(env, token_address, |stats| {
+        stats.locked = stats.locked.saturating_sub(amount);
+    });
+}
+
+/// Funds returned to the depositor.
+pub(crate) fn funds_refunded(env: &Env, token_address: &Address, amount: i128) {
+    update(env, |stats| {
+        stats.total_locked = stats.total_locked.saturating_sub(amount);
+    });
+    update_token(env, token_address, |stats| {
+        stats.locked = stats.locked.saturating_sub(amount);
+        stats.refunded = stats.refunded.saturating_add(amount);
+    });
+}
+
+/// A funded escrow reached a terminal status.
+pub(crate) fn escrow_finished(env: &Env) {
+    update(env, |stats| {
+        stats.active_escrows = stats.active_escrows.saturating_sub(1)
+    });
+}
+
+pub(crate) fn dispute_opened(env: &Env, escrow_id: u64, reason: DisputeReason) {
+    update(env, |stats| stats.disputes_open += 1);
+
+    let opened_at = env.ledger().timestamp();
+    let mut tally = dispute_tally(env);
+    tally.open += 1;
+    tally.opened_at_sum = tally.opened_at_sum.saturating_add(opened_at);
+    let count = tally.by_reason.get(reason).unwrap_or(0);
+    tally.by_reason.set(reason, count + 1);
+    env.storage().instance().set(&dispute_tally_key(), &tally);
+
+    let mut open = open_disputes(env);
+    open.push_back(OpenDispute {
+        escrow_id,
+        reason,
+        opened_at,
+    });
+    write_persistent(env, &open_disputes_key(), &open);
+}
+
+pub(crate) fn dispute_closed(env: &Env, escrow_id: u64) {
+    update(env, |stats| {
+        stats.disputes_open = stats.disputes_open.saturating_sub(1)
+    });
+
+    // Disputes raised before the backlog was tracked only count towards `disputes_open`
+    let mut open = open_disputes(env);
+    let Some(index) = open
+        .iter()
+        .position(|dispute| dispute.escrow_id == escrow_id)
+    else {
+        return;
+    };
+    let dispute = open.get_unchecked(index as u32);
+    open.remove(index as u32);
+    write_persistent(env, &open_disputes_key(), &open);
+
+    let mut tally = dispute_tally(env);
+    tally.open = tally.open.saturating_sub(1);
+    tally.opened_at_sum = tally.opened_at_sum.saturating_sub(dispute.opened_at);
+    match tally.by_reason.get(dispute.reason).unwrap_or(0) {
+        0 | 1 => {
+            tally.by_reason.remove(dispute.reason);
+        }
+        count => tally.by_reason.set(dispute.reason, count - 1),
+    }
+    env.storage().instance().set(&dispute_tally_key(), &tally);
+}
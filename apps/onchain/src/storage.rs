@@ -0,0 +1,1107 @@
+//! Storage keys, TTL policy and low-level persistence helpers.
+
+// `DataKey` variants are named after their on-chain symbols; set module-wide so it also covers
+// the types `contracttype` derives from the enum
+#![allow(non_camel_case_types)]
+
+use crate::{
+    errors::Error,
+    types::{
+        Config, ContractState, DisputeFeePolicy, Escrow, Milestone, MilestoneStatus, Role,
+        TtlPolicy,
+    },
+};
+use soroban_sdk::{
+    contracttype, symbol_short, Address, Env, IntoVal, String, Symbol, TryFromVal, Val,
+};
+
+// Platform fee configuration (in basis points: 1 bps = 0.01%)
+// Default: 50 bps = 0.5%
+pub(crate) const DEFAULT_FEE_BPS: i128 = 50;
+
+// Storage layout version. 1: milestones inline in the escrow with Symbol descriptions,
+// config under separate keys. 2: per-index milestone entries, bitmask status, Config struct.
+// 3: singletons under `DataKey` variants rather than bare symbols. 4: the treasury's Pauser and
+// FeeManager roles are explicit grants rather than implied by the treasury address.
+pub(crate) const STORAGE_SCHEMA_VERSION: u32 = 4;
+pub(crate) const LEGACY_SCHEMA_VERSION: u32 = 1;
+
+// Defaults for the admin-settable configuration
+pub(crate) const DEFAULT_MAX_MILESTONES: u32 = 64; // Every milestone the bitmasks can track
+pub(crate) const DEFAULT_MIN_MILESTONE_AMOUNT: i128 = 1;
+pub(crate) const DEFAULT_REVIEW_WINDOW: u64 = 7 * 24 * 60 * 60;
+pub(crate) const DEFAULT_QUOTA_WINDOW: u64 = 24 * 60 * 60;
+
+// Unless the admin sets a TTL policy, persistent entries are bumped back to ~115 days (at 5s
+// ledgers) whenever they are touched and have fewer than ~30 days of TTL left
+pub(crate) const ESCROW_TTL_THRESHOLD: u32 = 518_400;
+pub(crate) const ESCROW_TTL_EXTEND_TO: u32 = 2_000_000;
+
+// Archived escrows stay readable from temporary storage for ~30 days
+pub(crate) const ARCHIVE_TTL: u32 = 518_400;
+
+/// Every key the contract stores entries under.
+///
+/// Variants are named after the symbol that led the `(Symbol, ..)` tuple each key used to be, so
+/// a keyed variant encodes exactly as that tuple did and entries written before the enum stay
+/// readable where they are. The exception is accrued yield, whose `yield` symbol is a reserved
+/// word; see [`legacy_accrued_yield_key`]. Singletons used to be bare symbols, which a unit
+/// variant does not encode as; `migrate` moves them (schema 3).
+#[contracttype]
+#[derive(Clone)]
+pub(crate) enum DataKey {
+    nonce(Address),
+    nonce_idx(Address, u64),
+    nonce_of(u64),
+    recurring(u64),
+    rec_start(u64),
+    stream(u64, u32),
+    disp_at(u64),
+    bond(u64),
+    settle(u64),
+    d_origin(u64),
+    d_reason(u64),
+    triggers(u64, u32),
+    parent(u64),
+    stmt(u64),
+    rul_lock,
+    rul_queue(u64),
+    dao_cfg,
+    dao_mode(u64),
+    dao_vote(u64),
+    panel(u64),
+    panel_vot(u64),
+    nft(u64),
+    swap_leg(u64),
+    terms(u64),
+    terms_rev(u64),
+    ballot(u64, Address),
+    esc_len,
+    tags(u64),
+    tag_len(Symbol),
+    tag_idx(Symbol, u64),
+    esc_idx(u64),
+    esc_pos(u64),
+    tag_pos(u64),
+    ruling(u64),
+    rationale(u64),
+    ev_due(u64),
+    evidence(u64, Address),
+    ev_commit(u64, Address),
+    review(u64, u32),
+    delivered(u64, u32),
+    rejected(u64, u32),
+    created(u64),
+    claimants(u64),
+    offers(u64),
+    offer_exp(u64),
+    d_grace(u64),
+    d_raiser(u64),
+    d_cool(u64, Address),
+    role(Role, Address),
+    role_mbrs(Role),
+    funding(u64),
+    fund_plan(u64),
+    yield_pol(u64),
+    accrued(u64),
+    excess(u64),
+    dormant(u64),
+    backup(u64),
+    agreement(Address, Address),
+    agreed(u64),
+    notifier(Address),
+    seen(Address),
+    rcv_addr(u64),
+    rcv_req(u64),
+    appr_pol(u64),
+    approvals(u64, u32),
+    project(u64),
+    esc_proj(u64),
+    org(u64),
+    org_mem(u64, Address),
+    stable(u64),
+    route(u64),
+    messages(u64),
+    dl_prop(u64),
+    goods(u64),
+    adm_log(u32),
+    adm_len,
+    currency(u64),
+    d_tally,
+    d_open,
+    notes(u64),
+    shipment(u64, u32),
+    logistics(Address),
+    amm_pool(Address, Address),
+    operator(Symbol),
+    esc_oper(u64),
+    esc_org(u64),
+    spend_cap(u64),
+    spent(u64),
+    hook(u64),
+    vesting(u64),
+    template(u64),
+    tpl_arb(u64),
+    rev_win(u64),
+    arbiter(u64),
+    escrow(u64),
+    milestone(u64, u32),
+    assignee(u64),
+    ext_ref(u64),
+    ref_idx(String),
+    blocked(Address),
+    allowed(Address),
+    fee_tiers,
+    fee_free(Address),
+    fee_token(Address),
+    memo(Address),
+    tokens,
+    admin,
+    quota(Address),
+    pair_open(Address, Address),
+    amt_lim(Address),
+    ins_pool(Address),
+    claim(u64),
+    claimed(u64),
+    rep(Address),
+    rating(u64, Address),
+    rate_sum(Address),
+    hist(u64, u32),
+    hist_len(u64),
+    stats,
+    tok_stats(Address),
+    arbiters,
+    d_queue,
+    arb_cfg,
+    stake(Address),
+    arb_open(Address),
+    assignmt(u64),
+    arb_fees(Address, Address),
+    treasury,
+    screener,
+    kyc_att,
+    val_floor,
+    ins_bps,
+    receipt,
+    cert,
+    amm,
+    yield_src,
+    esc_ctr,
+    org_ctr,
+    proj_ctr,
+    tpl_ctr,
+    claim_ctr,
+    imported,
+    config,
+    schema,
+    ttl,
+}
+
+pub(crate) fn nonce_counter_key(depositor: &Address) -> DataKey {
+    DataKey::nonce(depositor.clone())
+}
+
+pub(crate) fn nonce_index_key(depositor: &Address, nonce: u64) -> DataKey {
+    DataKey::nonce_idx(depositor.clone(), nonce)
+}
+
+pub(crate) fn escrow_nonce_key(escrow_id: u64) -> DataKey {
+    DataKey::nonce_of(escrow_id)
+}
+
+/// Returns the first unused escrow ID at or after the auto-ID counter and advances it.
+pub(crate) fn next_escrow_id(env: &Env) -> u64 {
+    let mut escrow_id: u64 = env.storage().instance().get(&DataKey::esc_ctr).unwrap_or(0);
+    while escrow_exists(env, escrow_id) {
+        escrow_id += 1;
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::esc_ctr, &(escrow_id + 1));
+    escrow_id
+}
+
+pub(crate) fn recurring_key(escrow_id: u64) -> DataKey {
+    DataKey::recurring(escrow_id)
+}
+
+pub(crate) fn recurring_start_key(escrow_id: u64) -> DataKey {
+    DataKey::rec_start(escrow_id)
+}
+
+pub(crate) fn stream_storage_key(escrow_id: u64, milestone_index: u32) -> DataKey {
+    DataKey::stream(escrow_id, milestone_index)
+}
+
+pub(crate) fn dispute_ledger_key(escrow_id: u64) -> DataKey {
+    DataKey::disp_at(escrow_id)
+}
+
+pub(crate) fn dispute_bond_key(escrow_id: u64) -> DataKey {
+    DataKey::bond(escrow_id)
+}
+
+pub(crate) fn settlement_key(escrow_id: u64) -> DataKey {
+    DataKey::settle(escrow_id)
+}
+
+pub(crate) fn dispute_origin_key(escrow_id: u64) -> DataKey {
+    DataKey::d_origin(escrow_id)
+}
+
+pub(crate) fn dispute_reason_key(escrow_id: u64) -> DataKey {
+    DataKey::d_reason(escrow_id)
+}
+
+pub(crate) fn trigger_key(escrow_id: u64, milestone_index: u32) -> DataKey {
+    DataKey::triggers(escrow_id, milestone_index)
+}
+
+pub(crate) fn parent_key(escrow_id: u64) -> DataKey {
+    DataKey::parent(escrow_id)
+}
+
+pub(crate) fn statement_key(escrow_id: u64) -> DataKey {
+    DataKey::stmt(escrow_id)
+}
+
+/// Timelock on admin rulings, if one is configured
+pub(crate) fn ruling_timelock_key() -> DataKey {
+    DataKey::rul_lock
+}
+
+pub(crate) fn pending_ruling_key(escrow_id: u64) -> DataKey {
+    DataKey::rul_queue(escrow_id)
+}
+
+pub(crate) fn dao_config_key() -> DataKey {
+    DataKey::dao_cfg
+}
+
+/// Set when the escrow's disputes go to a token-holder vote
+pub(crate) fn dao_mode_key(escrow_id: u64) -> DataKey {
+    DataKey::dao_mode(escrow_id)
+}
+
+pub(crate) fn dao_vote_key(escrow_id: u64) -> DataKey {
+    DataKey::dao_vote(escrow_id)
+}
+
+/// Set when the escrow's disputes go to a vote of the arbitrator pool
+pub(crate) fn panel_mode_key(escrow_id: u64) -> DataKey {
+    DataKey::panel(escrow_id)
+}
+
+pub(crate) fn panel_vote_key(escrow_id: u64) -> DataKey {
+    DataKey::panel_vot(escrow_id)
+}
+
+pub(crate) fn nft_key(escrow_id: u64) -> DataKey {
+    DataKey::nft(escrow_id)
+}
+
+pub(crate) fn swap_leg_key(escrow_id: u64) -> DataKey {
+    DataKey::swap_leg(escrow_id)
+}
+
+/// SHA-256 of the escrow's private terms document
+pub(crate) fn terms_hash_key(escrow_id: u64) -> DataKey {
+    DataKey::terms(escrow_id)
+}
+
+pub(crate) fn revealed_terms_key(escrow_id: u64) -> DataKey {
+    DataKey::terms_rev(escrow_id)
+}
+
+/// Snapshot ledger of the vote `voter` last cast on the escrow, in temporary storage
+pub(crate) fn ballot_key(escrow_id: u64, voter: &Address) -> DataKey {
+    DataKey::ballot(escrow_id, voter.clone())
+}
+
+/// Number of escrows ever created, which is also the next free position in the export index.
+pub(crate) fn escrow_index_len_key() -> DataKey {
+    DataKey::esc_len
+}
+
+/// ID of the escrow created at `position`, in creation order
+pub(crate) fn escrow_index_key(position: u64) -> DataKey {
+    DataKey::esc_idx(position)
+}
+
+/// Position of an escrow in the export index
+pub(crate) fn escrow_position_key(escrow_id: u64) -> DataKey {
+    DataKey::esc_pos(escrow_id)
+}
+
+/// Tags the depositor set on an escrow
+pub(crate) fn tags_key(escrow_id: u64) -> DataKey {
+    DataKey::tags(escrow_id)
+}
+
+/// Number of escrows ever tagged with `tag`, which is also the next free position in its index
+pub(crate) fn tag_len_key(tag: &Symbol) -> DataKey {
+    DataKey::tag_len(tag.clone())
+}
+
+/// ID of the escrow tagged with `tag` at `position`, in tagging order
+pub(crate) fn tag_index_key(tag: &Symbol, position: u64) -> DataKey {
+    DataKey::tag_idx(tag.clone(), position)
+}
+
+/// Positions of an escrow in the indexes of its tags, in the order of the tags
+pub(crate) fn tag_positions_key(escrow_id: u64) -> DataKey {
+    DataKey::tag_pos(escrow_id)
+}
+
+pub(crate) fn ruling_key(escrow_id: u64) -> DataKey {
+    DataKey::ruling(escrow_id)
+}
+
+pub(crate) fn ruling_rationale_key(escrow_id: u64) -> DataKey {
+    DataKey::rationale(escrow_id)
+}
+
+pub(crate) fn evidence_deadline_key(escrow_id: u64) -> DataKey {
+    DataKey::ev_due(escrow_id)
+}
+
+pub(crate) fn evidence_key(escrow_id: u64, party: &Address) -> DataKey {
+    DataKey::evidence(escrow_id, party.clone())
+}
+
+pub(crate) fn evidence_commit_key(escrow_id: u64, party: &Address) -> DataKey {
+    DataKey::ev_commit(escrow_id, party.clone())
+}
+
+pub(crate) fn review_key(escrow_id: u64, milestone_index: u32) -> DataKey {
+    DataKey::review(escrow_id, milestone_index)
+}
+
+pub(crate) fn deliverable_key(escrow_id: u64, milestone_index: u32) -> DataKey {
+    DataKey::delivered(escrow_id, milestone_index)
+}
+
+pub(crate) fn rejection_reason_key(escrow_id: u64, milestone_index: u32) -> DataKey {
+    DataKey::rejected(escrow_id, milestone_index)
+}
+
+pub(crate) fn created_at_key(escrow_id: u64) -> DataKey {
+    DataKey::created(escrow_id)
+}
+
+pub(crate) fn open_claimants_key(escrow_id: u64) -> DataKey {
+    DataKey::claimants(escrow_id)
+}
+
+pub(crate) fn offers_key(escrow_id: u64) -> DataKey {
+    DataKey::offers(escrow_id)
+}
+
+pub(crate) fn offer_expiry_key(escrow_id: u64) -> DataKey {
+    DataKey::offer_exp(escrow_id)
+}
+
+pub(crate) fn dispute_grace_key(escrow_id: u64) -> DataKey {
+    DataKey::d_grace(escrow_id)
+}
+
+pub(crate) fn dispute_raiser_key(escrow_id: u64) -> DataKey {
+    DataKey::d_raiser(escrow_id)
+}
+
+pub(crate) fn dispute_cooldown_key(escrow_id: u64, party: &Address) -> DataKey {
+    DataKey::d_cool(escrow_id, party.clone())
+}
+
+pub(crate) fn role_key(role: Role, account: &Address) -> DataKey {
+    DataKey::role(role, account.clone())
+}
+
+/// Accounts explicitly granted `role`, in grant order
+pub(crate) fn role_members_key(role: Role) -> DataKey {
+    DataKey::role_mbrs(role)
+}
+
+pub(crate) fn funding_key(escrow_id: u64) -> DataKey {
+    DataKey::funding(escrow_id)
+}
+
+pub(crate) fn funding_plan_key(escrow_id: u64) -> DataKey {
+    DataKey::fund_plan(escrow_id)
+}
+
+pub(crate) fn yield_policy_key(escrow_id: u64) -> DataKey {
+    DataKey::yield_pol(escrow_id)
+}
+
+pub(crate) fn accrued_yield_key(escrow_id: u64) -> DataKey {
+    DataKey::accrued(escrow_id)
+}
+
+/// Where accrued yield was kept before schema 3. `yield` is a reserved word in Rust, so no
+/// `DataKey` variant encodes the same; balances still there are read until the escrow's next
+/// credit or payout clears them.
+pub(crate) fn legacy_accrued_yield_key(escrow_id: u64) -> (Symbol, u64) {
+    (symbol_short!("yield"), escrow_id)
+}
+
+pub(crate) fn excess_key(escrow_id: u64) -> DataKey {
+    DataKey::excess(escrow_id)
+}
+
+pub(crate) fn dormant_recovery_key(escrow_id: u64) -> DataKey {
+    DataKey::dormant(escrow_id)
+}
+
+pub(crate) fn backup_key(escrow_id: u64) -> DataKey {
+    DataKey::backup(escrow_id)
+}
+
+/// Keyed by the two addresses in a fixed order, so either order finds the same agreement
+pub(crate) fn standing_agreement_key(a: &Address, b: &Address) -> DataKey {
+    let (low, high) = if a < b { (a, b) } else { (b, a) };
+    DataKey::agreement(low.clone(), high.clone())
+}
+
+pub(crate) fn agreed_terms_key(escrow_id: u64) -> DataKey {
+    DataKey::agreed(escrow_id)
+}
+
+pub(crate) fn notifier_key(address: &Address) -> DataKey {
+    DataKey::notifier(address.clone())
+}
+
+pub(crate) fn last_seen_key(address: &Address) -> DataKey {
+    DataKey::seen(address.clone())
+}
+
+pub(crate) fn recipient_recovery_key(escrow_id: u64) -> DataKey {
+    DataKey::rcv_addr(escrow_id)
+}
+
+pub(crate) fn recovery_request_key(escrow_id: u64) -> DataKey {
+    DataKey::rcv_req(escrow_id)
+}
+
+pub(crate) fn approval_policy_key(escrow_id: u64) -> DataKey {
+    DataKey::appr_pol(escrow_id)
+}
+
+pub(crate) fn approvals_key(escrow_id: u64, milestone_index: u32) -> DataKey {
+    DataKey::approvals(escrow_id, milestone_index)
+}
+
+pub(crate) fn next_org_id(env: &Env) -> u64 {
+    let org_id: u64 = env.storage().instance().get(&DataKey::org_ctr).unwrap_or(0);
+    env.storage()
+        .instance()
+        .set(&DataKey::org_ctr, &(org_id + 1));
+    org_id
+}
+
+pub(crate) fn next_project_id(env: &Env) -> u64 {
+    let project_id: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::proj_ctr)
+        .unwrap_or(0);
+    env.storage()
+        .instance()
+        .set(&DataKey::proj_ctr, &(project_id + 1));
+    project_id
+}
+
+pub(crate) fn project_key(project_id: u64) -> DataKey {
+    DataKey::project(project_id)
+}
+
+pub(crate) fn escrow_project_key(escrow_id: u64) -> DataKey {
+    DataKey::esc_proj(escrow_id)
+}
+
+pub(crate) fn org_key(org_id: u64) -> DataKey {
+    DataKey::org(org_id)
+}
+
+pub(crate) fn org_member_key(org_id: u64, member: &Address) -> DataKey {
+    DataKey::org_mem(org_id, member.clone())
+}
+
+pub(crate) fn stable_payout_key(escrow_id: u64) -> DataKey {
+    DataKey::stable(escrow_id)
+}
+
+pub(crate) fn payout_route_key(escrow_id: u64) -> DataKey {
+    DataKey::route(escrow_id)
+}
+
+pub(crate) fn messages_key(escrow_id: u64) -> DataKey {
+    DataKey::messages(escrow_id)
+}
+
+pub(crate) fn deadline_proposal_key(escrow_id: u64) -> DataKey {
+    DataKey::dl_prop(escrow_id)
+}
+
+pub(crate) fn admin_log_page_key(page: u32) -> DataKey {
+    DataKey::adm_log(page)
+}
+
+pub(crate) fn admin_log_len_key() -> DataKey {
+    DataKey::adm_len
+}
+
+pub(crate) fn currency_key(escrow_id: u64) -> DataKey {
+    DataKey::currency(escrow_id)
+}
+
+pub(crate) fn notes_key(escrow_id: u64) -> DataKey {
+    DataKey::notes(escrow_id)
+}
+
+pub(crate) fn goods_oracle_key(escrow_id: u64) -> DataKey {
+    DataKey::goods(escrow_id)
+}
+
+pub(crate) fn shipment_key(escrow_id: u64, milestone_index: u32) -> DataKey {
+    DataKey::shipment(escrow_id, milestone_index)
+}
+
+pub(crate) fn logistics_oracle_key(oracle: &Address) -> DataKey {
+    DataKey::logistics(oracle.clone())
+}
+
+pub(crate) fn amm_pool_key(a: &Address, b: &Address) -> DataKey {
+    let (low, high) = if a < b { (a, b) } else { (b, a) };
+    DataKey::amm_pool(low.clone(), high.clone())
+}
+
+pub(crate) fn operator_key(operator: &Symbol) -> DataKey {
+    DataKey::operator(operator.clone())
+}
+
+pub(crate) fn escrow_operator_key(escrow_id: u64) -> DataKey {
+    DataKey::esc_oper(escrow_id)
+}
+
+pub(crate) fn escrow_org_key(escrow_id: u64) -> DataKey {
+    DataKey::esc_org(escrow_id)
+}
+
+pub(crate) fn spending_limits_key(escrow_id: u64) -> DataKey {
+    DataKey::spend_cap(escrow_id)
+}
+
+pub(crate) fn delegate_spend_key(escrow_id: u64) -> DataKey {
+    DataKey::spent(escrow_id)
+}
+
+pub(crate) fn hook_key(escrow_id: u64) -> DataKey {
+    DataKey::hook(escrow_id)
+}
+
+pub(crate) fn vesting_key(escrow_id: u64) -> DataKey {
+    DataKey::vesting(escrow_id)
+}
+
+pub(crate) fn template_storage_key(template_id: u64) -> DataKey {
+    DataKey::template(template_id)
+}
+
+pub(crate) fn template_arbiter_key(template_id: u64) -> DataKey {
+    DataKey::tpl_arb(template_id)
+}
+
+pub(crate) fn review_window_key(escrow_id: u64) -> DataKey {
+    DataKey::rev_win(escrow_id)
+}
+
+/// Seconds the depositor has to review a delivered milestone of the escrow: the window of the
+/// template it was created from, if that set one, else the configured default.
+pub(crate) fn review_window(env: &Env, escrow_id: u64) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&review_window_key(escrow_id))
+        .unwrap_or_else(|| load_config(env).review_window)
+}
+
+pub(crate) fn arbiter_storage_key(escrow_id: u64) -> DataKey {
+    DataKey::arbiter(escrow_id)
+}
+
+/// Every entry keyed by the escrow id alone, other than the escrow itself, its nonce and its
+/// history. `extend_escrow_ttl` bumps these and `close_escrow` removes them, so a new per-escrow
+/// entry only needs adding here.
+pub(crate) fn escrow_keys(escrow_id: u64) -> [DataKey; 61] {
+    [
+        assignee_storage_key(escrow_id),
+        external_ref_key(escrow_id),
+        arbiter_storage_key(escrow_id),
+        review_window_key(escrow_id),
+        recurring_key(escrow_id),
+        recurring_start_key(escrow_id),
+        vesting_key(escrow_id),
+        dispute_ledger_key(escrow_id),
+        dispute_bond_key(escrow_id),
+        dispute_origin_key(escrow_id),
+        dispute_reason_key(escrow_id),
+        evidence_deadline_key(escrow_id),
+        created_at_key(escrow_id),
+        dispute_grace_key(escrow_id),
+        dispute_raiser_key(escrow_id),
+        dormant_recovery_key(escrow_id),
+        backup_key(escrow_id),
+        recipient_recovery_key(escrow_id),
+        recovery_request_key(escrow_id),
+        approval_policy_key(escrow_id),
+        escrow_org_key(escrow_id),
+        escrow_operator_key(escrow_id),
+        escrow_project_key(escrow_id),
+        tags_key(escrow_id),
+        stable_payout_key(escrow_id),
+        payout_route_key(escrow_id),
+        messages_key(escrow_id),
+        deadline_proposal_key(escrow_id),
+        goods_oracle_key(escrow_id),
+        currency_key(escrow_id),
+        notes_key(escrow_id),
+        claimed_key(escrow_id),
+        escrow_position_key(escrow_id),
+        tag_positions_key(escrow_id),
+        spending_limits_key(escrow_id),
+        delegate_spend_key(escrow_id),
+        hook_key(escrow_id),
+        offers_key(escrow_id),
+        offer_expiry_key(escrow_id),
+        open_claimants_key(escrow_id),
+        ruling_key(escrow_id),
+        ruling_rationale_key(escrow_id),
+        excess_key(escrow_id),
+        funding_key(escrow_id),
+        funding_plan_key(escrow_id),
+        yield_policy_key(escrow_id),
+        accrued_yield_key(escrow_id),
+        parent_key(escrow_id),
+        statement_key(escrow_id),
+        pending_ruling_key(escrow_id),
+        dao_mode_key(escrow_id),
+        dao_vote_key(escrow_id),
+        panel_mode_key(escrow_id),
+        panel_vote_key(escrow_id),
+        terms_hash_key(escrow_id),
+        revealed_terms_key(escrow_id),
+        nft_key(escrow_id),
+        swap_leg_key(escrow_id),
+        agreed_terms_key(escrow_id),
+        settlement_key(escrow_id),
+        assignment_key(escrow_id),
+    ]
+}
+
+pub(crate) fn get_storage_key(escrow_id: u64) -> DataKey {
+    DataKey::escrow(escrow_id)
+}
+
+pub(crate) fn milestone_storage_key(escrow_id: u64, milestone_index: u32) -> DataKey {
+    DataKey::milestone(escrow_id, milestone_index)
+}
+
+pub(crate) fn load_milestone(
+    env: &Env,
+    escrow_id: u64,
+    milestone_index: u32,
+) -> Result<Milestone, Error> {
+    let storage_key = milestone_storage_key(escrow_id, milestone_index);
+    if let Some(milestone) = read_persistent(env, &storage_key) {
+        return Ok(milestone);
+    }
+    env.storage()
+        .temporary()
+        .get(&storage_key)
+        .ok_or(Error::MilestoneNotFound)
+}
+
+/// An archived escrow lives only in temporary storage.
+pub(crate) fn is_archived(env: &Env, escrow_id: u64) -> bool {
+    let storage_key = get_storage_key(escrow_id);
+    !env.storage().persistent().has(&storage_key) && env.storage().temporary().has(&storage_key)
+}
+
+/// Whether an escrow id is taken, counting archived escrows that have not yet expired.
+pub(crate) fn escrow_exists(env: &Env, escrow_id: u64) -> bool {
+    let storage_key = get_storage_key(escrow_id);
+    env.storage().persistent().has(&storage_key) || env.storage().temporary().has(&storage_key)
+}
+
+/// TTL policy the admin has set, or the built-in thresholds.
+pub(crate) fn ttl_policy(env: &Env) -> TtlPolicy {
+    env.storage()
+        .instance()
+        .get(&DataKey::ttl)
+        .unwrap_or(TtlPolicy {
+            threshold: ESCROW_TTL_THRESHOLD,
+            extend_to: ESCROW_TTL_EXTEND_TO,
+        })
+}
+
+/// Bumps a persistent entry's TTL once it drops below the policy threshold.
+pub(crate) fn extend_persistent<K: IntoVal<Env, Val>>(env: &Env, key: &K) {
+    let policy = ttl_policy(env);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, policy.threshold, policy.extend_to);
+}
+
+/// Reads a persistent entry, bumping its TTL if it exists.
+pub(crate) fn read_persistent<K, V>(env: &Env, key: &K) -> Option<V>
+where
+    K: IntoVal<Env, Val>,
+    V: TryFromVal<Env, Val>,
+{
+    let value = env.storage().persistent().get(key)?;
+    extend_persistent(env, key);
+    Some(value)
+}
+
+/// Writes a persistent entry and bumps its TTL.
+pub(crate) fn write_persistent<K, V>(env: &Env, key: &K, value: &V)
+where
+    K: IntoVal<Env, Val>,
+    V: IntoVal<Env, Val>,
+{
+    env.storage().persistent().set(key, value);
+    extend_persistent(env, key);
+}
+
+pub(crate) fn milestone_bit(milestone_index: u32) -> u64 {
+    1u64 << milestone_index
+}
+
+pub(crate) fn all_milestones_mask(milestone_count: u32) -> u64 {
+    if milestone_count >= 64 {
+        u64::MAX
+    } else {
+        (1u64 << milestone_count) - 1
+    }
+}
+
+/// Status of a milestone as tracked by the escrow header bitmasks.
+pub(crate) fn milestone_status(escrow: &Escrow, milestone_index: u32) -> MilestoneStatus {
+    let bit = milestone_bit(milestone_index);
+    if escrow.released_mask & bit != 0 {
+        MilestoneStatus::Released
+    } else if escrow.disputed_mask & bit != 0 {
+        MilestoneStatus::Disputed
+    } else {
+        MilestoneStatus::Pending
+    }
+}
+
+pub(crate) fn save_milestone(
+    env: &Env,
+    escrow_id: u64,
+    milestone_index: u32,
+    milestone: &Milestone,
+) {
+    let key = milestone_storage_key(escrow_id, milestone_index);
+    write_persistent(env, &key, milestone);
+}
+
+pub(crate) fn assignee_storage_key(escrow_id: u64) -> DataKey {
+    DataKey::assignee(escrow_id)
+}
+
+/// Address that receives recipient-side payouts: the assignee if the claim was assigned.
+pub(crate) fn external_ref_key(escrow_id: u64) -> DataKey {
+    DataKey::ext_ref(escrow_id)
+}
+
+pub(crate) fn external_ref_index_key(external_ref: &String) -> DataKey {
+    DataKey::ref_idx(external_ref.clone())
+}
+
+pub(crate) fn blocked_key(address: &Address) -> DataKey {
+    DataKey::blocked(address.clone())
+}
+
+pub(crate) fn allowlist_key(address: &Address) -> DataKey {
+    DataKey::allowed(address.clone())
+}
+
+pub(crate) fn fee_tiers_key() -> DataKey {
+    DataKey::fee_tiers
+}
+
+pub(crate) fn fee_exempt_key(address: &Address) -> DataKey {
+    DataKey::fee_free(address.clone())
+}
+
+pub(crate) fn fee_token_key(asset: &Address) -> DataKey {
+    DataKey::fee_token(asset.clone())
+}
+
+pub(crate) fn payout_memo_key(address: &Address) -> DataKey {
+    DataKey::memo(address.clone())
+}
+
+pub(crate) fn allowed_tokens_key() -> DataKey {
+    DataKey::tokens
+}
+
+pub(crate) fn admin_storage_key() -> DataKey {
+    DataKey::admin
+}
+
+/// Reads the admin from instance storage. Instances set up before schema 3 hold it under the
+/// bare `admin` symbol, in instance storage or, before that, persistent storage; it is migrated
+/// on first access.
+pub(crate) fn get_admin(env: &Env) -> Result<Address, Error> {
+    if let Some(admin) = env.storage().instance().get(&admin_storage_key()) {
+        extend_instance(env);
+        return Ok(admin);
+    }
+
+    let legacy = symbol_short!("admin");
+    let admin: Address = match env.storage().instance().get(&legacy) {
+        Some(admin) => admin,
+        None => env
+            .storage()
+            .persistent()
+            .get(&legacy)
+            .ok_or(Error::AdminNotInitialized)?,
+    };
+    env.storage().instance().remove(&legacy);
+    env.storage().persistent().remove(&legacy);
+    env.storage().instance().set(&admin_storage_key(), &admin);
+    extend_instance(env);
+    Ok(admin)
+}
+
+/// Reads the global configuration. Instances configured before schema 3 keep it under the bare
+/// `config` symbol, and those configured before `Config` existed kept the fee and pause flag
+/// under their own keys; either is folded in until the next save.
+pub(crate) fn load_config(env: &Env) -> Config {
+    if let Some(config) = env.storage().instance().get(&DataKey::config) {
+        return config;
+    }
+    if let Some(config) = env.storage().instance().get(&symbol_short!("config")) {
+        return config;
+    }
+
+    let fee_bps = env
+        .storage()
+        .instance()
+        .get(&symbol_short!("fee_bps"))
+        .unwrap_or(DEFAULT_FEE_BPS);
+    let state = env
+        .storage()
+        .instance()
+        .get(&symbol_short!("state"))
+        .unwrap_or(ContractState::Active);
+
+    Config {
+        max_milestones: DEFAULT_MAX_MILESTONES,
+        min_milestone_amount: DEFAULT_MIN_MILESTONE_AMOUNT,
+        review_window: DEFAULT_REVIEW_WINDOW,
+        fee_bps,
+        dispute_bond: 0,
+        paused: state == ContractState::Paused,
+        creation_quota: 0,
+        quota_window: DEFAULT_QUOTA_WINDOW,
+        allowlist_only: false,
+        evidence_window: 0,
+        max_rejections: 0,
+        dispute_grace: 0,
+        dispute_cooldown: 0,
+        dormancy_period: 0,
+        escalation_window: 0,
+        dust_precision: 0,
+        dispute_fee_policy: DisputeFeePolicy::FromBond,
+        max_pair_escrows: 0,
+    }
+}
+
+pub(crate) fn save_config(env: &Env, config: &Config) {
+    let instance = env.storage().instance();
+    instance.set(&DataKey::config, config);
+    for legacy in [
+        symbol_short!("config"),
+        symbol_short!("fee_bps"),
+        symbol_short!("state"),
+    ] {
+        instance.remove(&legacy);
+    }
+    extend_instance(env);
+}
+
+/// Instances initialized before schema tracking carry no version and use the legacy layout.
+/// Until `migrate` moves it, the version of an instance set up before schema 3 sits under the
+/// bare `schema` symbol.
+pub(crate) fn stored_schema_version(env: &Env) -> u32 {
+    let instance = env.storage().instance();
+    instance
+        .get(&DataKey::schema)
+        .or_else(|| instance.get(&symbol_short!("schema")))
+        .unwrap_or(LEGACY_SCHEMA_VERSION)
+}
+
+/// Records the current schema on a fresh deployment. Upgraded instances keep reporting the
+/// schema they were written with until `migrate` runs.
+pub(crate) fn record_schema_if_fresh(env: &Env) {
+    let instance = env.storage().instance();
+    if !instance.has(&DataKey::schema)
+        && !instance.has(&DataKey::config)
+        && !instance.has(&symbol_short!("schema"))
+        && !instance.has(&symbol_short!("fee_bps"))
+        && !instance.has(&symbol_short!("config"))
+    {
+        instance.set(&DataKey::schema, &STORAGE_SCHEMA_VERSION);
+    }
+}
+
+/// Records the current schema, once `migrate` has brought storage up to it.
+pub(crate) fn record_schema(env: &Env) {
+    let instance = env.storage().instance();
+    instance.set(&DataKey::schema, &STORAGE_SCHEMA_VERSION);
+    instance.remove(&symbol_short!("schema"));
+}
+
+/// Moves the singletons an instance set up before schema 3 keeps under bare symbols, from
+/// whichever storage they are in, to their `DataKey`. The admin, config and schema version are
+/// left to `get_admin`, `save_config` and `record_schema`.
+pub(crate) fn migrate_singletons(env: &Env) {
+    for (legacy, key) in [
+        (symbol_short!("treasury"), DataKey::treasury),
+        (symbol_short!("screener"), DataKey::screener),
+        (symbol_short!("kyc_att"), DataKey::kyc_att),
+        (symbol_short!("val_floor"), DataKey::val_floor),
+        (symbol_short!("ins_bps"), DataKey::ins_bps),
+        (symbol_short!("receipt"), DataKey::receipt),
+        (symbol_short!("amm"), DataKey::amm),
+        (symbol_short!("yield_src"), DataKey::yield_src),
+        (symbol_short!("esc_ctr"), DataKey::esc_ctr),
+        (symbol_short!("org_ctr"), DataKey::org_ctr),
+        (symbol_short!("proj_ctr"), DataKey::proj_ctr),
+        (symbol_short!("tpl_ctr"), DataKey::tpl_ctr),
+        (symbol_short!("claim_ctr"), DataKey::claim_ctr),
+        (symbol_short!("imported"), DataKey::imported),
+        (symbol_short!("rul_lock"), DataKey::rul_lock),
+        (symbol_short!("dao_cfg"), DataKey::dao_cfg),
+        (symbol_short!("esc_len"), DataKey::esc_len),
+        (symbol_short!("fee_tiers"), DataKey::fee_tiers),
+        (symbol_short!("tokens"), DataKey::tokens),
+        (symbol_short!("stats"), DataKey::stats),
+        (symbol_short!("arbiters"), DataKey::arbiters),
+        (symbol_short!("d_queue"), DataKey::d_queue),
+        (symbol_short!("arb_cfg"), DataKey::arb_cfg),
+    ] {
+        let instance = env.storage().instance();
+        if let Some(value) = instance.get::<_, Val>(&legacy) {
+            instance.set(&key, &value);
+            instance.remove(&legacy);
+        }
+        let persistent = env.storage().persistent();
+        if let Some(value) = persistent.get::<_, Val>(&legacy) {
+            persistent.set(&key, &value);
+            extend_persistent(env, &key);
+            persistent.remove(&legacy);
+        }
+    }
+}
+
+/// Bumps the contract instance (admin and global config) along with its code.
+pub(crate) fn extend_instance(env: &Env) {
+    let policy = ttl_policy(env);
+    env.storage()
+        .instance()
+        .extend_ttl(policy.threshold, policy.extend_to);
+}
+
+pub(crate) fn creation_quota_key(depositor: &Address) -> DataKey {
+    DataKey::quota(depositor.clone())
+}
+
+pub(crate) fn pair_escrows_key(depositor: &Address, recipient: &Address) -> DataKey {
+    DataKey::pair_open(depositor.clone(), recipient.clone())
+}
+
+pub(crate) fn amount_limits_key(token_address: &Address) -> DataKey {
+    DataKey::amt_lim(token_address.clone())
+}
+
+pub(crate) fn insurance_pool_key(token_address: &Address) -> DataKey {
+    DataKey::ins_pool(token_address.clone())
+}
+
+pub(crate) fn claim_storage_key(claim_id: u64) -> DataKey {
+    DataKey::claim(claim_id)
+}
+
+/// Total of the pending and approved insurance claims filed against an escrow
+pub(crate) fn claimed_key(escrow_id: u64) -> DataKey {
+    DataKey::claimed(escrow_id)
+}
+
+pub(crate) fn reputation_key(address: &Address) -> DataKey {
+    DataKey::rep(address.clone())
+}
+
+pub(crate) fn rating_storage_key(escrow_id: u64, rater: &Address) -> DataKey {
+    DataKey::rating(escrow_id, rater.clone())
+}
+
+pub(crate) fn rating_summary_key(address: &Address) -> DataKey {
+    DataKey::rate_sum(address.clone())
+}
+
+pub(crate) fn history_page_key(escrow_id: u64, page: u32) -> DataKey {
+    DataKey::hist(escrow_id, page)
+}
+
+pub(crate) fn history_len_key(escrow_id: u64) -> DataKey {
+    DataKey::hist_len(escrow_id)
+}
+
+pub(crate) fn stats_key() -> DataKey {
+    DataKey::stats
+}
+
+pub(crate) fn token_stats_key(token_address: &Address) -> DataKey {
+    DataKey::tok_stats(token_address.clone())
+}
+
+pub(crate) fn arbitrators_key() -> DataKey {
+    DataKey::arbiters
+}
+
+pub(crate) fn dispute_queue_key() -> DataKey {
+    DataKey::d_queue
+}
+
+pub(crate) fn dispute_tally_key() -> DataKey {
+    DataKey::d_tally
+}
+
+pub(crate) fn open_disputes_key() -> DataKey {
+    DataKey::d_open
+}
+
+pub(crate) fn arbitration_config_key() -> DataKey {
+    DataKey::arb_cfg
+}
+
+pub(crate) fn stake_key(arbitrator: &Address) -> DataKey {
+    DataKey::stake(arbitrator.clone())
+}
+
+pub(crate) fn open_assignments_key(arbitrator: &Address) -> DataKey {
+    DataKey::arb_open(arbitrator.clone())
+}
+
+pub(crate) fn assignment_key(escrow_id: u64) -> DataKey {
+    DataKey::assignmt(escrow_id)
+}
+
+pub(crate) fn arbitrator_fees_key(arbitrator: &Address, token_address: &Address) -> DataKey {
+    DataKey::arb_fees(arbitrator.clone(), token_address.clone())
+}
@@ -0,0 +1,212 @@
+//! Two-sided swaps.
+//!
+//! An escrow can trade its payment for another token instead of for off-chain work: the
+//! depositor sets the counter leg (a token and amount) before funding, and the recipient
+//! deposits it into the contract. Once the escrow is fully funded with the counter leg held,
+//! both parties confirm and the legs swap atomically: the counter leg goes to the depositor,
+//! every milestone is released to the recipient and the escrow completes. A ruling settles a
+//! disputed swap instead, sending the counter leg to the depositor if the recipient was paid
+//! and back to the recipient otherwise. Until the swap, the recipient can withdraw the counter
+//! leg unless a dispute is open.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    dispute::ensure_party,
+    errors::Error,
+    escrow::{load_live_escrow, release_all},
+    funding::funding,
+    history,
+    storage::{swap_leg_key, write_persistent},
+    types::{Escrow, EscrowStatus, SwapLeg},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, token, Address, Env};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Makes the escrow pay for `amount` of `token`, deposited by the recipient. Depositor
+    /// only, before funding.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If the escrow doesn't exist
+    /// * `EscrowAlreadyFunded` - If the escrow has been funded
+    /// * `ZeroAmount` - If amount is not positive
+    pub fn set_swap_leg(
+        env: Env,
+        escrow_id: u64,
+        token: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if !matches!(
+            escrow.status,
+            EscrowStatus::Draft | EscrowStatus::Proposed | EscrowStatus::Created
+        ) {
+            return Err(Error::EscrowAlreadyFunded);
+        }
+        if amount <= 0 {
+            return Err(Error::ZeroAmount);
+        }
+
+        save_leg(
+            &env,
+            escrow_id,
+            &SwapLeg {
+                token,
+                amount,
+                held: false,
+                depositor_confirmed: false,
+                recipient_confirmed: false,
+            },
+        );
+        Ok(())
+    }
+
+    /// The counter leg the escrow's payment swaps against, if any.
+    pub fn get_swap_leg(env: Env, escrow_id: u64) -> Option<SwapLeg> {
+        env.storage().persistent().get(&swap_leg_key(escrow_id))
+    }
+
+    /// Moves the counter leg from the recipient into the contract.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If the escrow doesn't exist
+    /// * `InvalidConfiguration` - If the escrow has no counter leg
+    /// * `InvalidEscrowStatus` - If the counter leg is already held, or the escrow is neither
+    ///   created nor active
+    pub fn deposit_swap_leg(env: Env, escrow_id: u64) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.recipient.require_auth();
+
+        let mut leg =
+            Self::get_swap_leg(env.clone(), escrow_id).ok_or(Error::InvalidConfiguration)?;
+        if leg.held || !matches!(escrow.status, EscrowStatus::Created | EscrowStatus::Active) {
+            return Err(Error::InvalidEscrowStatus);
+        }
+
+        leg.held = true;
+        save_leg(&env, escrow_id, &leg);
+        token::Client::new(&env, &leg.token).transfer(
+            &escrow.recipient,
+            &env.current_contract_address(),
+            &leg.amount,
+        );
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("leg_in"),
+            &escrow.recipient,
+            leg.amount,
+        );
+        Ok(())
+    }
+
+    /// Returns the counter leg to the recipient before the swap, withdrawing both parties'
+    /// confirmations.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If the escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the contract doesn't hold the counter leg, or the escrow
+    ///   is disputed
+    pub fn withdraw_swap_leg(env: Env, escrow_id: u64) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.recipient.require_auth();
+
+        if escrow.status == EscrowStatus::Disputed {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        let amount = hand_over(&env, escrow_id, &escrow.recipient)?;
+        history::record(
+            &env,
+            escrow_id,
+            symbol_short!("leg_out"),
+            &escrow.recipient,
+            amount,
+        );
+        Ok(())
+    }
+
+    /// Confirms the swap for `caller`. Once both parties have confirmed, the legs swap and
+    /// the escrow completes.
+    ///
+    /// # Returns
+    /// Whether the swap executed
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If the escrow doesn't exist
+    /// * `UnauthorizedAccess` - If caller is not a party
+    /// * `InvalidConfiguration` - If the escrow has no counter leg
+    /// * `InvalidEscrowStatus` - If the escrow is not active or the counter leg isn't held
+    /// * `InsufficientBalance` - If the escrow is not fully funded
+    pub fn confirm_swap(env: Env, escrow_id: u64, caller: Address) -> Result<bool, Error> {
+        let mut escrow = load_live_escrow(&env, escrow_id)?;
+        ensure_party(&escrow, &caller)?;
+        caller.require_auth();
+
+        let mut leg =
+            Self::get_swap_leg(env.clone(), escrow_id).ok_or(Error::InvalidConfiguration)?;
+        if !leg.held || escrow.status != EscrowStatus::Active {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        if funding(&env, escrow_id, &escrow).0 < escrow.milestone_count {
+            return Err(Error::InsufficientBalance);
+        }
+
+        if caller == escrow.depositor {
+            leg.depositor_confirmed = true;
+        } else {
+            leg.recipient_confirmed = true;
+        }
+        save_leg(&env, escrow_id, &leg);
+        if !(leg.depositor_confirmed && leg.recipient_confirmed) {
+            return Ok(false);
+        }
+
+        let depositor = escrow.depositor.clone();
+        hand_over(&env, escrow_id, &depositor)?;
+        release_all(&env, escrow_id, &mut escrow)?;
+        Ok(true)
+    }
+}
+
+fn save_leg(env: &Env, escrow_id: u64, leg: &SwapLeg) {
+    let key = swap_leg_key(escrow_id);
+    write_persistent(env, &key, leg);
+}
+
+/// Transfers the counter leg held for the escrow to `to`, returning its amount.
+fn hand_over(env: &Env, escrow_id: u64, to: &Address) -> Result<i128, Error> {
+    let mut leg = VaultixEscrow::get_swap_leg(env.clone(), escrow_id)
+        .filter(|leg| leg.held)
+        .ok_or(Error::InvalidEscrowStatus)?;
+
+    leg.held = false;
+    leg.depositor_confirmed = false;
+    leg.recipient_confirmed = false;
+    save_leg(env, escrow_id, &leg);
+    token::Client::new(env, &leg.token).transfer(&env.current_contract_address(), to, &leg.amount);
+    Ok(leg.amount)
+}
+
+/// Settles a counter leg held for a disputed escrow on its ruling: the depositor receives it
+/// if the recipient was paid, otherwise it goes back to the recipient.
+pub(crate) fn settle_ruling(
+    env: &Env,
+    escrow_id: u64,
+    escrow: &Escrow,
+    recipient_won: bool,
+) -> Result<(), Error> {
+    let held = VaultixEscrow::get_swap_leg(env.clone(), escrow_id).is_some_and(|leg| leg.held);
+    if !held {
+        return Ok(());
+    }
+    let to = if recipient_won {
+        &escrow.depositor
+    } else {
+        &escrow.recipient
+    };
+    hand_over(env, escrow_id, to).map(|_| ())
+}
@@ -0,0 +1,117 @@
+//! Escrow tags.
+//!
+//! The depositor can label a new escrow with a few short tags (design, audit, development...)
+//! before funding it. Each tag keeps its own index of escrows in tagging order, which
+//! `get_escrows_by_tag` pages through, so category dashboards can be built from the chain
+//! alone. Tags are fixed once set; closing an escrow drops it from the indexes, since its ID
+//! may be reused by an escrow that was never tagged.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    errors::Error,
+    storage::{
+        escrow_exists, tag_index_key, tag_len_key, tag_positions_key, tags_key, write_persistent,
+    },
+    types::EscrowStatus,
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, Env, Symbol, Vec};
+
+/// Most tags one escrow may carry
+const MAX_TAGS: u32 = 5;
+
+/// Escrow IDs returned per page of `get_escrows_by_tag`
+const TAG_PAGE_SIZE: u64 = 20;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Tags an escrow that has not been funded yet (depositor only). Tags can be set once.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `InvalidEscrowStatus` - If the escrow has been funded
+    /// * `VectorTooLarge` - If there are more than 5 tags
+    /// * `InvalidConfiguration` - If the escrow is already tagged, or tags are empty or repeated
+    pub fn set_tags(env: Env, escrow_id: u64, tags: Vec<Symbol>) -> Result<(), Error> {
+        let escrow = Self::get_escrow(env.clone(), escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if escrow.status != EscrowStatus::Created {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        if tags.len() > MAX_TAGS {
+            return Err(Error::VectorTooLarge);
+        }
+        let key = tags_key(escrow_id);
+        if tags.is_empty() || env.storage().persistent().has(&key) {
+            return Err(Error::InvalidConfiguration);
+        }
+        for (index, tag) in tags.iter().enumerate() {
+            if tags.first_index_of(&tag) != Some(index as u32) {
+                return Err(Error::InvalidConfiguration);
+            }
+        }
+
+        write_persistent(&env, &key, &tags);
+        let mut positions = Vec::new(&env);
+        for tag in tags.iter() {
+            let len_key = tag_len_key(&tag);
+            let position: u64 = env.storage().persistent().get(&len_key).unwrap_or(0);
+            let index_key = tag_index_key(&tag, position);
+            write_persistent(&env, &index_key, &escrow_id);
+            write_persistent(&env, &len_key, &(position + 1));
+            positions.push_back(position);
+        }
+        write_persistent(&env, &tag_positions_key(escrow_id), &positions);
+        Ok(())
+    }
+
+    /// Tags of the escrow; empty if it was never tagged.
+    pub fn get_tags(env: Env, escrow_id: u64) -> Vec<Symbol> {
+        env.storage()
+            .persistent()
+            .get(&tags_key(escrow_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Page `page` (from 0) of the escrows tagged with `tag`, 20 positions per page in tagging
+    /// order. Escrows closed since are skipped, so a page can be short before the last one.
+    pub fn get_escrows_by_tag(env: Env, tag: Symbol, page: u32) -> Vec<u64> {
+        let len: u64 = env
+            .storage()
+            .persistent()
+            .get(&tag_len_key(&tag))
+            .unwrap_or(0);
+        let start = (page as u64).saturating_mul(TAG_PAGE_SIZE);
+        let end = start.saturating_add(TAG_PAGE_SIZE).min(len);
+        let mut escrows = Vec::new(&env);
+        for position in start..end {
+            let Some(escrow_id) = env
+                .storage()
+                .persistent()
+                .get::<_, u64>(&tag_index_key(&tag, position))
+            else {
+                continue;
+            };
+            if escrow_exists(&env, escrow_id) {
+                escrows.push_back(escrow_id);
+            }
+        }
+        escrows
+    }
+}
+
+/// Drops a closed escrow from the indexes of its tags, along with its tags.
+pub(crate) fn untag(env: &Env, escrow_id: u64) {
+    let storage = env.storage().persistent();
+    let tags: Vec<Symbol> = storage.get(&tags_key(escrow_id)).unwrap_or(Vec::new(env));
+    let positions: Vec<u64> = storage
+        .get(&tag_positions_key(escrow_id))
+        .unwrap_or(Vec::new(env));
+    for (tag, position) in tags.iter().zip(positions.iter()) {
+        storage.remove(&tag_index_key(&tag, position));
+    }
+    storage.remove(&tags_key(escrow_id));
+    storage.remove(&tag_positions_key(escrow_id));
+}
@@ -0,0 +1,92 @@
+//! Sealed terms.
+//!
+//! A depositor who wants to keep an escrow's statement of work private can leave the milestone
+//! descriptions blank and store only the hash of the full terms document. Nothing about the
+//! terms is visible on-chain until a dispute, when either party can reveal the document so that
+//! whoever rules on it can check the work against what was agreed.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    dispute::ensure_party,
+    errors::Error,
+    escrow::load_live_escrow,
+    storage::{revealed_terms_key, terms_hash_key, write_persistent},
+    types::EscrowStatus,
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Bytes, BytesN, Env};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Records the SHA-256 hash of the escrow's private terms. Depositor only, once, before
+    /// funding.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If the escrow doesn't exist
+    /// * `EscrowAlreadyFunded` - If the escrow has been funded
+    /// * `InvalidConfiguration` - If the terms have already been sealed
+    pub fn seal_terms(env: Env, escrow_id: u64, terms_hash: BytesN<32>) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if !matches!(
+            escrow.status,
+            EscrowStatus::Draft | EscrowStatus::Proposed | EscrowStatus::Created
+        ) {
+            return Err(Error::EscrowAlreadyFunded);
+        }
+        let key = terms_hash_key(escrow_id);
+        if env.storage().persistent().has(&key) {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        write_persistent(&env, &key, &terms_hash);
+        env.events()
+            .publish((symbol_short!("sealed"), escrow_id), terms_hash);
+        Ok(())
+    }
+
+    /// Hash of the escrow's sealed terms, if any.
+    pub fn get_terms_hash(env: Env, escrow_id: u64) -> Option<BytesN<32>> {
+        env.storage().persistent().get(&terms_hash_key(escrow_id))
+    }
+
+    /// Publishes the escrow's sealed terms for its open dispute. Either party may reveal them.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If the escrow doesn't exist
+    /// * `UnauthorizedAccess` - If caller is not a party, or `terms` don't match the sealed hash
+    /// * `InvalidEscrowStatus` - If the escrow is not disputed
+    pub fn reveal_terms(
+        env: Env,
+        escrow_id: u64,
+        caller: Address,
+        terms: Bytes,
+    ) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        ensure_party(&escrow, &caller)?;
+        caller.require_auth();
+
+        if escrow.status != EscrowStatus::Disputed {
+            return Err(Error::InvalidEscrowStatus);
+        }
+        let terms_hash = env.crypto().sha256(&terms);
+        if Self::get_terms_hash(env.clone(), escrow_id) != Some(terms_hash) {
+            return Err(Error::UnauthorizedAccess);
+        }
+
+        let key = revealed_terms_key(escrow_id);
+        write_persistent(&env, &key, &terms);
+        env.events()
+            .publish((symbol_short!("terms_rev"), escrow_id), caller);
+        Ok(())
+    }
+
+    /// The escrow's sealed terms, once revealed in a dispute.
+    pub fn get_revealed_terms(env: Env, escrow_id: u64) -> Option<Bytes> {
+        env.storage()
+            .persistent()
+            .get(&revealed_terms_key(escrow_id))
+    }
+}
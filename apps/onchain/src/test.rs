@@ -1,7 +1,8 @@
 use super::*;
 use soroban_sdk::{
-    testutils::{Address as _, Events},
-    token, vec, Address, Env, IntoVal,
+    symbol_short,
+    testutils::{Address as _, Events, Ledger},
+    token, vec, Address, BytesN, Env, IntoVal, String, Symbol, Vec,
 };
 
 /// Helper function to create and initialize a test token
@@ -32,7 +33,7 @@ fn test_create_escrow_fails_when_paused() {
 
     let treasury = Address::generate(&env);
     client.initialize(&treasury, &None);
-    client.set_paused(&true);
+    client.set_paused(&treasury, &true);
 
     let depositor = Address::generate(&env);
     let recipient = Address::generate(&env);
@@ -47,7 +48,7 @@ fn test_create_escrow_fails_when_paused() {
         Milestone {
             amount: 10_000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Work"),
+            description: String::from_str(&env, "Work"),
         },
     ];
 
@@ -89,7 +90,7 @@ fn test_deposit_funds_fails_when_paused() {
         Milestone {
             amount: 10_000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Work"),
+            description: String::from_str(&env, "Work"),
         },
     ];
 
@@ -105,7 +106,7 @@ fn test_deposit_funds_fails_when_paused() {
 
     token_client.approve(&depositor, &contract_id, &10_000, &200);
 
-    client.set_paused(&true);
+    client.set_paused(&treasury, &true);
     let result = client.try_deposit_funds(&escrow_id);
     assert_eq!(result, Err(Ok(Error::ContractPaused)));
 }
@@ -132,17 +133,17 @@ fn test_create_and_get_escrow() {
         Milestone {
             amount: 3000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Design"),
+            description: String::from_str(&env, "Design"),
         },
         Milestone {
             amount: 3000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Dev"),
+            description: String::from_str(&env, "Dev"),
         },
         Milestone {
             amount: 4000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Deploy"),
+            description: String::from_str(&env, "Deploy"),
         },
     ];
 
@@ -164,7 +165,7 @@ fn test_create_and_get_escrow() {
     assert_eq!(escrow.total_amount, 10000);
     assert_eq!(escrow.total_released, 0);
     assert_eq!(escrow.status, EscrowStatus::Created);
-    assert_eq!(escrow.milestones.len(), 3);
+    assert_eq!(escrow.milestone_count, 3);
 
     // Verify Create Event
     let events = env.events().all();
@@ -214,12 +215,12 @@ fn test_deposit_funds() {
         Milestone {
             amount: 5000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Phase1"),
+            description: String::from_str(&env, "Phase1"),
         },
         Milestone {
             amount: 5000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Phase2"),
+            description: String::from_str(&env, "Phase2"),
         },
     ];
 
@@ -276,12 +277,12 @@ fn test_release_milestone_with_tokens() {
         Milestone {
             amount: 6000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Phase1"),
+            description: String::from_str(&env, "Phase1"),
         },
         Milestone {
             amount: 4000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Phase2"),
+            description: String::from_str(&env, "Phase2"),
         },
     ];
 
@@ -312,11 +313,11 @@ fn test_release_milestone_with_tokens() {
     let escrow = client.get_escrow(&escrow_id);
     assert_eq!(escrow.total_released, 6000);
     assert_eq!(
-        escrow.milestones.get(0).unwrap().status,
+        client.get_milestone(&escrow_id, &0).status,
         MilestoneStatus::Released
     );
     assert_eq!(
-        escrow.milestones.get(1).unwrap().status,
+        client.get_milestone(&escrow_id, &1).status,
         MilestoneStatus::Pending
     );
 
@@ -346,7 +347,7 @@ fn test_dispute_blocks_release() {
         Milestone {
             amount: 500,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Task"),
+            description: String::from_str(&env, "Task"),
         },
     ];
 
@@ -362,7 +363,7 @@ fn test_dispute_blocks_release() {
     token_client.approve(&depositor, &contract_id, &1000, &200);
     client.deposit_funds(&escrow_id);
 
-    client.raise_dispute(&escrow_id, &depositor);
+    client.raise_dispute(&escrow_id, &depositor, &DisputeReason::Other);
 
     let escrow = client.get_escrow(&escrow_id);
     assert_eq!(escrow.status, EscrowStatus::Disputed);
@@ -377,6 +378,7 @@ fn test_complete_escrow_with_all_releases() {
 
     let contract_id = env.register_contract(None, VaultixEscrow);
     let client = VaultixEscrowClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env), &Some(0));
 
     let depositor = Address::generate(&env);
     let recipient = Address::generate(&env);
@@ -392,12 +394,12 @@ fn test_complete_escrow_with_all_releases() {
         Milestone {
             amount: 5000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Task1"),
+            description: String::from_str(&env, "Task1"),
         },
         Milestone {
             amount: 5000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Task2"),
+            description: String::from_str(&env, "Task2"),
         },
     ];
 
@@ -450,7 +452,7 @@ fn test_cancel_escrow_with_refund() {
         Milestone {
             amount: 10000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Work"),
+            description: String::from_str(&env, "Work"),
         },
     ];
 
@@ -501,7 +503,7 @@ fn test_cancel_unfunded_escrow() {
         Milestone {
             amount: 5000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Task"),
+            description: String::from_str(&env, "Task"),
         },
     ];
 
@@ -545,12 +547,12 @@ fn test_admin_resolves_dispute_to_recipient() {
         Milestone {
             amount: 4000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Phase1"),
+            description: String::from_str(&env, "Phase1"),
         },
         Milestone {
             amount: 6000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Phase2"),
+            description: String::from_str(&env, "Phase2"),
         },
     ];
 
@@ -566,16 +568,16 @@ fn test_admin_resolves_dispute_to_recipient() {
     token_client.approve(&depositor, &contract_id, &10000, &200);
     client.deposit_funds(&escrow_id);
 
-    client.raise_dispute(&escrow_id, &recipient);
+    client.raise_dispute(&escrow_id, &recipient, &DisputeReason::Other);
 
-    client.resolve_dispute(&escrow_id, &recipient);
+    client.resolve_dispute(&escrow_id, &recipient, &RulingReason::WorkAccepted, &None);
 
     let escrow = client.get_escrow(&escrow_id);
     assert_eq!(escrow.status, EscrowStatus::Resolved);
     assert_eq!(escrow.resolution, Resolution::Recipient);
     assert_eq!(escrow.total_released, escrow.total_amount);
-    assert!(escrow
-        .milestones
+    assert!(client
+        .get_milestones(&escrow_id)
         .iter()
         .all(|m| m.status == MilestoneStatus::Released));
 
@@ -607,12 +609,12 @@ fn test_admin_resolves_dispute_to_depositor() {
         Milestone {
             amount: 2000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Alpha"),
+            description: String::from_str(&env, "Alpha"),
         },
         Milestone {
             amount: 3000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Beta"),
+            description: String::from_str(&env, "Beta"),
         },
     ];
 
@@ -628,16 +630,31 @@ fn test_admin_resolves_dispute_to_depositor() {
     token_client.approve(&depositor, &contract_id, &5000, &200);
     client.deposit_funds(&escrow_id);
 
-    client.raise_dispute(&escrow_id, &depositor);
+    client.raise_dispute(&escrow_id, &depositor, &DisputeReason::Other);
+    assert_eq!(client.get_ruling(&escrow_id), None);
 
-    client.resolve_dispute(&escrow_id, &depositor);
+    let rationale = BytesN::from_array(&env, &[4u8; 32]);
+    client.resolve_dispute(
+        &escrow_id,
+        &depositor,
+        &RulingReason::NotDelivered,
+        &Some(rationale.clone()),
+    );
+
+    let ruling = client.get_ruling(&escrow_id).unwrap();
+    assert_eq!(
+        (ruling.winner, ruling.reason, ruling.resolver),
+        (depositor.clone(), RulingReason::NotDelivered, admin.clone())
+    );
+    assert_eq!(ruling.ledger, env.ledger().sequence());
+    assert_eq!(client.get_ruling_rationale(&escrow_id), Some(rationale));
 
     let escrow = client.get_escrow(&escrow_id);
     assert_eq!(escrow.status, EscrowStatus::Resolved);
     assert_eq!(escrow.resolution, Resolution::Depositor);
     assert_eq!(escrow.total_released, 0);
-    assert!(escrow
-        .milestones
+    assert!(client
+        .get_milestones(&escrow_id)
         .iter()
         .all(|m| m.status == MilestoneStatus::Disputed));
 
@@ -668,7 +685,7 @@ fn test_duplicate_escrow_id() {
         Milestone {
             amount: 1000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Test"),
+            description: String::from_str(&env, "Test"),
         },
     ];
 
@@ -715,7 +732,7 @@ fn test_double_release() {
         Milestone {
             amount: 1000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Task"),
+            description: String::from_str(&env, "Task"),
         },
     ];
 
@@ -756,11 +773,11 @@ fn test_too_many_milestones() {
     token_admin.mint(&depositor, &10000);
 
     let mut milestones = Vec::new(&env);
-    for _i in 0..21 {
+    for _i in 0..65 {
         milestones.push_back(Milestone {
             amount: 100,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Task"),
+            description: String::from_str(&env, "Task"),
         });
     }
 
@@ -796,7 +813,7 @@ fn test_invalid_milestone_amount() {
         Milestone {
             amount: 0, // Invalid: zero amount
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Task"),
+            description: String::from_str(&env, "Task"),
         },
     ];
 
@@ -833,7 +850,7 @@ fn test_unauthorized_confirm_delivery() {
         Milestone {
             amount: 1000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Task"),
+            description: String::from_str(&env, "Task"),
         },
     ];
 
@@ -860,6 +877,7 @@ fn test_double_confirm_delivery() {
 
     let contract_id = env.register_contract(None, VaultixEscrow);
     let client = VaultixEscrowClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env), &Some(0));
 
     let buyer = Address::generate(&env);
     let seller = Address::generate(&env);
@@ -874,7 +892,7 @@ fn test_double_confirm_delivery() {
         Milestone {
             amount: 1000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Task"),
+            description: String::from_str(&env, "Task"),
         },
     ];
 
@@ -895,6 +913,54 @@ fn test_double_confirm_delivery() {
     client.confirm_delivery(&escrow_id, &0, &buyer);
 }
 
+#[test]
+fn test_confirm_delivery_requires_treasury() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let escrow_id = 10u64;
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&buyer, &1000);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Task"),
+        },
+    ];
+    client.create_escrow(
+        &escrow_id,
+        &buyer,
+        &seller,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    token_client.approve(&buyer, &contract_id, &1000, &200);
+    client.deposit_funds(&escrow_id);
+
+    // Confirmed deliveries pay out through the fee path, which needs a treasury
+    assert_eq!(
+        client.try_confirm_delivery(&escrow_id, &0, &buyer),
+        Err(Ok(Error::TreasuryNotInitialized))
+    );
+    assert_eq!(client.get_escrow(&escrow_id).total_released, 0);
+    assert_eq!(token_client.balance(&seller), 0);
+
+    client.initialize(&Address::generate(&env), &Some(0));
+    client.confirm_delivery(&escrow_id, &0, &buyer);
+    assert_eq!(token_client.balance(&seller), 1000);
+}
+
 #[test]
 fn test_zero_amount_milestone_rejected() {
     let env = Env::default();
@@ -915,7 +981,7 @@ fn test_zero_amount_milestone_rejected() {
         Milestone {
             amount: 0,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Test"),
+            description: String::from_str(&env, "Test"),
         },
     ];
 
@@ -951,7 +1017,7 @@ fn test_negative_amount_milestone_rejected() {
         Milestone {
             amount: -1000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Test"),
+            description: String::from_str(&env, "Test"),
         },
     ];
 
@@ -986,7 +1052,7 @@ fn test_self_dealing_rejected() {
         Milestone {
             amount: 5000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Task"),
+            description: String::from_str(&env, "Task"),
         },
     ];
 
@@ -1022,12 +1088,12 @@ fn test_valid_escrow_creation_succeeds() {
         Milestone {
             amount: 3000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Phase1"),
+            description: String::from_str(&env, "Phase1"),
         },
         Milestone {
             amount: 7000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Phase2"),
+            description: String::from_str(&env, "Phase2"),
         },
     ];
 
@@ -1072,7 +1138,7 @@ fn test_double_deposit_rejected() {
         Milestone {
             amount: 5000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Task"),
+            description: String::from_str(&env, "Task"),
         },
     ];
 
@@ -1113,7 +1179,7 @@ fn test_release_milestone_before_deposit() {
         Milestone {
             amount: 5000,
             status: MilestoneStatus::Pending,
-            description: symbol_short!("Task"),
+            description: String::from_str(&env, "Task"),
         },
     ];
 
@@ -1130,3 +1196,7321 @@ fn test_release_milestone_before_deposit() {
     // This should panic with Error #9 (EscrowNotActive)
     client.release_milestone(&escrow_id, &0);
 }
+
+#[test]
+fn test_insurance_pool_funds_approved_claim() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id = 40u64;
+
+    // 10% fee, half of which is diverted into the insurance pool
+    client.initialize(&treasury, &Some(1000));
+    client.set_insurance_share(&treasury, &5000);
+    client.init(&admin);
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &20_000);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 10_000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase1"),
+        },
+        Milestone {
+            amount: 10_000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase2"),
+        },
+    ];
+
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    token_client.approve(&depositor, &contract_id, &20_000, &200);
+    client.deposit_funds(&escrow_id);
+
+    client.release_milestone(&escrow_id, &0);
+
+    assert_eq!(token_client.balance(&recipient), 9_000);
+    assert_eq!(token_client.balance(&treasury), 500);
+    assert_eq!(client.get_insurance_pool(&token_address), 500);
+
+    // Claims are only accepted once the escrow has gone through a dispute
+    assert_eq!(
+        client.try_file_claim(&depositor, &escrow_id, &300),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+
+    client.raise_dispute(&escrow_id, &depositor, &DisputeReason::Other);
+    client.resolve_dispute(&escrow_id, &depositor, &RulingReason::NotDelivered, &None);
+
+    let claim_id = client.file_claim(&depositor, &escrow_id, &300);
+    let claim = client.get_claim(&claim_id);
+    assert_eq!(claim.status, ClaimStatus::Pending);
+    assert_eq!(claim.amount, 300);
+
+    client.approve_claim(&admin, &claim_id);
+
+    assert_eq!(client.get_claim(&claim_id).status, ClaimStatus::Approved);
+    assert_eq!(client.get_insurance_pool(&token_address), 200);
+    assert_eq!(token_client.balance(&depositor), 10_300);
+    assert_eq!(
+        client.try_approve_claim(&admin, &claim_id),
+        Err(Ok(Error::ClaimAlreadyProcessed))
+    );
+
+    // A claim larger than the remaining pool cannot be paid
+    let large_claim = client.file_claim(&depositor, &escrow_id, &1_000);
+    assert_eq!(
+        client.try_approve_claim(&admin, &large_claim),
+        Err(Ok(Error::InsufficientBalance))
+    );
+    client.reject_claim(&admin, &large_claim);
+    assert_eq!(client.get_claim(&large_claim).status, ClaimStatus::Rejected);
+}
+
+/// Minimal receipt NFT used to observe mint/burn calls from the escrow contract
+#[soroban_sdk::contract]
+pub struct MockReceipt;
+
+#[soroban_sdk::contractimpl]
+impl MockReceipt {
+    pub fn mint(env: Env, to: Address, escrow_id: u64, transferable: bool) {
+        env.storage()
+            .persistent()
+            .set(&escrow_id, &(to, transferable));
+    }
+
+    pub fn burn(env: Env, escrow_id: u64) {
+        env.storage().persistent().remove(&escrow_id);
+    }
+
+    pub fn owner_of(env: Env, escrow_id: u64) -> Option<(Address, bool)> {
+        env.storage().persistent().get(&escrow_id)
+    }
+}
+
+#[test]
+fn test_receipt_minted_on_create_and_burned_on_cancel() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let receipt_id = env.register_contract(None, MockReceipt);
+    let receipt_client = MockReceiptClient::new(&env, &receipt_id);
+
+    let treasury = Address::generate(&env);
+    client.initialize(&treasury, &None);
+    client.set_receipt_contract(&receipt_id, &false);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let escrow_id = 41u64;
+
+    let (_, token_address) = create_test_token(&env, &admin);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 5000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Task"),
+        },
+    ];
+
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+
+    assert_eq!(
+        receipt_client.owner_of(&escrow_id),
+        Some((recipient.clone(), false))
+    );
+
+    client.cancel_escrow(&escrow_id);
+
+    assert_eq!(receipt_client.owner_of(&escrow_id), None);
+}
+
+#[test]
+fn test_assigned_claim_redirects_releases() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let treasury = Address::generate(&env);
+    client.initialize(&treasury, &Some(0));
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let factor = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let escrow_id = 42u64;
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &10_000);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 4000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase1"),
+        },
+        Milestone {
+            amount: 6000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase2"),
+        },
+    ];
+
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+
+    // Assignment is rejected until the depositor opts in
+    assert_eq!(
+        client.try_assign_claim(&escrow_id, &factor),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+
+    client.set_assignable(&escrow_id, &true);
+    token_client.approve(&depositor, &contract_id, &10_000, &200);
+    client.deposit_funds(&escrow_id);
+
+    // Opt-in can no longer change once funded
+    assert_eq!(
+        client.try_set_assignable(&escrow_id, &false),
+        Err(Ok(Error::EscrowAlreadyFunded))
+    );
+
+    client.release_milestone(&escrow_id, &0);
+    assert_eq!(token_client.balance(&recipient), 4000);
+
+    client.assign_claim(&escrow_id, &factor);
+    assert_eq!(client.get_assignee(&escrow_id), Some(factor.clone()));
+
+    client.confirm_delivery(&escrow_id, &1, &depositor);
+    assert_eq!(token_client.balance(&recipient), 4000);
+    assert_eq!(token_client.balance(&factor), 6000);
+}
+
+#[test]
+fn test_reputation_tracks_lifecycle() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env), &Some(0));
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    client.init(&admin);
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &8000);
+    token_client.approve(&depositor, &contract_id, &8000, &200);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 4000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Work"),
+        },
+    ];
+
+    // First escrow completes normally
+    client.create_escrow(
+        &43u64,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    client.deposit_funds(&43u64);
+    client.confirm_delivery(&43u64, &0, &depositor);
+    client.complete_escrow(&43u64);
+
+    // Second escrow is disputed by the recipient and resolved for the depositor
+    client.create_escrow(
+        &44u64,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    client.deposit_funds(&44u64);
+    client.raise_dispute(&44u64, &recipient, &DisputeReason::Other);
+    client.resolve_dispute(&44u64, &depositor, &RulingReason::NotDelivered, &None);
+
+    let depositor_rep = client.get_reputation(&depositor);
+    assert_eq!(depositor_rep.escrows_completed, 1);
+    assert_eq!(depositor_rep.disputes_raised, 0);
+    assert_eq!(depositor_rep.disputes_lost, 0);
+    assert_eq!(depositor_rep.volume, 4000);
+
+    let recipient_rep = client.get_reputation(&recipient);
+    assert_eq!(recipient_rep.escrows_completed, 1);
+    assert_eq!(recipient_rep.disputes_raised, 1);
+    assert_eq!(recipient_rep.disputes_lost, 1);
+    assert_eq!(recipient_rep.volume, 4000);
+
+    assert_eq!(
+        client.get_reputation(&Address::generate(&env)),
+        Reputation::default()
+    );
+}
+
+#[test]
+fn test_post_completion_ratings() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env), &Some(0));
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let escrow_id = 45u64;
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &1000);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Work"),
+        },
+    ];
+
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+
+    let comment = BytesN::from_array(&env, &[7u8; 32]);
+
+    // Ratings are only accepted once the escrow has finished
+    assert_eq!(
+        client.try_submit_rating(&escrow_id, &depositor, &5, &comment),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+
+    token_client.approve(&depositor, &contract_id, &1000, &200);
+    client.deposit_funds(&escrow_id);
+    client.confirm_delivery(&escrow_id, &0, &depositor);
+    client.complete_escrow(&escrow_id);
+
+    assert_eq!(
+        client.try_submit_rating(&escrow_id, &depositor, &6, &comment),
+        Err(Ok(Error::InvalidRating))
+    );
+
+    client.submit_rating(&escrow_id, &depositor, &5, &comment);
+    client.submit_rating(&escrow_id, &recipient, &3, &comment);
+
+    assert_eq!(
+        client.try_submit_rating(&escrow_id, &depositor, &4, &comment),
+        Err(Ok(Error::AlreadyRated))
+    );
+
+    let rating = client.get_rating(&escrow_id, &depositor).unwrap();
+    assert_eq!(rating.ratee, recipient);
+    assert_eq!(rating.score, 5);
+
+    let recipient_summary = client.get_rating_summary(&recipient);
+    assert_eq!(recipient_summary.count, 1);
+    assert_eq!(recipient_summary.total_score, 5);
+
+    let depositor_summary = client.get_rating_summary(&depositor);
+    assert_eq!(depositor_summary.count, 1);
+    assert_eq!(depositor_summary.total_score, 3);
+}
+
+#[test]
+fn test_long_milestone_description() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+
+    let (_, token_address) = create_test_token(&env, &admin);
+
+    let description = String::from_str(
+        &env,
+        "Deliver the landing page redesign with responsive layouts",
+    );
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: description.clone(),
+        },
+    ];
+
+    client.create_escrow(
+        &46u64,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    assert_eq!(client.get_milestone(&46u64, &0).description, description);
+
+    let too_long = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_bytes(&env, &[b'a'; 257]),
+        },
+    ];
+    assert_eq!(
+        client.try_create_escrow(
+            &47u64,
+            &depositor,
+            &recipient,
+            &token_address,
+            &too_long,
+            &1706400000u64,
+        ),
+        Err(Ok(Error::DescriptionTooLong))
+    );
+}
+
+#[test]
+fn test_migrate_legacy_symbol_descriptions() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id = 48u64;
+
+    client.init(&admin);
+
+    let (_, token_address) = create_test_token(&env, &admin);
+
+    // Write an escrow in the previous storage layout
+    let legacy = LegacyEscrow {
+        depositor: depositor.clone(),
+        recipient: recipient.clone(),
+        token_address,
+        total_amount: 1000,
+        total_released: 0,
+        milestones: vec![
+            &env,
+            LegacyMilestone {
+                amount: 1000,
+                status: MilestoneStatus::Pending,
+                description: Symbol::new(&env, "Landing_page_redesign"),
+            },
+        ],
+        status: EscrowStatus::Created,
+        deadline: 1706400000u64,
+        resolution: Resolution::None,
+        assignable: false,
+    };
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("escrow"), escrow_id), &legacy);
+    });
+
+    client.migrate_escrow(&admin, &escrow_id);
+
+    let escrow = client.get_escrow(&escrow_id);
+    assert_eq!(escrow.depositor, depositor);
+    assert_eq!(escrow.total_amount, 1000);
+    assert_eq!(
+        client.get_milestone(&escrow_id, &0).description,
+        String::from_str(&env, "Landing_page_redesign")
+    );
+}
+
+#[test]
+fn test_external_ref_lookup() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+
+    let (_, token_address) = create_test_token(&env, &admin);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Work"),
+        },
+    ];
+
+    for escrow_id in [49u64, 50u64] {
+        client.create_escrow(
+            &escrow_id,
+            &depositor,
+            &recipient,
+            &token_address,
+            &milestones,
+            &1706400000u64,
+        );
+    }
+
+    let order = String::from_str(&env, "ORDER-2024-0001");
+    client.set_external_ref(&49u64, &order);
+
+    assert_eq!(client.get_external_ref(&49u64), Some(order.clone()));
+    let (found_id, found) = client.get_escrow_by_ref(&order);
+    assert_eq!(found_id, 49u64);
+    assert_eq!(found.depositor, depositor);
+    assert_eq!(client.find_by_ref(&order), Some(49u64));
+
+    // References are unique across escrows
+    assert_eq!(
+        client.try_set_external_ref(&50u64, &order),
+        Err(Ok(Error::EscrowAlreadyExists))
+    );
+
+    // Replacing a reference frees the old one
+    let invoice = String::from_str(&env, "INV-77");
+    client.set_external_ref(&49u64, &invoice);
+    assert!(matches!(
+        client.try_get_escrow_by_ref(&order),
+        Err(Ok(Error::EscrowNotFound))
+    ));
+    assert_eq!(client.find_by_ref(&order), None);
+    client.set_external_ref(&50u64, &order);
+    assert_eq!(client.get_escrow_by_ref(&order).0, 50u64);
+}
+
+#[test]
+fn test_create_from_template() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let organization = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    client.init(&admin);
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &1001);
+
+    // Shares must add up to 100%
+    let unbalanced = vec![
+        &env,
+        TemplateMilestone {
+            description: String::from_str(&env, "Design"),
+            bps: 5000,
+        },
+    ];
+    assert_eq!(
+        client.try_register_template(
+            &organization,
+            &token_address,
+            &unbalanced,
+            &86_400,
+            &0,
+            &None
+        ),
+        Err(Ok(Error::TotalAmountMismatch))
+    );
+
+    let slots = vec![
+        &env,
+        TemplateMilestone {
+            description: String::from_str(&env, "Design"),
+            bps: 3000,
+        },
+        TemplateMilestone {
+            description: String::from_str(&env, "Build"),
+            bps: 7000,
+        },
+    ];
+    let template_id = client.register_template(
+        &organization,
+        &token_address,
+        &slots,
+        &86_400,
+        &3_600,
+        &Some(arbiter.clone()),
+    );
+    assert_eq!(client.get_template(&template_id).owner, organization);
+
+    let escrow_id = client.create_from_template(&template_id, &depositor, &recipient, &1001);
+    let escrow = client.get_escrow(&escrow_id);
+    assert_eq!(escrow.total_amount, 1001);
+    assert_eq!(client.get_milestone(&escrow_id, &0).amount, 300);
+    // Rounding remainder goes to the last milestone
+    assert_eq!(client.get_milestone(&escrow_id, &1).amount, 701);
+    assert_eq!(
+        client.get_milestone(&escrow_id, &1).description,
+        String::from_str(&env, "Build")
+    );
+    assert_eq!(escrow.deadline, env.ledger().timestamp() + 86_400);
+    assert_eq!(client.get_arbiter(&escrow_id), Some(arbiter.clone()));
+    assert_eq!(client.get_review_window(&escrow_id), 3_600);
+    // Escrows not created from a template use the configured window
+    assert_eq!(
+        client.get_review_window(&99),
+        client.get_config().review_window
+    );
+
+    // The template's arbiter resolves disputes on escrows created from it
+    token_client.approve(&depositor, &contract_id, &1001, &200);
+    client.deposit_funds(&escrow_id);
+    client.raise_dispute(&escrow_id, &recipient, &DisputeReason::Other);
+    client.resolve_dispute(&escrow_id, &recipient, &RulingReason::WorkAccepted, &None);
+    assert_eq!(env.auths().last().unwrap().0, arbiter);
+    assert_eq!(token_client.balance(&recipient), 1001);
+
+    assert_eq!(
+        client.try_create_from_template(&99u64, &depositor, &recipient, &1000),
+        Err(Ok(Error::TemplateNotFound))
+    );
+}
+
+#[test]
+fn test_clone_escrow_resets_progress() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env), &Some(0));
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let new_recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let source_id = 51u64;
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &5000);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 2000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Sprint 1"),
+        },
+        Milestone {
+            amount: 3000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Sprint 2"),
+        },
+    ];
+
+    client.create_escrow(
+        &source_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    token_client.approve(&depositor, &contract_id, &5000, &200);
+    client.deposit_funds(&source_id);
+    client.confirm_delivery(&source_id, &0, &depositor);
+
+    let clone_id = client.clone_escrow(&source_id, &new_recipient, &1806400000u64);
+    assert_ne!(clone_id, source_id);
+
+    let clone = client.get_escrow(&clone_id);
+    assert_eq!(clone.depositor, depositor);
+    assert_eq!(clone.recipient, new_recipient);
+    assert_eq!(clone.token_address, token_address);
+    assert_eq!(clone.status, EscrowStatus::Created);
+    assert_eq!(clone.total_amount, 5000);
+    assert_eq!(clone.total_released, 0);
+    assert_eq!(clone.deadline, 1806400000u64);
+    assert!(client
+        .get_milestones(&clone_id)
+        .iter()
+        .all(|m| m.status == MilestoneStatus::Pending));
+    assert_eq!(
+        client.get_milestone(&clone_id, &1).description,
+        String::from_str(&env, "Sprint 2")
+    );
+}
+
+#[test]
+fn test_recurring_escrow_with_notice_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let treasury = Address::generate(&env);
+    client.initialize(&treasury, &Some(0));
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let escrow_id = 52u64;
+    let month = 2_592_000u64;
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &3000);
+
+    let schedule = RecurringSchedule {
+        amount: 1000,
+        interval: month,
+        count: 3,
+        notice_period: month,
+    };
+    client.create_recurring_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &schedule,
+    );
+    assert_eq!(client.get_escrow(&escrow_id).total_amount, 3000);
+
+    token_client.approve(&depositor, &contract_id, &3000, &200);
+    client.deposit_funds(&escrow_id);
+
+    // Nothing is claimable before the first period elapses
+    assert_eq!(
+        client.try_claim_recurring(&escrow_id),
+        Err(Ok(Error::MilestoneNotFound))
+    );
+
+    // Plain cancellation would skip the notice period
+    assert_eq!(
+        client.try_cancel_escrow(&escrow_id),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp += month);
+    assert_eq!(client.claim_recurring(&escrow_id), 1000);
+    assert_eq!(token_client.balance(&recipient), 1000);
+
+    // Cancelling mid-way through period two keeps periods due within the notice window
+    env.ledger().with_mut(|li| li.timestamp += month / 2);
+    assert_eq!(client.cancel_recurring(&escrow_id), 1000);
+    assert_eq!(token_client.balance(&depositor), 1000);
+    assert_eq!(client.get_recurring_schedule(&escrow_id).unwrap().count, 2);
+
+    env.ledger().with_mut(|li| li.timestamp += month);
+    assert_eq!(client.claim_recurring(&escrow_id), 1000);
+
+    let escrow = client.get_escrow(&escrow_id);
+    assert_eq!(escrow.status, EscrowStatus::Completed);
+    assert_eq!(escrow.milestone_count, 2);
+    assert_eq!(token_client.balance(&recipient), 2000);
+    assert_eq!(token_client.balance(&contract_id), 0);
+}
+
+#[test]
+fn test_streaming_milestone_freezes_on_dispute() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let treasury = Address::generate(&env);
+    client.initialize(&treasury, &Some(0));
+
+    let admin = Address::generate(&env);
+    client.init(&admin);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id = 53u64;
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &1000);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Retainer"),
+        },
+    ];
+
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+
+    env.ledger().with_mut(|li| li.sequence_number = 100);
+    client.set_milestone_stream(&escrow_id, &0, &100, &200);
+
+    token_client.approve(&depositor, &contract_id, &1000, &200);
+    client.deposit_funds(&escrow_id);
+
+    env.ledger().with_mut(|li| li.sequence_number = 125);
+    assert_eq!(client.claim_stream(&escrow_id, &0), 250);
+    assert_eq!(
+        client.try_claim_stream(&escrow_id, &0),
+        Err(Ok(Error::ZeroAmount))
+    );
+
+    // Dispute at ledger 150 freezes vesting there
+    env.ledger().with_mut(|li| li.sequence_number = 150);
+    client.raise_dispute(&escrow_id, &depositor, &DisputeReason::Other);
+
+    env.ledger().with_mut(|li| li.sequence_number = 190);
+    assert_eq!(client.claim_stream(&escrow_id, &0), 250);
+    assert_eq!(token_client.balance(&recipient), 500);
+
+    let escrow = client.get_escrow(&escrow_id);
+    assert_eq!(escrow.total_released, 500);
+    assert_eq!(
+        client.get_milestone_stream(&escrow_id, &0).unwrap().claimed,
+        500
+    );
+
+    // Resolution only moves the unstreamed remainder
+    client.resolve_dispute(&escrow_id, &depositor, &RulingReason::NotDelivered, &None);
+    assert_eq!(token_client.balance(&depositor), 500);
+    assert_eq!(token_client.balance(&contract_id), 0);
+}
+
+#[test]
+fn test_vesting_escrow_cliff_claims_and_clawback() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let treasury = Address::generate(&env);
+    client.initialize(&treasury, &Some(0));
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let escrow_id = 54u64;
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &12_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let schedule = VestingSchedule {
+        start: 1_000,
+        cliff: 300,
+        duration: 1_200,
+    };
+    client.create_vesting_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &12_000,
+        &schedule,
+    );
+    token_client.approve(&depositor, &contract_id, &12_000, &200);
+    client.deposit_funds(&escrow_id);
+
+    // No manual releases on a vesting escrow
+    assert_eq!(
+        client.try_release_milestone(&escrow_id, &0),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+
+    // Before the cliff nothing is claimable
+    env.ledger().with_mut(|li| li.timestamp = 1_200);
+    assert_eq!(
+        client.try_claim_vested(&escrow_id),
+        Err(Ok(Error::ZeroAmount))
+    );
+
+    // Past the cliff, vesting is linear from the start
+    env.ledger().with_mut(|li| li.timestamp = 1_300);
+    assert_eq!(client.claim_vested(&escrow_id), 3_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_600);
+    assert_eq!(client.clawback_vesting(&escrow_id), 6_000);
+
+    assert_eq!(token_client.balance(&recipient), 6_000);
+    assert_eq!(token_client.balance(&depositor), 6_000);
+    assert_eq!(token_client.balance(&contract_id), 0);
+    assert_eq!(
+        client.get_escrow(&escrow_id).status,
+        EscrowStatus::Cancelled
+    );
+}
+
+#[test]
+fn test_extend_escrow_ttl_keeps_entries_live() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let (_, _, token_address) = create_token_contract(&env, &admin);
+
+    let escrow_id = 55u64;
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase 1"),
+        },
+    ];
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+
+    // Also bumps the contract instance, which starts with only the minimum TTL
+    client.extend_escrow_ttl(&escrow_id);
+
+    // Past the bump threshold but before the entries would be archived
+    env.ledger().with_mut(|li| li.sequence_number += 1_600_000);
+    client.extend_escrow_ttl(&escrow_id);
+
+    // Without the bump the escrow entries would have expired by now
+    env.ledger().with_mut(|li| li.sequence_number += 1_000_000);
+    assert_eq!(client.get_escrow(&escrow_id).milestone_count, 1);
+    assert_eq!(client.get_milestone(&escrow_id, &0).amount, 1000);
+
+    assert_eq!(
+        client.try_extend_escrow_ttl(&999),
+        Err(Ok(Error::EscrowNotFound))
+    );
+}
+
+#[test]
+fn test_archive_finished_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let (_, _, token_address) = create_token_contract(&env, &admin);
+
+    let escrow_id = 56u64;
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase 1"),
+        },
+    ];
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+
+    // Only terminal escrows can be archived
+    assert_eq!(
+        client.try_archive_escrow(&escrow_id),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+
+    client.cancel_escrow(&escrow_id);
+    client.archive_escrow(&escrow_id);
+
+    // Still readable from temporary storage
+    let escrow = client.get_escrow(&escrow_id);
+    assert_eq!(escrow.status, EscrowStatus::Cancelled);
+    assert_eq!(client.get_milestones(&escrow_id).len(), 1);
+    assert_eq!(client.get_milestone(&escrow_id, &0).amount, 1000);
+
+    // The id stays reserved and the archived copy is not kept alive
+    assert_eq!(
+        client.try_create_escrow(
+            &escrow_id,
+            &depositor,
+            &recipient,
+            &token_address,
+            &milestones,
+            &1706400000u64,
+        ),
+        Err(Ok(Error::EscrowAlreadyExists))
+    );
+    assert_eq!(
+        client.try_extend_escrow_ttl(&escrow_id),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+    assert_eq!(
+        client.try_archive_escrow(&escrow_id),
+        Err(Ok(Error::EscrowNotFound))
+    );
+}
+
+#[test]
+fn test_close_escrow_removes_entries() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let (_, _, token_address) = create_token_contract(&env, &admin);
+
+    let escrow_id = 57u64;
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase 1"),
+        },
+    ];
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    let external_ref = String::from_str(&env, "INV-57");
+    client.set_external_ref(&escrow_id, &external_ref);
+
+    assert_eq!(
+        client.try_close_escrow(&escrow_id),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+
+    client.cancel_escrow(&escrow_id);
+    // Entries a dispute can leave behind go too
+    env.as_contract(&contract_id, || {
+        let persistent = env.storage().persistent();
+        persistent.set(
+            &crate::storage::settlement_key(escrow_id),
+            &(recipient.clone(), 0i128),
+        );
+        persistent.set(
+            &crate::storage::assignment_key(escrow_id),
+            &(admin.clone(), 0u64),
+        );
+    });
+    client.close_escrow(&escrow_id);
+
+    assert!(matches!(
+        client.try_get_escrow(&escrow_id),
+        Err(Ok(Error::EscrowNotFound))
+    ));
+    assert_eq!(client.get_external_ref(&escrow_id), None);
+    assert!(client.try_get_escrow_by_ref(&external_ref).is_err());
+    env.as_contract(&contract_id, || {
+        for key in crate::storage::escrow_keys(escrow_id).iter() {
+            assert!(!env.storage().persistent().has(key));
+        }
+    });
+
+    // The id can be reused once closed
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    assert_eq!(client.get_escrow(&escrow_id).status, EscrowStatus::Created);
+}
+
+#[test]
+fn test_legacy_persistent_admin_is_migrated() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    // Instances deployed before the move kept the admin in persistent storage
+    let admin = Address::generate(&env);
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&symbol_short!("admin"), &admin);
+    });
+
+    assert_eq!(client.get_admin(), admin);
+    // No treasury yet, so the deployment is only half configured
+    assert!(!client.is_initialized());
+    assert_eq!(
+        client.try_init(&Address::generate(&env)),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+
+    env.as_contract(&contract_id, || {
+        assert!(!env.storage().persistent().has(&symbol_short!("admin")));
+        assert_eq!(
+            env.storage()
+                .instance()
+                .get::<_, Address>(&crate::storage::DataKey::admin),
+            Some(admin.clone())
+        );
+    });
+}
+
+#[test]
+fn test_setup_configures_admin_and_treasury_once() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    assert!(!client.is_initialized());
+    assert_eq!(client.try_get_admin(), Err(Ok(Error::AdminNotInitialized)));
+    client.setup(&admin, &treasury, &Some(100));
+    assert!(client.is_initialized());
+    assert_eq!(client.get_schema_version(), client.version().schema);
+
+    assert_eq!(client.get_admin(), admin);
+    assert_eq!(client.get_treasury(), treasury);
+    assert_eq!(client.get_config().fee_bps, 100);
+
+    assert_eq!(
+        client.try_setup(&Address::generate(&env), &treasury, &None),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+    assert_eq!(
+        client.try_init(&Address::generate(&env)),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+
+    // The deprecated treasury shim can't take over a configured deployment
+    let logged = client.get_admin_actions_len();
+    assert_eq!(
+        client.try_initialize(&Address::generate(&env), &Some(10_000)),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+    assert_eq!(client.get_treasury(), treasury);
+    assert_eq!(client.get_config().fee_bps, 100);
+
+    // Nor is anything logged for a rejected fee on a fresh one
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let fresh = VaultixEscrowClient::new(&env, &contract_id);
+    assert_eq!(
+        fresh.try_initialize(&treasury, &Some(10_001)),
+        Err(Ok(Error::InvalidFeeConfiguration))
+    );
+    assert_eq!(fresh.get_admin_actions_len(), 0);
+    assert_eq!(client.get_admin_actions_len(), logged);
+}
+
+#[test]
+fn test_config_limits_and_dispute_bond() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &5000);
+    token_admin.mint(&recipient, &100);
+
+    client.init(&admin);
+
+    let mut config = client.get_config();
+    assert_eq!(config.max_milestones, 64);
+    config.max_milestones = 65;
+    assert_eq!(
+        client.try_set_config(&admin, &config),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+
+    config.max_milestones = 2;
+    config.min_milestone_amount = 100;
+    config.dispute_bond = 40;
+    client.set_config(&admin, &config);
+    assert_eq!(client.get_config(), config);
+
+    let milestone = |amount: i128| Milestone {
+        amount,
+        status: MilestoneStatus::Pending,
+        description: String::from_str(&env, "Work"),
+    };
+    let escrow_id = 58u64;
+    assert_eq!(
+        client.try_create_escrow(
+            &escrow_id,
+            &depositor,
+            &recipient,
+            &token_address,
+            &vec![&env, milestone(1000), milestone(1000), milestone(1000)],
+            &1706400000u64,
+        ),
+        Err(Ok(Error::VectorTooLarge))
+    );
+    assert_eq!(
+        client.try_create_escrow(
+            &escrow_id,
+            &depositor,
+            &recipient,
+            &token_address,
+            &vec![&env, milestone(99)],
+            &1706400000u64,
+        ),
+        Err(Ok(Error::InvalidMilestoneAmount))
+    );
+
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &vec![&env, milestone(2000), milestone(3000)],
+        &1706400000u64,
+    );
+    token_client.approve(&depositor, &contract_id, &5000, &200);
+    client.deposit_funds(&escrow_id);
+
+    // The recipient posts the bond and loses it to the depositor
+    client.raise_dispute(&escrow_id, &recipient, &DisputeReason::Other);
+    assert_eq!(token_client.balance(&recipient), 60);
+    client.resolve_dispute(&escrow_id, &depositor, &RulingReason::NotDelivered, &None);
+
+    assert_eq!(token_client.balance(&depositor), 5040);
+    assert_eq!(token_client.balance(&contract_id), 0);
+}
+
+#[test]
+fn test_escrow_amount_limits_per_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let (_, _, token_address) = create_token_contract(&env, &admin);
+    let (_, _, other_token) = create_token_contract(&env, &admin);
+
+    client.init(&admin);
+    let limits = AmountLimits {
+        min: 500,
+        max: 10_000,
+    };
+    client.set_amount_limits(&admin, &token_address, &limits);
+    assert_eq!(client.get_amount_limits(&token_address), Some(limits));
+    assert_eq!(client.get_amount_limits(&other_token), None);
+
+    let milestones = |amount: i128| {
+        vec![
+            &env,
+            Milestone {
+                amount,
+                status: MilestoneStatus::Pending,
+                description: String::from_str(&env, "Work"),
+            },
+        ]
+    };
+
+    for amount in [499, 10_001] {
+        assert_eq!(
+            client.try_create_escrow(
+                &59,
+                &depositor,
+                &recipient,
+                &token_address,
+                &milestones(amount),
+                &1706400000u64,
+            ),
+            Err(Ok(Error::AmountOutOfRange))
+        );
+    }
+
+    client.create_escrow(
+        &59,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones(10_000),
+        &1706400000u64,
+    );
+    // Tokens without limits accept any total
+    client.create_escrow(
+        &60,
+        &depositor,
+        &recipient,
+        &other_token,
+        &milestones(1),
+        &1706400000u64,
+    );
+
+    assert_eq!(
+        client.try_set_amount_limits(&admin, &token_address, &AmountLimits { min: 10, max: 5 }),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+}
+
+#[test]
+fn test_creation_quota_per_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let (_, _, token_address) = create_token_contract(&env, &admin);
+
+    client.init(&admin);
+    assert_eq!(client.get_remaining_quota(&depositor), u32::MAX);
+
+    let mut config = client.get_config();
+    config.creation_quota = 2;
+    config.quota_window = 3600;
+    client.set_config(&admin, &config);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Work"),
+        },
+    ];
+    let create = |escrow_id: u64| {
+        client.try_create_escrow(
+            &escrow_id,
+            &depositor,
+            &recipient,
+            &token_address,
+            &milestones,
+            &1706400000u64,
+        )
+    };
+
+    assert_eq!(client.get_remaining_quota(&depositor), 2);
+    assert!(create(61).is_ok());
+    assert!(create(62).is_ok());
+    assert_eq!(client.get_remaining_quota(&depositor), 0);
+    assert_eq!(create(63), Err(Ok(Error::RateLimited)));
+
+    // A new window restores the quota
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+    assert_eq!(client.get_remaining_quota(&depositor), 2);
+    assert!(create(63).is_ok());
+}
+
+#[test]
+fn test_blocked_address_cannot_participate() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &2000);
+
+    client.init(&admin);
+    client.initialize(&treasury, &Some(0));
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Work"),
+        },
+    ];
+
+    client.create_escrow(
+        &64,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    token_client.approve(&depositor, &contract_id, &1000, &200);
+    client.deposit_funds(&64);
+
+    client.block_address(&admin, &recipient);
+    assert!(client.is_blocked(&recipient));
+
+    assert_eq!(
+        client.try_create_escrow(
+            &65,
+            &depositor,
+            &recipient,
+            &token_address,
+            &milestones,
+            &1706400000u64,
+        ),
+        Err(Ok(Error::AddressBlocked))
+    );
+    assert_eq!(
+        client.try_release_milestone(&64, &0),
+        Err(Ok(Error::AddressBlocked))
+    );
+
+    client.unblock_address(&admin, &recipient);
+    assert!(!client.is_blocked(&recipient));
+    client.release_milestone(&64, &0);
+    assert_eq!(token_client.balance(&recipient), 1000);
+}
+
+/// Minimal KYC attestor vouching for addresses it has been told about
+#[soroban_sdk::contract]
+pub struct MockAttestor;
+
+#[soroban_sdk::contractimpl]
+impl MockAttestor {
+    pub fn verify(env: Env, address: Address) {
+        env.storage().persistent().set(&address, &true);
+    }
+
+    pub fn is_verified(env: Env, address: Address) -> bool {
+        env.storage().persistent().has(&address)
+    }
+}
+
+#[test]
+fn test_allowlist_only_mode() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+    let attestor_id = env.register_contract(None, MockAttestor);
+    let attestor = MockAttestorClient::new(&env, &attestor_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let (_, _, token_address) = create_token_contract(&env, &admin);
+
+    client.init(&admin);
+    let mut config = client.get_config();
+    config.allowlist_only = true;
+    client.set_config(&admin, &config);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Work"),
+        },
+    ];
+    let create = |escrow_id: u64| {
+        client.try_create_escrow(
+            &escrow_id,
+            &depositor,
+            &recipient,
+            &token_address,
+            &milestones,
+            &1706400000u64,
+        )
+    };
+
+    assert_eq!(create(66), Err(Ok(Error::NotAllowlisted)));
+
+    // Depositor allowlisted on-chain, recipient verified by the attestor
+    client.allow_address(&admin, &depositor);
+    client.set_kyc_attestor(&admin, &Some(attestor_id.clone()));
+    assert!(client.is_allowed(&depositor));
+    assert!(!client.is_allowed(&recipient));
+    assert_eq!(create(66), Err(Ok(Error::NotAllowlisted)));
+
+    attestor.verify(&recipient);
+    assert!(client.is_allowed(&recipient));
+    assert!(create(66).is_ok());
+
+    client.disallow_address(&admin, &depositor);
+    assert_eq!(create(67), Err(Ok(Error::NotAllowlisted)));
+}
+
+#[test]
+fn test_salted_escrow_ids_cannot_be_squatted() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let griefer = Address::generate(&env);
+    let (_, _, token_address) = create_token_contract(&env, &admin);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Work"),
+        },
+    ];
+    let salt = BytesN::from_array(&env, &[7u8; 32]);
+    let expected_id = client.get_salted_escrow_id(&depositor, &recipient, &salt);
+
+    // The derived id cannot be claimed through create_escrow
+    assert_eq!(
+        client.try_create_escrow(
+            &expected_id,
+            &griefer,
+            &recipient,
+            &token_address,
+            &milestones,
+            &1706400000u64,
+        ),
+        Err(Ok(Error::ReservedEscrowId))
+    );
+
+    let escrow_id = client.create_escrow_salted(
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+        &salt,
+    );
+    assert_eq!(escrow_id, expected_id);
+    assert_eq!(client.get_escrow(&escrow_id).depositor, depositor);
+
+    // Another depositor using the same salt gets a different id
+    let other_id = client.create_escrow_salted(
+        &griefer,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+        &salt,
+    );
+    assert_ne!(other_id, escrow_id);
+}
+
+#[test]
+fn test_escrows_namespaced_by_depositor_nonce() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let (_, _, token_address) = create_token_contract(&env, &admin);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Work"),
+        },
+    ];
+    let create = |depositor: &Address| {
+        client.create_escrow_with_nonce(
+            depositor,
+            &recipient,
+            &token_address,
+            &milestones,
+            &1706400000u64,
+        )
+    };
+
+    assert_eq!(client.get_next_nonce(&alice), 0);
+    let alice_0 = create(&alice);
+    let bob_0 = create(&bob);
+    let alice_1 = create(&alice);
+
+    assert_eq!(client.get_next_nonce(&alice), 2);
+    assert_eq!(client.get_next_nonce(&bob), 1);
+    assert_eq!(client.get_escrow_id_by_nonce(&alice, &0), Some(alice_0));
+    assert_eq!(client.get_escrow_id_by_nonce(&alice, &1), Some(alice_1));
+    assert_eq!(client.get_escrow_id_by_nonce(&bob, &0), Some(bob_0));
+    assert_eq!(client.get_escrow(&bob_0).depositor, bob);
+
+    // Closing the escrow drops its nonce index entry
+    client.cancel_escrow(&alice_0);
+    client.close_escrow(&alice_0);
+    assert_eq!(client.get_escrow_id_by_nonce(&alice, &0), None);
+    assert_eq!(client.get_next_nonce(&alice), 2);
+}
+
+#[test]
+fn test_version_and_schema_migration() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let version = client.version();
+    assert_eq!(version.version, String::from_str(&env, "0.1.0"));
+    assert_eq!(version.schema, 4);
+
+    // An instance configured by the previous release: persistent admin, loose fee key
+    let admin = Address::generate(&env);
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .set(&symbol_short!("admin"), &admin);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("fee_bps"), &75i128);
+    });
+    assert_eq!(client.get_schema_version(), 1);
+
+    assert_eq!(client.migrate(&admin), 4);
+    assert_eq!(client.get_schema_version(), 4);
+    assert_eq!(client.get_config().fee_bps, 75);
+    // Migrating again is a no-op
+    assert_eq!(client.migrate(&admin), 4);
+
+    // Storage written by a newer schema is refused
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .instance()
+            .set(&crate::storage::DataKey::schema, &5u32);
+    });
+    assert_eq!(
+        client.try_migrate(&admin),
+        Err(Ok(Error::IncompatibleSchema))
+    );
+}
+
+#[test]
+fn test_state_machine_transition_table() {
+    use EscrowStatus::*;
+
+    let statuses = [Created, Active, Completed, Cancelled, Disputed, Resolved];
+    let allowed = [
+        (Created, Active),
+        (Created, Cancelled),
+        (Created, Disputed),
+        (Active, Completed),
+        (Active, Cancelled),
+        (Active, Disputed),
+        (Disputed, Active),
+        (Disputed, Resolved),
+    ];
+
+    for from in statuses {
+        for to in statuses {
+            assert_eq!(
+                state_machine::can_transition(from, to),
+                allowed.contains(&(from, to)),
+                "{:?} -> {:?}",
+                from,
+                to
+            );
+        }
+        // Terminal statuses have no way out
+        if state_machine::is_terminal(from) {
+            assert!(statuses
+                .iter()
+                .all(|to| !state_machine::can_transition(from, *to)));
+        }
+    }
+
+    assert_eq!(
+        statuses
+            .iter()
+            .filter(|s| state_machine::is_terminal(**s))
+            .count(),
+        3
+    );
+}
+
+#[test]
+fn test_state_machine_transition_errors() {
+    let env = Env::default();
+    let mut escrow = Escrow {
+        depositor: Address::generate(&env),
+        recipient: Address::generate(&env),
+        token_address: Address::generate(&env),
+        total_amount: 1000,
+        total_released: 0,
+        milestone_count: 1,
+        released_mask: 0,
+        disputed_mask: 0,
+        status: EscrowStatus::Created,
+        deadline: 0,
+        resolution: Resolution::None,
+        assignable: false,
+    };
+
+    state_machine::transition(&mut escrow, EscrowStatus::Active).unwrap();
+    assert_eq!(escrow.status, EscrowStatus::Active);
+    assert_eq!(
+        state_machine::transition(&mut escrow, EscrowStatus::Active),
+        Err(Error::EscrowAlreadyFunded)
+    );
+
+    state_machine::transition(&mut escrow, EscrowStatus::Disputed).unwrap();
+    assert_eq!(
+        state_machine::transition(&mut escrow, EscrowStatus::Disputed),
+        Err(Error::AlreadyInDispute)
+    );
+    assert_eq!(
+        state_machine::ensure_active(escrow.status),
+        Err(Error::EscrowNotActive)
+    );
+    assert_eq!(
+        state_machine::transition(&mut escrow, EscrowStatus::Completed),
+        Err(Error::InvalidEscrowStatus)
+    );
+    // A rejected transition leaves the status untouched
+    assert_eq!(escrow.status, EscrowStatus::Disputed);
+}
+
+#[test]
+fn test_history_records_actor_and_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id = 70u64;
+
+    client.init(&admin);
+    client.initialize(&Address::generate(&env), &Some(0));
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &10_000);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 6000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase1"),
+        },
+        Milestone {
+            amount: 4000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase2"),
+        },
+    ];
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    token_client.approve(&depositor, &contract_id, &10_000, &200);
+    client.deposit_funds(&escrow_id);
+    client.release_milestone(&escrow_id, &0);
+    client.raise_dispute(&escrow_id, &recipient, &DisputeReason::Other);
+    client.resolve_dispute(&escrow_id, &recipient, &RulingReason::WorkAccepted, &None);
+
+    let history = client.get_history(&escrow_id, &0);
+    let expected = [
+        (symbol_short!("create"), &depositor, 10_000),
+        (symbol_short!("deposit"), &depositor, 10_000),
+        (symbol_short!("released"), &depositor, 6000),
+        (symbol_short!("disputed"), &recipient, 0),
+        (symbol_short!("resolved"), &admin, 4000),
+    ];
+    assert_eq!(client.get_history_len(&escrow_id), expected.len() as u32);
+    assert_eq!(history.len(), expected.len() as u32);
+    for (entry, (action, actor, amount)) in history.iter().zip(expected.iter()) {
+        assert_eq!(entry.action, *action);
+        assert_eq!(entry.actor, **actor);
+        assert_eq!(entry.amount, *amount);
+        assert_eq!(entry.ledger, env.ledger().sequence());
+    }
+    assert!(client.get_history(&escrow_id, &1).is_empty());
+}
+
+#[test]
+fn test_history_prunes_oldest_pages() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+    let actor = Address::generate(&env);
+
+    let total = history::HISTORY_PAGE_SIZE * history::MAX_HISTORY_PAGES + 5;
+    env.as_contract(&contract_id, || {
+        for amount in 0..total {
+            history::record(&env, 71, symbol_short!("claim"), &actor, amount as i128);
+        }
+    });
+
+    assert_eq!(client.get_history_len(&71), total);
+    // The first page was dropped when the eleventh one started
+    assert!(client.get_history(&71, &0).is_empty());
+    assert_eq!(
+        client.get_history(&71, &1).get(0).unwrap().amount,
+        history::HISTORY_PAGE_SIZE as i128
+    );
+    let last = client.get_history(&71, &history::MAX_HISTORY_PAGES);
+    assert_eq!(last.len(), 5);
+    assert_eq!(last.get(4).unwrap().amount, (total - 1) as i128);
+}
+
+#[test]
+fn test_action_predicates_report_blocking_error() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let escrow_id = 72u64;
+
+    client.initialize(&Address::generate(&env), &Some(0));
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &1000);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Work"),
+        },
+    ];
+
+    assert_eq!(
+        client.can_release(&escrow_id, &0, &depositor),
+        Some(Error::EscrowNotFound as u32)
+    );
+
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    assert_eq!(
+        client.can_release(&escrow_id, &0, &depositor),
+        Some(Error::EscrowNotActive as u32)
+    );
+    assert_eq!(client.can_cancel(&escrow_id, &depositor), None);
+    assert_eq!(
+        client.can_cancel(&escrow_id, &recipient),
+        Some(Error::UnauthorizedAccess as u32)
+    );
+
+    token_client.approve(&depositor, &contract_id, &1000, &200);
+    client.deposit_funds(&escrow_id);
+    assert_eq!(client.can_release(&escrow_id, &0, &depositor), None);
+    assert_eq!(
+        client.can_release(&escrow_id, &1, &depositor),
+        Some(Error::MilestoneNotFound as u32)
+    );
+    assert_eq!(client.can_dispute(&escrow_id, &recipient), None);
+    assert_eq!(
+        client.can_dispute(&escrow_id, &stranger),
+        Some(Error::UnauthorizedAccess as u32)
+    );
+
+    client.release_milestone(&escrow_id, &0);
+    assert_eq!(
+        client.can_release(&escrow_id, &0, &depositor),
+        Some(Error::MilestoneAlreadyReleased as u32)
+    );
+    assert_eq!(
+        client.can_cancel(&escrow_id, &depositor),
+        Some(Error::MilestoneAlreadyReleased as u32)
+    );
+
+    client.raise_dispute(&escrow_id, &recipient, &DisputeReason::Other);
+    assert_eq!(
+        client.can_dispute(&escrow_id, &depositor),
+        Some(Error::AlreadyInDispute as u32)
+    );
+    assert_eq!(
+        client.can_cancel(&escrow_id, &depositor),
+        Some(Error::InvalidEscrowStatus as u32)
+    );
+}
+
+#[test]
+fn test_remaining_amount_and_pending_milestones() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id = 73u64;
+
+    client.initialize(&Address::generate(&env), &Some(0));
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &6000);
+
+    let mut milestones = Vec::new(&env);
+    for amount in [1000i128, 2000, 3000] {
+        milestones.push_back(Milestone {
+            amount,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase"),
+        });
+    }
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    assert_eq!(client.get_remaining_amount(&escrow_id), 0);
+    assert_eq!(
+        client.get_pending_milestones(&escrow_id),
+        vec![&env, 0, 1, 2]
+    );
+
+    token_client.approve(&depositor, &contract_id, &6000, &200);
+    client.deposit_funds(&escrow_id);
+    client.release_milestone(&escrow_id, &1);
+    assert_eq!(client.get_remaining_amount(&escrow_id), 4000);
+    assert_eq!(client.get_pending_milestones(&escrow_id), vec![&env, 0, 2]);
+
+    client.raise_dispute(&escrow_id, &depositor, &DisputeReason::Other);
+    assert_eq!(client.get_remaining_amount(&escrow_id), 4000);
+    assert!(client.get_pending_milestones(&escrow_id).is_empty());
+}
+
+#[test]
+fn test_global_stats_track_lifecycle() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    client.init(&admin);
+    client.initialize(&Address::generate(&env), &Some(0));
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &5000);
+    token_client.approve(&depositor, &contract_id, &5000, &200);
+
+    assert_eq!(client.get_stats(), Stats::default());
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase1"),
+        },
+        Milestone {
+            amount: 1500,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase2"),
+        },
+    ];
+    for escrow_id in [74u64, 75, 76] {
+        client.create_escrow(
+            &escrow_id,
+            &depositor,
+            &recipient,
+            &token_address,
+            &milestones,
+            &1706400000u64,
+        );
+    }
+    client.deposit_funds(&74);
+    client.deposit_funds(&75);
+    client.cancel_escrow(&76);
+
+    client.release_milestone(&74, &0);
+    client.raise_dispute(&75, &recipient, &DisputeReason::Other);
+
+    let stats = client.get_stats();
+    assert_eq!(stats.escrows_created, 3);
+    assert_eq!(stats.active_escrows, 2);
+    assert_eq!(stats.disputes_open, 1);
+    assert_eq!(stats.total_locked, 4000);
+    assert_eq!(stats.total_released, 1000);
+
+    client.resolve_dispute(&75, &depositor, &RulingReason::NotDelivered, &None);
+    client.release_milestone(&74, &1);
+    client.complete_escrow(&74);
+
+    let stats = client.get_stats();
+    assert_eq!(stats.active_escrows, 0);
+    assert_eq!(stats.disputes_open, 0);
+    assert_eq!(stats.total_locked, 0);
+    assert_eq!(stats.total_released, 2500);
+    assert_eq!(token_client.balance(&contract_id), stats.total_locked);
+}
+
+#[test]
+fn test_token_stats_reconcile_with_balances() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    client.initialize(&Address::generate(&env), &Some(0));
+    let (usdc, usdc_admin, usdc_address) = create_token_contract(&env, &admin);
+    let (eurc, eurc_admin, eurc_address) = create_token_contract(&env, &admin);
+    usdc_admin.mint(&depositor, &1000);
+    eurc_admin.mint(&depositor, &2000);
+    usdc.approve(&depositor, &contract_id, &1000, &200);
+    eurc.approve(&depositor, &contract_id, &2000, &200);
+
+    let milestones = |amount: i128| {
+        vec![
+            &env,
+            Milestone {
+                amount,
+                status: MilestoneStatus::Pending,
+                description: String::from_str(&env, "Work"),
+            },
+        ]
+    };
+    client.create_escrow(
+        &77,
+        &depositor,
+        &recipient,
+        &usdc_address,
+        &milestones(1000),
+        &1706400000u64,
+    );
+    client.create_escrow(
+        &78,
+        &depositor,
+        &recipient,
+        &eurc_address,
+        &milestones(2000),
+        &1706400000u64,
+    );
+    client.deposit_funds(&77);
+    client.deposit_funds(&78);
+    client.release_milestone(&77, &0);
+    client.cancel_escrow(&78);
+
+    let usdc_stats = client.get_token_stats(&usdc_address);
+    assert_eq!(usdc_stats.locked, 0);
+    assert_eq!(usdc_stats.released, 1000);
+    assert_eq!(usdc_stats.refunded, 0);
+
+    let eurc_stats = client.get_token_stats(&eurc_address);
+    assert_eq!(eurc_stats.locked, 0);
+    assert_eq!(eurc_stats.released, 0);
+    assert_eq!(eurc_stats.refunded, 2000);
+
+    assert_eq!(usdc.balance(&contract_id), usdc_stats.locked);
+    assert_eq!(eurc.balance(&contract_id), eurc_stats.locked);
+}
+
+#[test]
+fn test_arbitrator_claims_queued_dispute() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let outsider = Address::generate(&env);
+
+    client.init(&admin);
+    client.initialize(&Address::generate(&env), &Some(0));
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &2000);
+    token_client.approve(&depositor, &contract_id, &2000, &200);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Work"),
+        },
+    ];
+    for escrow_id in [79u64, 80] {
+        client.create_escrow(
+            &escrow_id,
+            &depositor,
+            &recipient,
+            &token_address,
+            &milestones,
+            &1706400000u64,
+        );
+        client.deposit_funds(&escrow_id);
+        client.raise_dispute(&escrow_id, &depositor, &DisputeReason::Other);
+    }
+    // Nobody was registered to draw from, so both disputes wait in the queue
+    assert_eq!(client.get_dispute_queue(), vec![&env, 79, 80]);
+
+    client.add_arbitrator(&admin, &arbitrator);
+    assert!(client.is_arbitrator(&arbitrator));
+
+    assert_eq!(
+        client.try_claim_dispute(&79, &outsider),
+        Err(Ok(Error::NotArbitrator))
+    );
+    client.claim_dispute(&79, &arbitrator);
+    assert_eq!(client.get_arbiter(&79), Some(arbitrator.clone()));
+    assert_eq!(client.get_dispute_queue(), vec![&env, 80]);
+    assert_eq!(
+        client.try_claim_dispute(&79, &arbitrator),
+        Err(Ok(Error::DisputeNotQueued))
+    );
+
+    // Only the claiming arbitrator may now rule on it
+    client.resolve_dispute(&79, &recipient, &RulingReason::WorkAccepted, &None);
+    let auths = env.auths();
+    assert_eq!(auths[0].0, arbitrator);
+
+    // An unclaimed dispute is still resolved by the admin and leaves the queue
+    client.resolve_dispute(&80, &depositor, &RulingReason::NotDelivered, &None);
+    assert_eq!(env.auths()[0].0, admin);
+    assert!(client.get_dispute_queue().is_empty());
+}
+
+#[test]
+fn test_dispute_draws_arbitrator_from_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id = 81u64;
+
+    client.init(&admin);
+    client.initialize(&Address::generate(&env), &Some(0));
+
+    // A party to the escrow is registered too, but must never be drawn for it
+    let pool = [
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    for arbitrator in pool.iter().chain([&depositor]) {
+        client.add_arbitrator(&admin, arbitrator);
+    }
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &1000);
+    token_client.approve(&depositor, &contract_id, &1000, &200);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Work"),
+        },
+    ];
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    client.deposit_funds(&escrow_id);
+    client.raise_dispute(&escrow_id, &recipient, &DisputeReason::Other);
+
+    let arbiter = client.get_arbiter(&escrow_id).unwrap();
+    assert!(pool.contains(&arbiter));
+    assert!(client.get_dispute_queue().is_empty());
+
+    client.resolve_dispute(&escrow_id, &recipient, &RulingReason::WorkAccepted, &None);
+    assert_eq!(env.auths()[0].0, arbiter);
+}
+
+#[test]
+fn test_arbitrator_stake_and_slash_on_missed_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let escrow_id = 82u64;
+
+    client.init(&admin);
+    client.initialize(&Address::generate(&env), &Some(0));
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &1000);
+    token_admin.mint(&arbitrator, &500);
+    token_client.approve(&depositor, &contract_id, &1000, &200);
+
+    let day = 24 * 60 * 60;
+    client.set_arbitration_config(
+        &admin,
+        &ArbitrationConfig {
+            stake_token: token_address.clone(),
+            min_stake: 500,
+            slash_bps: 2000,
+            resolution_window: day,
+            fee_bps: 0,
+        },
+    );
+
+    client.stake(&arbitrator, &400);
+    assert!(!client.is_arbitrator(&arbitrator));
+    client.stake(&arbitrator, &100);
+    assert!(client.is_arbitrator(&arbitrator));
+    assert_eq!(client.get_stake(&arbitrator), 500);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Work"),
+        },
+    ];
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    client.deposit_funds(&escrow_id);
+    client.raise_dispute(&escrow_id, &recipient, &DisputeReason::Other);
+
+    assert_eq!(client.get_arbiter(&escrow_id), Some(arbitrator.clone()));
+    let deadline = client.get_resolution_deadline(&escrow_id).unwrap();
+    assert_eq!(deadline, env.ledger().timestamp() + day);
+    assert_eq!(
+        client.try_unstake(&arbitrator, &500),
+        Err(Ok(Error::StakeLocked))
+    );
+    assert_eq!(
+        client.try_slash_missed_deadline(&escrow_id),
+        Err(Ok(Error::InvalidDeadline))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = deadline + 1);
+    assert_eq!(client.slash_missed_deadline(&escrow_id), 100);
+
+    // The slashed arbitrator leaves the pool and the dispute goes back to the queue
+    assert_eq!(client.get_stake(&arbitrator), 400);
+    assert_eq!(client.get_insurance_pool(&token_address), 100);
+    assert!(!client.is_arbitrator(&arbitrator));
+    assert_eq!(client.get_arbiter(&escrow_id), None);
+    assert_eq!(client.get_dispute_queue(), vec![&env, escrow_id]);
+
+    client.unstake(&arbitrator, &400);
+    assert_eq!(token_client.balance(&arbitrator), 400);
+}
+
+#[test]
+fn test_resolving_arbitrator_earns_share_of_bond() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let escrow_id = 83u64;
+
+    client.init(&admin);
+    client.initialize(&Address::generate(&env), &Some(0));
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &1000);
+    token_admin.mint(&recipient, &200);
+    token_client.approve(&depositor, &contract_id, &1000, &200);
+
+    let mut config = client.get_config();
+    config.dispute_bond = 200;
+    client.set_config(&admin, &config);
+    client.set_arbitration_config(
+        &admin,
+        &ArbitrationConfig {
+            stake_token: token_address.clone(),
+            min_stake: 0,
+            slash_bps: 0,
+            resolution_window: 0,
+            fee_bps: 2500,
+        },
+    );
+    client.add_arbitrator(&admin, &arbitrator);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Work"),
+        },
+    ];
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    client.deposit_funds(&escrow_id);
+    client.raise_dispute(&escrow_id, &recipient, &DisputeReason::Other);
+    assert_eq!(client.get_arbiter(&escrow_id), Some(arbitrator.clone()));
+
+    client.resolve_dispute(&escrow_id, &recipient, &RulingReason::WorkAccepted, &None);
+    assert_eq!(token_client.balance(&recipient), 1000 + 150);
+    assert_eq!(client.get_claimable_fees(&arbitrator, &token_address), 50);
+
+    assert_eq!(
+        client.claim_arbitration_fees(&arbitrator, &token_address),
+        50
+    );
+    assert_eq!(token_client.balance(&arbitrator), 50);
+    assert_eq!(client.get_claimable_fees(&arbitrator, &token_address), 0);
+    assert_eq!(
+        client.try_claim_arbitration_fees(&arbitrator, &token_address),
+        Err(Ok(Error::ZeroAmount))
+    );
+}
+
+#[test]
+fn test_parties_settle_dispute_without_arbitrator() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id = 84u64;
+
+    client.initialize(&Address::generate(&env), &Some(0));
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &1000);
+    token_client.approve(&depositor, &contract_id, &1000, &200);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Work"),
+        },
+    ];
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    client.deposit_funds(&escrow_id);
+    client.raise_dispute(&escrow_id, &depositor, &DisputeReason::Other);
+
+    assert_eq!(
+        client.try_accept_settlement(&escrow_id, &recipient, &0),
+        Err(Ok(Error::NoSettlementProposed))
+    );
+    assert_eq!(
+        client.try_propose_settlement(&escrow_id, &recipient, &1001),
+        Err(Ok(Error::AmountOutOfRange))
+    );
+    client.propose_settlement(&escrow_id, &recipient, &700);
+    // A party cannot accept its own proposal
+    assert_eq!(
+        client.try_accept_settlement(&escrow_id, &recipient, &700),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+
+    client.accept_settlement(&escrow_id, &depositor, &700);
+
+    let escrow = client.get_escrow(&escrow_id);
+    assert_eq!(escrow.status, EscrowStatus::Resolved);
+    assert_eq!(escrow.resolution, Resolution::Settled);
+    assert_eq!(token_client.balance(&recipient), 700);
+    assert_eq!(token_client.balance(&depositor), 300);
+    assert_eq!(token_client.balance(&contract_id), 0);
+    assert_eq!(client.get_settlement(&escrow_id), None);
+}
+
+#[test]
+fn test_partial_dispute_resolution_resumes_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id = 85u64;
+
+    client.init(&admin);
+    client.initialize(&Address::generate(&env), &Some(0));
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &600);
+    token_client.approve(&depositor, &contract_id, &600, &200);
+
+    let mut milestones = Vec::new(&env);
+    for amount in [100i128, 200, 300] {
+        milestones.push_back(Milestone {
+            amount,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase"),
+        });
+    }
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    client.deposit_funds(&escrow_id);
+    client.release_milestone(&escrow_id, &0);
+    client.raise_dispute(&escrow_id, &depositor, &DisputeReason::Other);
+
+    assert_eq!(
+        client.try_resolve_dispute_partial(&escrow_id, &vec![&env, 0u32]),
+        Err(Ok(Error::MilestoneAlreadyReleased))
+    );
+    client.resolve_dispute_partial(&escrow_id, &vec![&env, 1u32]);
+
+    let escrow = client.get_escrow(&escrow_id);
+    assert_eq!(escrow.status, EscrowStatus::Active);
+    assert_eq!(escrow.resolution, Resolution::None);
+    assert_eq!(escrow.total_released, 300);
+    assert_eq!(client.get_pending_milestones(&escrow_id), vec![&env, 2u32]);
+    assert_eq!(token_client.balance(&recipient), 300);
+
+    // The remaining milestone follows the normal flow again
+    client.release_milestone(&escrow_id, &2);
+    client.complete_escrow(&escrow_id);
+    assert_eq!(
+        client.get_escrow(&escrow_id).status,
+        EscrowStatus::Completed
+    );
+    assert_eq!(token_client.balance(&recipient), 600);
+}
+
+#[test]
+fn test_dispute_reason_recorded_and_published() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id = 86u64;
+
+    client.initialize(&Address::generate(&env), &Some(0));
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &500);
+    token_client.approve(&depositor, &contract_id, &500, &200);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 500,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Delivery"),
+        },
+    ];
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    client.deposit_funds(&escrow_id);
+    assert_eq!(client.get_dispute_reason(&escrow_id), None);
+
+    client.raise_dispute(&escrow_id, &depositor, &DisputeReason::NonDelivery);
+    assert_eq!(
+        client.get_dispute_reason(&escrow_id),
+        Some(DisputeReason::NonDelivery)
+    );
+
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (symbol_short!("disputed"), escrow_id).into_val(&env)
+    );
+    let (raiser, reason): (Address, DisputeReason) = data.into_val(&env);
+    assert_eq!(raiser, depositor);
+    assert_eq!(reason, DisputeReason::NonDelivery);
+}
+
+#[test]
+fn test_evidence_window_gates_submission_and_resolution() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id = 87u64;
+
+    client.init(&admin);
+    client.initialize(&Address::generate(&env), &Some(0));
+    let mut config = client.get_config();
+    config.evidence_window = 3600;
+    client.set_config(&admin, &config);
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &500);
+    token_client.approve(&depositor, &contract_id, &500, &200);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 500,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Delivery"),
+        },
+    ];
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    client.deposit_funds(&escrow_id);
+    client.raise_dispute(&escrow_id, &recipient, &DisputeReason::Scope);
+    assert_eq!(client.get_evidence_deadline(&escrow_id), Some(4_600));
+
+    let hash = BytesN::from_array(&env, &[7u8; 32]);
+    client.submit_evidence(&escrow_id, &recipient, &hash);
+    assert_eq!(
+        client.get_evidence(&escrow_id, &recipient),
+        Some(Evidence {
+            hash: hash.clone(),
+            submitted_at: 1_000,
+        })
+    );
+    assert_eq!(
+        client.try_resolve_dispute(&escrow_id, &recipient, &RulingReason::WorkAccepted, &None),
+        Err(Ok(Error::EvidenceWindowOpen))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 4_601);
+    assert_eq!(
+        client.try_submit_evidence(&escrow_id, &depositor, &hash),
+        Err(Ok(Error::EvidenceWindowClosed))
+    );
+    client.resolve_dispute(&escrow_id, &recipient, &RulingReason::WorkAccepted, &None);
+    assert_eq!(token_client.balance(&recipient), 500);
+}
+
+#[test]
+fn test_reject_and_resubmit_milestone() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id = 88u64;
+
+    client.initialize(&Address::generate(&env), &Some(0));
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &500);
+    token_client.approve(&depositor, &contract_id, &500, &200);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 500,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Design"),
+        },
+    ];
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    client.deposit_funds(&escrow_id);
+
+    let reason = BytesN::from_array(&env, &[1u8; 32]);
+    assert_eq!(
+        client.try_reject_milestone(&escrow_id, &0, &reason),
+        Err(Ok(Error::MilestoneNotSubmitted))
+    );
+
+    let draft = BytesN::from_array(&env, &[7u8; 32]);
+    client.submit_milestone(&escrow_id, &0, &draft, &None);
+    assert_eq!(
+        client.try_submit_milestone(&escrow_id, &0, &draft, &None),
+        Err(Ok(Error::MilestoneAlreadySubmitted))
+    );
+    let deliverable = client.get_deliverable(&escrow_id, &0).unwrap();
+    assert_eq!(deliverable.hash, draft);
+    assert_eq!(deliverable.uri.len(), 0);
+    assert_eq!(deliverable.ledger, env.ledger().sequence());
+    client.reject_milestone(&escrow_id, &0, &reason);
+
+    let review = client.get_milestone_review(&escrow_id, &0);
+    assert!(!review.submitted);
+    assert_eq!(review.rejections, 1);
+    assert_eq!(client.get_rejection_reason(&escrow_id, &0), Some(reason));
+    assert_eq!(
+        client.get_milestone(&escrow_id, &0).status,
+        MilestoneStatus::Pending
+    );
+
+    // The recipient reworks and resubmits; the depositor accepts this time
+    let reworked = BytesN::from_array(&env, &[8u8; 32]);
+    let long_uri = String::from_bytes(&env, &[b'a'; 257]);
+    assert_eq!(
+        client.try_submit_milestone(&escrow_id, &0, &reworked, &Some(long_uri)),
+        Err(Ok(Error::DescriptionTooLong))
+    );
+    let uri = String::from_str(
+        &env,
+        "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+    );
+    client.submit_milestone(&escrow_id, &0, &reworked, &Some(uri.clone()));
+    let deliverable = client.get_deliverable(&escrow_id, &0).unwrap();
+    assert_eq!((deliverable.hash, deliverable.uri), (reworked, uri));
+    assert!(client.get_milestone_review(&escrow_id, &0).submitted);
+    client.release_milestone(&escrow_id, &0);
+    assert_eq!(token_client.balance(&recipient), 500);
+}
+
+#[test]
+fn test_repeated_rejections_escalate_to_dispute() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id = 89u64;
+
+    client.init(&admin);
+    client.initialize(&Address::generate(&env), &Some(0));
+    let mut config = client.get_config();
+    config.max_rejections = 2;
+    client.set_config(&admin, &config);
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &800);
+    token_client.approve(&depositor, &contract_id, &800, &200);
+
+    let mut milestones = Vec::new(&env);
+    for amount in [300i128, 500] {
+        milestones.push_back(Milestone {
+            amount,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase"),
+        });
+    }
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    client.deposit_funds(&escrow_id);
+
+    let reason = BytesN::from_array(&env, &[2u8; 32]);
+    let work = BytesN::from_array(&env, &[3u8; 32]);
+    client.submit_milestone(&escrow_id, &1, &work, &None);
+    client.reject_milestone(&escrow_id, &1, &reason);
+    assert_eq!(client.get_escrow(&escrow_id).status, EscrowStatus::Active);
+
+    client.submit_milestone(&escrow_id, &1, &work, &None);
+    client.reject_milestone(&escrow_id, &1, &reason);
+
+    let (_, topics, _) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (symbol_short!("escalated"), escrow_id, 1u32).into_val(&env)
+    );
+    assert_eq!(client.get_escrow(&escrow_id).status, EscrowStatus::Disputed);
+    assert_eq!(
+        client.get_dispute_reason(&escrow_id),
+        Some(DisputeReason::Quality)
+    );
+    // Only the contested milestone is frozen
+    assert_eq!(
+        client.get_milestone(&escrow_id, &0).status,
+        MilestoneStatus::Pending
+    );
+    assert_eq!(
+        client.get_milestone(&escrow_id, &1).status,
+        MilestoneStatus::Disputed
+    );
+    assert_eq!(
+        client.try_submit_milestone(&escrow_id, &1, &work, &None),
+        Err(Ok(Error::EscrowNotActive))
+    );
+}
+
+#[test]
+fn test_dispute_grace_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id = 90u64;
+
+    client.init(&admin);
+    client.initialize(&Address::generate(&env), &Some(0));
+    let mut config = client.get_config();
+    config.dispute_grace = 600;
+    client.set_config(&admin, &config);
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &500);
+    token_client.approve(&depositor, &contract_id, &500, &200);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 500,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Delivery"),
+        },
+    ];
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    assert_eq!(client.get_dispute_opens_at(&escrow_id), 10_600);
+
+    // The depositor may tailor the grace period until the escrow is funded
+    client.set_dispute_grace(&escrow_id, &3_600);
+    client.deposit_funds(&escrow_id);
+    assert_eq!(
+        client.try_set_dispute_grace(&escrow_id, &0),
+        Err(Ok(Error::EscrowAlreadyFunded))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 13_599);
+    assert_eq!(
+        client.can_dispute(&escrow_id, &recipient),
+        Some(Error::DisputeTooEarly as u32)
+    );
+    assert_eq!(
+        client.try_raise_dispute(&escrow_id, &recipient, &DisputeReason::Other),
+        Err(Ok(Error::DisputeTooEarly))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 13_600);
+    client.raise_dispute(&escrow_id, &recipient, &DisputeReason::Other);
+    assert_eq!(client.get_escrow(&escrow_id).status, EscrowStatus::Disputed);
+}
+
+#[test]
+fn test_dispute_cooldown_after_resume() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 5_000);
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id = 91u64;
+
+    client.init(&admin);
+    client.initialize(&Address::generate(&env), &Some(0));
+    let mut config = client.get_config();
+    config.dispute_cooldown = 1_000;
+    client.set_config(&admin, &config);
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &600);
+    token_client.approve(&depositor, &contract_id, &600, &200);
+
+    let mut milestones = Vec::new(&env);
+    for amount in [200i128, 400] {
+        milestones.push_back(Milestone {
+            amount,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase"),
+        });
+    }
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    client.deposit_funds(&escrow_id);
+    client.raise_dispute(&escrow_id, &depositor, &DisputeReason::Quality);
+    client.resolve_dispute_partial(&escrow_id, &vec![&env, 0u32]);
+
+    assert_eq!(
+        client.try_raise_dispute(&escrow_id, &depositor, &DisputeReason::Quality),
+        Err(Ok(Error::DisputeCooldown))
+    );
+    // The counterparty is not held back by the other side's cooldown
+    assert_eq!(client.can_dispute(&escrow_id, &recipient), None);
+
+    env.ledger().with_mut(|li| li.timestamp = 6_000);
+    client.raise_dispute(&escrow_id, &depositor, &DisputeReason::Quality);
+    assert_eq!(client.get_escrow(&escrow_id).status, EscrowStatus::Disputed);
+}
+
+#[test]
+fn test_roles_gate_privileged_operations() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let pauser = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    client.setup(&admin, &treasury, &Some(0));
+
+    // The admin holds every role; setup grants the treasury the fee and pause powers it had
+    assert!(client.has_role(&Role::Upgrader, &admin));
+    assert!(client.has_role(&Role::FeeManager, &treasury));
+    assert!(!client.has_role(&Role::Admin, &treasury));
+    assert_eq!(
+        client.get_role_members(&Role::FeeManager, &0, &10),
+        vec![&env, admin.clone(), treasury.clone()]
+    );
+    // Those grants are revocable like any other
+    client.revoke_role(&admin, &Role::FeeManager, &treasury);
+    assert!(!client.has_role(&Role::FeeManager, &treasury));
+    assert_eq!(
+        client.try_update_fee(&treasury, &100),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+
+    assert_eq!(
+        client.try_set_paused(&pauser, &true),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    client.grant_role(&admin, &Role::Pauser, &pauser);
+    assert_eq!(env.auths()[0].0, admin);
+    client.set_paused(&pauser, &true);
+    assert!(client.get_config().paused);
+    // A pauser cannot touch anything else
+    assert_eq!(
+        client.try_update_fee(&pauser, &100),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+
+    client.revoke_role(&admin, &Role::Pauser, &pauser);
+    assert_eq!(
+        client.try_set_paused(&pauser, &false),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+
+    // The Arbitrator role is membership of the arbitrator pool
+    client.grant_role(&admin, &Role::Arbitrator, &arbitrator);
+    assert_eq!(client.get_arbitrators(), vec![&env, arbitrator.clone()]);
+    client.revoke_role(&admin, &Role::Arbitrator, &arbitrator);
+    assert!(!client.has_role(&Role::Arbitrator, &arbitrator));
+}
+
+#[test]
+fn test_dormant_escrow_recovery_with_timelock() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id = 92u64;
+    let year = 365 * 24 * 60 * 60u64;
+    let delay = 30 * 24 * 60 * 60u64;
+
+    client.setup(&admin, &Address::generate(&env), &Some(0));
+    let mut config = client.get_config();
+    config.dormancy_period = 60;
+    assert_eq!(
+        client.try_set_config(&admin, &config),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+    config.dormancy_period = year;
+    client.set_config(&admin, &config);
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &1000);
+    token_client.approve(&depositor, &contract_id, &1000, &200);
+
+    let mut milestones = Vec::new(&env);
+    for amount in [400i128, 600] {
+        milestones.push_back(Milestone {
+            amount,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase"),
+        });
+    }
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    client.deposit_funds(&escrow_id);
+    assert_eq!(
+        client.try_announce_dormant_recovery(&admin, &escrow_id),
+        Err(Ok(Error::RecoveryNotReady))
+    );
+
+    // Activity after the announcement voids it
+    env.ledger().with_mut(|li| li.timestamp = 1_000 + year);
+    client.announce_dormant_recovery(&admin, &escrow_id);
+    client.release_milestone(&escrow_id, &0);
+    env.ledger()
+        .with_mut(|li| li.timestamp = 1_000 + year + delay);
+    assert_eq!(
+        client.try_execute_dormant_recovery(&admin, &escrow_id),
+        Err(Ok(Error::RecoveryNotReady))
+    );
+
+    let now = 1_000 + 2 * year;
+    env.ledger().with_mut(|li| li.timestamp = now);
+    assert_eq!(
+        client.announce_dormant_recovery(&admin, &escrow_id),
+        now + delay
+    );
+    assert_eq!(client.get_dormant_recovery(&escrow_id), Some(now + delay));
+    assert_eq!(
+        client.try_execute_dormant_recovery(&admin, &escrow_id),
+        Err(Ok(Error::RecoveryNotReady))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = now + delay);
+    assert_eq!(client.execute_dormant_recovery(&admin, &escrow_id), 600);
+    assert_eq!(
+        client.get_escrow(&escrow_id).status,
+        EscrowStatus::Cancelled
+    );
+    assert_eq!(token_client.balance(&depositor), 600);
+    assert_eq!(token_client.balance(&recipient), 400);
+}
+
+#[test]
+fn test_backup_inherits_inactive_depositor_authority() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let backup = Address::generate(&env);
+    let escrow_id = 93u64;
+
+    client.initialize(&Address::generate(&env), &Some(0));
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &1000);
+    token_client.approve(&depositor, &contract_id, &1000, &200);
+
+    let mut milestones = Vec::new(&env);
+    for amount in [400i128, 600] {
+        milestones.push_back(Milestone {
+            amount,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase"),
+        });
+    }
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    assert_eq!(
+        client.try_set_backup(&escrow_id, &recipient, &5_000),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+    client.set_backup(&escrow_id, &backup, &5_000);
+    client.deposit_funds(&escrow_id);
+    assert_eq!(client.get_depositor_authority(&escrow_id), depositor);
+    assert_eq!(
+        client.can_dispute(&escrow_id, &backup),
+        Some(Error::UnauthorizedAccess as u32)
+    );
+
+    // The depositor goes silent; the backup takes over
+    env.ledger().with_mut(|li| li.timestamp = 6_000);
+    assert_eq!(client.get_depositor_authority(&escrow_id), backup);
+    client.release_milestone(&escrow_id, &0);
+    assert_eq!(env.auths()[0].0, backup);
+    assert_eq!(token_client.balance(&recipient), 400);
+
+    // Checking in hands authority back to the depositor
+    client.check_in(&depositor);
+    assert_eq!(client.get_depositor_authority(&escrow_id), depositor);
+    assert_eq!(client.get_last_seen(&depositor), 6_000);
+}
+
+#[test]
+fn test_recipient_recovery_with_veto() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let recovery = Address::generate(&env);
+    let escrow_id = 94u64;
+    let delay = 7 * 24 * 60 * 60u64;
+
+    client.initialize(&Address::generate(&env), &Some(0));
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &500);
+    token_client.approve(&depositor, &contract_id, &500, &200);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 500,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Delivery"),
+        },
+    ];
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    client.deposit_funds(&escrow_id);
+    client.set_recovery_address(&escrow_id, &recovery);
+
+    // The depositor vetoes the first attempt
+    client.start_recipient_recovery(&escrow_id);
+    client.veto_recipient_recovery(&escrow_id, &depositor);
+    assert_eq!(
+        client.try_finalize_recipient_recovery(&escrow_id),
+        Err(Ok(Error::RecoveryNotReady))
+    );
+
+    assert_eq!(client.start_recipient_recovery(&escrow_id), 1_000 + delay);
+    assert_eq!(
+        client.try_finalize_recipient_recovery(&escrow_id),
+        Err(Ok(Error::RecoveryNotReady))
+    );
+    env.ledger().with_mut(|li| li.timestamp = 1_000 + delay);
+    client.finalize_recipient_recovery(&escrow_id);
+    assert_eq!(client.get_escrow(&escrow_id).recipient, recovery);
+
+    client.release_milestone(&escrow_id, &0);
+    assert_eq!(token_client.balance(&recovery), 500);
+    assert_eq!(token_client.balance(&recipient), 0);
+}
+
+#[test]
+fn test_quorum_of_approvers_releases_milestone() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let qa = Address::generate(&env);
+    let escrow_id = 95u64;
+
+    client.initialize(&Address::generate(&env), &Some(0));
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &1000);
+    token_client.approve(&depositor, &contract_id, &1000, &200);
+
+    let mut milestones = Vec::new(&env);
+    for amount in [400i128, 600] {
+        milestones.push_back(Milestone {
+            amount,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase"),
+        });
+    }
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+
+    let mut policy = ApprovalPolicy {
+        approvers: vec![&env, depositor.clone(), manager.clone(), qa.clone()],
+        threshold: 4,
+        approval_ttl: 500,
+    };
+    assert_eq!(
+        client.try_set_approval_policy(&escrow_id, &policy),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+    policy.threshold = 2;
+    client.set_approval_policy(&escrow_id, &policy);
+    client.deposit_funds(&escrow_id);
+
+    // The depositor alone can no longer release
+    assert_eq!(
+        client.try_release_milestone(&escrow_id, &0),
+        Err(Ok(Error::ApprovalRequired))
+    );
+    assert_eq!(
+        client.try_approve_milestone(&escrow_id, &0, &recipient),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+
+    // An approval that expires before the second one arrives does not count
+    assert!(!client.approve_milestone(&escrow_id, &0, &manager));
+    env.ledger().with_mut(|li| li.timestamp = 1_500);
+    assert_eq!(client.get_approvals(&escrow_id, &0).len(), 0);
+    assert!(!client.approve_milestone(&escrow_id, &0, &qa));
+    assert_eq!(token_client.balance(&recipient), 0);
+
+    assert!(client.approve_milestone(&escrow_id, &0, &depositor));
+    assert_eq!(token_client.balance(&recipient), 400);
+    assert_eq!(client.get_approvals(&escrow_id, &0).len(), 0);
+    assert_eq!(
+        client.try_approve_milestone(&escrow_id, &0, &manager),
+        Err(Ok(Error::MilestoneAlreadyReleased))
+    );
+}
+
+#[test]
+fn test_named_approvers_release_without_party_powers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let finance = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let manager = Address::generate(&env);
+
+    client.initialize(&Address::generate(&env), &Some(0));
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&finance, &1000);
+    token_client.approve(&finance, &contract_id, &1000, &200);
+
+    let mut milestones = Vec::new(&env);
+    for amount in [400i128, 600] {
+        milestones.push_back(Milestone {
+            amount,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase"),
+        });
+    }
+    assert_eq!(
+        client.try_create_escrow_with_approvers(
+            &finance,
+            &recipient,
+            &token_address,
+            &milestones,
+            &1706400000u64,
+            &vec![&env, recipient.clone()],
+        ),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+    let escrow_id = client.create_escrow_with_approvers(
+        &finance,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+        &vec![&env, manager.clone()],
+    );
+    assert_eq!(client.get_approval_policy(&escrow_id).unwrap().threshold, 1);
+    client.deposit_funds(&escrow_id);
+
+    // A single approver releases; the funder cannot
+    assert_eq!(
+        client.try_release_milestone(&escrow_id, &0),
+        Err(Ok(Error::ApprovalRequired))
+    );
+    assert!(client.approve_milestone(&escrow_id, &0, &manager));
+    assert_eq!(token_client.balance(&recipient), 400);
+
+    // Approvers are not parties to the escrow
+    assert_eq!(
+        client.try_raise_dispute(&escrow_id, &manager, &DisputeReason::Quality),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    client.raise_dispute(&escrow_id, &finance, &DisputeReason::Scope);
+    assert_eq!(client.get_escrow(&escrow_id).status, EscrowStatus::Disputed);
+}
+
+#[test]
+fn test_org_members_operate_org_escrows() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let company = Address::generate(&env);
+    let lead = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    client.initialize(&Address::generate(&env), &Some(0));
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&company, &1000);
+    token_client.approve(&company, &contract_id, &1000, &200);
+
+    let org_id = client.register_org(&company, &lead);
+    client.set_org_member(&org_id, &lead, &employee, &OrgRole::Operator);
+    assert_eq!(
+        client.try_set_org_member(&org_id, &employee, &outsider, &OrgRole::Admin),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+
+    let mut milestones = Vec::new(&env);
+    for amount in [400i128, 600] {
+        milestones.push_back(Milestone {
+            amount,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase"),
+        });
+    }
+    let escrow_id = client.create_org_escrow(
+        &org_id,
+        &employee,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    assert_eq!(client.get_escrow(&escrow_id).depositor, company);
+    assert_eq!(client.get_escrow_org(&escrow_id), Some(org_id));
+    client.deposit_funds(&escrow_id);
+
+    client.org_release_milestone(&escrow_id, &0, &employee);
+    assert_eq!(env.auths()[0].0, employee);
+    assert_eq!(token_client.balance(&recipient), 400);
+
+    // Rotating the employee out revokes their access to every org escrow
+    client.remove_org_member(&org_id, &lead, &employee);
+    assert_eq!(
+        client.try_org_release_milestone(&escrow_id, &1, &employee),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    client.raise_dispute(&escrow_id, &lead, &DisputeReason::NonDelivery);
+    assert_eq!(client.get_escrow(&escrow_id).status, EscrowStatus::Disputed);
+}
+
+#[test]
+fn test_delegate_spending_limit_per_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let company = Address::generate(&env);
+    let ops = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    client.initialize(&Address::generate(&env), &Some(0));
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&company, &900);
+    token_client.approve(&company, &contract_id, &900, &200);
+
+    let org_id = client.register_org(&company, &ops);
+    let mut milestones = Vec::new(&env);
+    for _ in 0..3 {
+        milestones.push_back(Milestone {
+            amount: 300,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Sprint"),
+        });
+    }
+    let escrow_id = client.create_org_escrow(
+        &org_id,
+        &ops,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    client.deposit_funds(&escrow_id);
+
+    let week = 7 * 24 * 60 * 60;
+    client.set_spending_limit(
+        &escrow_id,
+        &ops,
+        &SpendingLimit {
+            amount: 500,
+            period: week,
+        },
+    );
+
+    client.org_release_milestone(&escrow_id, &0, &ops);
+    assert_eq!(client.get_delegate_spent(&escrow_id, &ops), 300);
+    assert_eq!(
+        client.try_org_release_milestone(&escrow_id, &1, &ops),
+        Err(Ok(Error::RateLimited))
+    );
+
+    // The cap resets with the next period
+    env.ledger().with_mut(|li| li.timestamp = 1_000 + week);
+    assert_eq!(client.get_delegate_spent(&escrow_id, &ops), 0);
+    client.org_release_milestone(&escrow_id, &1, &ops);
+    assert_eq!(token_client.balance(&recipient), 600);
+}
+
+/// Hook contract recording the lifecycle callbacks it receives
+#[soroban_sdk::contract]
+pub struct MockHook;
+
+#[soroban_sdk::contractimpl]
+impl MockHook {
+    pub fn on_release(env: Env, escrow_id: u64, milestone_index: u32, amount: i128) {
+        env.storage().persistent().set(
+            &symbol_short!("release"),
+            &(escrow_id, milestone_index, amount),
+        );
+    }
+
+    pub fn on_resolve(env: Env, escrow_id: u64, resolution: Resolution) {
+        env.storage()
+            .persistent()
+            .set(&symbol_short!("resolve"), &(escrow_id, resolution));
+    }
+
+    pub fn last_release(env: Env) -> Option<(u64, u32, i128)> {
+        env.storage().persistent().get(&symbol_short!("release"))
+    }
+
+    pub fn last_resolve(env: Env) -> Option<(u64, Resolution)> {
+        env.storage().persistent().get(&symbol_short!("resolve"))
+    }
+}
+
+#[test]
+fn test_hook_notified_of_release_and_resolution() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+    let hook_id = env.register_contract(None, MockHook);
+    let hook = MockHookClient::new(&env, &hook_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id = 96u64;
+
+    client.init(&admin);
+    client.initialize(&Address::generate(&env), &Some(0));
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &600);
+    token_client.approve(&depositor, &contract_id, &600, &200);
+
+    let mut milestones = Vec::new(&env);
+    for amount in [200i128, 400] {
+        milestones.push_back(Milestone {
+            amount,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Phase"),
+        });
+    }
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    client.set_hook(&escrow_id, &hook_id);
+    client.deposit_funds(&escrow_id);
+
+    client.release_milestone(&escrow_id, &0);
+    assert_eq!(hook.last_release(), Some((escrow_id, 0, 200)));
+
+    client.raise_dispute(&escrow_id, &recipient, &DisputeReason::NonDelivery);
+    client.resolve_dispute(&escrow_id, &recipient, &RulingReason::WorkAccepted, &None);
+    assert_eq!(
+        hook.last_resolve(),
+        Some((escrow_id, Resolution::Recipient))
+    );
+
+    // A hook that does not implement the interface never blocks payouts
+    let other_id = 97u64;
+    client.create_escrow(
+        &other_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    token_admin.mint(&depositor, &600);
+    token_client.approve(&depositor, &contract_id, &600, &200);
+    client.set_hook(&other_id, &token_address);
+    client.deposit_funds(&other_id);
+    client.release_milestone(&other_id, &1);
+    assert_eq!(token_client.balance(&recipient), 1000);
+}
+
+#[test]
+fn test_lifecycle_published_in_versioned_schema() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let escrow_id = 98u64;
+
+    client.initialize(&Address::generate(&env), &Some(0));
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &500);
+    token_client.approve(&depositor, &contract_id, &500, &200);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 500,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "All"),
+        },
+    ];
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    client.deposit_funds(&escrow_id);
+    client.release_milestone(&escrow_id, &0);
+    client.complete_escrow(&escrow_id);
+
+    let schema = symbol_short!("escrow_v1");
+    let mut published = Vec::new(&env);
+    for (_, topics, data) in env.events().all().iter() {
+        let first: Symbol = topics.get(0).unwrap().into_val(&env);
+        if first != schema {
+            continue;
+        }
+        let action: Symbol = topics.get(1).unwrap().into_val(&env);
+        let id: u64 = topics.get(2).unwrap().into_val(&env);
+        if id == escrow_id {
+            let (actor, amount): (Address, i128) = data.into_val(&env);
+            published.push_back((action, actor, amount));
+        }
+    }
+    assert_eq!(
+        published,
+        vec![
+            &env,
+            (symbol_short!("create"), depositor.clone(), 500),
+            (symbol_short!("deposit"), depositor.clone(), 500),
+            (symbol_short!("released"), depositor.clone(), 500),
+            (symbol_short!("completed"), depositor.clone(), 0),
+        ]
+    );
+}
+
+#[test]
+fn test_allowed_tokens_restrict_new_escrows() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let (_, _, usdc) = create_token_contract(&env, &admin);
+    let (_, _, other) = create_token_contract(&env, &admin);
+
+    client.init(&admin);
+    assert_eq!(client.get_allowed_tokens(), Vec::new(&env));
+    client.set_allowed_tokens(&admin, &vec![&env, usdc.clone()]);
+    assert_eq!(client.get_allowed_tokens(), vec![&env, usdc.clone()]);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 100,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Work"),
+        },
+    ];
+    let create = |escrow_id: u64, token: &Address| {
+        client.try_create_escrow(
+            &escrow_id,
+            &depositor,
+            &recipient,
+            token,
+            &milestones,
+            &1706400000u64,
+        )
+    };
+
+    assert_eq!(create(99, &other), Err(Ok(Error::NotAllowlisted)));
+    assert!(create(99, &usdc).is_ok());
+
+    client.set_allowed_tokens(&admin, &Vec::new(&env));
+    assert!(create(100, &other).is_ok());
+}
+
+#[test]
+fn test_testutils_builders_and_scenarios() {
+    use crate::testutils::{assert_auth, authorize, EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+
+    let escrow = EscrowBuilder::new(&vaultix, 1)
+        .milestone(MilestoneBuilder::new(300).description("Design"))
+        .milestone(MilestoneBuilder::new(700))
+        .funded()
+        .build();
+    assert_eq!(escrow.total, 1000);
+    assert_eq!(vaultix.client.get_state(&1), EscrowStatus::Active);
+    assert_eq!(
+        vaultix.client.get_milestone(&1, &0).description,
+        String::from_str(&env, "Design")
+    );
+
+    vaultix.client.release_milestone(&1, &0);
+    assert_auth(
+        &env,
+        &escrow.depositor,
+        &vaultix.address,
+        "release_milestone",
+    );
+
+    let completed = vaultix.completed_escrow(2, &[100, 200]);
+    assert_eq!(
+        vaultix.client.get_state(&completed.id),
+        EscrowStatus::Completed
+    );
+    let disputed = vaultix.disputed_escrow(3, &[100]);
+    assert_eq!(
+        vaultix.client.get_state(&disputed.id),
+        EscrowStatus::Disputed
+    );
+
+    // Exactly the depositor's signature for this release is mocked
+    authorize(
+        &env,
+        &escrow.depositor,
+        &vaultix.address,
+        "release_milestone",
+        (1u64, 1u32),
+    );
+    vaultix.client.release_milestone(&1, &1);
+    assert_eq!(vaultix.client.get_escrow(&1).total_released, 1000);
+}
+
+#[test]
+fn test_random_operation_sequences_preserve_invariants() {
+    use crate::testutils::Vaultix;
+
+    // xorshift64, so every failing sequence is reproducible from its seed
+    fn next(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    for seed in 1..=8u64 {
+        let env = Env::default();
+        let vaultix = Vaultix::setup(&env);
+        let escrow = vaultix.funded_escrow(1, &[100, 250, 400, 250]);
+        vaultix.check_invariants(1).unwrap();
+
+        let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        for _ in 0..40 {
+            let pick = next(&mut state);
+            match pick % 7 {
+                0 => {
+                    let _ = vaultix
+                        .client
+                        .try_release_milestone(&1, &((pick >> 8) as u32 % 5));
+                }
+                1 => {
+                    let caller = if pick & 0x100 == 0 {
+                        &escrow.depositor
+                    } else {
+                        &escrow.recipient
+                    };
+                    let _ =
+                        vaultix
+                            .client
+                            .try_raise_dispute(&1, caller, &DisputeReason::NonDelivery);
+                }
+                2 => {
+                    let winner = if pick & 0x100 == 0 {
+                        &escrow.depositor
+                    } else {
+                        &escrow.recipient
+                    };
+                    let _ =
+                        vaultix
+                            .client
+                            .try_resolve_dispute(&1, winner, &RulingReason::Other, &None);
+                }
+                3 => {
+                    let awarded = vec![&env, (pick >> 8) as u32 % 4];
+                    let _ = vaultix.client.try_resolve_dispute_partial(&1, &awarded);
+                }
+                4 => {
+                    let _ = vaultix.client.try_cancel_escrow(&1);
+                }
+                5 => {
+                    let _ = vaultix.client.try_complete_escrow(&1);
+                }
+                _ => env.ledger().with_mut(|ledger| {
+                    ledger.timestamp += (pick >> 8) % (3 * 24 * 60 * 60);
+                }),
+            }
+
+            if let Err(violation) = vaultix.check_invariants(1) {
+                panic!("seed {seed}: {violation}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_dispute_cost_does_not_grow_with_milestones() {
+    // Disputes only flip the released/disputed bitmaps, never the milestone entries
+    use crate::testutils::Vaultix;
+
+    // CPU instructions to raise and then resolve a dispute on an escrow with `milestones`
+    // milestones. A second escrow pads the ledger to the same number of entries either way,
+    // since host storage access itself gets dearer as the ledger grows.
+    fn dispute_cost(milestones: usize, winner_is_recipient: bool) -> (u64, u64) {
+        let env = Env::default();
+        let vaultix = Vaultix::setup(&env);
+        let amounts = [100i128; 21];
+        let escrow = vaultix.funded_escrow(1, &amounts[..milestones]);
+        vaultix.funded_escrow(2, &amounts[milestones..]);
+        let winner = if winner_is_recipient {
+            &escrow.recipient
+        } else {
+            &escrow.depositor
+        };
+
+        env.budget().reset_default();
+        vaultix
+            .client
+            .raise_dispute(&1, &escrow.recipient, &DisputeReason::NonDelivery);
+        let raise = env.budget().cpu_instruction_cost();
+
+        env.budget().reset_default();
+        vaultix
+            .client
+            .resolve_dispute(&1, winner, &RulingReason::Other, &None);
+        let resolve = env.budget().cpu_instruction_cost();
+        (raise, resolve)
+    }
+
+    for winner_is_recipient in [true, false] {
+        let (raise_one, resolve_one) = dispute_cost(1, winner_is_recipient);
+        let (raise_many, resolve_many) = dispute_cost(20, winner_is_recipient);
+        assert_eq!(raise_many, raise_one);
+        assert_eq!(resolve_many, resolve_one);
+    }
+}
+
+#[test]
+fn test_release_cost_does_not_grow_with_milestones() {
+    use crate::testutils::Vaultix;
+
+    // Releasing reads one milestone entry and rewrites only the escrow header, so releasing
+    // from a 20-milestone escrow costs what releasing from a two-milestone one does. The
+    // second escrow keeps the ledger the same size in both runs.
+    fn release_cost(milestones: usize) -> u64 {
+        let env = Env::default();
+        let vaultix = Vaultix::setup(&env);
+        let amounts = [100i128; 21];
+        vaultix.funded_escrow(1, &amounts[..milestones]);
+        vaultix.funded_escrow(2, &amounts[milestones..]);
+
+        env.budget().reset_default();
+        vaultix.client.release_milestone(&1, &0);
+        let cost = env.budget().cpu_instruction_cost();
+
+        // The stored milestone entry is untouched; its status lives in the header bitmap
+        let stored = env.as_contract(&vaultix.address, || {
+            crate::storage::load_milestone(&env, 1, 0).unwrap()
+        });
+        assert_eq!(stored.status, MilestoneStatus::Pending);
+        assert_eq!(
+            vaultix.client.get_milestone(&1, &0).status,
+            MilestoneStatus::Released
+        );
+        cost
+    }
+
+    // Host key lookups vary slightly with where the entries sort, hence the 1% slack
+    let few = release_cost(2);
+    let many = release_cost(20);
+    assert!(many <= few + few / 100, "{many} vs {few}");
+}
+
+#[test]
+fn test_get_milestones_page_walks_large_escrows() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    env.budget().reset_unlimited();
+    let vaultix = Vaultix::setup(&env);
+    let mut amounts = [0i128; 64];
+    for (index, amount) in amounts.iter_mut().enumerate() {
+        *amount = index as i128 + 1;
+    }
+    vaultix.funded_escrow(1, &amounts);
+    vaultix.client.release_milestone(&1, &30);
+
+    let mut seen = 0u32;
+    for page in 0..3 {
+        let milestones = vaultix.client.get_milestones_page(&1, &(page * 25), &25);
+        assert_eq!(milestones.len(), if page < 2 { 25 } else { 14 });
+        for milestone in milestones.iter() {
+            assert_eq!(milestone.amount, i128::from(seen) + 1);
+            let expected = if seen == 30 {
+                MilestoneStatus::Released
+            } else {
+                MilestoneStatus::Pending
+            };
+            assert_eq!(milestone.status, expected);
+            seen += 1;
+        }
+    }
+    assert_eq!(seen, 64);
+    assert!(vaultix.client.get_milestones_page(&1, &64, &25).is_empty());
+    assert_eq!(vaultix.client.get_milestones(&1).len(), 64);
+    assert_eq!(
+        vaultix.client.try_get_milestones_page(&2, &0, &25),
+        Err(Ok(Error::EscrowNotFound))
+    );
+}
+
+#[test]
+fn test_draft_milestones_editable_until_activation() {
+    use crate::testutils::{MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let token_address = vaultix.create_token();
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let milestone = |amount| MilestoneBuilder::new(amount).build(&env);
+
+    client.create_draft(
+        &1,
+        &depositor,
+        &recipient,
+        &token_address,
+        &vec![&env, milestone(100), milestone(200), milestone(300)],
+        &(env.ledger().timestamp() + 86_400),
+    );
+    assert_eq!(client.get_state(&1), EscrowStatus::Draft);
+
+    // Renegotiated down to two milestones; the dropped one is gone from storage
+    client.update_draft(&1, &vec![&env, milestone(250), milestone(350)]);
+    let escrow = client.get_escrow(&1);
+    assert_eq!((escrow.milestone_count, escrow.total_amount), (2, 600));
+    assert_eq!(client.get_milestone(&1, &1).amount, 350);
+    assert_eq!(
+        client.try_get_milestone(&1, &2).err(),
+        Some(Ok(Error::MilestoneNotFound))
+    );
+    assert_eq!(
+        client.try_deposit_funds(&1),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+
+    client.activate_draft(&1);
+    assert_eq!(client.get_state(&1), EscrowStatus::Created);
+    assert_eq!(
+        client.try_update_draft(&1, &vec![&env, milestone(600)]),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+    assert_eq!(
+        client.try_activate_draft(&1),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+
+    token::StellarAssetClient::new(&env, &token_address).mint(&depositor, &600);
+    token::Client::new(&env, &token_address).approve(
+        &depositor,
+        &vaultix.address,
+        &600,
+        &(env.ledger().sequence() + 1_000),
+    );
+    client.deposit_funds(&1);
+    assert_eq!(client.get_state(&1), EscrowStatus::Active);
+
+    // An abandoned draft is simply cancelled
+    client.create_draft(
+        &2,
+        &depositor,
+        &recipient,
+        &token_address,
+        &vec![&env, milestone(100)],
+        &(env.ledger().timestamp() + 86_400),
+    );
+    client.cancel_escrow(&2);
+    assert_eq!(client.get_state(&2), EscrowStatus::Cancelled);
+}
+
+#[test]
+fn test_proposed_escrow_needs_recipient_acceptance() {
+    use crate::testutils::{assert_auth, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let token_address = vaultix.create_token();
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let milestones = vec![&env, MilestoneBuilder::new(500).build(&env)];
+    let deadline = env.ledger().timestamp() + 86_400;
+
+    client.propose_escrow(
+        &1,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &deadline,
+    );
+    assert_eq!(client.get_state(&1), EscrowStatus::Proposed);
+    assert_eq!(
+        client.try_deposit_funds(&1),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+    assert_eq!(
+        client.try_release_milestone(&1, &0),
+        Err(Ok(Error::EscrowNotActive))
+    );
+
+    client.accept_escrow(&1);
+    assert_auth(&env, &recipient, &vaultix.address, "accept_escrow");
+    assert_eq!(client.get_state(&1), EscrowStatus::Created);
+    assert_eq!(
+        client.try_accept_escrow(&1),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+
+    token::StellarAssetClient::new(&env, &token_address).mint(&depositor, &500);
+    token::Client::new(&env, &token_address).approve(
+        &depositor,
+        &vaultix.address,
+        &500,
+        &(env.ledger().sequence() + 1_000),
+    );
+    client.deposit_funds(&1);
+    client.release_milestone(&1, &0);
+    assert_eq!(client.get_escrow(&1).total_released, 500);
+
+    // The depositor may withdraw a proposal nobody has accepted
+    client.propose_escrow(
+        &2,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &deadline,
+    );
+    client.cancel_escrow(&2);
+    assert_eq!(client.get_state(&2), EscrowStatus::Cancelled);
+}
+
+#[test]
+fn test_proposal_expires_after_its_ledger() {
+    use crate::testutils::{MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let token_address = vaultix.create_token();
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let milestones = vec![&env, MilestoneBuilder::new(500).build(&env)];
+    let deadline = env.ledger().timestamp() + 86_400;
+    let now = env.ledger().sequence();
+
+    client.propose_escrow(
+        &1,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &deadline,
+    );
+    assert_eq!(
+        client.get_offer_expiry(&1),
+        Some(now + crate::proposal::DEFAULT_OFFER_LIFETIME)
+    );
+    assert_eq!(
+        client.try_set_offer_expiry(&1, &now),
+        Err(Ok(Error::InvalidDeadline))
+    );
+    client.set_offer_expiry(&1, &(now + 10));
+    assert_eq!(client.try_expire_offer(&1), Err(Ok(Error::InvalidDeadline)));
+
+    env.ledger()
+        .with_mut(|ledger| ledger.sequence_number = now + 11);
+    assert_eq!(
+        client.try_accept_escrow(&1),
+        Err(Ok(Error::InvalidDeadline))
+    );
+    assert_eq!(
+        client.try_set_offer_expiry(&1, &(now + 100)),
+        Err(Ok(Error::InvalidDeadline))
+    );
+    client.expire_offer(&1);
+    assert_eq!(client.get_state(&1), EscrowStatus::Cancelled);
+    assert_eq!(client.get_offer_expiry(&1), None);
+}
+
+#[test]
+fn test_counter_offers_alternate_until_accepted() {
+    use crate::testutils::{assert_auth, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let token_address = vaultix.create_token();
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let milestone = |amount| MilestoneBuilder::new(amount).build(&env);
+    let deadline = env.ledger().timestamp() + 86_400;
+
+    client.propose_escrow(
+        &1,
+        &depositor,
+        &recipient,
+        &token_address,
+        &vec![&env, milestone(500)],
+        &deadline,
+    );
+
+    // The recipient asks for more, split over two milestones and a later deadline
+    let counter = vec![&env, milestone(300), milestone(400)];
+    assert_eq!(
+        client.counter_offer(&1, &recipient, &counter, &(deadline + 3_600)),
+        1
+    );
+    assert_eq!(
+        client.try_counter_offer(&1, &recipient, &counter, &deadline),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    let escrow = client.get_escrow(&1);
+    assert_eq!(
+        (escrow.total_amount, escrow.milestone_count, escrow.deadline),
+        (700, 2, deadline + 3_600)
+    );
+
+    // The depositor meets them halfway, and the recipient signs that version
+    client.counter_offer(&1, &depositor, &vec![&env, milestone(600)], &deadline);
+    client.accept_escrow(&1);
+    assert_auth(&env, &recipient, &vaultix.address, "accept_escrow");
+    assert_eq!(client.get_state(&1), EscrowStatus::Created);
+
+    let offers = client.get_offers(&1);
+    assert_eq!(offers.len(), 3);
+    assert_eq!(offers.get(1).unwrap().proposer, recipient);
+    assert_eq!(offers.get(2).unwrap().total_amount, 600);
+    assert_eq!(client.get_escrow(&1).total_amount, 600);
+    assert_eq!(
+        client.try_get_milestone(&1, &1).err(),
+        Some(Ok(Error::MilestoneNotFound))
+    );
+
+    // Negotiations are bounded
+    client.propose_escrow(
+        &2,
+        &depositor,
+        &recipient,
+        &token_address,
+        &vec![&env, milestone(500)],
+        &deadline,
+    );
+    for round in 1..crate::proposal::MAX_OFFERS {
+        let caller = if round % 2 == 1 {
+            &recipient
+        } else {
+            &depositor
+        };
+        client.counter_offer(&2, caller, &vec![&env, milestone(500)], &deadline);
+    }
+    assert_eq!(
+        client.try_counter_offer(&2, &depositor, &vec![&env, milestone(500)], &deadline),
+        Err(Ok(Error::VectorTooLarge))
+    );
+}
+
+#[test]
+fn test_open_escrow_is_claimed_by_listed_recipient() {
+    use crate::testutils::{MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let token_address = vaultix.create_token();
+    let token = token::Client::new(&env, &token_address);
+    let depositor = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    let milestones = vec![&env, MilestoneBuilder::new(400).build(&env)];
+    let deadline = env.ledger().timestamp() + 86_400;
+
+    token::StellarAssetClient::new(&env, &token_address).mint(&depositor, &800);
+    token.approve(&depositor, &vaultix.address, &800, &1_000);
+
+    // The reward is locked as soon as the bounty is posted
+    client.create_open_escrow(
+        &1,
+        &depositor,
+        &token_address,
+        &milestones,
+        &deadline,
+        &Some(vec![&env, freelancer.clone()]),
+    );
+    assert_eq!(client.get_state(&1), EscrowStatus::Open);
+    assert_eq!(token.balance(&vaultix.address), 400);
+    assert_eq!(
+        client.try_release_milestone(&1, &0),
+        Err(Ok(Error::EscrowNotActive))
+    );
+
+    assert_eq!(
+        client.try_claim_open_escrow(&1, &outsider),
+        Err(Ok(Error::NotAllowlisted))
+    );
+    assert_eq!(
+        client.try_claim_open_escrow(&1, &depositor),
+        Err(Ok(Error::SelfDealing))
+    );
+    client.claim_open_escrow(&1, &freelancer);
+    assert_eq!(client.get_state(&1), EscrowStatus::Active);
+    assert_eq!(client.get_escrow(&1).recipient, freelancer);
+    assert_eq!(client.get_open_claimants(&1), None);
+    assert_eq!(
+        client.try_claim_open_escrow(&1, &freelancer),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+
+    client.release_milestone(&1, &0);
+    assert_eq!(client.get_escrow(&1).total_released, 400);
+    assert!(token.balance(&freelancer) > 0);
+
+    // An unclaimed bounty can be withdrawn for a refund
+    client.create_open_escrow(
+        &2,
+        &depositor,
+        &token_address,
+        &milestones,
+        &deadline,
+        &None,
+    );
+    client.cancel_escrow(&2);
+    assert_eq!(client.get_state(&2), EscrowStatus::Cancelled);
+    assert_eq!(token.balance(&depositor), 400);
+}
+
+/// Token whose `transfer_from` rounds every amount up to a whole 100 units
+#[soroban_sdk::contract]
+pub struct MockRoundingToken;
+
+#[soroban_sdk::contractimpl]
+impl MockRoundingToken {
+    pub fn credit(env: Env, to: Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone());
+        env.storage().persistent().set(&to, &(balance + amount));
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().persistent().get(&id).unwrap_or(0)
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        Self::credit(env.clone(), from, -amount);
+        Self::credit(env, to, amount);
+    }
+
+    pub fn transfer_from(env: Env, _spender: Address, from: Address, to: Address, amount: i128) {
+        let rounded = (amount + 99) / 100 * 100;
+        Self::transfer(env, from, to, rounded);
+    }
+}
+
+#[test]
+fn test_funding_overage_is_refundable() {
+    use crate::testutils::{MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let token_address = env.register_contract(None, MockRoundingToken);
+    let token = MockRoundingTokenClient::new(&env, &token_address);
+    let depositor = Address::generate(&env);
+    let deadline = env.ledger().timestamp() + 86_400;
+
+    token.credit(&depositor, &1_000);
+    client.create_escrow(
+        &1,
+        &depositor,
+        &Address::generate(&env),
+        &token_address,
+        &vec![&env, MilestoneBuilder::new(250).build(&env)],
+        &deadline,
+    );
+    assert_eq!(client.get_excess(&1), 0);
+
+    client.deposit_funds(&1);
+    assert_eq!(token.balance(&vaultix.address), 300);
+    assert_eq!(client.get_escrow(&1).total_amount, 250);
+    assert_eq!(client.get_excess(&1), 50);
+
+    // The overage is the depositor's whatever state the escrow is in
+    assert_eq!(client.refund_excess(&1), 50);
+    assert_eq!(token.balance(&depositor), 750);
+    assert_eq!(token.balance(&vaultix.address), 250);
+    assert_eq!(client.get_excess(&1), 0);
+    assert_eq!(client.refund_excess(&1), 0);
+    assert_eq!(client.get_state(&1), EscrowStatus::Active);
+}
+
+#[test]
+fn test_progressive_funding_unlocks_milestones_in_order() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let staged = |escrow_id| {
+        let escrow = EscrowBuilder::new(&vaultix, escrow_id)
+            .milestone(MilestoneBuilder::new(100))
+            .milestone(MilestoneBuilder::new(200))
+            .milestone(MilestoneBuilder::new(300))
+            .build();
+        token::StellarAssetClient::new(&env, &escrow.token).mint(&escrow.depositor, &600);
+        token::Client::new(&env, &escrow.token).approve(
+            &escrow.depositor,
+            &vaultix.address,
+            &600,
+            &1_000,
+        );
+        escrow
+    };
+    let statuses = |escrow_id| {
+        let mut statuses = Vec::new(&env);
+        for milestone in client.get_milestones(&escrow_id).iter() {
+            statuses.push_back(milestone.status);
+        }
+        statuses
+    };
+
+    let escrow = staged(1);
+    let token = token::Client::new(&env, &escrow.token);
+    assert_eq!(client.get_funding(&1), (0, 0));
+    client.fund_milestones(&1, &1);
+    assert_eq!(client.get_state(&1), EscrowStatus::Active);
+    assert_eq!(client.get_funding(&1), (1, 100));
+    assert_eq!(token.balance(&vaultix.address), 100);
+    assert_eq!(
+        statuses(1),
+        vec![
+            &env,
+            MilestoneStatus::Pending,
+            MilestoneStatus::Unfunded,
+            MilestoneStatus::Unfunded
+        ]
+    );
+    assert_eq!(client.get_pending_milestones(&1), vec![&env, 0]);
+    assert_eq!(
+        client.try_release_milestone(&1, &1),
+        Err(Ok(Error::InsufficientBalance))
+    );
+    assert_eq!(
+        client.try_fund_milestones(&1, &3),
+        Err(Ok(Error::MilestoneNotFound))
+    );
+    client.release_milestone(&1, &0);
+
+    client.fund_milestones(&1, &1);
+    assert_eq!(client.get_funding(&1), (2, 300));
+    assert_eq!(client.get_remaining_amount(&1), 200);
+    assert_eq!(vaultix.check_invariants(1), Ok(()));
+
+    // A dispute can only award what was actually deposited
+    client.raise_dispute(&1, &escrow.recipient, &DisputeReason::NonDelivery);
+    client.resolve_dispute(&1, &escrow.recipient, &RulingReason::WorkAccepted, &None);
+    assert_eq!(token.balance(&vaultix.address), 0);
+    assert_eq!(client.get_escrow(&1).total_released, 300);
+    assert_eq!(statuses(1).get(2), Some(MilestoneStatus::Unfunded));
+    assert_eq!(vaultix.check_invariants(1), Ok(()));
+
+    // Cancelling refunds the deposits made so far; funding everything ends staging
+    let escrow = staged(2);
+    client.fund_milestones(&2, &1);
+    client.cancel_escrow(&2);
+    assert_eq!(
+        token::Client::new(&env, &escrow.token).balance(&escrow.depositor),
+        600
+    );
+
+    staged(3);
+    client.fund_milestones(&3, &2);
+    client.fund_milestones(&3, &1);
+    assert_eq!(client.get_funding(&3), (3, 600));
+    assert_eq!(
+        client.try_fund_milestones(&3, &1),
+        Err(Ok(Error::EscrowAlreadyFunded))
+    );
+}
+
+#[test]
+fn test_funding_plan_tracks_arrears() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    env.ledger().with_mut(|li| li.sequence_number = 100);
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let escrow = EscrowBuilder::new(&vaultix, 1)
+        .milestone(MilestoneBuilder::new(100))
+        .milestone(MilestoneBuilder::new(200))
+        .milestone(MilestoneBuilder::new(300))
+        .build();
+    token::StellarAssetClient::new(&env, &escrow.token).mint(&escrow.depositor, &600);
+    token::Client::new(&env, &escrow.token).approve(
+        &escrow.depositor,
+        &vaultix.address,
+        &600,
+        &1_000,
+    );
+    let installment = |amount, due_ledger| Installment { amount, due_ledger };
+
+    assert_eq!(
+        client.try_fund_installment(&1),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+    // Installments must end on milestone boundaries, fall due in order and cover the total
+    for (plan, error) in [
+        (
+            vec![&env, installment(150, 110), installment(450, 200)],
+            Error::TotalAmountMismatch,
+        ),
+        (
+            vec![&env, installment(100, 200), installment(500, 110)],
+            Error::InvalidDeadline,
+        ),
+        (
+            vec![&env, installment(300, 110)],
+            Error::TotalAmountMismatch,
+        ),
+    ] {
+        assert_eq!(client.try_set_funding_plan(&1, &plan), Err(Ok(error)));
+    }
+    let plan = vec![&env, installment(100, 110), installment(500, 200)];
+    client.set_funding_plan(&1, &plan);
+    assert_eq!(client.get_funding_plan(&1), Some(plan));
+    assert_eq!(client.get_funding_arrears(&1), 0);
+
+    env.ledger().with_mut(|li| li.sequence_number = 120);
+    assert_eq!(client.get_funding_arrears(&1), 100);
+    assert_eq!(client.fund_installment(&1), 0);
+    assert_eq!(client.get_state(&1), EscrowStatus::Active);
+    assert_eq!(client.get_funding(&1), (1, 100));
+    assert_eq!(client.get_funding_arrears(&1), 0);
+
+    // Paying ahead counts towards the installment, which then only asks for the rest
+    env.ledger().with_mut(|li| li.sequence_number = 250);
+    assert_eq!(client.get_funding_arrears(&1), 500);
+    client.fund_milestones(&1, &1);
+    assert_eq!(client.get_funding_arrears(&1), 300);
+    assert_eq!(client.fund_installment(&1), 1);
+    assert_eq!(client.get_funding(&1), (3, 600));
+    assert_eq!(client.get_funding_arrears(&1), 0);
+    assert_eq!(
+        client.try_fund_installment(&1),
+        Err(Ok(Error::EscrowAlreadyFunded))
+    );
+}
+
+#[test]
+fn test_yield_is_paid_out_under_escrow_policy() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let token_address = vaultix.create_token();
+    let token = token::Client::new(&env, &token_address);
+    let source = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&source, &1_000);
+    let escrow_with = |escrow_id, policy| {
+        let escrow = EscrowBuilder::new(&vaultix, escrow_id)
+            .token(&token_address)
+            .milestone(MilestoneBuilder::new(1_000))
+            .build();
+        client.set_yield_policy(&escrow_id, &policy);
+        token::StellarAssetClient::new(&env, &token_address).mint(&escrow.depositor, &1_000);
+        token.approve(&escrow.depositor, &vaultix.address, &1_000, &1_000);
+        client.deposit_funds(&escrow_id);
+        escrow
+    };
+
+    let escrow = escrow_with(1, YieldPolicy::Split);
+    assert_eq!(
+        client.try_credit_yield(&1, &101),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    client.set_yield_source(&vaultix.admin, &Some(source.clone()));
+    client.credit_yield(&1, &101);
+    assert_eq!(client.get_accrued_yield(&1), 101);
+    assert_eq!(
+        client.try_set_yield_policy(&1, &YieldPolicy::Recipient),
+        Err(Ok(Error::EscrowAlreadyFunded))
+    );
+
+    client.release_milestone(&1, &0);
+    let paid = token.balance(&escrow.recipient);
+    client.complete_escrow(&1);
+    assert_eq!(token.balance(&escrow.recipient), paid + 50);
+    assert_eq!(token.balance(&escrow.depositor), 51);
+    assert_eq!(client.get_accrued_yield(&1), 0);
+
+    // Yield on a cancelled escrow can go to the insurance pool instead
+    let escrow = escrow_with(2, YieldPolicy::FeePool);
+    let pool = client.get_insurance_pool(&token_address);
+    client.credit_yield(&2, &40);
+    client.cancel_escrow(&2);
+    assert_eq!(token.balance(&escrow.depositor), 1_000);
+    assert_eq!(client.get_insurance_pool(&token_address), pool + 40);
+}
+
+#[test]
+fn test_fee_tiers_discount_high_volume_parties() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let veteran = vaultix.completed_escrow(1, &[10_000]).recipient;
+
+    let unordered = vec![
+        &env,
+        FeeTier {
+            min_volume: 5_000,
+            fee_bps: 20,
+        },
+        FeeTier {
+            min_volume: 1_000,
+            fee_bps: 30,
+        },
+    ];
+    assert_eq!(
+        client.try_set_fee_tiers(&vaultix.admin, &unordered),
+        Err(Ok(Error::InvalidFeeConfiguration))
+    );
+    let tiers = vec![
+        &env,
+        FeeTier {
+            min_volume: 5_000,
+            fee_bps: 20,
+        },
+        FeeTier {
+            min_volume: 50_000,
+            fee_bps: 0,
+        },
+    ];
+    client.set_fee_tiers(&vaultix.treasury, &tiers);
+    assert_eq!(client.get_fee_tiers(), tiers);
+
+    let escrow = EscrowBuilder::new(&vaultix, 2)
+        .recipient(&veteran)
+        .milestone(MilestoneBuilder::new(10_000))
+        .funded()
+        .build();
+    let newcomer = vaultix.funded_escrow(3, &[10_000]);
+    assert_eq!(client.get_fee_bps(&2), 20);
+    assert_eq!(client.get_fee_bps(&3), 50);
+
+    let token = token::Client::new(&env, &escrow.token);
+    let before = token.balance(&veteran);
+    client.release_milestone(&2, &0);
+    assert_eq!(token.balance(&veteran), before + 9_980);
+    client.release_milestone(&3, &0);
+    assert_eq!(
+        token::Client::new(&env, &newcomer.token).balance(&newcomer.recipient),
+        9_950
+    );
+}
+
+#[test]
+fn test_fee_exempt_parties_skip_platform_fee() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let escrow = vaultix.funded_escrow(1, &[1_000, 1_000]);
+    let token = token::Client::new(&env, &escrow.token);
+
+    client.set_fee_exempt(&vaultix.admin, &escrow.depositor, &true);
+    assert!(client.is_fee_exempt(&escrow.depositor));
+    assert_eq!(client.get_fee_bps(&1), 0);
+    client.release_milestone(&1, &0);
+    assert_eq!(token.balance(&escrow.recipient), 1_000);
+    assert_eq!(token.balance(&vaultix.treasury), 0);
+
+    client.set_fee_exempt(&vaultix.admin, &escrow.depositor, &false);
+    assert!(!client.is_fee_exempt(&escrow.depositor));
+    client.release_milestone(&1, &1);
+    assert_eq!(token.balance(&escrow.recipient), 1_995);
+}
+
+#[test]
+fn test_fee_is_charged_in_configured_fee_token() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let escrow = vaultix.funded_escrow(1, &[1_000, 1_000]);
+    let fee_token_address = vaultix.create_token();
+    let fee_token = token::Client::new(&env, &fee_token_address);
+    let token = token::Client::new(&env, &escrow.token);
+
+    let mut config = FeeToken {
+        token: escrow.token.clone(),
+        rate: 20_000_000,
+    };
+    assert_eq!(
+        client.try_set_fee_token(&vaultix.treasury, &escrow.token, &Some(config.clone())),
+        Err(Ok(Error::InvalidFeeConfiguration))
+    );
+    config.token = fee_token_address.clone();
+    client.set_fee_token(&vaultix.treasury, &escrow.token, &Some(config.clone()));
+    assert_eq!(client.get_fee_token(&escrow.token), Some(config));
+
+    // A 0.5% fee on 1,000 is 5 units of the asset, or 10 fee tokens at a rate of 2
+    token::StellarAssetClient::new(&env, &fee_token_address).mint(&escrow.depositor, &10);
+    fee_token.approve(&escrow.depositor, &vaultix.address, &10, &1_000);
+    client.release_milestone(&1, &0);
+    assert_eq!(token.balance(&escrow.recipient), 1_000);
+    assert_eq!(fee_token.balance(&escrow.depositor), 0);
+    assert_eq!(fee_token.balance(&vaultix.treasury), 10);
+    assert_eq!(token.balance(&vaultix.treasury), 0);
+
+    client.set_fee_token(&vaultix.treasury, &escrow.token, &None);
+    client.release_milestone(&1, &1);
+    assert_eq!(token.balance(&escrow.recipient), 1_995);
+}
+
+#[test]
+fn test_payout_memo_is_published_with_payouts() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let escrow = vaultix.funded_escrow(1, &[1_000, 1_000]);
+
+    assert_eq!(
+        client.try_set_payout_memo(
+            &escrow.recipient,
+            &Some(String::from_bytes(&env, &[b'7'; 29]))
+        ),
+        Err(Ok(Error::DescriptionTooLong))
+    );
+    let memo = String::from_str(&env, "104729");
+    client.set_payout_memo(&escrow.recipient, &Some(memo.clone()));
+    assert_eq!(
+        client.get_payout_memo(&escrow.recipient),
+        Some(memo.clone())
+    );
+
+    client.release_milestone(&1, &1);
+    let mut published = Vec::new(&env);
+    for (_, topics, data) in env.events().all().iter() {
+        let first: Symbol = topics.get(0).unwrap().into_val(&env);
+        if first == symbol_short!("memo") {
+            let index: u32 = topics.get(2).unwrap().into_val(&env);
+            let (payee, amount, memo): (Address, i128, String) = data.into_val(&env);
+            published.push_back((index, payee, amount, memo));
+        }
+    }
+    assert_eq!(
+        published,
+        vec![&env, (1, escrow.recipient.clone(), 995, memo)]
+    );
+
+    client.set_payout_memo(&escrow.recipient, &None);
+    assert_eq!(client.get_payout_memo(&escrow.recipient), None);
+}
+
+#[test]
+fn test_get_escrows_returns_existing_ids() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    vaultix.funded_escrow(1, &[500]);
+    vaultix.completed_escrow(2, &[700]);
+
+    let escrows = client.get_escrows(&vec![&env, 1, 2, 3]);
+    assert_eq!(escrows.len(), 2);
+    assert_eq!(escrows.get(1).unwrap().status, EscrowStatus::Active);
+    assert_eq!(escrows.get(2).unwrap().total_released, 700);
+    assert!(!escrows.contains_key(3));
+
+    let mut too_many = Vec::new(&env);
+    for escrow_id in 0..51u64 {
+        too_many.push_back(escrow_id);
+    }
+    assert_eq!(
+        client.try_get_escrows(&too_many),
+        Err(Ok(Error::VectorTooLarge))
+    );
+}
+
+#[test]
+fn test_multicall_releases_atomically() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let first = vaultix.funded_escrow(1, &[100, 200]);
+    EscrowBuilder::new(&vaultix, 2)
+        .depositor(&first.depositor)
+        .milestone(MilestoneBuilder::new(300))
+        .funded()
+        .build();
+
+    let hash = BytesN::from_array(&env, &[7; 32]);
+    assert_eq!(
+        client.try_multicall(
+            &first.depositor,
+            &vec![&env, EscrowOp::Submit(1, 0, hash.clone())]
+        ),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    client.multicall(
+        &first.recipient,
+        &vec![&env, EscrowOp::Submit(1, 0, hash.clone())],
+    );
+    assert!(client.get_milestone_review(&1, &0).submitted);
+
+    // A failing operation rolls back the ones before it
+    assert_eq!(
+        client.try_multicall(
+            &first.depositor,
+            &vec![&env, EscrowOp::Release(1, 1), EscrowOp::Release(1, 2)]
+        ),
+        Err(Ok(Error::MilestoneNotFound))
+    );
+    assert_eq!(client.get_escrow(&1).total_released, 0);
+
+    client.multicall(
+        &first.depositor,
+        &vec![
+            &env,
+            EscrowOp::Release(1, 0),
+            EscrowOp::Release(1, 1),
+            EscrowOp::Release(2, 0),
+        ],
+    );
+    assert_eq!(client.get_escrow(&1).total_released, 300);
+    assert_eq!(client.get_escrow(&2).total_released, 300);
+}
+
+#[test]
+fn test_previews_match_executed_payouts() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let escrow = vaultix.funded_escrow(1, &[1_000, 1_000]);
+    let token = token::Client::new(&env, &escrow.token);
+    client.set_insurance_share(&vaultix.treasury, &5000);
+
+    let preview = client.preview_release(&1, &0);
+    assert_eq!(
+        preview,
+        PayoutPreview {
+            to_recipient: 995,
+            to_depositor: 0,
+            to_treasury: 3,
+            to_insurance: 2,
+            to_arbitrator: 0,
+            fee_asset: escrow.token.clone(),
+        }
+    );
+    client.release_milestone(&1, &0);
+    assert_eq!(token.balance(&escrow.recipient), preview.to_recipient);
+    assert_eq!(token.balance(&vaultix.treasury), preview.to_treasury);
+    assert_eq!(
+        client.try_preview_release(&1, &0),
+        Err(Ok(Error::MilestoneAlreadyReleased))
+    );
+
+    // A ruling for the recipient pays the rest plus their bond, less the arbitrator's cut
+    let arbitrator = Address::generate(&env);
+    let mut config = client.get_config();
+    config.dispute_bond = 200;
+    client.set_config(&vaultix.admin, &config);
+    client.set_arbitration_config(
+        &vaultix.admin,
+        &ArbitrationConfig {
+            stake_token: escrow.token.clone(),
+            min_stake: 0,
+            slash_bps: 0,
+            resolution_window: 0,
+            fee_bps: 2500,
+        },
+    );
+    client.add_arbitrator(&vaultix.admin, &arbitrator);
+    token::StellarAssetClient::new(&env, &escrow.token).mint(&escrow.recipient, &200);
+    token.approve(&escrow.recipient, &vaultix.address, &200, &1_000);
+    assert_eq!(
+        client.try_preview_resolution(&1, &escrow.recipient),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+    client.raise_dispute(&1, &escrow.recipient, &DisputeReason::Other);
+    assert_eq!(
+        client.try_preview_resolution(&1, &arbitrator),
+        Err(Ok(Error::InvalidWinner))
+    );
+
+    let preview = client.preview_resolution(&1, &escrow.recipient);
+    assert_eq!(preview.to_recipient, 1_000 + 150);
+    assert_eq!(preview.to_arbitrator, 50);
+    let before = token.balance(&escrow.recipient);
+    client.resolve_dispute(&1, &escrow.recipient, &RulingReason::WorkAccepted, &None);
+    assert_eq!(
+        token.balance(&escrow.recipient),
+        before + preview.to_recipient
+    );
+    assert_eq!(
+        client.get_claimable_fees(&arbitrator, &escrow.token),
+        preview.to_arbitrator
+    );
+}
+
+#[test]
+fn test_child_escrow_releases_after_parent_completes() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let parent = vaultix.funded_escrow(1, &[1_000]);
+    let child = EscrowBuilder::new(&vaultix, 2)
+        .depositor(&parent.recipient)
+        .milestone(MilestoneBuilder::new(400))
+        .build();
+
+    assert_eq!(
+        client.try_set_parent_escrow(&1, &2),
+        Err(Ok(Error::EscrowAlreadyFunded))
+    );
+    assert_eq!(
+        client.try_set_parent_escrow(&2, &2),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+    client.set_parent_escrow(&2, &1);
+    assert_eq!(client.get_parent_escrow(&2), Some(1));
+
+    token::StellarAssetClient::new(&env, &child.token).mint(&child.depositor, &400);
+    token::Client::new(&env, &child.token).approve(
+        &child.depositor,
+        &vaultix.address,
+        &400,
+        &1_000,
+    );
+    client.deposit_funds(&2);
+    assert_eq!(
+        client.try_release_milestone(&2, &0),
+        Err(Ok(Error::ParentNotCompleted))
+    );
+
+    client.release_milestone(&1, &0);
+    client.complete_escrow(&1);
+    client.release_milestone(&2, &0);
+    assert_eq!(client.get_escrow(&2).total_released, 400);
+}
+
+#[test]
+fn test_release_fires_cross_escrow_triggers() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let prime = vaultix.funded_escrow(1, &[1_000]);
+    EscrowBuilder::new(&vaultix, 2)
+        .depositor(&prime.recipient)
+        .milestone(MilestoneBuilder::new(300))
+        .milestone(MilestoneBuilder::new(300))
+        .funded()
+        .build();
+
+    let release = MilestoneTrigger {
+        escrow_id: 2,
+        milestone_index: 0,
+        action: TriggerAction::Release,
+    };
+    let submit = MilestoneTrigger {
+        escrow_id: 2,
+        milestone_index: 1,
+        action: TriggerAction::Submit,
+    };
+    client.add_milestone_trigger(&1, &0, &release);
+    client.add_milestone_trigger(&1, &0, &submit);
+    assert_eq!(
+        client.get_milestone_triggers(&1, &0),
+        vec![&env, release.clone(), submit]
+    );
+
+    let hash = BytesN::from_array(&env, &[9; 32]);
+    client.submit_milestone(&1, &0, &hash, &None);
+    client.release_milestone(&1, &0);
+
+    let sub = client.get_escrow(&2);
+    assert_eq!(sub.total_released, 300);
+    assert!(client.get_milestone_review(&2, &1).submitted);
+    assert_eq!(client.get_deliverable(&2, &1).unwrap().hash, hash);
+    assert_eq!(client.get_milestone_triggers(&1, &0).len(), 0);
+    assert_eq!(
+        client.try_add_milestone_trigger(&1, &0, &release),
+        Err(Ok(Error::MilestoneAlreadyReleased))
+    );
+}
+
+#[test]
+fn test_net_escrows_settles_only_the_difference() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let token_address = vaultix.create_token();
+    let token = token::Client::new(&env, &token_address);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    // Alice owes Bob 500 + 300, Bob owes Alice 500
+    EscrowBuilder::new(&vaultix, 1)
+        .depositor(&alice)
+        .recipient(&bob)
+        .token(&token_address)
+        .milestone(MilestoneBuilder::new(500))
+        .milestone(MilestoneBuilder::new(300))
+        .build();
+    EscrowBuilder::new(&vaultix, 2)
+        .depositor(&bob)
+        .recipient(&alice)
+        .token(&token_address)
+        .milestone(MilestoneBuilder::new(500))
+        .build();
+    EscrowBuilder::new(&vaultix, 3)
+        .depositor(&bob)
+        .recipient(&alice)
+        .token(&token_address)
+        .milestone(MilestoneBuilder::new(400))
+        .build();
+
+    assert_eq!(
+        client.try_net_escrows(&1, &3),
+        Err(Ok(Error::TotalAmountMismatch))
+    );
+    assert_eq!(client.net_escrows(&1, &2), 500);
+
+    assert_eq!(client.get_escrow(&2).status, EscrowStatus::Completed);
+    let netted = client.get_escrow(&1);
+    assert_eq!(netted.status, EscrowStatus::Active);
+    assert_eq!(netted.total_released, 500);
+    assert_eq!(client.get_funding(&1), (1, 500));
+    assert_eq!(
+        client.try_net_escrows(&1, &3),
+        Err(Ok(Error::EscrowAlreadyFunded))
+    );
+
+    // Only the difference is ever deposited and paid
+    token::StellarAssetClient::new(&env, &token_address).mint(&alice, &300);
+    token.approve(&alice, &vaultix.address, &300, &1_000);
+    client.fund_milestones(&1, &1);
+    client.release_milestone(&1, &1);
+    client.complete_escrow(&1);
+    assert_eq!(token.balance(&bob), 300 - 1);
+    assert_eq!(vaultix.check_invariants(1), Ok(()));
+    assert_eq!(vaultix.check_invariants(2), Ok(()));
+}
+
+#[test]
+fn test_statement_summarizes_settlement() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let mut config = client.get_config();
+    config.dispute_bond = 50;
+    client.set_config(&vaultix.admin, &config);
+
+    let escrow = vaultix.funded_escrow(1, &[600, 400]);
+    client.release_milestone(&1, &0);
+
+    let statement = client.get_statement(&1);
+    assert_eq!(statement.status, EscrowStatus::Active);
+    assert_eq!(statement.lines.len(), 2);
+    assert_eq!(
+        statement.lines.get(0).unwrap().status,
+        MilestoneStatus::Released
+    );
+    assert_eq!(statement.deposited, 1000);
+    assert_eq!(statement.released, 600);
+    assert_eq!(statement.fees, 3);
+    assert_eq!(statement.recipient_net, 597);
+    assert_eq!(statement.held, 400);
+    assert_eq!(statement.refunded, 0);
+
+    // The recipient disputes the rest, loses, and forfeits the bond
+    token::StellarAssetClient::new(&env, &escrow.token).mint(&escrow.recipient, &50);
+    token::Client::new(&env, &escrow.token).approve(
+        &escrow.recipient,
+        &vaultix.address,
+        &50,
+        &1_000,
+    );
+    client.raise_dispute(&1, &escrow.recipient, &DisputeReason::Other);
+    client.resolve_dispute(&1, &escrow.depositor, &RulingReason::NotDelivered, &None);
+
+    let statement = client.get_statement(&1);
+    assert_eq!(statement.status, EscrowStatus::Resolved);
+    assert_eq!(statement.released, 600);
+    assert_eq!(statement.held, 0);
+    assert_eq!(statement.refunded, 400);
+    assert_eq!(statement.forfeited_bond, 50);
+    assert_eq!(client.try_get_statement(&2), Err(Ok(Error::EscrowNotFound)));
+}
+
+#[test]
+fn test_export_escrows_pages_in_creation_order() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    vaultix.funded_escrow(30, &[100]);
+    vaultix.completed_escrow(10, &[200]);
+    vaultix.funded_escrow(20, &[300]);
+
+    assert_eq!(
+        client
+            .try_export_escrows(&Address::generate(&env), &0, &2)
+            .err(),
+        Some(Ok(Error::UnauthorizedAccess))
+    );
+    assert_eq!(
+        client.try_export_escrows(&vaultix.admin, &0, &51).err(),
+        Some(Ok(Error::VectorTooLarge))
+    );
+
+    let page = client.export_escrows(&vaultix.admin, &0, &2);
+    assert_eq!(page.records.len(), 2);
+    assert_eq!(page.records.get(0).unwrap().escrow_id, 30);
+    assert_eq!(page.records.get(1).unwrap().escrow_id, 10);
+    assert_eq!(
+        page.records.get(1).unwrap().escrow.status,
+        EscrowStatus::Completed
+    );
+    assert!(!page.complete);
+
+    // Closed escrows drop out of the export
+    client.close_escrow(&10);
+    let page = client.export_escrows(&vaultix.admin, &0, &2);
+    assert_eq!(page.records.len(), 1);
+    let page = client.export_escrows(&vaultix.admin, &page.next_cursor, &2);
+    assert_eq!(page.records.len(), 1);
+    assert_eq!(page.records.get(0).unwrap().escrow.total_amount, 300);
+    assert_eq!(page.next_cursor, 3);
+    assert!(page.complete);
+}
+
+/// Minimal screening contract flagging addresses it has been told about
+#[soroban_sdk::contract]
+pub struct MockScreener;
+
+#[soroban_sdk::contractimpl]
+impl MockScreener {
+    pub fn flag(env: Env, address: Address) {
+        env.storage().persistent().set(&address, &true);
+    }
+
+    pub fn is_blocked(env: Env, address: Address) -> bool {
+        env.storage().persistent().has(&address)
+    }
+}
+
+#[test]
+fn test_screener_refuses_flagged_parties() {
+    use crate::testutils::{MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let screener_id = env.register_contract(None, MockScreener);
+    let screener = MockScreenerClient::new(&env, &screener_id);
+
+    let escrow = vaultix.funded_escrow(1, &[100]);
+    assert_eq!(
+        client.try_set_screener(&escrow.depositor, &Some(screener_id.clone())),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    client.set_screener(&vaultix.admin, &Some(screener_id.clone()));
+    assert_eq!(client.get_screener(), Some(screener_id));
+
+    screener.flag(&escrow.recipient);
+    assert!(client.is_blocked(&escrow.recipient));
+    assert_eq!(
+        client.try_release_milestone(&1, &0),
+        Err(Ok(Error::AddressBlocked))
+    );
+    let flagged_depositor = escrow.recipient.clone();
+    let create = || {
+        client.try_create_escrow(
+            &2,
+            &flagged_depositor,
+            &Address::generate(&env),
+            &escrow.token,
+            &vec![&env, MilestoneBuilder::new(100).build(&env)],
+            &(env.ledger().timestamp() + 1_000),
+        )
+    };
+    assert_eq!(create(), Err(Ok(Error::AddressBlocked)));
+
+    // Unregistering the screener lifts its flags
+    client.set_screener(&vaultix.admin, &None);
+    assert!(!client.is_blocked(&escrow.recipient));
+    client.release_milestone(&1, &0);
+    assert!(create().is_ok());
+}
+
+#[test]
+fn test_role_members_are_enumerable() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let admin = &vaultix.admin;
+    let pauser = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+
+    client.grant_role(admin, &Role::Pauser, &pauser);
+    client.grant_role(admin, &Role::Pauser, &pauser);
+    client.grant_role(admin, &Role::FeeManager, &manager);
+    client.grant_role(admin, &Role::Arbitrator, &arbitrator);
+
+    assert_eq!(
+        client.get_role_members(&Role::Pauser, &0, &10),
+        vec![
+            &env,
+            admin.clone(),
+            vaultix.treasury.clone(),
+            pauser.clone()
+        ]
+    );
+    assert_eq!(
+        client.get_role_members(&Role::FeeManager, &2, &10),
+        vec![&env, manager.clone()]
+    );
+    assert_eq!(
+        client.get_role_members(&Role::Arbitrator, &0, &10),
+        vec![&env, admin.clone(), arbitrator.clone()]
+    );
+    assert_eq!(
+        client.get_role_members(&Role::Admin, &0, &10),
+        vec![&env, admin.clone()]
+    );
+    assert_eq!(client.get_role_members(&Role::Pauser, &1, &1).len(), 1);
+    assert!(client.get_role_members(&Role::Pauser, &5, &10).is_empty());
+
+    client.revoke_role(admin, &Role::Pauser, &pauser);
+    assert_eq!(
+        client.get_role_members(&Role::Pauser, &0, &10),
+        vec![&env, admin.clone(), vaultix.treasury.clone()]
+    );
+}
+
+#[test]
+fn test_large_admin_rulings_wait_out_timelock() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let admin = &vaultix.admin;
+    let timelock = RulingTimelock {
+        threshold: 500,
+        delay: 3_600,
+    };
+    client.set_ruling_timelock(admin, &timelock);
+    assert_eq!(
+        client.try_set_ruling_timelock(
+            admin,
+            &RulingTimelock {
+                threshold: 1_000,
+                delay: 3_600,
+            }
+        ),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+
+    // Small escrows are ruled on immediately
+    let small = vaultix.disputed_escrow(1, &[100]);
+    client.resolve_dispute(&1, &small.depositor, &RulingReason::NotDelivered, &None);
+    assert_eq!(client.get_escrow(&1).status, EscrowStatus::Resolved);
+
+    let large = vaultix.disputed_escrow(2, &[1_000]);
+    client.resolve_dispute(&2, &large.depositor, &RulingReason::NotDelivered, &None);
+    assert_eq!(client.get_escrow(&2).status, EscrowStatus::Disputed);
+    assert_eq!(
+        client.try_execute_ruling(&2),
+        Err(Ok(Error::RulingNotReady))
+    );
+
+    // An appeal throws the queued ruling out
+    client.appeal_ruling(&2, &large.recipient);
+    assert_eq!(client.get_pending_ruling(&2), None);
+    assert_eq!(
+        client.try_execute_ruling(&2),
+        Err(Ok(Error::RulingNotReady))
+    );
+
+    client.resolve_dispute(&2, &large.depositor, &RulingReason::NotDelivered, &None);
+    let pending = client.get_pending_ruling(&2).unwrap();
+    assert_eq!(
+        pending.ruling,
+        QueuedRuling::Full(large.depositor.clone(), RulingReason::NotDelivered)
+    );
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp = pending.executable_at);
+    client.execute_ruling(&2);
+    assert_eq!(client.get_escrow(&2).status, EscrowStatus::Resolved);
+    assert_eq!(
+        token::Client::new(&env, &large.token).balance(&large.depositor),
+        1_000
+    );
+    assert_eq!(client.get_pending_ruling(&2), None);
+}
+
+/// Minimal voting contract with fixed weights, whatever the snapshot ledger
+#[soroban_sdk::contract]
+pub struct MockVotes;
+
+#[soroban_sdk::contractimpl]
+impl MockVotes {
+    pub fn set_votes(env: Env, account: Address, weight: i128) {
+        let supply: i128 = env.storage().instance().get(&()).unwrap_or(0);
+        env.storage().instance().set(&(), &(supply + weight));
+        env.storage().persistent().set(&account, &weight);
+    }
+
+    pub fn get_past_votes(env: Env, account: Address, _ledger: u32) -> i128 {
+        env.storage().persistent().get(&account).unwrap_or(0)
+    }
+
+    pub fn get_past_total_supply(env: Env, _ledger: u32) -> i128 {
+        env.storage().instance().get(&()).unwrap_or(0)
+    }
+}
+
+#[test]
+fn test_token_holders_decide_dao_disputes() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let votes_id = env.register_contract(None, MockVotes);
+    let votes = MockVotesClient::new(&env, &votes_id);
+    let (alice, bob, carol) = (
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+    votes.set_votes(&alice, &30);
+    votes.set_votes(&bob, &20);
+    votes.set_votes(&carol, &50);
+
+    let funded = vaultix.funded_escrow(1, &[1_000]);
+    assert_eq!(
+        client.try_use_dao_arbitration(&1),
+        Err(Ok(Error::EscrowAlreadyFunded))
+    );
+    client.set_dao_config(
+        &vaultix.admin,
+        &DaoConfig {
+            votes: votes_id.clone(),
+            quorum_bps: 2_000,
+            period: 3_600,
+        },
+    );
+    let token = funded.token.clone();
+    let dao_dispute = |escrow_id: u64| {
+        let escrow = EscrowBuilder::new(&vaultix, escrow_id)
+            .token(&token)
+            .milestone(MilestoneBuilder::new(1_000))
+            .build();
+        client.use_dao_arbitration(&escrow_id);
+        token::StellarAssetClient::new(&env, &token).mint(&escrow.depositor, &1_000);
+        token::Client::new(&env, &token).approve(
+            &escrow.depositor,
+            &vaultix.address,
+            &1_000,
+            &1_000,
+        );
+        client.deposit_funds(&escrow_id);
+        client.raise_dispute(&escrow_id, &escrow.recipient, &DisputeReason::Other);
+        escrow
+    };
+
+    // A majority of the snapshot supply decides at once
+    let escrow = dao_dispute(2);
+    assert_eq!(client.get_dao_vote(&2).unwrap().quorum, 20);
+    assert_eq!(
+        client.try_resolve_dispute(&2, &escrow.depositor, &RulingReason::Other, &None),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    assert_eq!(
+        client.try_cast_vote(&2, &escrow.recipient, &true),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    assert_eq!(client.cast_vote(&2, &alice, &true), 30);
+    assert_eq!(
+        client.try_cast_vote(&2, &alice, &true),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    assert_eq!(client.try_finalize_vote(&2), Err(Ok(Error::RulingNotReady)));
+    client.cast_vote(&2, &bob, &false);
+    assert_eq!(client.get_escrow(&2).status, EscrowStatus::Disputed);
+    client.cast_vote(&2, &carol, &false);
+    assert_eq!(client.get_escrow(&2).status, EscrowStatus::Resolved);
+    assert_eq!(client.get_ruling(&2).unwrap().resolver, votes_id);
+    assert_eq!(
+        token::Client::new(&env, &token).balance(&escrow.depositor),
+        1_000
+    );
+
+    // Otherwise the weightier side wins once voting ends, if the quorum was reached
+    let escrow = dao_dispute(3);
+    dao_dispute(4);
+    client.cast_vote(&3, &bob, &true);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3_600);
+    assert_eq!(client.finalize_vote(&3), Some(escrow.recipient));
+    assert_eq!(client.get_escrow(&3).status, EscrowStatus::Resolved);
+
+    // An undecided vote falls back to the arbitrator pool
+    assert_eq!(client.finalize_vote(&4), None);
+    assert_eq!(client.get_dispute_queue(), vec![&env, 4]);
+    assert_eq!(client.get_dao_vote(&4), None);
+}
+
+#[test]
+fn test_panel_votes_weigh_stake_and_share_fee() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let token_address = vaultix.create_token();
+    let minter = token::StellarAssetClient::new(&env, &token_address);
+    let token = token::Client::new(&env, &token_address);
+    client.set_arbitration_config(
+        &vaultix.admin,
+        &ArbitrationConfig {
+            stake_token: token_address.clone(),
+            min_stake: 10,
+            slash_bps: 5_000,
+            resolution_window: 3_600,
+            fee_bps: 4_000,
+        },
+    );
+    let mut config = client.get_config();
+    config.dispute_bond = 100;
+    client.set_config(&vaultix.admin, &config);
+
+    let mut jurors = Vec::new(&env);
+    for stake in [60i128, 30, 20, 50] {
+        let juror = Address::generate(&env);
+        minter.mint(&juror, &stake);
+        client.stake(&juror, &stake);
+        jurors.push_back(juror);
+    }
+    let (big, middle, small, silent) = (
+        jurors.get(0).unwrap(),
+        jurors.get(1).unwrap(),
+        jurors.get(2).unwrap(),
+        jurors.get(3).unwrap(),
+    );
+
+    let escrow = EscrowBuilder::new(&vaultix, 1)
+        .token(&token_address)
+        .milestone(MilestoneBuilder::new(1_000))
+        .build();
+    client.use_panel_arbitration(&1);
+    minter.mint(&escrow.depositor, &1_000);
+    token.approve(&escrow.depositor, &vaultix.address, &1_000, &1_000);
+    client.deposit_funds(&1);
+    minter.mint(&escrow.recipient, &100);
+    token.approve(&escrow.recipient, &vaultix.address, &100, &1_000);
+    client.raise_dispute(&1, &escrow.recipient, &DisputeReason::Other);
+    assert_eq!(client.get_arbiter(&1), None);
+
+    let salt = BytesN::from_array(&env, &[7u8; 32]);
+    let ballots = [
+        (&big, true),
+        (&middle, false),
+        (&small, true),
+        (&silent, false),
+    ];
+    for (juror, for_recipient) in ballots {
+        let commitment = client.panel_commitment(&1, juror, &for_recipient, &salt);
+        client.commit_panel_vote(&1, juror, &commitment);
+    }
+    let commitment = client.panel_commitment(&1, &small, &false, &salt);
+    assert_eq!(
+        client.try_commit_panel_vote(&1, &small, &commitment),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    assert_eq!(client.try_unstake(&big, &60), Err(Ok(Error::StakeLocked)));
+    // Nothing can be revealed until committing ends
+    assert_eq!(
+        client.try_reveal_panel_vote(&1, &big, &true, &salt),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3_600);
+    assert_eq!(
+        client.try_reveal_panel_vote(&1, &big, &false, &salt),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    for (juror, for_recipient) in &ballots[..3] {
+        client.reveal_panel_vote(&1, juror, for_recipient, &salt);
+    }
+    assert_eq!(
+        client.try_finalize_panel_vote(&1),
+        Err(Ok(Error::RulingNotReady))
+    );
+
+    // The silent juror's 50 would have tied the vote, but is slashed and left out instead
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3_600);
+    assert_eq!(
+        client.finalize_panel_vote(&1),
+        Some(escrow.recipient.clone())
+    );
+    assert_eq!(client.get_escrow(&1).status, EscrowStatus::Resolved);
+
+    // The 40 fee out of the bond goes to the majority, pro rata to stake
+    assert_eq!(client.get_claimable_fees(&big, &token_address), 30);
+    assert_eq!(client.get_claimable_fees(&small, &token_address), 10);
+    assert_eq!(client.get_claimable_fees(&middle, &token_address), 0);
+    assert_eq!(token.balance(&escrow.recipient), 1_000 + 60);
+    assert_eq!(client.get_stake(&silent), 25);
+    client.unstake(&big, &60);
+}
+
+#[test]
+fn test_sealed_evidence_reveals_after_both_commit() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let mut config = client.get_config();
+    config.evidence_window = 3_600;
+    client.set_config(&vaultix.admin, &config);
+    let escrow = vaultix.disputed_escrow(1, &[500]);
+    let (depositor, recipient) = (&escrow.depositor, &escrow.recipient);
+
+    let salt = BytesN::from_array(&env, &[9u8; 32]);
+    let delivered = BytesN::from_array(&env, &[1u8; 32]);
+    let missing = BytesN::from_array(&env, &[2u8; 32]);
+    let commitment = client.evidence_commitment(&1, recipient, &delivered, &salt);
+    client.commit_evidence(&1, recipient, &commitment);
+    assert_eq!(
+        client.try_commit_evidence(&1, recipient, &commitment),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    // Evidence is sealed now, so it can't be submitted in the clear
+    assert_eq!(
+        client.try_submit_evidence(&1, depositor, &missing),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+    assert_eq!(
+        client.try_reveal_evidence(&1, recipient, &delivered, &salt),
+        Err(Ok(Error::EvidenceWindowOpen))
+    );
+
+    let commitment = client.evidence_commitment(&1, depositor, &missing, &salt);
+    client.commit_evidence(&1, depositor, &commitment);
+    assert_eq!(
+        client.try_reveal_evidence(&1, recipient, &missing, &salt),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    client.reveal_evidence(&1, recipient, &delivered, &salt);
+    client.reveal_evidence(&1, depositor, &missing, &salt);
+    assert_eq!(client.get_evidence(&1, recipient).unwrap().hash, delivered);
+    assert_eq!(client.get_evidence(&1, depositor).unwrap().hash, missing);
+}
+
+#[test]
+fn test_sealed_terms_reveal_in_dispute() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let escrow = EscrowBuilder::new(&vaultix, 1)
+        .milestone(MilestoneBuilder::new(500).description(""))
+        .build();
+
+    let terms = soroban_sdk::Bytes::from_slice(&env, b"Logo in three colourways, SVG and PNG");
+    let terms_hash = env.crypto().sha256(&terms);
+    client.seal_terms(&1, &terms_hash);
+    assert_eq!(
+        client.try_seal_terms(&1, &terms_hash),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+    assert_eq!(client.get_terms_hash(&1), Some(terms_hash));
+
+    token::StellarAssetClient::new(&env, &escrow.token).mint(&escrow.depositor, &500);
+    token::Client::new(&env, &escrow.token).approve(
+        &escrow.depositor,
+        &vaultix.address,
+        &500,
+        &1_000,
+    );
+    client.deposit_funds(&1);
+    assert_eq!(
+        client.try_reveal_terms(&1, &escrow.recipient, &terms),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+    client.raise_dispute(&1, &escrow.recipient, &DisputeReason::Other);
+    let altered = soroban_sdk::Bytes::from_slice(&env, b"Logo in one colour, PNG");
+    assert_eq!(
+        client.try_reveal_terms(&1, &escrow.recipient, &altered),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    client.reveal_terms(&1, &escrow.recipient, &terms);
+    assert_eq!(client.get_revealed_terms(&1), Some(terms));
+}
+
+mod nft_collection {
+    use soroban_sdk::{contract, contractimpl, Address, Env};
+
+    /// Bare-bones NFT collection tracking one owner per token
+    #[contract]
+    pub struct MockNft;
+
+    #[contractimpl]
+    impl MockNft {
+        pub fn mint(env: Env, to: Address, token_id: u32) {
+            env.storage().persistent().set(&token_id, &to);
+        }
+
+        pub fn owner_of(env: Env, token_id: u32) -> Address {
+            env.storage().persistent().get(&token_id).unwrap()
+        }
+
+        pub fn transfer(env: Env, from: Address, to: Address, token_id: u32) {
+            from.require_auth();
+            assert_eq!(Self::owner_of(env.clone(), token_id), from);
+            env.storage().persistent().set(&token_id, &to);
+        }
+    }
+}
+
+#[test]
+fn test_nft_deliverable_swaps_against_payment() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+    use nft_collection::{MockNft, MockNftClient};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let collection_id = env.register_contract(None, MockNft);
+    let collection = MockNftClient::new(&env, &collection_id);
+
+    let escrow = EscrowBuilder::new(&vaultix, 1)
+        .milestone(MilestoneBuilder::new(600))
+        .milestone(MilestoneBuilder::new(400))
+        .build();
+    let token = token::Client::new(&env, &escrow.token);
+    token::StellarAssetClient::new(&env, &escrow.token).mint(&escrow.depositor, &2_000);
+    token.approve(&escrow.depositor, &vaultix.address, &2_000, &1_000);
+    collection.mint(&escrow.recipient, &7);
+    assert_eq!(
+        client.try_deposit_nft(&1),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+    client.require_nft(&1, &collection_id, &7);
+
+    // Held in custody until the payment is in, and withdrawable until then
+    client.deposit_nft(&1);
+    assert_eq!(collection.owner_of(&7), vaultix.address);
+    client.withdraw_nft(&1);
+    assert_eq!(collection.owner_of(&7), escrow.recipient);
+    client.deposit_nft(&1);
+
+    client.deposit_funds(&1);
+    assert_eq!(collection.owner_of(&7), escrow.depositor);
+    assert_eq!(client.get_escrow(&1).status, EscrowStatus::Completed);
+    assert_eq!(token.balance(&escrow.recipient), 995);
+    assert!(!client.get_nft_deliverable(&1).unwrap().held);
+
+    // A disputed swap is settled by the ruling
+    let escrow = EscrowBuilder::new(&vaultix, 2)
+        .depositor(&escrow.depositor)
+        .token(&escrow.token)
+        .milestone(MilestoneBuilder::new(600))
+        .milestone(MilestoneBuilder::new(400))
+        .build();
+    collection.mint(&escrow.recipient, &8);
+    client.require_nft(&2, &collection_id, &8);
+    client.fund_milestones(&2, &1);
+    client.deposit_nft(&2);
+    assert_eq!(collection.owner_of(&8), vaultix.address);
+    client.raise_dispute(&2, &escrow.depositor, &DisputeReason::Other);
+    assert_eq!(
+        client.try_withdraw_nft(&2),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+    client.resolve_dispute(&2, &escrow.depositor, &RulingReason::NotDelivered, &None);
+    assert_eq!(collection.owner_of(&8), escrow.recipient);
+
+    // So is one the parties settle, with the NFT going to the depositor once the recipient is paid
+    let escrow = EscrowBuilder::new(&vaultix, 3)
+        .depositor(&escrow.depositor)
+        .token(&escrow.token)
+        .milestone(MilestoneBuilder::new(600))
+        .milestone(MilestoneBuilder::new(400))
+        .build();
+    token::StellarAssetClient::new(&env, &escrow.token).mint(&escrow.depositor, &600);
+    token.approve(&escrow.depositor, &vaultix.address, &600, &1_000);
+    collection.mint(&escrow.recipient, &9);
+    client.require_nft(&3, &collection_id, &9);
+    client.fund_milestones(&3, &1);
+    client.deposit_nft(&3);
+    client.raise_dispute(&3, &escrow.depositor, &DisputeReason::Other);
+    client.propose_settlement(&3, &escrow.recipient, &300);
+    client.accept_settlement(&3, &escrow.depositor, &300);
+    assert_eq!(collection.owner_of(&9), escrow.depositor);
+    assert!(!client.get_nft_deliverable(&3).unwrap().held);
+}
+
+#[test]
+fn test_swap_legs_exchange_on_mutual_confirmation() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let counter_token = vaultix.create_token();
+    let counter = token::Client::new(&env, &counter_token);
+    let counter_minter = token::StellarAssetClient::new(&env, &counter_token);
+
+    let escrow = EscrowBuilder::new(&vaultix, 1)
+        .milestone(MilestoneBuilder::new(1_000))
+        .build();
+    let payment = token::Client::new(&env, &escrow.token);
+    client.set_swap_leg(&1, &counter_token, &300);
+    counter_minter.mint(&escrow.recipient, &600);
+    client.deposit_swap_leg(&1);
+    assert_eq!(counter.balance(&vaultix.address), 300);
+    assert_eq!(
+        client.try_confirm_swap(&1, &escrow.depositor),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+
+    token::StellarAssetClient::new(&env, &escrow.token).mint(&escrow.depositor, &2_000);
+    payment.approve(&escrow.depositor, &vaultix.address, &2_000, &1_000);
+    client.deposit_funds(&1);
+    assert!(!client.confirm_swap(&1, &escrow.depositor));
+    assert!(client.confirm_swap(&1, &escrow.recipient));
+    assert_eq!(counter.balance(&escrow.depositor), 300);
+    assert_eq!(payment.balance(&escrow.recipient), 995);
+    assert_eq!(client.get_escrow(&1).status, EscrowStatus::Completed);
+
+    // A ruling for the recipient completes a disputed swap
+    let escrow = EscrowBuilder::new(&vaultix, 2)
+        .depositor(&escrow.depositor)
+        .recipient(&escrow.recipient)
+        .token(&escrow.token)
+        .milestone(MilestoneBuilder::new(1_000))
+        .build();
+    client.set_swap_leg(&2, &counter_token, &300);
+    client.deposit_funds(&2);
+    client.deposit_swap_leg(&2);
+    client.raise_dispute(&2, &escrow.recipient, &DisputeReason::Other);
+    assert_eq!(
+        client.try_withdraw_swap_leg(&2),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+    client.resolve_dispute(&2, &escrow.recipient, &RulingReason::WorkAccepted, &None);
+    assert_eq!(counter.balance(&escrow.depositor), 600);
+    assert_eq!(payment.balance(&escrow.recipient), 995 + 1_000);
+
+    // A settlement refunding the depositor in full hands the leg back to the recipient
+    let escrow = EscrowBuilder::new(&vaultix, 3)
+        .depositor(&escrow.depositor)
+        .recipient(&escrow.recipient)
+        .token(&escrow.token)
+        .milestone(MilestoneBuilder::new(500))
+        .build();
+    client.set_swap_leg(&3, &counter_token, &300);
+    token::StellarAssetClient::new(&env, &escrow.token).mint(&escrow.depositor, &500);
+    payment.approve(&escrow.depositor, &vaultix.address, &500, &1_000);
+    client.deposit_funds(&3);
+    counter_minter.mint(&escrow.recipient, &300);
+    client.deposit_swap_leg(&3);
+    client.raise_dispute(&3, &escrow.depositor, &DisputeReason::Other);
+    client.propose_settlement(&3, &escrow.depositor, &0);
+    client.accept_settlement(&3, &escrow.recipient, &0);
+    assert_eq!(counter.balance(&escrow.recipient), 300);
+    assert_eq!(counter.balance(&vaultix.address), 0);
+    assert!(!client.get_swap_leg(&3).unwrap().held);
+}
+
+#[test]
+fn test_unreviewed_submission_escalates_to_arbitration() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    vaultix.funded_escrow(1, &[300, 200]);
+    client.submit_milestone(&1, &1, &BytesN::from_array(&env, &[5u8; 32]), &None);
+    assert_eq!(
+        client.try_escalate_unreviewed(&1, &1),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+
+    let mut config = client.get_config();
+    config.escalation_window = config.review_window;
+    assert_eq!(
+        client.try_set_config(&vaultix.admin, &config),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+    config.escalation_window = config.review_window * 2;
+    client.set_config(&vaultix.admin, &config);
+
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp += config.review_window + 1);
+    assert_eq!(
+        client.try_escalate_unreviewed(&1, &1),
+        Err(Ok(Error::DisputeTooEarly))
+    );
+    assert_eq!(
+        client.try_escalate_unreviewed(&1, &0),
+        Err(Ok(Error::MilestoneNotSubmitted))
+    );
+
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp += config.review_window);
+    client.escalate_unreviewed(&1, &1);
+    let disputed = client.get_escrow(&1);
+    assert_eq!(disputed.status, EscrowStatus::Disputed);
+    assert_eq!(disputed.disputed_mask, 0b10);
+    assert_eq!(client.get_dispute_reason(&1), Some(DisputeReason::Other));
+}
+
+mod notifiers {
+    use soroban_sdk::{contract, contractimpl, Address, Env, Error, Symbol};
+
+    /// Notifier remembering how many events it heard and the latest action, unless broken
+    #[contract]
+    pub struct MockNotifier;
+
+    #[contractimpl]
+    impl MockNotifier {
+        pub fn break_down(env: Env) {
+            env.storage().instance().set(&true, &true);
+        }
+
+        pub fn on_escrow_event(
+            env: Env,
+            escrow_id: u64,
+            action: Symbol,
+            _actor: Address,
+            _amount: i128,
+        ) -> Result<(), Error> {
+            if env.storage().instance().has(&true) {
+                return Err(Error::from_contract_error(1));
+            }
+            let heard: u32 = env.storage().instance().get(&escrow_id).unwrap_or(0);
+            env.storage().instance().set(&escrow_id, &(heard + 1));
+            env.storage().instance().set(&(), &action);
+            Ok(())
+        }
+
+        pub fn heard(env: Env, escrow_id: u64) -> (u32, Option<Symbol>) {
+            let heard = env.storage().instance().get(&escrow_id).unwrap_or(0);
+            (heard, env.storage().instance().get(&()))
+        }
+    }
+}
+
+#[test]
+fn test_party_notifiers_hear_escrow_events() {
+    use crate::testutils::Vaultix;
+    use notifiers::{MockNotifier, MockNotifierClient};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let notifier_id = env.register_contract(None, MockNotifier);
+    let notifier = MockNotifierClient::new(&env, &notifier_id);
+    let broken_id = env.register_contract(None, MockNotifier);
+    MockNotifierClient::new(&env, &broken_id).break_down();
+
+    let escrow = vaultix.funded_escrow(1, &[100, 200]);
+    client.set_notifier(&escrow.depositor, &Some(notifier_id.clone()));
+    client.set_notifier(&escrow.recipient, &Some(broken_id));
+    assert_eq!(client.get_notifier(&escrow.depositor), Some(notifier_id));
+
+    // The recipient's failing notifier doesn't hold up the release
+    client.release_milestone(&1, &0);
+    assert_eq!(notifier.heard(&1), (1, Some(symbol_short!("released"))));
+
+    client.set_notifier(&escrow.depositor, &None);
+    client.release_milestone(&1, &1);
+    assert_eq!(notifier.heard(&1).0, 1);
+    assert_eq!(client.get_escrow(&1).total_released, 300);
+}
+
+#[test]
+fn test_standing_agreement_applies_to_new_escrows() {
+    use crate::testutils::{EscrowBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let (client_firm, studio, arbiter) = (
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+    let before = EscrowBuilder::new(&vaultix, 1)
+        .depositor(&client_firm)
+        .recipient(&studio)
+        .build();
+
+    let agreement = StandingAgreement {
+        arbiter: arbiter.clone(),
+        bond: 0,
+        evidence_window: 600,
+    };
+    let bad = StandingAgreement {
+        arbiter: studio.clone(),
+        ..agreement.clone()
+    };
+    assert_eq!(
+        client.try_set_standing_agreement(&client_firm, &studio, &bad),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+    client.set_standing_agreement(&client_firm, &studio, &agreement);
+    assert_eq!(
+        client.get_standing_agreement(&studio, &client_firm),
+        Some(agreement.clone())
+    );
+
+    // Applies in either direction, but not to escrows that already existed
+    EscrowBuilder::new(&vaultix, 2)
+        .depositor(&studio)
+        .recipient(&client_firm)
+        .token(&before.token)
+        .funded()
+        .build();
+    assert_eq!(client.get_arbiter(&1), None);
+    assert_eq!(client.get_arbiter(&2), Some(arbiter));
+    assert_eq!(client.get_agreed_terms(&2), Some(agreement));
+    client.raise_dispute(&2, &client_firm, &DisputeReason::Quality);
+    assert_eq!(
+        client.get_evidence_deadline(&2),
+        Some(env.ledger().timestamp() + 600)
+    );
+
+    client.revoke_standing_agreement(&studio, &client_firm);
+    EscrowBuilder::new(&vaultix, 3)
+        .depositor(&client_firm)
+        .recipient(&studio)
+        .build();
+    assert_eq!(client.get_arbiter(&3), None);
+    assert_eq!(client.get_agreed_terms(&3), None);
+}
+
+#[test]
+fn test_operator_namespace_policy() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let (depositor, recipient) = (Address::generate(&env), Address::generate(&env));
+    let (token, other_token) = (vaultix.create_token(), vaultix.create_token());
+    let platform = symbol_short!("gigs");
+
+    let policy = OperatorConfig {
+        fee_bps: 100,
+        arbiters: Vec::new(&env),
+        tokens: vec![&env, token.clone()],
+        min_amount: 0,
+        max_amount: 5_000,
+    };
+    assert_eq!(
+        client.try_set_operator_config(&depositor, &platform, &Some(policy.clone())),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    client.set_operator_config(&vaultix.admin, &platform, &Some(policy.clone()));
+    assert_eq!(client.get_operator_config(&platform), Some(policy));
+
+    let milestones = |amount: i128| {
+        vec![
+            &env,
+            Milestone {
+                amount,
+                status: MilestoneStatus::Pending,
+                description: String::from_str(&env, "Gig"),
+            },
+        ]
+    };
+    let deadline = 1706400000u64;
+    assert_eq!(
+        client.try_create_operator_escrow(
+            &platform,
+            &depositor,
+            &recipient,
+            &other_token,
+            &milestones(1_000),
+            &deadline
+        ),
+        Err(Ok(Error::NotAllowlisted))
+    );
+    assert_eq!(
+        client.try_create_operator_escrow(
+            &platform,
+            &depositor,
+            &recipient,
+            &token,
+            &milestones(6_000),
+            &deadline
+        ),
+        Err(Ok(Error::AmountOutOfRange))
+    );
+    let id = client.create_operator_escrow(
+        &platform,
+        &depositor,
+        &recipient,
+        &token,
+        &milestones(1_000),
+        &deadline,
+    );
+    assert_eq!(client.get_escrow_operator(&id), Some(platform));
+
+    // The operator's rate replaces the deployment's 50 bps
+    token::StellarAssetClient::new(&env, &token).mint(&depositor, &1_000);
+    token::Client::new(&env, &token).approve(&depositor, &vaultix.address, &1_000, &1_000);
+    client.deposit_funds(&id);
+    assert_eq!(client.get_fee_bps(&id), 100);
+    client.release_milestone(&id, &0);
+    assert_eq!(token::Client::new(&env, &token).balance(&recipient), 990);
+}
+
+#[test]
+fn test_project_groups_escrows() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let owner = Address::generate(&env);
+    let project_id = client.create_project(&owner);
+
+    let first = EscrowBuilder::new(&vaultix, 1)
+        .depositor(&owner)
+        .milestone(MilestoneBuilder::new(400))
+        .milestone(MilestoneBuilder::new(600))
+        .funded()
+        .build();
+    let second = EscrowBuilder::new(&vaultix, 2)
+        .depositor(&owner)
+        .token(&first.token)
+        .milestone(MilestoneBuilder::new(500))
+        .funded()
+        .build();
+    let stranger = EscrowBuilder::new(&vaultix, 3).build();
+
+    client.add_to_project(&project_id, &1);
+    client.add_to_project(&project_id, &2);
+    assert_eq!(
+        client.try_add_to_project(&project_id, &3),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    assert_eq!(
+        client.try_add_to_project(&project_id, &1),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+    assert_eq!(client.get_escrow_project(&2), Some(project_id));
+    assert_eq!(client.get_escrow_project(&stranger.id), None);
+
+    client.release_milestone(&1, &0);
+    client.raise_dispute(&2, &second.recipient, &DisputeReason::Quality);
+    assert_eq!(
+        client.get_project_summary(&project_id),
+        Some(ProjectSummary {
+            escrows: 2,
+            budget: 1_500,
+            released: 400,
+            open_disputes: 1,
+        })
+    );
+    assert_eq!(
+        client.get_project(&project_id).unwrap().escrows,
+        vec![&env, 1, 2]
+    );
+}
+
+#[test]
+fn test_escrows_by_tag() {
+    use crate::testutils::{EscrowBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let (design, audit) = (symbol_short!("design"), symbol_short!("audit"));
+
+    EscrowBuilder::new(&vaultix, 1).build();
+    EscrowBuilder::new(&vaultix, 2).build();
+    EscrowBuilder::new(&vaultix, 3).funded().build();
+
+    assert_eq!(
+        client.try_set_tags(&1, &vec![&env, design.clone(), design.clone()]),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+    assert_eq!(
+        client.try_set_tags(&3, &vec![&env, design.clone()]),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+    client.set_tags(&1, &vec![&env, design.clone(), audit.clone()]);
+    client.set_tags(&2, &vec![&env, design.clone()]);
+    assert_eq!(
+        client.try_set_tags(&2, &vec![&env, audit.clone()]),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+
+    assert_eq!(
+        client.get_tags(&1),
+        vec![&env, design.clone(), audit.clone()]
+    );
+    assert_eq!(client.get_escrows_by_tag(&design, &0), vec![&env, 1, 2]);
+    assert_eq!(client.get_escrows_by_tag(&audit, &0), vec![&env, 1]);
+    assert_eq!(
+        client.get_escrows_by_tag(&design, &1),
+        Vec::<u64>::new(&env)
+    );
+
+    // A closed escrow leaves the indexes, so reusing its ID doesn't inherit its tags or position
+    client.cancel_escrow(&1);
+    client.close_escrow(&1);
+    EscrowBuilder::new(&vaultix, 1).build();
+    assert_eq!(client.get_tags(&1), Vec::<Symbol>::new(&env));
+    assert_eq!(client.get_escrows_by_tag(&design, &0), vec![&env, 2]);
+    assert_eq!(client.get_escrows_by_tag(&audit, &0), Vec::<u64>::new(&env));
+    let mut exported = Vec::new(&env);
+    for record in client
+        .export_escrows(&vaultix.admin, &0, &10)
+        .records
+        .iter()
+    {
+        exported.push_back(record.escrow_id);
+    }
+    assert_eq!(exported, vec![&env, 2, 3, 1]);
+}
+
+#[test]
+fn test_clawback_shortfall_freezes_and_reconciles() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let first = vaultix.funded_escrow(1, &[500, 500]);
+    let second = EscrowBuilder::new(&vaultix, 2)
+        .token(&first.token)
+        .milestone(MilestoneBuilder::new(1_000))
+        .funded()
+        .build();
+    let asset = token::Client::new(&env, &first.token);
+
+    // Stands in for the issuer clawing back part of the contract's balance
+    let clawback = |amount: i128| {
+        env.as_contract(&vaultix.address, || {
+            asset.transfer(&vaultix.address, &vaultix.admin, &amount)
+        });
+    };
+    clawback(300);
+
+    assert_eq!(
+        client.try_release_milestone(&1, &0),
+        Err(Ok(Error::InsufficientBalance))
+    );
+    assert_eq!(client.report_shortfall(&1), 300);
+    assert_eq!(client.get_escrow(&1).status, EscrowStatus::Frozen);
+    assert_eq!(
+        client.try_cancel_escrow(&1),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+
+    let issuer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &first.token).mint(&issuer, &300);
+    assert_eq!(client.reconcile_shortfall(&1, &issuer), 300);
+    client.release_milestone(&1, &0);
+    assert_eq!(asset.balance(&first.recipient), 498);
+
+    // A shortfall nobody covers is written off against the frozen escrow
+    clawback(400);
+    client.report_shortfall(&2);
+    assert_eq!(client.write_off_shortfall(&vaultix.admin, &2), 600);
+    assert_eq!(client.get_escrow(&2).status, EscrowStatus::Cancelled);
+    assert_eq!(asset.balance(&second.depositor), 600);
+    client.release_milestone(&1, &1);
+    assert_eq!(client.get_escrow(&1).total_released, 1_000);
+}
+
+#[test]
+fn test_dust_milestones_rejected_by_token_decimals() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let token = vaultix.create_token(); // Stellar assets have 7 decimals
+
+    let mut config = client.get_config();
+    config.dust_precision = 19;
+    assert_eq!(
+        client.try_set_config(&vaultix.admin, &config),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+    config.dust_precision = 2;
+    client.set_config(&vaultix.admin, &config);
+
+    // A hundredth of a token is 100_000 stroops
+    let dust = vec![
+        &env,
+        MilestoneBuilder::new(100_000).build(&env),
+        MilestoneBuilder::new(99_999).build(&env),
+    ];
+    assert_eq!(
+        client.try_create_escrow(
+            &1,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &token,
+            &dust,
+            &1706400000u64
+        ),
+        Err(Ok(Error::InvalidMilestoneAmount))
+    );
+    EscrowBuilder::new(&vaultix, 1)
+        .token(&token)
+        .milestone(MilestoneBuilder::new(100_000))
+        .build();
+}
+
+mod price_feed {
+    use soroban_sdk::{contract, contractimpl, Address, Env};
+
+    /// Prices every token at $1 per 10_000_000 units, in cents
+    #[contract]
+    pub struct MockOracle;
+
+    #[contractimpl]
+    impl MockOracle {
+        pub fn usd_value(_env: Env, _token: Address, amount: i128) -> i128 {
+            amount / 100_000
+        }
+    }
+}
+
+#[test]
+fn test_value_floor_rejects_cheap_milestones() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let oracle = env.register_contract(None, price_feed::MockOracle);
+
+    let floor = ValueFloor {
+        oracle: oracle.clone(),
+        min_milestone_usd: 500, // $5
+    };
+    assert_eq!(
+        client.try_set_value_floor(
+            &vaultix.admin,
+            &Some(ValueFloor {
+                min_milestone_usd: 0,
+                ..floor.clone()
+            })
+        ),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+    client.set_value_floor(&vaultix.admin, &Some(floor.clone()));
+    assert_eq!(client.get_value_floor(), Some(floor));
+
+    let milestones = vec![
+        &env,
+        MilestoneBuilder::new(50_000_000).build(&env),
+        MilestoneBuilder::new(49_999_999).build(&env),
+    ];
+    assert_eq!(
+        client.try_create_escrow(
+            &1,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &vaultix.create_token(),
+            &milestones,
+            &1706400000u64
+        ),
+        Err(Ok(Error::AmountOutOfRange))
+    );
+    EscrowBuilder::new(&vaultix, 1)
+        .milestone(MilestoneBuilder::new(50_000_000))
+        .build();
+
+    client.set_value_floor(&vaultix.admin, &None);
+    EscrowBuilder::new(&vaultix, 2)
+        .milestone(MilestoneBuilder::new(1))
+        .build();
+}
+
+mod amm {
+    use soroban_sdk::{contract, contractimpl, symbol_short, token, Address, Env};
+
+    /// Router paying out two units of `token_out` per unit in, from liquidity minted to it,
+    /// unless told to fail
+    #[contract]
+    pub struct MockRouter;
+
+    #[contractimpl]
+    impl MockRouter {
+        pub fn set_failing(env: Env, failing: bool) {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("failing"), &failing);
+        }
+
+        pub fn quote(_env: Env, _token_in: Address, _token_out: Address, amount_in: i128) -> i128 {
+            amount_in * 2
+        }
+
+        pub fn swap(
+            env: Env,
+            from: Address,
+            token_in: Address,
+            token_out: Address,
+            amount_in: i128,
+            min_out: i128,
+            to: Address,
+        ) -> Result<i128, soroban_sdk::Error> {
+            let failing: bool = env
+                .storage()
+                .instance()
+                .get(&symbol_short!("failing"))
+                .unwrap_or(false);
+            let out = amount_in * 2;
+            if failing || out < min_out {
+                return Err(soroban_sdk::Error::from_contract_error(1));
+            }
+            let router = env.current_contract_address();
+            token::Client::new(&env, &token_in).transfer_from(&router, &from, &router, &amount_in);
+            token::Client::new(&env, &token_out).transfer(&router, &to, &out);
+            Ok(out)
+        }
+    }
+}
+
+#[test]
+fn test_payouts_convert_to_stablecoin_or_fall_back_in_kind() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let router = env.register_contract(None, amm::MockRouter);
+    let router_client = amm::MockRouterClient::new(&env, &router);
+    let stable = vaultix.create_token();
+    token::StellarAssetClient::new(&env, &stable).mint(&router, &10_000);
+    client.set_amm_router(&vaultix.admin, &Some(router.clone()));
+
+    let escrow = EscrowBuilder::new(&vaultix, 1)
+        .milestone(MilestoneBuilder::new(1_000))
+        .milestone(MilestoneBuilder::new(1_000))
+        .build();
+    let target = StablePayout {
+        token: stable.clone(),
+        max_slippage_bps: 100,
+    };
+    assert_eq!(
+        client.try_set_stable_payout(
+            &1,
+            &Some(StablePayout {
+                token: escrow.token.clone(),
+                ..target.clone()
+            })
+        ),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+    client.set_stable_payout(&1, &Some(target.clone()));
+    assert_eq!(client.get_stable_payout(&1), Some(target));
+    token::StellarAssetClient::new(&env, &escrow.token).mint(&escrow.depositor, &2_000);
+    token::Client::new(&env, &escrow.token).approve(
+        &escrow.depositor,
+        &vaultix.address,
+        &2_000,
+        &1_000,
+    );
+    client.deposit_funds(&1);
+
+    // The fee is taken in the escrow token, the rest arrives as stablecoin
+    client.release_milestone(&1, &0);
+    assert_eq!(
+        token::Client::new(&env, &stable).balance(&escrow.recipient),
+        1_990
+    );
+    assert_eq!(
+        token::Client::new(&env, &escrow.token).balance(&router),
+        995
+    );
+
+    router_client.set_failing(&true);
+    client.release_milestone(&1, &1);
+    assert_eq!(
+        token::Client::new(&env, &escrow.token).balance(&escrow.recipient),
+        995
+    );
+}
+
+mod pools {
+    use soroban_sdk::{contract, contractimpl, symbol_short, token, Address, Env};
+
+    /// Pool for one token pair paying out two of the other token per token in, from
+    /// liquidity minted to it
+    #[contract]
+    pub struct MockPool;
+
+    #[contractimpl]
+    impl MockPool {
+        pub fn init(env: Env, token_a: Address, token_b: Address) {
+            env.storage().instance().set(&symbol_short!("a"), &token_a);
+            env.storage().instance().set(&symbol_short!("b"), &token_b);
+        }
+
+        pub fn quote(_env: Env, _token_in: Address, amount_in: i128) -> i128 {
+            amount_in * 2
+        }
+
+        pub fn swap(
+            env: Env,
+            from: Address,
+            token_in: Address,
+            amount_in: i128,
+            min_out: i128,
+            to: Address,
+        ) -> i128 {
+            let a: Address = env.storage().instance().get(&symbol_short!("a")).unwrap();
+            let b: Address = env.storage().instance().get(&symbol_short!("b")).unwrap();
+            let token_out = if token_in == a { b } else { a };
+            let out = amount_in * 2;
+            assert!(out >= min_out);
+            let pool = env.current_contract_address();
+            token::Client::new(&env, &token_in).transfer_from(&pool, &from, &pool, &amount_in);
+            token::Client::new(&env, &token_out).transfer(&pool, &to, &out);
+            out
+        }
+    }
+}
+
+#[test]
+fn test_payout_route_hops_through_registered_pools() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let (bridge, exotic) = (vaultix.create_token(), vaultix.create_token());
+    let escrow = EscrowBuilder::new(&vaultix, 1)
+        .milestone(MilestoneBuilder::new(1_000))
+        .milestone(MilestoneBuilder::new(1_000))
+        .build();
+
+    let route = PayoutRoute {
+        hops: vec![&env, bridge.clone(), exotic.clone()],
+        max_slippage_bps: 50,
+    };
+    assert_eq!(
+        client.try_set_payout_route(
+            &1,
+            &Some(PayoutRoute {
+                hops: vec![&env, escrow.token.clone()],
+                ..route.clone()
+            })
+        ),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+    client.set_payout_route(&1, &Some(route.clone()));
+    assert_eq!(client.get_payout_route(&1), Some(route));
+    token::StellarAssetClient::new(&env, &escrow.token).mint(&escrow.depositor, &2_000);
+    token::Client::new(&env, &escrow.token).approve(
+        &escrow.depositor,
+        &vaultix.address,
+        &2_000,
+        &1_000,
+    );
+    client.deposit_funds(&1);
+
+    // Without a pool for the second hop the payout is made in kind
+    let first = env.register_contract(None, pools::MockPool);
+    pools::MockPoolClient::new(&env, &first).init(&escrow.token, &bridge);
+    token::StellarAssetClient::new(&env, &bridge).mint(&first, &10_000);
+    client.set_amm_pool(&vaultix.admin, &escrow.token, &bridge, &Some(first));
+    client.release_milestone(&1, &0);
+    assert_eq!(
+        token::Client::new(&env, &escrow.token).balance(&escrow.recipient),
+        995
+    );
+
+    let second = env.register_contract(None, pools::MockPool);
+    pools::MockPoolClient::new(&env, &second).init(&bridge, &exotic);
+    token::StellarAssetClient::new(&env, &exotic).mint(&second, &10_000);
+    client.set_amm_pool(&vaultix.admin, &exotic, &bridge, &Some(second.clone()));
+    assert_eq!(client.get_amm_pool(&bridge, &exotic), Some(second));
+    client.release_milestone(&1, &1);
+    assert_eq!(
+        token::Client::new(&env, &exotic).balance(&escrow.recipient),
+        3_980
+    );
+}
+
+mod reentrant_token {
+    use crate::VaultixEscrowClient;
+    use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env};
+
+    /// Token that calls back into the escrow on its first outgoing transfer, trying to
+    /// release the same milestone again, and records whether the call went through
+    #[contract]
+    pub struct ReentrantToken;
+
+    #[contractimpl]
+    impl ReentrantToken {
+        pub fn arm(env: Env, escrow: Address, escrow_id: u64) {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("target"), &(escrow, escrow_id));
+        }
+
+        pub fn reentered(env: Env) -> Option<bool> {
+            env.storage().instance().get(&symbol_short!("reentered"))
+        }
+
+        pub fn mint(env: Env, to: Address, amount: i128) {
+            let balance = Self::balance(env.clone(), to.clone());
+            env.storage().persistent().set(&to, &(balance + amount));
+        }
+
+        pub fn balance(env: Env, id: Address) -> i128 {
+            env.storage().persistent().get(&id).unwrap_or(0)
+        }
+
+        pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+            Self::mint(env.clone(), from, -amount);
+            Self::mint(env.clone(), to, amount);
+
+            let target: Option<(Address, u64)> =
+                env.storage().instance().get(&symbol_short!("target"));
+            if let Some((escrow, escrow_id)) = target {
+                env.storage().instance().remove(&symbol_short!("target"));
+                let replayed = VaultixEscrowClient::new(&env, &escrow)
+                    .try_release_milestone(&escrow_id, &0)
+                    .is_ok();
+                env.storage()
+                    .instance()
+                    .set(&symbol_short!("reentered"), &replayed);
+            }
+        }
+
+        pub fn transfer_from(
+            env: Env,
+            _spender: Address,
+            from: Address,
+            to: Address,
+            amount: i128,
+        ) {
+            Self::mint(env.clone(), from, -amount);
+            Self::mint(env, to, amount);
+        }
+    }
+}
+
+#[test]
+fn test_release_cannot_be_replayed_from_token_callback() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let token = env.register_contract(None, reentrant_token::ReentrantToken);
+    let token_client = reentrant_token::ReentrantTokenClient::new(&env, &token);
+
+    let escrow = EscrowBuilder::new(&vaultix, 1)
+        .token(&token)
+        .milestone(MilestoneBuilder::new(1_000))
+        .milestone(MilestoneBuilder::new(1_000))
+        .build();
+    token_client.mint(&escrow.depositor, &2_000);
+    client.deposit_funds(&1);
+
+    token_client.arm(&vaultix.address, &1);
+    client.release_milestone(&1, &0);
+
+    assert_eq!(token_client.reentered(), Some(false));
+    assert_eq!(token_client.balance(&escrow.recipient), 995);
+    let stored = client.get_escrow(&1);
+    assert_eq!(stored.total_released, 1_000);
+    assert_eq!(stored.released_mask, 1);
+    assert_eq!(
+        client.try_release_milestone(&1, &0),
+        Err(Ok(Error::MilestoneAlreadyReleased))
+    );
+}
+
+#[test]
+fn test_parties_anchor_messages_in_thread() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let escrow = vaultix.funded_escrow(1, &[1_000]);
+
+    let redo = BytesN::from_array(&env, &[1; 32]);
+    let done = BytesN::from_array(&env, &[2; 32]);
+    assert_eq!(client.post_message(&1, &escrow.depositor, &redo), 0);
+    assert_eq!(client.post_message(&1, &escrow.recipient, &done), 1);
+    assert_eq!(
+        client.try_post_message(&1, &Address::generate(&env), &redo),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+
+    let thread = client.get_messages(&1);
+    assert_eq!(thread.len(), 2);
+    assert_eq!(thread.get(0).unwrap().author, escrow.depositor);
+    assert_eq!(thread.get(0).unwrap().message_hash, redo);
+    assert_eq!(thread.get(1).unwrap().author, escrow.recipient);
+    assert_eq!(thread.get(1).unwrap().message_hash, done);
+
+    for _ in 2..50 {
+        client.post_message(&1, &escrow.recipient, &done);
+    }
+    assert_eq!(
+        client.try_post_message(&1, &escrow.depositor, &redo),
+        Err(Ok(Error::VectorTooLarge))
+    );
+    assert!(client.get_messages(&2).is_empty());
+}
+
+#[test]
+fn test_status_reflects_changes_without_milestones() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    vaultix.funded_escrow(1, &[1_000, 500]);
+
+    let funded = client.get_status(&1);
+    assert_eq!(funded.status, EscrowStatus::Active);
+    assert_eq!(funded.total_amount, 1_500);
+    assert_eq!(funded.total_released, 0);
+    assert_eq!(client.get_status(&1), funded);
+
+    client.release_milestone(&1, &0);
+    let released = client.get_status(&1);
+    assert_eq!(released.total_released, 1_000);
+    assert_eq!(released.released_mask, 1);
+    assert!(released.changes > funded.changes);
+
+    assert_eq!(client.try_get_status(&2), Err(Ok(Error::EscrowNotFound)));
+}
+
+#[test]
+fn test_snapshot_chunks_headers_and_milestones() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    vaultix.funded_escrow(7, &[100, 250]);
+    vaultix.funded_escrow(3, &[400]);
+    client.release_milestone(&7, &0);
+
+    assert_eq!(
+        client.try_snapshot(&Address::generate(&env), &0, &1).err(),
+        Some(Ok(Error::UnauthorizedAccess))
+    );
+    assert_eq!(
+        client.try_snapshot(&vaultix.admin, &0, &11).err(),
+        Some(Ok(Error::VectorTooLarge))
+    );
+
+    let chunk = client.snapshot(&vaultix.admin, &0, &1);
+    assert_eq!(chunk.version, 1);
+    assert!(!chunk.complete);
+    let entry = chunk.entries.get(0).unwrap();
+    assert_eq!(entry.escrow_id, 7);
+    assert_eq!(entry.escrow.total_released, 100);
+    assert_eq!(entry.milestones.len(), 2);
+    assert_eq!(entry.milestones.get(1).unwrap().amount, 250);
+
+    let chunk = client.snapshot(&vaultix.admin, &chunk.next_cursor, &10);
+    assert_eq!(chunk.entries.len(), 1);
+    assert_eq!(chunk.entries.get(0).unwrap().escrow_id, 3);
+    assert_eq!(chunk.next_cursor, 2);
+    assert!(chunk.complete);
+}
+
+#[test]
+fn test_import_rebuilds_snapshot_on_fresh_deployment() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let source = Vaultix::setup(&env);
+    source.funded_escrow(7, &[100, 250]);
+    source.funded_escrow(3, &[400]);
+    source.client.release_milestone(&7, &0);
+    let chunk = source.client.snapshot(&source.admin, &0, &10);
+
+    let target = Vaultix::setup(&env);
+    let client = &target.client;
+    let mut stale = chunk.clone();
+    stale.version = 0;
+    assert_eq!(
+        client.try_import_escrows(&target.admin, &stale),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+
+    assert_eq!(client.import_escrows(&target.admin, &chunk), 2);
+    let escrow = client.get_escrow(&7);
+    assert_eq!(escrow.status, EscrowStatus::Active);
+    assert_eq!(escrow.total_released, 100);
+    assert_eq!(escrow.released_mask, 1);
+    assert_eq!(client.get_milestones(&7), source.client.get_milestones(&7));
+    assert_eq!(client.get_stats().total_locked, 650);
+    assert_eq!(
+        client.try_import_escrows(&target.admin, &chunk),
+        Err(Ok(Error::EscrowAlreadyExists))
+    );
+
+    // Importing closes once the deployment creates escrows of its own
+    target.funded_escrow(9, &[50]);
+    let mut later = chunk.clone();
+    later.entries = Vec::new(&env);
+    assert_eq!(
+        client.try_import_escrows(&target.admin, &later),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+}
+
+#[test]
+fn test_loser_pays_arbitrator_fee_when_policy_set() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let arbitrator = Address::generate(&env);
+    let escrow_id = 84u64;
+
+    client.init(&admin);
+    client.initialize(&Address::generate(&env), &Some(0));
+
+    let (token_client, token_admin, token_address) = create_token_contract(&env, &admin);
+    token_admin.mint(&depositor, &1050);
+    token_admin.mint(&recipient, &200);
+    token_client.approve(&depositor, &contract_id, &1050, &200);
+
+    let mut config = client.get_config();
+    assert_eq!(config.dispute_fee_policy, DisputeFeePolicy::FromBond);
+    config.dispute_bond = 200;
+    config.dispute_fee_policy = DisputeFeePolicy::LoserPays;
+    client.set_config(&admin, &config);
+    client.set_arbitration_config(
+        &admin,
+        &ArbitrationConfig {
+            stake_token: token_address.clone(),
+            min_stake: 0,
+            slash_bps: 0,
+            resolution_window: 0,
+            fee_bps: 2500,
+        },
+    );
+    client.add_arbitrator(&admin, &arbitrator);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Work"),
+        },
+    ];
+    client.create_escrow(
+        &escrow_id,
+        &depositor,
+        &recipient,
+        &token_address,
+        &milestones,
+        &1706400000u64,
+    );
+    client.deposit_funds(&escrow_id);
+    client.raise_dispute(&escrow_id, &recipient, &DisputeReason::Other);
+
+    // The depositor's remaining allowance covers the fee, so the winning raiser's bond is whole
+    let preview = client.preview_resolution(&escrow_id, &recipient);
+    assert_eq!(preview.to_recipient, 1200);
+    assert_eq!(preview.to_arbitrator, 50);
+
+    client.resolve_dispute(&escrow_id, &recipient, &RulingReason::WorkAccepted, &None);
+    assert_eq!(token_client.balance(&recipient), 1200);
+    assert_eq!(token_client.balance(&depositor), 0);
+    assert_eq!(client.get_claimable_fees(&arbitrator, &token_address), 50);
+    assert_eq!(token_client.balance(&contract_id), 50);
+}
+
+#[test]
+fn test_open_escrows_capped_per_pair() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let other_recipient = Address::generate(&env);
+    let (_, _, token_address) = create_token_contract(&env, &admin);
+
+    client.init(&admin);
+    let mut config = client.get_config();
+    config.max_pair_escrows = 21;
+    assert_eq!(
+        client.try_set_config(&admin, &config),
+        Err(Ok(Error::InvalidConfiguration))
+    );
+    config.max_pair_escrows = 2;
+    client.set_config(&admin, &config);
+
+    let milestones = vec![
+        &env,
+        Milestone {
+            amount: 1000,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(&env, "Work"),
+        },
+    ];
+    let create = |escrow_id: u64, recipient: &Address| {
+        client.try_create_escrow(
+            &escrow_id,
+            &depositor,
+            recipient,
+            &token_address,
+            &milestones,
+            &1706400000u64,
+        )
+    };
+
+    assert!(create(71, &recipient).is_ok());
+    assert!(create(72, &recipient).is_ok());
+    assert_eq!(create(73, &recipient), Err(Ok(Error::RateLimited)));
+    assert!(create(73, &other_recipient).is_ok());
+
+    // A cancelled escrow frees its slot
+    client.cancel_escrow(&71);
+    assert!(create(74, &recipient).is_ok());
+    assert_eq!(create(75, &recipient), Err(Ok(Error::RateLimited)));
+}
+
+#[test]
+fn test_parties_agree_deadline_extension() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let escrow = vaultix.funded_escrow(1, &[1_000]);
+    let deadline = client.get_escrow(&1).deadline;
+
+    assert_eq!(
+        client.try_extend_deadline(&1, &escrow.recipient, &deadline),
+        Err(Ok(Error::InvalidDeadline))
+    );
+    assert_eq!(
+        client.try_extend_deadline(&1, &Address::generate(&env), &(deadline + 10)),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+
+    // A counter-proposal replaces the open one; repeating your own proposal doesn't accept it
+    assert!(!client.extend_deadline(&1, &escrow.recipient, &(deadline + 20)));
+    assert!(!client.extend_deadline(&1, &escrow.depositor, &(deadline + 10)));
+    assert!(!client.extend_deadline(&1, &escrow.depositor, &(deadline + 10)));
+    assert_eq!(
+        client.get_deadline_proposal(&1),
+        Some(DeadlineProposal {
+            proposer: escrow.depositor.clone(),
+            deadline: deadline + 10,
+        })
+    );
+    assert_eq!(client.get_escrow(&1).deadline, deadline);
+
+    assert!(client.extend_deadline(&1, &escrow.recipient, &(deadline + 10)));
+    assert_eq!(client.get_escrow(&1).deadline, deadline + 10);
+    assert_eq!(client.get_deadline_proposal(&1), None);
+}
+
+#[test]
+fn test_schema_two_singletons_move_to_data_keys() {
+    use crate::storage::DataKey;
+
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, VaultixEscrow);
+    let client = VaultixEscrowClient::new(&env, &contract_id);
+
+    // An instance written by the previous release: bare-symbol singletons, tuple keys
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let tags = vec![&env, symbol_short!("design")];
+    env.as_contract(&contract_id, || {
+        let instance = env.storage().instance();
+        instance.set(&symbol_short!("schema"), &2u32);
+        instance.set(&symbol_short!("admin"), &admin);
+        instance.set(&symbol_short!("treasury"), &treasury);
+        instance.set(&symbol_short!("esc_ctr"), &5u64);
+        let persistent = env.storage().persistent();
+        persistent.set(&(symbol_short!("tags"), 9u64), &tags);
+        persistent.set(&(symbol_short!("yield"), 9u64), &40i128);
+    });
+
+    // Tuple-keyed entries read in place; the admin is found before migrating
+    assert_eq!(client.get_tags(&9), tags);
+    assert_eq!(client.get_accrued_yield(&9), 40);
+    assert_eq!(client.get_schema_version(), 2);
+    assert_eq!(client.get_admin(), admin);
+
+    assert!(!client.has_role(&Role::FeeManager, &treasury));
+    assert_eq!(client.migrate(&admin), 4);
+    assert_eq!(client.get_treasury(), treasury);
+    assert!(client.has_role(&Role::Pauser, &treasury));
+    assert!(client.has_role(&Role::FeeManager, &treasury));
+    env.as_contract(&contract_id, || {
+        let instance = env.storage().instance();
+        assert_eq!(instance.get::<_, u64>(&DataKey::esc_ctr), Some(5));
+        for legacy in [
+            symbol_short!("schema"),
+            symbol_short!("admin"),
+            symbol_short!("treasury"),
+            symbol_short!("esc_ctr"),
+        ] {
+            assert!(!instance.has(&legacy));
+        }
+    });
+}
+
+#[test]
+fn test_ttl_policy_bumps_entries_on_access() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let admin = &vaultix.admin;
+
+    let policy = TtlPolicy {
+        threshold: 100_000,
+        extend_to: 200_000,
+    };
+    for bad in [
+        TtlPolicy {
+            threshold: 0,
+            ..policy
+        },
+        TtlPolicy {
+            threshold: 200_000,
+            ..policy
+        },
+        TtlPolicy {
+            extend_to: u32::MAX,
+            ..policy
+        },
+    ] {
+        assert_eq!(
+            client.try_set_ttl_policy(admin, &bad),
+            Err(Ok(Error::InvalidConfiguration))
+        );
+    }
+    assert_eq!(
+        client.try_set_ttl_policy(&Address::generate(&env), &policy),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    client.set_ttl_policy(admin, &policy);
+    assert_eq!(client.get_ttl_policy(), policy);
+
+    // Created under the policy, the escrow would be archived 200_000 ledgers on
+    vaultix.funded_escrow(1, &[1_000]);
+    env.ledger().with_mut(|li| li.sequence_number += 150_000);
+
+    // Reading it below the threshold bumps it back to the policy's TTL
+    assert_eq!(client.get_escrow(&1).total_amount, 1_000);
+    env.ledger().with_mut(|li| li.sequence_number += 150_000);
+    assert_eq!(client.get_escrow(&1).total_amount, 1_000);
+}
+
+#[test]
+fn test_logistics_oracle_advances_goods_milestones() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let escrow = EscrowBuilder::new(&vaultix, 1)
+        .milestone(MilestoneBuilder::new(1_000))
+        .milestone(MilestoneBuilder::new(2_000))
+        .build();
+    let proof = BytesN::from_array(&env, &[7; 32]);
+
+    let oracle = Address::generate(&env);
+    assert_eq!(
+        client.try_set_goods_oracle(&1, &Some(oracle.clone())),
+        Err(Ok(Error::NotAllowlisted))
+    );
+    client.set_logistics_oracle(&vaultix.admin, &oracle, &true);
+    client.set_goods_oracle(&1, &Some(oracle.clone()));
+    token::StellarAssetClient::new(&env, &escrow.token).mint(&escrow.depositor, &escrow.total);
+    token::Client::new(&env, &escrow.token).approve(
+        &escrow.depositor,
+        &vaultix.address,
+        &escrow.total,
+        &(env.ledger().sequence() + 1_000),
+    );
+    client.deposit_funds(&1);
+
+    // Shipping submits the milestone for the seller; a return withdraws it again
+    client.attest_delivery(&1, &1, &DeliveryEvent::Shipped, &proof);
+    assert!(client.get_milestone_review(&1, &1).submitted);
+    client.attest_delivery(&1, &1, &DeliveryEvent::Returned, &proof);
+    assert!(!client.get_milestone_review(&1, &1).submitted);
+
+    // An uncontested delivery is released once the review window passes
+    client.attest_delivery(&1, &0, &DeliveryEvent::Delivered, &proof);
+    assert_eq!(client.get_deliverable(&1, &0).unwrap().hash, proof);
+    assert_eq!(
+        client.try_finalize_delivery(&1, &0),
+        Err(Ok(Error::EvidenceWindowOpen))
+    );
+    let review_window = client.get_config().review_window;
+    env.ledger()
+        .with_mut(|li| li.timestamp += review_window + 1);
+    client.finalize_delivery(&1, &0);
+    assert_eq!(client.get_escrow(&1).total_released, 1_000);
+
+    // A contested delivery goes to dispute instead
+    client.attest_delivery(&1, &1, &DeliveryEvent::Delivered, &proof);
+    client.contest_delivery(&1, &1);
+    assert_eq!(client.get_escrow(&1).status, EscrowStatus::Disputed);
+    assert_eq!(client.get_shipment(&1, &1), None);
+
+    client.set_logistics_oracle(&vaultix.admin, &oracle, &false);
+    assert_eq!(
+        client.try_attest_delivery(&1, &1, &DeliveryEvent::Shipped, &proof),
+        Err(Ok(Error::NotAllowlisted))
+    );
+}
+
+mod certificates {
+    use crate::types::Certificate;
+    use soroban_sdk::{contract, contractimpl, Address, Env};
+
+    /// Certificate contract keeping the latest certificate issued to each holder
+    #[contract]
+    pub struct MockCertificates;
+
+    #[contractimpl]
+    impl MockCertificates {
+        pub fn issue(env: Env, holder: Address, certificate: Certificate) {
+            env.storage().instance().set(&holder, &certificate);
+        }
+
+        pub fn certificate_of(env: Env, holder: Address) -> Option<Certificate> {
+            env.storage().instance().get(&holder)
+        }
+    }
+}
+
+#[test]
+fn test_completion_issues_certificates_to_both_parties() {
+    use crate::testutils::Vaultix;
+    use certificates::{MockCertificates, MockCertificatesClient};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let certificates_id = env.register_contract(None, MockCertificates);
+    let certificates = MockCertificatesClient::new(&env, &certificates_id);
+
+    assert_eq!(
+        client
+            .try_set_certificate_contract(&Address::generate(&env), &Some(certificates_id.clone())),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    client.set_certificate_contract(&vaultix.admin, &Some(certificates_id.clone()));
+
+    let escrow = vaultix.completed_escrow(1, &[1_000, 500]);
+    let certificate = certificates.certificate_of(&escrow.recipient).unwrap();
+    assert_eq!(certificate.escrow_id, 1);
+    assert_eq!(certificate.depositor, escrow.depositor);
+    assert_eq!(certificate.volume_band, 3);
+    assert!(certificate.on_time);
+    assert_eq!(
+        certificates.certificate_of(&escrow.depositor),
+        Some(certificate)
+    );
+
+    // Without a certificate contract completions issue nothing
+    client.set_certificate_contract(&vaultix.admin, &None);
+    let escrow = vaultix.completed_escrow(2, &[1_000]);
+    assert_eq!(certificates.certificate_of(&escrow.recipient), None);
+}
+
+#[test]
+fn test_escrow_keeps_currency_snapshot_from_creation() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let escrow = vaultix.funded_escrow(1, &[1_000]);
+
+    let token = token::Client::new(&env, &escrow.token);
+    let currency = Currency {
+        symbol: token.symbol(),
+        decimals: token.decimals(),
+    };
+    assert_eq!(client.get_escrow_currency(&1), Some(currency.clone()));
+    assert_eq!(client.get_statement(&1).currency, currency.clone());
+    let export = client.export_escrows(&vaultix.admin, &0, &10);
+    assert_eq!(export.records.get(0).unwrap().currency, currency);
+    assert_eq!(client.get_escrow_currency(&2), None);
+}
+
+#[test]
+fn test_privileged_actions_are_logged() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let admin = &vaultix.admin;
+    let before = client.get_admin_actions_len();
+
+    let config = client.get_config();
+    client.set_config(admin, &config);
+    client.set_paused(admin, &true);
+    client.set_paused(admin, &false);
+    let escrow = vaultix.disputed_escrow(1, &[1_000]);
+    client.resolve_dispute(&1, &escrow.recipient, &RulingReason::WorkAccepted, &None);
+
+    assert_eq!(client.get_admin_actions_len(), before + 4);
+    let mut logged = Vec::new(&env);
+    for page in 0..=(before + 3) / 20 {
+        logged.append(&client.get_admin_actions(&page));
+    }
+    let last = logged.len() - 4;
+    let resolution = logged.get(last + 3).unwrap();
+    assert_eq!(
+        logged.get(last).unwrap().action,
+        Symbol::new(&env, "set_config")
+    );
+    assert_eq!(
+        logged.get(last + 1).unwrap().action,
+        Symbol::new(&env, "set_paused")
+    );
+    assert_eq!(resolution.action, Symbol::new(&env, "resolve_dispute"));
+    assert_eq!(resolution.actor, *admin);
+    assert_eq!(resolution.escrow_id, Some(1));
+    assert_eq!(resolution.ledger, env.ledger().sequence());
+    assert!(client.get_admin_actions(&100).is_empty());
+}
+
+#[test]
+fn test_dispute_stats_track_the_open_backlog() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+
+    let first = vaultix.disputed_escrow(1, &[100]);
+    env.ledger().with_mut(|li| li.timestamp += 100);
+    let second = vaultix.funded_escrow(2, &[100]);
+    client.raise_dispute(&2, &second.depositor, &DisputeReason::Quality);
+    env.ledger().with_mut(|li| li.timestamp += 50);
+
+    let stats = client.get_dispute_stats();
+    assert_eq!(stats.open, 2);
+    assert_eq!(stats.average_age, 100);
+    assert_eq!(stats.oldest_escrow, Some(1));
+    assert_eq!(stats.by_reason.get(DisputeReason::NonDelivery), Some(1));
+    assert_eq!(stats.by_reason.get(DisputeReason::Quality), Some(1));
+
+    client.resolve_dispute(&1, &first.recipient, &RulingReason::WorkAccepted, &None);
+    let stats = client.get_dispute_stats();
+    assert_eq!(stats.open, 1);
+    assert_eq!(stats.average_age, 50);
+    assert_eq!(stats.oldest_escrow, Some(2));
+    assert_eq!(stats.by_reason.get(DisputeReason::NonDelivery), None);
+
+    client.resolve_dispute(&2, &second.depositor, &RulingReason::NotDelivered, &None);
+    let stats = client.get_dispute_stats();
+    assert_eq!(stats.open, 0);
+    assert_eq!(stats.average_age, 0);
+    assert_eq!(stats.oldest_escrow, None);
+    assert!(stats.by_reason.is_empty());
+}
+
+#[test]
+fn test_relayer_submits_party_signed_multicall() {
+    use crate::testutils::Vaultix;
+    use soroban_sdk::testutils::{MockAuth, MockAuthInvoke};
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let escrow = vaultix.funded_escrow(1, &[100]);
+    let ops = vec![&env, EscrowOp::Release(1, 0), EscrowOp::Complete(1)];
+
+    // The depositor signs the batch once; whoever submits it needs no authorization
+    env.mock_auths(&[MockAuth {
+        address: &escrow.depositor,
+        invoke: &MockAuthInvoke {
+            contract: &vaultix.address,
+            fn_name: "multicall",
+            args: (&escrow.depositor, ops.clone()).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.multicall(&escrow.depositor, &ops);
+    assert_eq!(client.get_escrow(&1).status, EscrowStatus::Completed);
+
+    env.mock_all_auths();
+    let other = vaultix.funded_escrow(2, &[100]);
+    assert_eq!(
+        client.try_multicall(&other.recipient, &vec![&env, EscrowOp::Cancel(2)]),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+}
+
+#[test]
+fn test_escrow_notes_need_both_parties() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let escrow = vaultix.funded_escrow(1, &[100]);
+    assert_eq!(client.get_escrow_notes(&1), None);
+
+    let notes = BytesN::from_array(&env, &[4; 32]);
+    client.set_escrow_notes(&1, &Some(notes.clone()));
+    let auths = env.auths();
+    assert_eq!(auths[0].0, escrow.depositor);
+    assert_eq!(auths[1].0, escrow.recipient);
+    assert_eq!(client.get_escrow_notes(&1), Some(notes));
+
+    client.set_escrow_notes(&1, &None);
+    assert_eq!(client.get_escrow_notes(&1), None);
+
+    vaultix.completed_escrow(2, &[100]);
+    assert_eq!(
+        client.try_set_escrow_notes(&2, &None),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+}
+
+#[test]
+fn test_backup_confirmations_respect_spending_limit() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let escrow = EscrowBuilder::new(&vaultix, 1)
+        .milestone(MilestoneBuilder::new(300))
+        .milestone(MilestoneBuilder::new(300))
+        .build();
+
+    let backup = Address::generate(&env);
+    client.set_backup(&1, &backup, &100);
+    client.set_spending_limit(
+        &1,
+        &backup,
+        &SpendingLimit {
+            amount: 500,
+            period: 7 * 24 * 60 * 60,
+        },
+    );
+    token::StellarAssetClient::new(&env, &escrow.token).mint(&escrow.depositor, &600);
+    token::Client::new(&env, &escrow.token).approve(
+        &escrow.depositor,
+        &vaultix.address,
+        &600,
+        &(env.ledger().sequence() + 1_000),
+    );
+    client.deposit_funds(&1);
+
+    // Once the depositor has gone quiet the backup confirms, within its cap only
+    env.ledger().with_mut(|li| li.timestamp += 100);
+    client.confirm_delivery(&1, &0, &backup);
+    assert_eq!(client.get_delegate_spent(&1, &backup), 300);
+    assert_eq!(
+        client.try_confirm_delivery(&1, &1, &backup),
+        Err(Ok(Error::RateLimited))
+    );
+    assert_eq!(client.get_escrow(&1).total_released, 300);
+}
+
+#[test]
+fn test_backup_raised_dispute_is_weighed_for_the_depositor() {
+    use crate::testutils::{EscrowBuilder, MilestoneBuilder, Vaultix};
+
+    let env = Env::default();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+    let mut config = client.get_config();
+    config.dispute_bond = 50;
+    client.set_config(&vaultix.admin, &config);
+    let escrow = EscrowBuilder::new(&vaultix, 1)
+        .milestone(MilestoneBuilder::new(600))
+        .build();
+    let token = token::Client::new(&env, &escrow.token);
+
+    let backup = Address::generate(&env);
+    client.set_backup(&1, &backup, &100);
+    let minter = token::StellarAssetClient::new(&env, &escrow.token);
+    minter.mint(&escrow.depositor, &600);
+    minter.mint(&backup, &50);
+    token.approve(
+        &escrow.depositor,
+        &vaultix.address,
+        &600,
+        &(env.ledger().sequence() + 1_000),
+    );
+    client.deposit_funds(&1);
+
+    // The backup disputes for the silent depositor, paying the bond itself
+    env.ledger().with_mut(|li| li.timestamp += 100);
+    client.raise_dispute(&1, &backup, &DisputeReason::NonDelivery);
+    assert_eq!(token.balance(&backup), 0);
+    let preview = client.preview_resolution(&1, &escrow.depositor);
+    assert_eq!(preview.to_depositor, 600);
+
+    // The depositor's win counts as the raiser's, so the bond goes back to the backup
+    client.resolve_dispute(&1, &escrow.depositor, &RulingReason::NotDelivered, &None);
+    assert_eq!(token.balance(&escrow.depositor), 600);
+    assert_eq!(token.balance(&backup), 50);
+    assert_eq!(token.balance(&escrow.recipient), 0);
+    assert_eq!(token.balance(&vaultix.address), 0);
+}
+
+#[test]
+fn test_insurance_claims_are_capped_at_the_awarded_amount() {
+    use crate::testutils::Vaultix;
+
+    let env = Env::default();
+    let vaultix = Vaultix::setup(&env);
+    let client = &vaultix.client;
+
+    let escrow = vaultix.funded_escrow(1, &[400, 600]);
+    client.release_milestone(&1, &0);
+    client.raise_dispute(&1, &escrow.recipient, &DisputeReason::NonDelivery);
+    assert_eq!(
+        client.try_file_claim(&escrow.depositor, &1, &100),
+        Err(Ok(Error::InvalidEscrowStatus))
+    );
+    client.resolve_dispute(&1, &escrow.depositor, &RulingReason::NotDelivered, &None);
+
+    // The recipient lost the ruling, and the depositor was only refunded 600
+    assert_eq!(
+        client.try_file_claim(&escrow.recipient, &1, &100),
+        Err(Ok(Error::UnauthorizedAccess))
+    );
+    assert_eq!(
+        client.try_file_claim(&escrow.depositor, &1, &601),
+        Err(Ok(Error::InvalidMilestoneAmount))
+    );
+
+    // A second claim only fits in what the first left of the refund
+    let first = client.file_claim(&escrow.depositor, &1, &500);
+    assert_eq!(
+        client.try_file_claim(&escrow.depositor, &1, &101),
+        Err(Ok(Error::InvalidMilestoneAmount))
+    );
+    client.file_claim(&escrow.depositor, &1, &100);
+
+    // Rejecting a claim frees its share of the cap
+    client.reject_claim(&vaultix.admin, &first);
+    client.file_claim(&escrow.depositor, &1, &500);
+}
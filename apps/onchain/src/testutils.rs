@@ -0,0 +1,282 @@
+//! Test helpers for contracts that integrate with Vaultix, behind the `testutils` feature.
+//!
+//! ```ignore
+//! let vaultix = Vaultix::setup(&env);
+//! let escrow = EscrowBuilder::new(&vaultix, 1)
+//!     .milestone(MilestoneBuilder::new(500).description("Design"))
+//!     .milestone(MilestoneBuilder::new(500).description("Build"))
+//!     .funded()
+//!     .build();
+//! vaultix.client.release_milestone(&escrow.id, &0);
+//! assert_auth(&env, &escrow.depositor, &vaultix.address, "release_milestone");
+//! ```
+//!
+//! Everything here runs under `Env::mock_all_auths`, which [`Vaultix::setup`] enables.
+
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction},
+    token, Address, Env, IntoVal, String, Symbol, Vec,
+};
+
+use crate::{Milestone, MilestoneStatus, VaultixEscrow, VaultixEscrowClient};
+
+/// Default escrow lifetime used by [`EscrowBuilder`]: 30 days.
+const DEFAULT_DURATION: u64 = 30 * 24 * 60 * 60;
+
+/// A registered, initialized escrow contract.
+pub struct Vaultix<'a> {
+    pub env: Env,
+    pub address: Address,
+    pub client: VaultixEscrowClient<'a>,
+    pub admin: Address,
+    pub treasury: Address,
+}
+
+impl<'a> Vaultix<'a> {
+    /// Registers the contract and runs `setup` with a fresh admin and treasury at the default
+    /// fee. Enables `mock_all_auths` on `env`.
+    pub fn setup(env: &Env) -> Self {
+        env.mock_all_auths();
+
+        let address = env.register_contract(None, VaultixEscrow);
+        let client = VaultixEscrowClient::new(env, &address);
+        let admin = Address::generate(env);
+        let treasury = Address::generate(env);
+        client.setup(&admin, &treasury, &None);
+
+        Self {
+            env: env.clone(),
+            address,
+            client,
+            admin,
+            treasury,
+        }
+    }
+
+    /// Registers a Stellar asset contract whose admin is the Vaultix admin.
+    pub fn create_token(&self) -> Address {
+        self.env.register_stellar_asset_contract(self.admin.clone())
+    }
+
+    /// A funded escrow with one milestone per amount, ready for releases.
+    pub fn funded_escrow(&self, escrow_id: u64, amounts: &[i128]) -> TestEscrow {
+        let mut builder = EscrowBuilder::new(self, escrow_id);
+        for amount in amounts {
+            builder = builder.milestone(MilestoneBuilder::new(*amount));
+        }
+        builder.funded().build()
+    }
+
+    /// A funded escrow whose every milestone has been released and which is completed.
+    pub fn completed_escrow(&self, escrow_id: u64, amounts: &[i128]) -> TestEscrow {
+        let escrow = self.funded_escrow(escrow_id, amounts);
+        for index in 0..amounts.len() as u32 {
+            self.client.release_milestone(&escrow_id, &index);
+        }
+        self.client.complete_escrow(&escrow_id);
+        escrow
+    }
+
+    /// A funded escrow the recipient has disputed.
+    pub fn disputed_escrow(&self, escrow_id: u64, amounts: &[i128]) -> TestEscrow {
+        let escrow = self.funded_escrow(escrow_id, amounts);
+        self.client.raise_dispute(
+            &escrow_id,
+            &escrow.recipient,
+            &crate::DisputeReason::NonDelivery,
+        );
+        escrow
+    }
+
+    /// Checks the stored escrow against the contract's bookkeeping invariants, returning the
+    /// first one violated. Meant to run after every step of a fuzzed operation sequence.
+    pub fn check_invariants(&self, escrow_id: u64) -> Result<(), &'static str> {
+        let escrow = self.client.get_escrow(&escrow_id);
+        self.env.as_contract(&self.address, || {
+            crate::invariants::check_invariants(&self.env, escrow_id, &escrow)
+        })
+    }
+}
+
+/// Builds a milestone; 100 units described as "Milestone" unless overridden.
+pub struct MilestoneBuilder<'s> {
+    amount: i128,
+    description: &'s str,
+}
+
+impl<'s> MilestoneBuilder<'s> {
+    pub fn new(amount: i128) -> Self {
+        Self {
+            amount,
+            description: "Milestone",
+        }
+    }
+
+    pub fn description(mut self, description: &'s str) -> Self {
+        self.description = description;
+        self
+    }
+
+    pub fn build(&self, env: &Env) -> Milestone {
+        Milestone {
+            amount: self.amount,
+            status: MilestoneStatus::Pending,
+            description: String::from_str(env, self.description),
+        }
+    }
+}
+
+/// An escrow created by [`EscrowBuilder`], with the parties it was created for.
+pub struct TestEscrow {
+    pub id: u64,
+    pub depositor: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub total: i128,
+}
+
+/// Creates an escrow on a [`Vaultix`] instance. Parties and token are generated unless given;
+/// without milestones the escrow gets a single default one.
+pub struct EscrowBuilder<'v, 'a> {
+    vaultix: &'v Vaultix<'a>,
+    escrow_id: u64,
+    depositor: Option<Address>,
+    recipient: Option<Address>,
+    token: Option<Address>,
+    milestones: Vec<Milestone>,
+    deadline: Option<u64>,
+    funded: bool,
+}
+
+impl<'v, 'a> EscrowBuilder<'v, 'a> {
+    pub fn new(vaultix: &'v Vaultix<'a>, escrow_id: u64) -> Self {
+        Self {
+            vaultix,
+            escrow_id,
+            depositor: None,
+            recipient: None,
+            token: None,
+            milestones: Vec::new(&vaultix.env),
+            deadline: None,
+            funded: false,
+        }
+    }
+
+    pub fn depositor(mut self, depositor: &Address) -> Self {
+        self.depositor = Some(depositor.clone());
+        self
+    }
+
+    pub fn recipient(mut self, recipient: &Address) -> Self {
+        self.recipient = Some(recipient.clone());
+        self
+    }
+
+    /// Uses an existing token. Funding mints it, so its admin must be the Vaultix admin.
+    pub fn token(mut self, token: &Address) -> Self {
+        self.token = Some(token.clone());
+        self
+    }
+
+    pub fn milestone(mut self, milestone: MilestoneBuilder) -> Self {
+        self.milestones
+            .push_back(milestone.build(&self.vaultix.env));
+        self
+    }
+
+    pub fn deadline(mut self, deadline: u64) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Mints the total to the depositor and deposits it after creation.
+    pub fn funded(mut self) -> Self {
+        self.funded = true;
+        self
+    }
+
+    pub fn build(self) -> TestEscrow {
+        let env = &self.vaultix.env;
+        let client = &self.vaultix.client;
+
+        let depositor = self.depositor.unwrap_or_else(|| Address::generate(env));
+        let recipient = self.recipient.unwrap_or_else(|| Address::generate(env));
+        let token = self.token.unwrap_or_else(|| self.vaultix.create_token());
+        let mut milestones = self.milestones;
+        if milestones.is_empty() {
+            milestones.push_back(MilestoneBuilder::new(100).build(env));
+        }
+        let total = milestones.iter().map(|milestone| milestone.amount).sum();
+        let deadline = self
+            .deadline
+            .unwrap_or(env.ledger().timestamp() + DEFAULT_DURATION);
+
+        client.create_escrow(
+            &self.escrow_id,
+            &depositor,
+            &recipient,
+            &token,
+            &milestones,
+            &deadline,
+        );
+        if self.funded {
+            token::StellarAssetClient::new(env, &token).mint(&depositor, &total);
+            let expiration = env.ledger().sequence() + 1_000;
+            token::Client::new(env, &token).approve(
+                &depositor,
+                &self.vaultix.address,
+                &total,
+                &expiration,
+            );
+            client.deposit_funds(&self.escrow_id);
+        }
+
+        TestEscrow {
+            id: self.escrow_id,
+            depositor,
+            recipient,
+            token,
+            total,
+        }
+    }
+}
+
+/// Authorizes exactly one call, `function` on `contract` by `address` with `args`, replacing
+/// any earlier mocks including `mock_all_auths`. Calls needing any other authorization fail.
+pub fn authorize<A: IntoVal<Env, Vec<soroban_sdk::Val>>>(
+    env: &Env,
+    address: &Address,
+    contract: &Address,
+    function: &str,
+    args: A,
+) {
+    use soroban_sdk::testutils::{MockAuth, MockAuthInvoke};
+
+    env.mock_auths(&[MockAuth {
+        address,
+        invoke: &MockAuthInvoke {
+            contract,
+            fn_name: function,
+            args: args.into_val(env),
+            sub_invokes: &[],
+        },
+    }]);
+}
+
+/// Asserts the last top-level invocation required `address` to authorize `function` on
+/// `contract`.
+pub fn assert_auth(env: &Env, address: &Address, contract: &Address, function: &str) {
+    let name = Symbol::new(env, function);
+    let found = env.auths().iter().any(|(signer, invocation)| {
+        signer == address
+            && matches!(
+                &invocation.function,
+                AuthorizedFunction::Contract((called, called_name, _))
+                    if called == contract && *called_name == name
+            )
+    });
+    assert!(
+        found,
+        "{function} did not require the expected authorization"
+    );
+}
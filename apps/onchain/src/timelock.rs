@@ -0,0 +1,167 @@
+//! Timelock on admin rulings.
+//!
+//! A dispute nobody else arbitrates is ruled on by the admin, so a compromised admin key could
+//! otherwise drain the largest escrows in a single transaction.
+//! Once a `RulingTimelock` is set, admin rulings on escrows of at least its threshold are queued
+//! instead of executed. After the delay anyone may execute the ruling; until then either party
+//! can appeal, which discards it and hands the dispute to the arbitrator pool. Rulings by a
+//! dedicated or pool arbitrator are not delayed.
+//!
+//! The timelock can only be tightened, since a key able to switch it off could bypass it.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin_log, arbitration,
+    dispute::{apply_partial_ruling, apply_ruling, ensure_party, get_resolver},
+    errors::Error,
+    escrow::load_live_escrow,
+    history,
+    rbac::require_role,
+    storage::{
+        extend_instance, get_admin, pending_ruling_key, ruling_timelock_key, write_persistent,
+    },
+    types::{EscrowStatus, PendingRuling, QueuedRuling, Role, RulingTimelock},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, BytesN, Env};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Delays admin rulings on escrows whose total is at least `timelock.threshold` by
+    /// `timelock.delay` seconds (Admin role). A timelock already in place can only be tightened,
+    /// by lowering the threshold or lengthening the delay.
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller lacks the Admin role
+    /// * `InvalidConfiguration` - If a value is out of range or the change loosens the timelock
+    pub fn set_ruling_timelock(
+        env: Env,
+        caller: Address,
+        timelock: RulingTimelock,
+    ) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "set_ruling_timelock", &caller, None);
+
+        if timelock.threshold < 0 || timelock.delay == 0 {
+            return Err(Error::InvalidConfiguration);
+        }
+        if let Some(current) = Self::get_ruling_timelock(env.clone()) {
+            if timelock.threshold > current.threshold || timelock.delay < current.delay {
+                return Err(Error::InvalidConfiguration);
+            }
+        }
+
+        env.storage()
+            .instance()
+            .set(&ruling_timelock_key(), &timelock);
+        extend_instance(&env);
+        Ok(())
+    }
+
+    /// Returns the timelock on admin rulings, if one is set.
+    pub fn get_ruling_timelock(env: Env) -> Option<RulingTimelock> {
+        env.storage().instance().get(&ruling_timelock_key())
+    }
+
+    /// The admin ruling queued on an escrow, if one is waiting out the timelock.
+    pub fn get_pending_ruling(env: Env, escrow_id: u64) -> Option<PendingRuling> {
+        env.storage()
+            .persistent()
+            .get::<_, (PendingRuling, Option<BytesN<32>>)>(&pending_ruling_key(escrow_id))
+            .map(|(pending, _)| pending)
+    }
+
+    /// Executes a queued admin ruling once its delay has passed. Anyone may call this.
+    ///
+    /// # Errors
+    /// * `RulingNotReady` - If no ruling is queued or its delay is still running
+    /// * `UnauthorizedAccess` - If the dispute has since been taken by an arbitrator
+    /// * Whatever the ruling itself would fail with
+    pub fn execute_ruling(env: Env, escrow_id: u64) -> Result<(), Error> {
+        let (pending, rationale) = env
+            .storage()
+            .persistent()
+            .get::<_, (PendingRuling, Option<BytesN<32>>)>(&pending_ruling_key(escrow_id))
+            .ok_or(Error::RulingNotReady)?;
+        if env.ledger().timestamp() < pending.executable_at {
+            return Err(Error::RulingNotReady);
+        }
+
+        let admin = get_admin(&env)?;
+        if get_resolver(&env, escrow_id)? != admin {
+            return Err(Error::UnauthorizedAccess);
+        }
+        match pending.ruling {
+            QueuedRuling::Full(winner, reason) => {
+                apply_ruling(env, escrow_id, admin, winner, reason, rationale)
+            }
+            QueuedRuling::Partial(awarded) => apply_partial_ruling(env, escrow_id, admin, awarded),
+        }
+    }
+
+    /// Appeals the admin ruling queued on an escrow. The ruling is discarded and the dispute
+    /// goes to the arbitrator pool: a drawn arbitrator if one is eligible, otherwise the dispute
+    /// queue, where the admin may rule on it again if no arbitrator claims it.
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If caller is not a party to the escrow
+    /// * `RulingNotReady` - If no ruling is queued
+    pub fn appeal_ruling(env: Env, escrow_id: u64, caller: Address) -> Result<(), Error> {
+        caller.require_auth();
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        ensure_party(&escrow, &caller)?;
+
+        let key = pending_ruling_key(escrow_id);
+        if !env.storage().persistent().has(&key) {
+            return Err(Error::RulingNotReady);
+        }
+        env.storage().persistent().remove(&key);
+
+        arbitration::dequeue(&env, escrow_id);
+        arbitration::assign_or_enqueue(&env, escrow_id, &escrow);
+        history::record(&env, escrow_id, symbol_short!("appealed"), &caller, 0);
+        Ok(())
+    }
+}
+
+/// Queues `ruling` instead of executing it when the admin is ruling on an escrow covered by the
+/// timelock, returning whether it was queued. A new ruling replaces a queued one and restarts
+/// the delay.
+pub(crate) fn queue_ruling(
+    env: &Env,
+    escrow_id: u64,
+    resolver: &Address,
+    ruling: QueuedRuling,
+    rationale: Option<BytesN<32>>,
+) -> Result<bool, Error> {
+    let Some(timelock) = VaultixEscrow::get_ruling_timelock(env.clone()) else {
+        return Ok(false);
+    };
+    if get_admin(env)? != *resolver {
+        return Ok(false);
+    }
+    let escrow = load_live_escrow(env, escrow_id)?;
+    if escrow.total_amount < timelock.threshold {
+        return Ok(false);
+    }
+
+    if escrow.status != EscrowStatus::Disputed {
+        return Err(Error::InvalidEscrowStatus);
+    }
+    if let QueuedRuling::Full(winner, _) = &ruling {
+        if *winner != escrow.depositor && *winner != escrow.recipient {
+            return Err(Error::InvalidWinner);
+        }
+    }
+
+    let executable_at = env.ledger().timestamp().saturating_add(timelock.delay);
+    let key = pending_ruling_key(escrow_id);
+    let pending = PendingRuling {
+        ruling,
+        executable_at,
+    };
+    write_persistent(env, &key, &(pending, rationale));
+    history::record(env, escrow_id, symbol_short!("rul_queue"), resolver, 0);
+    Ok(true)
+}
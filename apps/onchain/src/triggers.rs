@@ -0,0 +1,145 @@
+//! Cross-escrow milestone triggers.
+//!
+//! A milestone can be wired to follow a milestone in another escrow: when the source milestone
+//! is released, the target milestone is submitted for review or released automatically.
+//! Back-to-back subcontracting uses this to pay a subcontractor as soon as the prime contractor
+//! is paid for the same work. Each trigger fires once, on the next release of its source.
+//!
+//! A release trigger hands over the target depositor's release decision and a submit trigger
+//! the target recipient's submission, so wiring one needs that party's authorization. A trigger
+//! whose target cannot act when it fires (unfunded, already released, disputed or gated by
+//! approvers) is dropped without blocking the source release.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    approvals::ensure_no_approval_policy,
+    errors::Error,
+    escrow::{load_live_escrow, pay_milestone, release_guard},
+    review::record_submission,
+    storage::{milestone_bit, trigger_key, write_persistent},
+    types::{MilestoneTrigger, TriggerAction},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, BytesN, Env, String, Vec};
+
+// Maximum triggers hanging off one source milestone
+const MAX_TRIGGERS: u32 = 5;
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Fires `trigger` when milestone `milestone_index` of escrow `escrow_id` is released.
+    /// Authorized by the target's depositor for a release trigger, its recipient for a submit
+    /// trigger.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If either escrow doesn't exist
+    /// * `MilestoneNotFound` - If either index is out of bounds
+    /// * `MilestoneAlreadyReleased` - If the source milestone was already released
+    /// * `InvalidConfiguration` - If the trigger targets its own source milestone
+    /// * `VectorTooLarge` - If the source milestone already has 5 triggers
+    pub fn add_milestone_trigger(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+        trigger: MilestoneTrigger,
+    ) -> Result<(), Error> {
+        let source = load_live_escrow(&env, escrow_id)?;
+        let target = load_live_escrow(&env, trigger.escrow_id)?;
+        match trigger.action {
+            TriggerAction::Release => target.depositor.require_auth(),
+            TriggerAction::Submit => target.recipient.require_auth(),
+        }
+
+        if milestone_index >= source.milestone_count
+            || trigger.milestone_index >= target.milestone_count
+        {
+            return Err(Error::MilestoneNotFound);
+        }
+        if source.released_mask & milestone_bit(milestone_index) != 0 {
+            return Err(Error::MilestoneAlreadyReleased);
+        }
+        if trigger.escrow_id == escrow_id && trigger.milestone_index == milestone_index {
+            return Err(Error::InvalidConfiguration);
+        }
+
+        let mut triggers = Self::get_milestone_triggers(env.clone(), escrow_id, milestone_index);
+        if triggers.len() >= MAX_TRIGGERS {
+            return Err(Error::VectorTooLarge);
+        }
+        triggers.push_back(trigger);
+
+        let key = trigger_key(escrow_id, milestone_index);
+        write_persistent(&env, &key, &triggers);
+        Ok(())
+    }
+
+    /// Triggers waiting on the release of a milestone.
+    pub fn get_milestone_triggers(
+        env: Env,
+        escrow_id: u64,
+        milestone_index: u32,
+    ) -> Vec<MilestoneTrigger> {
+        env.storage()
+            .persistent()
+            .get(&trigger_key(escrow_id, milestone_index))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+}
+
+/// Runs the triggers registered on a milestone that was just released. Auto-submissions carry
+/// the source milestone's deliverable hash, or zeroes if it was never submitted.
+pub(crate) fn fire_triggers(env: &Env, escrow_id: u64, milestone_index: u32) -> Result<(), Error> {
+    let key = trigger_key(escrow_id, milestone_index);
+    let triggers = VaultixEscrow::get_milestone_triggers(env.clone(), escrow_id, milestone_index);
+    if triggers.is_empty() {
+        return Ok(());
+    }
+    env.storage().persistent().remove(&key);
+
+    for trigger in triggers.iter() {
+        let Ok(mut target) = load_live_escrow(env, trigger.escrow_id) else {
+            continue;
+        };
+        if release_guard(env, trigger.escrow_id, &target, trigger.milestone_index).is_err() {
+            continue;
+        }
+
+        match trigger.action {
+            TriggerAction::Release => {
+                if ensure_no_approval_policy(env, trigger.escrow_id).is_err() {
+                    continue;
+                }
+                let depositor = target.depositor.clone();
+                pay_milestone(
+                    env,
+                    trigger.escrow_id,
+                    &mut target,
+                    trigger.milestone_index,
+                    &depositor,
+                )?;
+            }
+            TriggerAction::Submit => {
+                let review = VaultixEscrow::get_milestone_review(
+                    env.clone(),
+                    trigger.escrow_id,
+                    trigger.milestone_index,
+                );
+                if review.submitted {
+                    continue;
+                }
+                let hash = VaultixEscrow::get_deliverable(env.clone(), escrow_id, milestone_index)
+                    .map_or_else(|| BytesN::from_array(env, &[0; 32]), |d| d.hash);
+                record_submission(
+                    env,
+                    trigger.escrow_id,
+                    &target,
+                    trigger.milestone_index,
+                    hash,
+                    String::from_str(env, ""),
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
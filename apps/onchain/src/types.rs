@@ -1,84 +1,871 @@
-use soroban_sdk::{contracttype, Address, Env};
-
-/// Represents the current state of an escrow transaction
-#[derive(Clone, PartialEq, Debug)]
-#[contracttype]
-pub enum EscrowStatus {
-    /// Escrow has been created but not yet funded
-    Created,
-    /// Funds have been deposited into the escrow
-    Funded,
-    /// Transaction completed successfully, funds released
-    Completed,
-    /// Dispute raised, awaiting resolution
-    Disputed,
-}
-
-/// Core escrow data structure holding all transaction details
-/// 
-/// This struct represents a single escrow instance containing:
-/// - Participant addresses (buyer and seller)
-/// - Asset information (token address and amount)
-/// - Current status and deadline for completion
-#[derive(Clone)]
-#[contracttype]
-pub struct Escrow {
-    /// Address of the buyer depositing funds
-    pub buyer: Address,
-    
-    /// Address of the seller receiving funds upon completion
-    pub seller: Address,
-    
-    /// Amount of tokens being held in escrow
+//! Contract data types shared by every entrypoint.
+
+use soroban_sdk::{contractclient, contracttype, Address, BytesN, Env, Map, String, Symbol, Vec};
+
+pub use vaultix_interface::{
+    DisputeReason, Escrow, EscrowStatus, Milestone, MilestoneStatus, Resolution,
+};
+
+/// Privileged roles. The admin set at setup holds all of them; setup grants the treasury Pauser
+/// and FeeManager, which it controlled before roles existed.
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    Admin,
+    Pauser,
+    FeeManager,
+    Arbitrator, // Membership of the arbitrator pool
+    Upgrader,
+}
+
+/// Address that inherits a depositor's authority once they have been inactive long enough
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Backup {
+    pub address: Address,
+    pub inactivity_period: u64, // Seconds without depositor activity before the backup takes over
+}
+
+/// Approvers whose quorum releases milestones in place of the depositor
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApprovalPolicy {
+    pub approvers: Vec<Address>,
+    pub threshold: u32,    // Approvals needed to release a milestone
+    pub approval_ttl: u64, // Seconds an approval counts towards the quorum; 0 never expires
+}
+
+/// One round of terms exchanged while negotiating a proposed escrow
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Offer {
+    pub proposer: Address,
+    pub milestones: Vec<Milestone>,
+    pub deadline: u64,
+    pub total_amount: i128,
+    pub ledger: u32, // Ledger the offer was made at
+}
+
+/// What a member may do on behalf of an organization
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OrgRole {
+    Admin,    // Manages members and acts in the org's escrows
+    Operator, // Acts in the org's escrows
+}
+
+/// A company account whose escrows any authorized member can operate
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Organization {
+    pub account: Address, // Funds the org's escrows and is their depositor
+}
+
+/// A program of escrows a client tracks as one unit
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Project {
+    pub owner: Address,    // Depositor of every escrow in the project
+    pub escrows: Vec<u64>, // In the order they were added
+}
+
+/// Aggregate view of the escrows in a project that still exist
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProjectSummary {
+    pub escrows: u32,
+    pub budget: i128,   // Sum of escrow totals
+    pub released: i128, // Sum paid out so far
+    pub open_disputes: u32,
+}
+
+/// Most a delegate may release from one escrow within each `period`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpendingLimit {
+    pub amount: i128,
+    pub period: u64, // Seconds
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DelegateSpend {
+    pub window_start: u64,
+    pub spent: i128,
+}
+
+/// Discounted platform fee for escrows where either party has settled at least `min_volume`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeeTier {
+    pub min_volume: i128,
+    pub fee_bps: i128,
+}
+
+/// Token the platform fee on an escrowed asset is charged in instead, at `rate` fee-token units
+/// per asset unit, scaled by 10^7
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeeToken {
+    pub token: Address,
+    pub rate: i128,
+}
+
+/// Where the funds moved by a release or dispute ruling would go. Treasury and insurance
+/// amounts are in `fee_asset`, everything else in the escrowed token.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PayoutPreview {
+    pub to_recipient: i128,
+    pub to_depositor: i128,
+    pub to_treasury: i128,
+    pub to_insurance: i128,
+    pub to_arbitrator: i128, // Arbitrator's fee, out of the dispute bond unless the loser pays it
+    pub fee_asset: Address,
+}
+
+/// Running money totals of an escrow that its milestones alone don't record
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StatementTotals {
+    pub deposited: i128,
+    pub netted: i128,    // Covered by offset against an opposing escrow
+    pub fees: i128,      // Platform fees withheld from payouts
+    pub forfeited: i128, // Dispute bond lost by the party that raised it
+}
+
+/// Token metadata captured when an escrow was created, for rendering its amounts
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Currency {
+    pub symbol: String,
+    pub decimals: u32, // Amounts are in units of 10^-decimals of the token
+}
+
+/// Settlement summary of an escrow, laid out for rendering as an invoice. Amounts are in the
+/// escrowed token; fees charged in a separate fee token are not included.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Statement {
+    pub escrow_id: u64,
+    pub depositor: Address,
+    pub recipient: Address,
+    pub token_address: Address,
+    pub status: EscrowStatus,
+    pub lines: Vec<Milestone>, // One line item per milestone, with its current status
+    pub total_amount: i128,
+    pub deposited: i128,
+    pub netted: i128,
+    pub released: i128, // Gross amount paid towards the recipient
+    pub fees: i128,
+    pub recipient_net: i128, // `released` less `fees`
+    pub refunded: i128,      // Returned to the depositor
+    pub held: i128,          // Still locked in the contract
+    pub forfeited_bond: i128,
+    pub currency: Currency, // Token metadata as of creation; empty symbol if never captured
+}
+
+/// One escrow in an audit export
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowRecord {
+    pub escrow_id: u64,
+    pub created_at: u64, // 0 if the creation time was never recorded
+    pub escrow: Escrow,
+    pub currency: Currency, // Token metadata as of creation; empty symbol if never captured
+}
+
+/// A page of `export_escrows`. Pass `next_cursor` back to continue until `complete` is set.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowExport {
+    pub records: Vec<EscrowRecord>,
+    pub next_cursor: u64,
+    pub complete: bool,
+}
+
+/// One escrow in a state snapshot: its header and milestones exactly as stored
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct SnapshotEntry {
+    pub escrow_id: u64,
+    pub created_at: u64, // 0 if the creation time was never recorded
+    pub escrow: Escrow,
+    pub milestones: Vec<Milestone>,
+}
+
+/// A chunk of `snapshot`. Pass `next_cursor` back to continue until `complete` is set.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    pub version: u32, // Layout of `entries`; bumped whenever a stored type changes
+    pub entries: Vec<SnapshotEntry>,
+    pub next_cursor: u64,
+    pub complete: bool,
+}
+
+/// Delay imposed on admin rulings over escrows of at least `threshold`, during which the
+/// parties can appeal
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RulingTimelock {
+    pub threshold: i128,
+    pub delay: u64, // Seconds
+}
+
+/// A ruling waiting out the timelock
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QueuedRuling {
+    Full(Address, RulingReason), // Winner and reason, as passed to `resolve_dispute`
+    Partial(Vec<u32>),           // Awarded milestones, as passed to `resolve_dispute_partial`
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingRuling {
+    pub ruling: QueuedRuling,
+    pub executable_at: u64,
+}
+
+/// Token-holder arbitration settings: disputes on opted-in escrows are decided by a vote
+/// weighted by the `votes` contract's snapshot balances
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DaoConfig {
+    pub votes: Address,
+    pub quorum_bps: u32, // Share of the snapshot supply that must vote
+    pub period: u64,     // Seconds the vote stays open
+}
+
+/// Running tally of a token-holder vote on a dispute
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DaoVote {
+    pub votes: Address, // Voting contract at the time the vote opened
+    pub snapshot: u32,  // Ledger vote weights are read at
+    pub ends_at: u64,
+    pub supply: i128, // Total vote weight at the snapshot
+    pub quorum: i128, // Weight that must be cast for the vote to count
+    pub for_depositor: i128,
+    pub for_recipient: i128,
+}
+
+/// An arbitrator's vote on a panel dispute, weighted by their stake when it was committed.
+/// `for_recipient` only counts once the vote is revealed.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PanelBallot {
+    pub commitment: BytesN<32>,
+    pub revealed: bool,
+    pub for_recipient: bool,
+    pub weight: i128,
+}
+
+/// NFT an escrow pays for, and whether the contract holds it yet
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NftDeliverable {
+    pub collection: Address,
+    pub token_id: u32,
+    pub held: bool,
+}
+
+/// Token and amount the escrow's payment swaps against, whether the contract holds them yet,
+/// and which parties have confirmed the swap
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapLeg {
+    pub token: Address,
+    pub amount: i128,
+    pub held: bool,
+    pub depositor_confirmed: bool,
+    pub recipient_confirmed: bool,
+}
+
+/// Smallest USD value a milestone may have at creation, as priced by `oracle`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValueFloor {
+    pub oracle: Address,
+    pub min_milestone_usd: i128, // In the oracle's USD units
+}
+
+/// Stablecoin an escrow's payouts are converted into at release
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StablePayout {
+    pub token: Address,
+    pub max_slippage_bps: u32, // Tolerated shortfall against the router's quote
+}
+
+/// Multi-hop conversion of an escrow's payouts through registered AMM pools
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PayoutRoute {
+    pub hops: Vec<Address>, // Tokens after the escrow token, ending with the payout token
+    pub max_slippage_bps: u32, // Tolerated shortfall of the final amount against the quotes
+}
+
+/// Dispute terms two addresses agreed on for every escrow between them
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StandingAgreement {
+    pub arbiter: Address,
+    pub bond: i128,           // Dispute bond, in the escrow token
+    pub evidence_window: u64, // Seconds after a dispute is raised for evidence; 0 disables it
+}
+
+/// Policy of an operator: a platform running its own escrows on this deployment
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OperatorConfig {
+    pub fee_bps: i128, // Replaces the deployment fee for the operator's escrows
+    pub arbiters: Vec<Address>, // Pool arbitrators its disputes are assigned to; empty for all
+    pub tokens: Vec<Address>, // Tokens its escrows may use; empty for any allowed token
+    pub min_amount: i128, // Smallest escrow total, inclusive
+    pub max_amount: i128, // Largest escrow total, inclusive; 0 for no limit
+}
+
+/// Votes of the arbitrator panel deciding a dispute
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PanelVote {
+    /// When committing ends and revealing starts
+    pub ends_at: u64,
+    pub reveal_ends_at: u64,
+    pub ballots: Map<Address, PanelBallot>,
+}
+
+/// What a cross-escrow trigger does to its target milestone
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriggerAction {
+    Submit,
+    Release,
+}
+
+/// Milestone acted on when the milestone a trigger is registered on is released
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MilestoneTrigger {
+    pub escrow_id: u64,
+    pub milestone_index: u32,
+    pub action: TriggerAction,
+}
+
+/// One operation in a `multicall` batch
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EscrowOp {
+    Release(u64, u32),            // escrow_id, milestone_index
+    Submit(u64, u32, BytesN<32>), // escrow_id, milestone_index, deliverable hash
+    Approve(u64, u32),            // escrow_id, milestone_index, approved by the caller
+    Fund(u64),                    // escrow_id
+    Reject(u64, u32, BytesN<32>), // escrow_id, milestone_index, reason hash
+    Complete(u64),                // escrow_id
+    Cancel(u64),                  // escrow_id
+}
+
+/// Who receives yield credited to an escrow's funds
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum YieldPolicy {
+    Depositor,
+    Recipient,
+    Split,   // Half each, any odd unit to the depositor
+    FeePool, // Into the token's insurance pool
+}
+
+/// One payment of an escrow's funding plan
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Installment {
+    pub amount: i128,
+    pub due_ledger: u32, // Ledger by which the depositor promises to pay it
+}
+
+/// Grounds an arbitrator gives for a ruling
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RulingReason {
+    WorkAccepted,  // Delivered work met the agreed terms
+    NotDelivered,  // Work was never delivered
+    BelowStandard, // Delivered work fell short of the agreed terms
+    OutOfScope,    // The claim went beyond what was agreed
+    Misconduct,    // Fraud or bad faith by the losing party
+    Other,
+}
+
+/// An arbitrator's recorded ruling on a resolved dispute
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ruling {
+    pub winner: Address,
+    pub reason: RulingReason,
+    pub resolver: Address,
+    pub ledger: u32,
+}
+
+/// A party's latest evidence in a dispute: the hash of an off-chain bundle
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Evidence {
+    pub hash: BytesN<32>,
+    pub submitted_at: u64,
+}
+
+/// Milestone layout from before descriptions became `String`.
+/// Only used by `migrate_escrow` to read entries written by the previous contract version.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct LegacyMilestone {
     pub amount: i128,
-    
-    /// Address of the token contract (e.g., XLM or custom token)
+    pub status: MilestoneStatus,
+    pub description: Symbol,
+}
+
+/// Escrow layout from before descriptions became `String`.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct LegacyEscrow {
+    pub depositor: Address,
+    pub recipient: Address,
     pub token_address: Address,
-    
-    /// Current state of the escrow transaction
+    pub total_amount: i128,
+    pub total_released: i128,
+    pub milestones: Vec<LegacyMilestone>,
     pub status: EscrowStatus,
-    
-    /// Unix timestamp deadline for escrow completion
-    /// After this time, funds may be refundable
     pub deadline: u64,
+    pub resolution: Resolution,
+    pub assignable: bool,
+}
+
+/// Who bears the arbitrator's fee when a dispute is ruled on in full
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DisputeFeePolicy {
+    FromBond,  // Taken out of the bond, which then goes to the winner
+    LoserPays, // Charged to the loser, so a winning raiser gets their bond back in full
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ContractState {
+    Active,
+    Paused,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Config {
+    pub max_milestones: u32, // At most 64, the width of the milestone bitmasks
+    pub min_milestone_amount: i128, // Smallest accepted milestone amount
+    pub review_window: u64,  // Seconds a depositor has to review a delivered milestone
+    pub fee_bps: i128,       // Platform fee in basis points
+    pub dispute_bond: i128,  // Posted in the escrow token by whoever raises a dispute
+    pub paused: bool,
+    pub creation_quota: u32, // Escrows a depositor may create per window; 0 disables the limit
+    pub quota_window: u64,   // Length of the creation quota window in seconds
+    pub allowlist_only: bool, // Parties must be allowlisted or verified by the KYC attestor
+    pub evidence_window: u64, // Seconds after a dispute is raised for evidence; 0 disables it
+    pub max_rejections: u32, // Rejections of one milestone before it escalates to a dispute; 0 disables
+    pub dispute_grace: u64,  // Default seconds after creation before a party may raise a dispute
+    pub dispute_cooldown: u64, // Seconds a party must wait to dispute again after the escrow resumes
+    pub dormancy_period: u64,  // Inactivity after which the admin may refund an escrow; 0 disables
+    pub escalation_window: u64, // Seconds a submission may go unreviewed before it can be escalated to arbitration; 0 disables
+    pub dust_precision: u32, // Decimal places a milestone must be worth at least one unit of, given its token's decimals; 0 disables
+    pub dispute_fee_policy: DisputeFeePolicy, // Who bears the arbitrator's fee on a full ruling
+    pub max_pair_escrows: u32, // Escrows one depositor may have open with one recipient at a time; 0 disables
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Version {
+    pub version: String, // Semantic version of the contract crate
+    pub schema: u32,     // Storage schema version this code reads and writes
+}
+
+/// When storage entries have their TTL bumped, in ledgers
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TtlPolicy {
+    pub threshold: u32, // An entry touched with less TTL left than this is bumped
+    pub extend_to: u32, // TTL the bump restores
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct CreationQuota {
+    pub window_start: u64,
+    pub used: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClaimStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct InsuranceClaim {
+    pub claimant: Address,
+    pub escrow_id: u64,
+    pub token_address: Address,
+    pub amount: i128,
+    pub status: ClaimStatus,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AmountLimits {
+    pub min: i128, // Smallest accepted escrow total, inclusive
+    pub max: i128, // Largest accepted escrow total, inclusive
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ReceiptConfig {
+    pub contract: Address,
+    pub transferable: bool,
+}
+
+/// Interface expected from the companion NFT contract that issues escrow receipts.
+/// The receipt token ID is the escrow ID.
+#[contractclient(name = "ReceiptClient")]
+pub trait ReceiptInterface {
+    fn mint(env: Env, to: Address, escrow_id: u64, transferable: bool);
+    fn burn(env: Env, escrow_id: u64);
+}
+
+/// Completed engagement a certificate attests, issued once to each party
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Certificate {
+    pub escrow_id: u64,
+    pub depositor: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub volume_band: u32, // Order of magnitude of the released volume, in the token's base units
+    pub on_time: bool,    // Completed by the escrow's deadline
+    pub completed_at: u64,
+}
+
+/// Interface expected from the companion contract issuing completion certificates. They are
+/// soulbound: the contract must not let a holder transfer one.
+#[contractclient(name = "CertificateClient")]
+pub trait CertificateInterface {
+    fn issue(env: Env, holder: Address, certificate: Certificate);
+}
+
+/// Interface expected from an NFT collection whose tokens escrows can pay for.
+#[contractclient(name = "NftClient")]
+pub trait NftInterface {
+    fn transfer(env: Env, from: Address, to: Address, token_id: u32);
+}
+
+/// Interface expected from a KYC attestor contract vouching for verified addresses.
+#[contractclient(name = "AttestorClient")]
+pub trait KycAttestorInterface {
+    fn is_verified(env: Env, address: Address) -> bool;
+}
+
+/// Interface expected from a price feed valuing token amounts in USD, in its own fixed-point
+/// units (e.g. cents).
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracleInterface {
+    fn usd_value(env: Env, token: Address, amount: i128) -> i128;
+}
+
+/// Interface expected from an AMM router converting payouts. `swap` pulls `amount_in` from
+/// `from` through an allowance and fails unless `to` receives at least `min_out`.
+#[contractclient(name = "AmmRouterClient")]
+pub trait AmmRouterInterface {
+    fn quote(env: Env, token_in: Address, token_out: Address, amount_in: i128) -> i128;
+    fn swap(
+        env: Env,
+        from: Address,
+        token_in: Address,
+        token_out: Address,
+        amount_in: i128,
+        min_out: i128,
+        to: Address,
+    ) -> i128;
 }
 
-impl Escrow {
-    /// Creates a new Escrow instance
-    ///
-    /// # Arguments
-    /// * `buyer` - Address depositing the funds
-    /// * `seller` - Address receiving the funds
-    /// * `token_address` - Contract address of the token being escrowed
-    /// * `amount` - Quantity of tokens to hold
-    /// * `deadline` - Unix timestamp when escrow expires
-    ///
-    /// # Returns
-    /// New Escrow instance with status set to Created
-    pub fn new(
-        buyer: Address,
-        seller: Address,
-        token_address: Address,
-        amount: i128,
-        deadline: u64,
-    ) -> Self {
-        Self {
-            buyer,
-            seller,
-            amount,
-            token_address,
-            status: EscrowStatus::Created,
-            deadline,
-        }
-    }
-}
-
-/// Storage keys for persistent contract data
-#[derive(Clone)]
-#[contracttype]
-pub enum DataKey {
-    /// Counter for generating unique escrow IDs
-    EscrowCounter,
-    /// Individual escrow instance, keyed by ID
-    Escrow(u64),
-}
\ No newline at end of file
+/// Interface expected from an AMM pool registered for one token pair. `swap` pulls `amount_in`
+/// of `token_in` from `from` through an allowance and sends the other token of the pair to
+/// `to`, failing below `min_out`.
+#[contractclient(name = "AmmPoolClient")]
+pub trait AmmPoolInterface {
+    fn quote(env: Env, token_in: Address, amount_in: i128) -> i128;
+    fn swap(
+        env: Env,
+        from: Address,
+        token_in: Address,
+        amount_in: i128,
+        min_out: i128,
+        to: Address,
+    ) -> i128;
+}
+
+/// Interface expected from a sanctions-screening contract flagging addresses that may not take
+/// part in escrows.
+#[contractclient(name = "ScreenerClient")]
+pub trait ScreenerInterface {
+    fn is_blocked(env: Env, address: Address) -> bool;
+}
+
+/// Interface expected from a governance token's voting contract, reporting checkpointed vote
+/// weights as of a past ledger.
+#[contractclient(name = "VotesClient")]
+pub trait VotesInterface {
+    fn get_past_votes(env: Env, account: Address, ledger: u32) -> i128;
+    fn get_past_total_supply(env: Env, ledger: u32) -> i128;
+}
+
+/// Registry entrypoint a deployment calls to list itself with the Vaultix registry.
+#[contractclient(name = "RegistryClient")]
+pub trait RegistryInterface {
+    fn register(env: Env, instance: Address, operator: Address, version: String);
+}
+
+/// Interface expected from a contract notified after an escrow's funds move.
+#[contractclient(name = "HookClient")]
+pub trait EscrowHookInterface {
+    fn on_release(env: Env, escrow_id: u64, milestone_index: u32, amount: i128);
+    /// `resolution` is `None` when a partial resolution returned the escrow to Active.
+    fn on_resolve(env: Env, escrow_id: u64, resolution: Resolution);
+}
+
+/// Interface expected from a contract an address registers to hear about its escrows' events.
+/// The arguments mirror an `escrow_v1` event.
+#[contractclient(name = "NotifierClient")]
+pub trait NotifierInterface {
+    fn on_escrow_event(env: Env, escrow_id: u64, action: Symbol, actor: Address, amount: i128);
+}
+
+/// Per-address track record maintained by the escrow lifecycle entrypoints
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Reputation {
+    pub escrows_completed: u32,
+    pub disputes_raised: u32,
+    pub disputes_lost: u32,
+    pub volume: i128, // Total value settled to recipients in finished escrows
+}
+
+/// Where a milestone stands in the submit / reject / resubmit cycle
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MilestoneReview {
+    pub submitted: bool, // Awaiting the depositor's review
+    pub submitted_at: u64,
+    pub rejections: u32, // Times the depositor has sent it back
+}
+
+/// Delivery event a logistics oracle attests for a goods escrow
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DeliveryEvent {
+    Shipped,   // Handed to the carrier; submits the milestone for review
+    Delivered, // Received by the buyer; releases the milestone once the contest window passes
+    Returned,  // Sent back to the seller; withdraws the milestone's submission
+}
+
+/// Latest delivery event attested for a goods escrow milestone
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Shipment {
+    pub event: DeliveryEvent,
+    pub proof: BytesN<32>, // Hash of the carrier's tracking record backing the event
+    pub attested_at: u64,
+}
+
+/// What the recipient delivered with a milestone's latest submission
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deliverable {
+    pub hash: BytesN<32>, // Hash of the delivered work
+    pub uri: String,      // Where the work can be fetched; empty if not shared
+    pub ledger: u32,      // Ledger sequence of the submission
+}
+
+/// A single post-completion rating left by one escrow party about the other
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Rating {
+    pub rater: Address,
+    pub ratee: Address,
+    pub score: u32,
+    pub comment_hash: BytesN<32>, // Hash of the off-chain review text
+}
+
+/// Aggregate of all ratings received by an address
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RatingSummary {
+    pub count: u32,
+    pub total_score: u32,
+}
+
+/// Milestone slot in a template, sized as a share of the escrow total
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TemplateMilestone {
+    pub description: String,
+    pub bps: u32, // Share of the total amount in basis points
+}
+
+/// Reusable escrow layout registered by an organization
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowTemplate {
+    pub owner: Address,
+    pub token_address: Address,
+    pub milestones: Vec<TemplateMilestone>,
+    pub duration: u64,      // Seconds from creation until the escrow deadline
+    pub review_window: u64, // Seconds a depositor has to review a delivered milestone; 0 uses the configured default
+}
+
+/// Retainer-style payment schedule: `count` payments of `amount`, one per `interval` seconds
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecurringSchedule {
+    pub amount: i128,
+    pub interval: u64,
+    pub count: u32,
+    pub notice_period: u64, // Seconds of payments still owed after the depositor cancels
+}
+
+/// Linear vesting parameters for a streaming milestone
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StreamConfig {
+    pub start_ledger: u32,
+    pub end_ledger: u32,
+    pub claimed: i128, // Amount already paid out of the stream
+}
+
+/// Time-based vesting schedule: nothing vests before `start + cliff`, everything by `start + duration`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VestingSchedule {
+    pub start: u64,
+    pub cliff: u64,
+    pub duration: u64,
+}
+
+/// One audit log entry: who did what to an escrow, and when
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub action: Symbol, // e.g. `create`, `released`, `disputed`
+    pub actor: Address,
+    pub amount: i128, // Tokens moved by the action, 0 if none
+    pub ledger: u32,
+    pub timestamp: u64,
+}
+
+/// One privileged action in the deployment's admin log
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AdminAction {
+    pub action: Symbol, // Entrypoint the action was taken through, e.g. `set_config`
+    pub actor: Address,
+    pub escrow_id: Option<u64>, // Escrow the action was about, if any
+    pub ledger: u32,
+    pub timestamp: u64,
+}
+
+/// Lightweight view of an escrow returned by `get_status`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EscrowState {
+    pub status: EscrowStatus,
+    pub total_amount: i128,
+    pub total_released: i128,
+    pub released_mask: u64,
+    pub disputed_mask: u64,
+    pub changes: u32, // Actions recorded in the escrow's history so far
+}
+
+/// One anchored message in an escrow's thread
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message {
+    pub author: Address,
+    pub message_hash: BytesN<32>, // Hash of the off-chain message content
+    pub ledger: u32,
+    pub timestamp: u64,
+}
+
+/// A later deadline one party has proposed and the other has yet to accept
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeadlineProposal {
+    pub proposer: Address,
+    pub deadline: u64,
+}
+
+/// Protocol-wide counters returned by `get_stats`
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub escrows_created: u64,
+    pub active_escrows: u64, // Funded escrows that have not finished yet
+    pub disputes_open: u64,
+    pub total_locked: i128, // Funds currently held for escrows, summed across tokens
+    pub total_released: i128,
+}
+
+/// Arbitration backlog returned by `get_dispute_stats`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DisputeStats {
+    pub open: u64,
+    pub average_age: u64, // Seconds the open disputes have been waiting, on average
+    pub oldest_escrow: Option<u64>,
+    pub by_reason: Map<DisputeReason, u64>, // Open disputes per reason
+}
+
+/// Running totals behind `get_dispute_stats`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DisputeTally {
+    pub open: u64,
+    pub opened_at_sum: u64, // Sum of the open disputes' opening timestamps
+    pub by_reason: Map<DisputeReason, u64>,
+}
+
+/// A dispute awaiting resolution, as tracked for `get_dispute_stats`
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpenDispute {
+    pub escrow_id: u64,
+    pub reason: DisputeReason,
+    pub opened_at: u64,
+}
+
+/// Escrow accounting for one token returned by `get_token_stats`
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TokenStats {
+    pub locked: i128,   // Deposited and not yet paid out or refunded
+    pub released: i128, // Paid out to recipients, fees included
+    pub refunded: i128, // Returned to depositors
+}
+
+/// Staking rules for the arbitrator pool
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArbitrationConfig {
+    pub stake_token: Address,
+    pub min_stake: i128,
+    pub slash_bps: u32,         // Share of the stake slashed per missed deadline
+    pub resolution_window: u64, // Seconds an assigned arbitrator has to resolve, 0 for no limit
+    pub fee_bps: u32,           // Share of the dispute bond paid to the resolving arbitrator
+}
@@ -0,0 +1,174 @@
+//! Yield earned on escrowed funds.
+//!
+//! Locked funds can be put to work through a yield source the admin trusts, such as a lending
+//! adapter, which credits what it earns back to the escrow with `credit_yield`. Nothing accrues
+//! unless a source is configured. The escrow's `YieldPolicy`, chosen by the depositor before
+//! funding, decides who receives the accrued yield, and it is paid out automatically when the
+//! escrow finishes: to the depositor (the default), to the recipient, split evenly, or into
+//! the token's insurance pool.
+
+#[cfg(any(test, feature = "testutils"))]
+use crate::__VaultixEscrow_fn_set_registry;
+use crate::{
+    admin_log,
+    dispute::add_to_insurance_pool,
+    errors::Error,
+    escrow::{load_live_escrow, payout_address},
+    rbac::require_role,
+    storage::{
+        accrued_yield_key, legacy_accrued_yield_key, write_persistent, yield_policy_key, DataKey,
+    },
+    types::{Escrow, EscrowStatus, Role, YieldPolicy},
+    VaultixEscrow, VaultixEscrowClient,
+};
+use soroban_sdk::{contractimpl, symbol_short, token, Address, Env};
+
+#[contractimpl]
+impl VaultixEscrow {
+    /// Sets the contract allowed to credit yield to escrows, or disables yield with `None`
+    /// (Admin role).
+    pub fn set_yield_source(
+        env: Env,
+        caller: Address,
+        source: Option<Address>,
+    ) -> Result<(), Error> {
+        require_role(&env, &caller, Role::Admin)?;
+        admin_log::record(&env, "set_yield_source", &caller, None);
+
+        match &source {
+            Some(source) => env.storage().instance().set(&DataKey::yield_src, source),
+            None => env.storage().instance().remove(&DataKey::yield_src),
+        }
+        env.events().publish((symbol_short!("yield_src"),), source);
+        Ok(())
+    }
+
+    /// The contract allowed to credit yield, if yield is enabled.
+    pub fn get_yield_source(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::yield_src)
+    }
+
+    /// Chooses who receives the yield credited to the escrow. Depositor only, and only before
+    /// the escrow is funded.
+    ///
+    /// # Errors
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `EscrowAlreadyFunded` - If the escrow has been funded
+    pub fn set_yield_policy(env: Env, escrow_id: u64, policy: YieldPolicy) -> Result<(), Error> {
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        escrow.depositor.require_auth();
+
+        if !matches!(
+            escrow.status,
+            EscrowStatus::Draft | EscrowStatus::Proposed | EscrowStatus::Created
+        ) {
+            return Err(Error::EscrowAlreadyFunded);
+        }
+
+        let key = yield_policy_key(escrow_id);
+        write_persistent(&env, &key, &policy);
+        Ok(())
+    }
+
+    /// Who receives the escrow's yield; the depositor unless a policy was chosen.
+    pub fn get_yield_policy(env: Env, escrow_id: u64) -> YieldPolicy {
+        env.storage()
+            .persistent()
+            .get(&yield_policy_key(escrow_id))
+            .unwrap_or(YieldPolicy::Depositor)
+    }
+
+    /// Transfers `amount` of yield from the yield source into the escrow, to be paid out under
+    /// its policy when the escrow finishes.
+    ///
+    /// # Errors
+    /// * `UnauthorizedAccess` - If no yield source is configured
+    /// * `ZeroAmount` - If amount is not positive
+    /// * `EscrowNotFound` - If escrow doesn't exist
+    /// * `EscrowNotActive` - If the escrow holds no funds to earn on
+    pub fn credit_yield(env: Env, escrow_id: u64, amount: i128) -> Result<(), Error> {
+        let source = Self::get_yield_source(env.clone()).ok_or(Error::UnauthorizedAccess)?;
+        source.require_auth();
+
+        if amount <= 0 {
+            return Err(Error::ZeroAmount);
+        }
+        let escrow = load_live_escrow(&env, escrow_id)?;
+        if !matches!(
+            escrow.status,
+            EscrowStatus::Active | EscrowStatus::Disputed | EscrowStatus::Open
+        ) {
+            return Err(Error::EscrowNotActive);
+        }
+
+        let key = accrued_yield_key(escrow_id);
+        let accrued = Self::get_accrued_yield(env.clone(), escrow_id)
+            .checked_add(amount)
+            .ok_or(Error::InvalidMilestoneAmount)?;
+        write_persistent(&env, &key, &accrued);
+        env.storage()
+            .persistent()
+            .remove(&legacy_accrued_yield_key(escrow_id));
+        token::Client::new(&env, &escrow.token_address).transfer(
+            &source,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        env.events()
+            .publish((symbol_short!("yield"), escrow_id), amount);
+        Ok(())
+    }
+
+    /// Yield credited to the escrow that has not been paid out yet.
+    pub fn get_accrued_yield(env: Env, escrow_id: u64) -> i128 {
+        let persistent = env.storage().persistent();
+        persistent
+            .get(&accrued_yield_key(escrow_id))
+            .or_else(|| persistent.get(&legacy_accrued_yield_key(escrow_id)))
+            .unwrap_or(0)
+    }
+}
+
+/// How the escrow's accrued yield divides under its policy, as (to recipient, to depositor, to
+/// insurance pool). An odd unit left over from an even split goes to the depositor.
+pub(crate) fn yield_split(env: &Env, escrow_id: u64) -> (i128, i128, i128) {
+    let accrued = VaultixEscrow::get_accrued_yield(env.clone(), escrow_id);
+    match VaultixEscrow::get_yield_policy(env.clone(), escrow_id) {
+        YieldPolicy::Depositor => (0, accrued, 0),
+        YieldPolicy::Recipient => (accrued, 0, 0),
+        YieldPolicy::Split => (accrued / 2, accrued - accrued / 2, 0),
+        YieldPolicy::FeePool => (0, 0, accrued),
+    }
+}
+
+/// Pays the escrow's accrued yield out under its policy.
+pub(crate) fn settle_yield(env: &Env, escrow_id: u64, escrow: &Escrow) -> Result<(), Error> {
+    let (to_recipient, to_depositor, to_pool) = yield_split(env, escrow_id);
+    if to_recipient == 0 && to_depositor == 0 && to_pool == 0 {
+        return Ok(());
+    }
+    env.storage()
+        .persistent()
+        .remove(&accrued_yield_key(escrow_id));
+    env.storage()
+        .persistent()
+        .remove(&legacy_accrued_yield_key(escrow_id));
+    if to_pool > 0 {
+        add_to_insurance_pool(env, &escrow.token_address, to_pool)?;
+    }
+
+    let token_client = token::Client::new(env, &escrow.token_address);
+    let contract = env.current_contract_address();
+    if to_recipient > 0 {
+        token_client.transfer(
+            &contract,
+            &payout_address(env, escrow_id, escrow),
+            &to_recipient,
+        );
+    }
+    if to_depositor > 0 {
+        token_client.transfer(&contract, &escrow.depositor, &to_depositor);
+    }
+    Ok(())
+}
@@ -0,0 +1,334 @@
+//! High-level client for a deployed Vaultix escrow contract.
+
+use std::{collections::BTreeMap, str::FromStr, time::Duration};
+
+use stellar_xdr::curr::{
+    AccountId, LedgerEntryData, LedgerKey, LedgerKeyAccount, Limits, PublicKey, ReadXdr, ScAddress,
+    ScVal, TransactionMeta, Uint256, WriteXdr,
+};
+
+use crate::{
+    events::EVENT_SCHEMA,
+    rpc::RpcClient,
+    tx,
+    types::{address, as_map, as_u64, as_vec, symbol, vec_val},
+    DisputeReason, Error, Escrow, EscrowEvent, EscrowStatus, Keypair, Milestone, Result,
+};
+
+pub const TESTNET_PASSPHRASE: &str = "Test SDF Network ; September 2015";
+pub const MAINNET_PASSPHRASE: &str = "Public Global Stellar Network ; September 2015";
+
+/// Source account used for read-only simulations; it never needs to exist or sign.
+const SIMULATION_SOURCE: [u8; 32] = [0; 32];
+/// How often a submitted transaction's status is polled.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How long to wait for a submitted transaction before giving up.
+const SUBMIT_TIMEOUT: Duration = Duration::from_secs(60);
+const EVENTS_PAGE_LIMIT: u32 = 200;
+
+/// Calls a Vaultix escrow contract through Soroban RPC.
+#[derive(Debug)]
+pub struct VaultixClient {
+    rpc: RpcClient,
+    contract: ScAddress,
+    contract_id: String,
+    network_passphrase: String,
+}
+
+/// Events read by [`VaultixClient::get_events`], with the ledger to resume from.
+#[derive(Debug, Clone)]
+pub struct EventPage {
+    pub events: Vec<EscrowEvent>,
+    pub latest_ledger: u32,
+    /// The page hit the RPC page limit, so more events may follow its last ledger.
+    pub truncated: bool,
+}
+
+impl VaultixClient {
+    /// # Panics
+    /// If `contract_id` is not a `C...` contract strkey.
+    pub fn new(rpc_url: &str, contract_id: &str, network_passphrase: &str) -> Self {
+        Self::try_new(rpc_url, contract_id, network_passphrase).expect("invalid contract id")
+    }
+
+    /// Like [`VaultixClient::new`], but reports an invalid `contract_id` instead of panicking.
+    pub fn try_new(rpc_url: &str, contract_id: &str, network_passphrase: &str) -> Result<Self> {
+        Ok(Self {
+            rpc: RpcClient::new(rpc_url),
+            contract: ScAddress::from_str(contract_id)?,
+            contract_id: contract_id.to_string(),
+            network_passphrase: network_passphrase.to_string(),
+        })
+    }
+
+    /// The underlying RPC client, for calls this SDK does not wrap.
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    /// Creates an escrow funded later by `depositor`, who signs the call.
+    pub async fn create_escrow(
+        &self,
+        depositor: &Keypair,
+        escrow_id: u64,
+        recipient: &str,
+        token_address: &str,
+        milestones: &[Milestone],
+        deadline: u64,
+    ) -> Result<()> {
+        let milestones = milestones
+            .iter()
+            .map(ScVal::try_from)
+            .collect::<Result<Vec<_>>>()?;
+        let args = vec![
+            escrow_id.into(),
+            address(&depositor.address())?,
+            address(recipient)?,
+            address(token_address)?,
+            vec_val(milestones)?,
+            deadline.into(),
+        ];
+        self.invoke(depositor, "create_escrow", args).await?;
+        Ok(())
+    }
+
+    /// Moves the escrow's total from the depositor into the contract.
+    pub async fn deposit_funds(&self, depositor: &Keypair, escrow_id: u64) -> Result<()> {
+        self.invoke(depositor, "deposit_funds", vec![escrow_id.into()])
+            .await?;
+        Ok(())
+    }
+
+    /// Releases a milestone to the recipient; signed by the depositor.
+    pub async fn release_milestone(
+        &self,
+        depositor: &Keypair,
+        escrow_id: u64,
+        milestone_index: u32,
+    ) -> Result<()> {
+        let args = vec![escrow_id.into(), milestone_index.into()];
+        self.invoke(depositor, "release_milestone", args).await?;
+        Ok(())
+    }
+
+    /// Confirms delivery of a milestone as the buyer, releasing it.
+    pub async fn confirm_delivery(
+        &self,
+        buyer: &Keypair,
+        escrow_id: u64,
+        milestone_index: u32,
+    ) -> Result<()> {
+        let args = vec![
+            escrow_id.into(),
+            milestone_index.into(),
+            address(&buyer.address())?,
+        ];
+        self.invoke(buyer, "confirm_delivery", args).await?;
+        Ok(())
+    }
+
+    /// Raises a dispute as either party.
+    pub async fn raise_dispute(
+        &self,
+        party: &Keypair,
+        escrow_id: u64,
+        reason: DisputeReason,
+    ) -> Result<()> {
+        let args = vec![
+            escrow_id.into(),
+            address(&party.address())?,
+            reason.try_into()?,
+        ];
+        self.invoke(party, "raise_dispute", args).await?;
+        Ok(())
+    }
+
+    pub async fn cancel_escrow(&self, depositor: &Keypair, escrow_id: u64) -> Result<()> {
+        self.invoke(depositor, "cancel_escrow", vec![escrow_id.into()])
+            .await?;
+        Ok(())
+    }
+
+    pub async fn complete_escrow(&self, depositor: &Keypair, escrow_id: u64) -> Result<()> {
+        self.invoke(depositor, "complete_escrow", vec![escrow_id.into()])
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_escrow(&self, escrow_id: u64) -> Result<Escrow> {
+        let value = self.read("get_escrow", vec![escrow_id.into()]).await?;
+        Escrow::try_from(&value)
+    }
+
+    /// Fetches several escrows in one simulation, keyed by ID. Missing IDs are left out.
+    pub async fn get_escrows(&self, escrow_ids: &[u64]) -> Result<BTreeMap<u64, Escrow>> {
+        let ids = escrow_ids.iter().map(|id| (*id).into()).collect();
+        let value = self.read("get_escrows", vec![vec_val(ids)?]).await?;
+        as_map(&value)?
+            .0
+            .iter()
+            .map(|entry| Ok((as_u64(&entry.key)?, Escrow::try_from(&entry.val)?)))
+            .collect()
+    }
+
+    pub async fn get_milestones(&self, escrow_id: u64) -> Result<Vec<Milestone>> {
+        let value = self.read("get_milestones", vec![escrow_id.into()]).await?;
+        as_vec(&value)?.iter().map(Milestone::try_from).collect()
+    }
+
+    pub async fn get_state(&self, escrow_id: u64) -> Result<EscrowStatus> {
+        let value = self.read("get_state", vec![escrow_id.into()]).await?;
+        EscrowStatus::try_from(&value)
+    }
+
+    /// Polls the escrow until it reaches `status`, returning its state at that point.
+    ///
+    /// # Errors
+    /// * `Timeout` - If the status was not reached within `timeout`
+    pub async fn wait_for_status(
+        &self,
+        escrow_id: u64,
+        status: EscrowStatus,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Result<Escrow> {
+        let poll = async {
+            loop {
+                let escrow = self.get_escrow(escrow_id).await?;
+                if escrow.status == status {
+                    return Ok(escrow);
+                }
+                tokio::time::sleep(interval).await;
+            }
+        };
+        tokio::time::timeout(timeout, poll)
+            .await
+            .map_err(|_| Error::Timeout(format!("escrow {escrow_id} to become {status:?}")))?
+    }
+
+    /// Escrow events from `start_ledger` on, decoded from the `escrow_v1` schema. Pass
+    /// `latest_ledger + 1` from the returned page to continue.
+    pub async fn get_events(&self, start_ledger: u32) -> Result<EventPage> {
+        let schema = symbol(EVENT_SCHEMA)?.to_xdr_base64(Limits::none())?;
+        let page = self
+            .rpc
+            .get_events(
+                start_ledger,
+                &self.contract_id,
+                Some(&schema),
+                EVENTS_PAGE_LIMIT,
+            )
+            .await?;
+
+        let mut events = Vec::with_capacity(page.events.len());
+        for raw in &page.events {
+            if let Some(event) = EscrowEvent::decode(raw)? {
+                events.push(event);
+            }
+        }
+        Ok(EventPage {
+            events,
+            latest_ledger: page.latest_ledger,
+            truncated: page.events.len() >= EVENTS_PAGE_LIMIT as usize,
+        })
+    }
+
+    /// Builds, simulates, signs and submits a call to `function`, waiting for it to land.
+    /// Returns the contract's return value.
+    pub async fn invoke(
+        &self,
+        signer: &Keypair,
+        function: &str,
+        args: Vec<ScVal>,
+    ) -> Result<ScVal> {
+        let source = signer.public_key_bytes();
+        let sequence = self.sequence(source).await? + 1;
+        let mut transaction =
+            tx::invoke_contract(source, sequence, self.contract.clone(), function, args)?;
+
+        let envelope = tx::unsigned(transaction.clone()).to_xdr_base64(Limits::none())?;
+        let simulation = self.rpc.simulate_transaction(&envelope).await?;
+        tx::apply_simulation(&mut transaction, &simulation)?;
+
+        let envelope = tx::sign(transaction, &self.network_passphrase, signer)?
+            .to_xdr_base64(Limits::none())?;
+        let sent = self.rpc.send_transaction(&envelope).await?;
+        if sent.status == "ERROR" {
+            return Err(Error::Transaction {
+                hash: sent.hash,
+                status: sent.status,
+            });
+        }
+        self.await_transaction(&sent.hash).await
+    }
+
+    /// Simulates a call without submitting it and returns its result; for getters.
+    pub async fn read(&self, function: &str, args: Vec<ScVal>) -> Result<ScVal> {
+        let transaction =
+            tx::invoke_contract(SIMULATION_SOURCE, 0, self.contract.clone(), function, args)?;
+        let envelope = tx::unsigned(transaction).to_xdr_base64(Limits::none())?;
+        let simulation = self.rpc.simulate_transaction(&envelope).await?;
+        if let Some(error) = simulation.error {
+            return Err(Error::from_simulation(error));
+        }
+
+        let result = simulation
+            .results
+            .as_deref()
+            .and_then(<[_]>::first)
+            .ok_or_else(|| Error::Simulation(format!("{function} returned no result")))?;
+        Ok(ScVal::from_xdr_base64(&result.xdr, Limits::none())?)
+    }
+
+    async fn sequence(&self, account: [u8; 32]) -> Result<i64> {
+        let account_id = AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(account)));
+        let key = LedgerKey::Account(LedgerKeyAccount {
+            account_id: account_id.clone(),
+        })
+        .to_xdr_base64(Limits::none())?;
+
+        let entries = self.rpc.get_ledger_entries(&[key]).await?;
+        let entry = entries
+            .entries
+            .unwrap_or_default()
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::AccountNotFound(account_id.to_string()))?;
+        match LedgerEntryData::from_xdr_base64(&entry.xdr, Limits::none())? {
+            LedgerEntryData::Account(account) => Ok(account.seq_num.0),
+            _ => Err(Error::AccountNotFound(account_id.to_string())),
+        }
+    }
+
+    async fn await_transaction(&self, hash: &str) -> Result<ScVal> {
+        let poll = async {
+            loop {
+                let status = self.rpc.get_transaction(hash).await?;
+                match status.status.as_str() {
+                    "NOT_FOUND" => tokio::time::sleep(POLL_INTERVAL).await,
+                    "SUCCESS" => {
+                        let meta = status.result_meta_xdr.as_deref().ok_or_else(|| {
+                            Error::Decode("successful transaction without meta".into())
+                        })?;
+                        return match TransactionMeta::from_xdr_base64(meta, Limits::none())? {
+                            TransactionMeta::V3(meta) => Ok(meta
+                                .soroban_meta
+                                .map(|soroban| soroban.return_value)
+                                .unwrap_or(ScVal::Void)),
+                            _ => Ok(ScVal::Void),
+                        };
+                    }
+                    other => {
+                        return Err(Error::Transaction {
+                            hash: hash.to_string(),
+                            status: other.to_string(),
+                        })
+                    }
+                }
+            }
+        };
+        tokio::time::timeout(SUBMIT_TIMEOUT, poll)
+            .await
+            .map_err(|_| Error::Timeout(format!("transaction {hash}")))?
+    }
+}
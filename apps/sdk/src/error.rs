@@ -0,0 +1,45 @@
+//! SDK error type.
+
+/// Everything that can go wrong between building a call and reading its result.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("http request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("rpc error {code}: {message}")]
+    Rpc { code: i64, message: String },
+    #[error("xdr: {0}")]
+    Xdr(#[from] stellar_xdr::curr::Error),
+    #[error("invalid key: {0}")]
+    Key(#[from] stellar_strkey::DecodeError),
+    /// The contract returned one of its error codes (see `vaultix_interface::Error`).
+    #[error("contract error #{0}")]
+    Contract(u32),
+    #[error("simulation failed: {0}")]
+    Simulation(String),
+    #[error("transaction {hash} failed with status {status}")]
+    Transaction { hash: String, status: String },
+    #[error("account {0} not found")]
+    AccountNotFound(String),
+    #[error("timed out waiting for {0}")]
+    Timeout(String),
+    #[error("unexpected contract value: {0}")]
+    Decode(String),
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+impl Error {
+    /// Extracts the contract error code from a host error message such as
+    /// `HostError: Error(Contract, #9)`, falling back to a generic simulation failure.
+    pub(crate) fn from_simulation(message: String) -> Self {
+        let code = message
+            .split("Error(Contract, #")
+            .nth(1)
+            .and_then(|rest| rest.split(')').next())
+            .and_then(|code| code.parse().ok());
+        match code {
+            Some(code) => Error::Contract(code),
+            None => Error::Simulation(message),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Decoding of the contract's `escrow_v1` events.
+//!
+//! Every escrow action is published with topics `("escrow_v1", action, escrow_id)` and data
+//! `(actor, amount)`; see the contract's `events` module for the list of actions.
+
+use stellar_xdr::curr::{Limits, ReadXdr, ScVal};
+
+use crate::{
+    rpc::RawEvent,
+    types::{as_address, as_i128, as_symbol, as_u64, as_vec},
+    Error, Result,
+};
+
+/// First topic of every event in the versioned schema.
+pub const EVENT_SCHEMA: &str = "escrow_v1";
+
+/// One escrow action, decoded from a contract event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EscrowEvent {
+    /// RPC event id, usable as a cursor.
+    pub id: String,
+    pub ledger: u32,
+    pub action: String,
+    pub escrow_id: u64,
+    pub actor: String,
+    pub amount: i128,
+}
+
+impl EscrowEvent {
+    /// Decodes `event`, returning `None` for events outside the `escrow_v1` schema.
+    pub fn decode(event: &RawEvent) -> Result<Option<Self>> {
+        let topics = event
+            .topic
+            .iter()
+            .map(|topic| Ok(ScVal::from_xdr_base64(topic, Limits::none())?))
+            .collect::<Result<Vec<_>>>()?;
+        let [schema, action, escrow_id] = topics.as_slice() else {
+            return Ok(None);
+        };
+        if as_symbol(schema).ok().as_deref() != Some(EVENT_SCHEMA) {
+            return Ok(None);
+        }
+
+        let data = ScVal::from_xdr_base64(&event.value, Limits::none())?;
+        let [actor, amount] = as_vec(&data)? else {
+            return Err(Error::Decode("event data is not (actor, amount)".into()));
+        };
+
+        Ok(Some(Self {
+            id: event.id.clone(),
+            ledger: event.ledger,
+            action: as_symbol(action)?,
+            escrow_id: as_u64(escrow_id)?,
+            actor: as_address(actor)?,
+            amount: as_i128(amount)?,
+        }))
+    }
+}
@@ -0,0 +1,40 @@
+//! Async Rust client for the Vaultix escrow contract.
+//!
+//! [`VaultixClient`] talks to a Soroban RPC server and hides the XDR plumbing: write calls are
+//! built, simulated for their footprint and fee, signed with a [`Keypair`], submitted and polled
+//! until they land; read calls are answered from simulation alone. Escrow state and the
+//! contract's `escrow_v1` events decode into the plain Rust types in [`types`] and [`events`].
+//!
+//! ```no_run
+//! # async fn run() -> vaultix_sdk::Result<()> {
+//! use vaultix_sdk::{Keypair, VaultixClient, TESTNET_PASSPHRASE};
+//!
+//! let client = VaultixClient::new(
+//!     "https://soroban-testnet.stellar.org",
+//!     "CCONTRACT...",
+//!     TESTNET_PASSPHRASE,
+//! );
+//! let depositor = Keypair::from_secret("SSECRET...")?;
+//! client.deposit_funds(&depositor, 42).await?;
+//! let escrow = client.get_escrow(42).await?;
+//! println!("{:?}: {} released", escrow.status, escrow.total_released);
+//! # Ok(())
+//! # }
+//! ```
+
+mod client;
+mod error;
+pub mod events;
+pub mod rpc;
+mod signer;
+mod tx;
+pub mod types;
+
+pub use client::{EventPage, VaultixClient, MAINNET_PASSPHRASE, TESTNET_PASSPHRASE};
+pub use error::{Error, Result};
+pub use events::EscrowEvent;
+pub use signer::Keypair;
+pub use types::{DisputeReason, Escrow, EscrowStatus, Milestone, MilestoneStatus, Resolution};
+
+#[cfg(test)]
+mod test;
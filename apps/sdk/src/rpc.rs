@@ -0,0 +1,199 @@
+//! Minimal Soroban RPC client covering the methods the SDK needs.
+//!
+//! Responses keep their XDR fields base64-encoded; [`crate::VaultixClient`] decodes them.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+
+use crate::{Error, Result};
+
+/// JSON-RPC client for a single Soroban RPC endpoint.
+#[derive(Debug)]
+pub struct RpcClient {
+    http: reqwest::Client,
+    url: String,
+    next_id: AtomicU64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatestLedger {
+    pub sequence: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerEntry {
+    pub xdr: String,
+    pub last_modified_ledger_seq: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerEntries {
+    #[serde(default)]
+    pub entries: Option<Vec<LedgerEntry>>,
+    pub latest_ledger: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationResult {
+    #[serde(default)]
+    pub auth: Vec<String>,
+    pub xdr: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Simulation {
+    #[serde(default)]
+    pub transaction_data: Option<String>,
+    #[serde(default)]
+    pub min_resource_fee: Option<String>,
+    #[serde(default)]
+    pub results: Option<Vec<SimulationResult>>,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub latest_ledger: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendResult {
+    pub status: String,
+    pub hash: String,
+    #[serde(default)]
+    pub error_result_xdr: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionStatus {
+    pub status: String,
+    #[serde(default)]
+    pub ledger: Option<u32>,
+    #[serde(default)]
+    pub result_meta_xdr: Option<String>,
+}
+
+/// A contract event as returned by `getEvents`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawEvent {
+    pub id: String,
+    pub ledger: u32,
+    pub contract_id: String,
+    pub topic: Vec<String>,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Events {
+    pub events: Vec<RawEvent>,
+    pub latest_ledger: u32,
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcClient {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            url: url.into(),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    pub async fn get_latest_ledger(&self) -> Result<LatestLedger> {
+        self.request("getLatestLedger", json!({})).await
+    }
+
+    /// Looks up ledger entries by their base64 `LedgerKey`s.
+    pub async fn get_ledger_entries(&self, keys: &[String]) -> Result<LedgerEntries> {
+        self.request("getLedgerEntries", json!({ "keys": keys }))
+            .await
+    }
+
+    pub async fn simulate_transaction(&self, envelope: &str) -> Result<Simulation> {
+        self.request("simulateTransaction", json!({ "transaction": envelope }))
+            .await
+    }
+
+    pub async fn send_transaction(&self, envelope: &str) -> Result<SendResult> {
+        self.request("sendTransaction", json!({ "transaction": envelope }))
+            .await
+    }
+
+    pub async fn get_transaction(&self, hash: &str) -> Result<TransactionStatus> {
+        self.request("getTransaction", json!({ "hash": hash }))
+            .await
+    }
+
+    /// Contract events from `contract_id` starting at `start_ledger`, optionally restricted to
+    /// three-topic events whose first topic is the given base64 `ScVal`.
+    pub async fn get_events(
+        &self,
+        start_ledger: u32,
+        contract_id: &str,
+        first_topic: Option<&str>,
+        limit: u32,
+    ) -> Result<Events> {
+        let mut filter = json!({ "type": "contract", "contractIds": [contract_id] });
+        if let Some(topic) = first_topic {
+            filter["topics"] = json!([[topic, "*", "*"]]);
+        }
+        self.request(
+            "getEvents",
+            json!({
+                "startLedger": start_ledger,
+                "filters": [filter],
+                "pagination": { "limit": limit },
+            }),
+        )
+        .await
+    }
+
+    async fn request<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": self.next_id.fetch_add(1, Ordering::Relaxed),
+            "method": method,
+            "params": params,
+        });
+        let response: Response<T> = self
+            .http
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(Error::Rpc {
+                code: error.code,
+                message: error.message,
+            }),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(Error::Rpc {
+                code: 0,
+                message: format!("{method} returned neither result nor error"),
+            }),
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//! Ed25519 keys for signing transactions.
+
+use ed25519_dalek::{Signer, SigningKey};
+use stellar_strkey::ed25519::{PrivateKey, PublicKey};
+
+use crate::Result;
+
+/// A Stellar account key able to sign the transactions it submits.
+pub struct Keypair {
+    signing_key: SigningKey,
+}
+
+impl Keypair {
+    /// Loads a key from its `S...` secret seed.
+    pub fn from_secret(secret: &str) -> Result<Self> {
+        let seed = PrivateKey::from_string(secret)?;
+        Ok(Self {
+            signing_key: SigningKey::from_bytes(&seed.0),
+        })
+    }
+
+    /// Raw ed25519 public key.
+    pub fn public_key_bytes(&self) -> [u8; 32] {
+        self.signing_key.verifying_key().to_bytes()
+    }
+
+    /// The account's `G...` address.
+    pub fn address(&self) -> String {
+        PublicKey(self.public_key_bytes()).to_string()
+    }
+
+    pub(crate) fn sign(&self, payload: &[u8]) -> [u8; 64] {
+        self.signing_key.sign(payload).to_bytes()
+    }
+}
+
+impl core::fmt::Debug for Keypair {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Keypair")
+            .field("address", &self.address())
+            .finish_non_exhaustive()
+    }
+}
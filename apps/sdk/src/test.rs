@@ -0,0 +1,118 @@
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use stellar_xdr::curr::{Limits, ScVal, TransactionEnvelope, WriteXdr};
+
+use crate::{
+    rpc::RawEvent,
+    tx,
+    types::{address, struct_val, symbol, vec_val},
+    Error, Escrow, EscrowEvent, EscrowStatus, Keypair, Milestone, MilestoneStatus, Resolution,
+    TESTNET_PASSPHRASE,
+};
+
+fn contract() -> String {
+    stellar_strkey::Contract([9; 32]).to_string()
+}
+
+fn keypair(seed: u8) -> Keypair {
+    Keypair::from_secret(&stellar_strkey::ed25519::PrivateKey([seed; 32]).to_string()).unwrap()
+}
+
+#[test]
+fn test_decodes_escrow_as_the_contract_encodes_it() {
+    let depositor = keypair(1).address();
+    let recipient = keypair(2).address();
+    let value = struct_val(vec![
+        ("assignable", ScVal::Bool(false)),
+        ("deadline", 1706400000u64.into()),
+        ("depositor", address(&depositor).unwrap()),
+        ("disputed_mask", 0u64.into()),
+        ("milestone_count", 2u32.into()),
+        ("recipient", address(&recipient).unwrap()),
+        ("released_mask", 1u64.into()),
+        ("resolution", Resolution::None.try_into().unwrap()),
+        ("status", EscrowStatus::Active.try_into().unwrap()),
+        ("token_address", address(&contract()).unwrap()),
+        ("total_amount", 1000i128.into()),
+        ("total_released", 400i128.into()),
+    ])
+    .unwrap();
+
+    let escrow = Escrow::try_from(&value).unwrap();
+    assert_eq!(escrow.depositor, depositor);
+    assert_eq!(escrow.token_address, contract());
+    assert_eq!(escrow.status, EscrowStatus::Active);
+    assert_eq!(escrow.total_released, 400);
+    assert_eq!(escrow.milestone_count, 2);
+
+    let milestone = Milestone {
+        amount: 600,
+        status: MilestoneStatus::Pending,
+        description: "Design".into(),
+    };
+    let encoded = ScVal::try_from(&milestone).unwrap();
+    assert_eq!(Milestone::try_from(&encoded).unwrap(), milestone);
+}
+
+#[test]
+fn test_decodes_versioned_events_only() {
+    let actor = keypair(1).address();
+    let b64 = |value: ScVal| value.to_xdr_base64(Limits::none()).unwrap();
+    let event = |first: &str| RawEvent {
+        id: "0000000042-0000000001".into(),
+        ledger: 42,
+        contract_id: contract(),
+        topic: vec![
+            b64(symbol(first).unwrap()),
+            b64(symbol("released").unwrap()),
+            b64(7u64.into()),
+        ],
+        value: b64(vec_val(vec![address(&actor).unwrap(), 250i128.into()]).unwrap()),
+    };
+
+    let decoded = EscrowEvent::decode(&event("escrow_v1")).unwrap().unwrap();
+    assert_eq!(decoded.action, "released");
+    assert_eq!(decoded.escrow_id, 7);
+    assert_eq!(decoded.actor, actor);
+    assert_eq!(decoded.amount, 250);
+
+    assert_eq!(EscrowEvent::decode(&event("released")).unwrap(), None);
+}
+
+#[test]
+fn test_signs_transaction_for_network() {
+    let signer = keypair(3);
+    let transaction = tx::invoke_contract(
+        signer.public_key_bytes(),
+        11,
+        contract().parse().unwrap(),
+        "deposit_funds",
+        vec![5u64.into()],
+    )
+    .unwrap();
+    let hash = tx::transaction_hash(&transaction, TESTNET_PASSPHRASE).unwrap();
+
+    let TransactionEnvelope::Tx(envelope) =
+        tx::sign(transaction, TESTNET_PASSPHRASE, &signer).unwrap()
+    else {
+        panic!("expected a v1 envelope");
+    };
+    let signature = &envelope.signatures[0];
+    assert_eq!(signature.hint.0, signer.public_key_bytes()[28..]);
+
+    let key = VerifyingKey::from_bytes(&signer.public_key_bytes()).unwrap();
+    let bytes: [u8; 64] = signature.signature.0.to_vec().try_into().unwrap();
+    assert!(key.verify(&hash, &Signature::from_bytes(&bytes)).is_ok());
+}
+
+#[test]
+fn test_contract_errors_surface_their_code() {
+    let message = "HostError: Error(Contract, #9)\n\nEvent log (newest first): ...".to_string();
+    assert!(matches!(
+        Error::from_simulation(message),
+        Error::Contract(9)
+    ));
+    assert!(matches!(
+        Error::from_simulation("transaction malformed".into()),
+        Error::Simulation(_)
+    ));
+}
@@ -0,0 +1,129 @@
+//! Transaction assembly and signing.
+
+use sha2::{Digest, Sha256};
+use stellar_xdr::curr::{
+    DecoratedSignature, Hash, HostFunction, InvokeContractArgs, InvokeHostFunctionOp, Limits, Memo,
+    MuxedAccount, Operation, OperationBody, Preconditions, ReadXdr, ScAddress, ScSymbol, ScVal,
+    SequenceNumber, Signature, SignatureHint, SorobanAuthorizationEntry, SorobanTransactionData,
+    Transaction, TransactionEnvelope, TransactionExt, TransactionSignaturePayload,
+    TransactionSignaturePayloadTaggedTransaction, TransactionV1Envelope, Uint256, VecM, WriteXdr,
+};
+
+use crate::{rpc::Simulation, Error, Keypair, Result};
+
+/// Inclusion fee offered on top of the resource fee reported by simulation.
+pub(crate) const BASE_FEE: u32 = 100;
+
+/// Builds an unsigned transaction invoking `function` on `contract`.
+pub(crate) fn invoke_contract(
+    source: [u8; 32],
+    sequence: i64,
+    contract: ScAddress,
+    function: &str,
+    args: Vec<ScVal>,
+) -> Result<Transaction> {
+    let host_function = HostFunction::InvokeContract(InvokeContractArgs {
+        contract_address: contract,
+        function_name: ScSymbol(function.try_into()?),
+        args: args.try_into()?,
+    });
+    let operation = Operation {
+        source_account: None,
+        body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+            host_function,
+            auth: VecM::default(),
+        }),
+    };
+
+    Ok(Transaction {
+        source_account: MuxedAccount::Ed25519(Uint256(source)),
+        fee: BASE_FEE,
+        seq_num: SequenceNumber(sequence),
+        cond: Preconditions::None,
+        memo: Memo::None,
+        operations: vec![operation].try_into()?,
+        ext: TransactionExt::V0,
+    })
+}
+
+/// Applies the footprint, resource fee and authorization entries found by simulation.
+///
+/// Only source-account authorization is supported: the signer must be every address the
+/// call requires auth from, which holds for the escrow's own entrypoints.
+pub(crate) fn apply_simulation(tx: &mut Transaction, simulation: &Simulation) -> Result<()> {
+    if let Some(error) = &simulation.error {
+        return Err(Error::from_simulation(error.clone()));
+    }
+    let data = simulation
+        .transaction_data
+        .as_deref()
+        .ok_or_else(|| Error::Simulation("missing transaction data".into()))?;
+    let resource_fee: u32 = simulation
+        .min_resource_fee
+        .as_deref()
+        .unwrap_or("0")
+        .parse()
+        .map_err(|_| Error::Simulation("invalid resource fee".into()))?;
+
+    let auth = simulation
+        .results
+        .iter()
+        .flatten()
+        .flat_map(|result| &result.auth)
+        .map(|entry| {
+            Ok(SorobanAuthorizationEntry::from_xdr_base64(
+                entry,
+                Limits::none(),
+            )?)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if let Some(operation) = tx.operations.first() {
+        let mut operation = operation.clone();
+        if let OperationBody::InvokeHostFunction(op) = &mut operation.body {
+            op.auth = auth.try_into()?;
+        }
+        tx.operations = vec![operation].try_into()?;
+    }
+
+    tx.ext = TransactionExt::V1(SorobanTransactionData::from_xdr_base64(
+        data,
+        Limits::none(),
+    )?);
+    tx.fee = BASE_FEE.saturating_add(resource_fee);
+    Ok(())
+}
+
+/// Hash identifying `tx` on the network named by `passphrase`; this is what gets signed.
+pub(crate) fn transaction_hash(tx: &Transaction, passphrase: &str) -> Result<[u8; 32]> {
+    let payload = TransactionSignaturePayload {
+        network_id: Hash(Sha256::digest(passphrase.as_bytes()).into()),
+        tagged_transaction: TransactionSignaturePayloadTaggedTransaction::Tx(tx.clone()),
+    };
+    Ok(Sha256::digest(payload.to_xdr(Limits::none())?).into())
+}
+
+pub(crate) fn sign(
+    tx: Transaction,
+    passphrase: &str,
+    signer: &Keypair,
+) -> Result<TransactionEnvelope> {
+    let signature = signer.sign(&transaction_hash(&tx, passphrase)?);
+    let public_key = signer.public_key_bytes();
+    let decorated = DecoratedSignature {
+        hint: SignatureHint(public_key[28..].try_into().expect("4-byte hint")),
+        signature: Signature(signature.to_vec().try_into()?),
+    };
+
+    Ok(TransactionEnvelope::Tx(TransactionV1Envelope {
+        tx,
+        signatures: vec![decorated].try_into()?,
+    }))
+}
+
+/// Wraps an unsigned transaction for simulation.
+pub(crate) fn unsigned(tx: Transaction) -> TransactionEnvelope {
+    TransactionEnvelope::Tx(TransactionV1Envelope {
+        tx,
+        signatures: VecM::default(),
+    })
+}
@@ -0,0 +1,291 @@
+//! Off-chain mirrors of the contract's types, with conversions to and from `ScVal`.
+//!
+//! Addresses are kept as their `G...`/`C...` strkeys.
+
+use std::str::FromStr;
+
+use stellar_xdr::curr::{ScAddress, ScMap, ScMapEntry, ScString, ScSymbol, ScVal, ScVec, VecM};
+
+use crate::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MilestoneStatus {
+    Pending,
+    Released,
+    Disputed,
+    Unfunded,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Milestone {
+    pub amount: i128,
+    pub status: MilestoneStatus,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscrowStatus {
+    Created,
+    Active,
+    Completed,
+    Cancelled,
+    Disputed,
+    Resolved,
+    Draft,
+    Proposed,
+    Open,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    None,
+    Depositor,
+    Recipient,
+    Settled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisputeReason {
+    NonDelivery,
+    Quality,
+    Scope,
+    Fraud,
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Escrow {
+    pub depositor: String,
+    pub recipient: String,
+    pub token_address: String,
+    pub total_amount: i128,
+    pub total_released: i128,
+    pub milestone_count: u32,
+    pub released_mask: u64,
+    pub disputed_mask: u64,
+    pub status: EscrowStatus,
+    pub deadline: u64,
+    pub resolution: Resolution,
+    pub assignable: bool,
+}
+
+/// Unit-variant enums are encoded by the contract as a one-element vector holding the
+/// variant's name.
+macro_rules! unit_enum {
+    ($ty:ident { $($variant:ident),* $(,)? }) => {
+        impl $ty {
+            pub fn name(self) -> &'static str {
+                match self {
+                    $($ty::$variant => stringify!($variant),)*
+                }
+            }
+        }
+
+        impl TryFrom<$ty> for ScVal {
+            type Error = Error;
+
+            fn try_from(value: $ty) -> Result<ScVal> {
+                let variant = symbol(value.name())?;
+                Ok(ScVal::Vec(Some(ScVec(VecM::try_from(vec![variant])?))))
+            }
+        }
+
+        impl TryFrom<&ScVal> for $ty {
+            type Error = Error;
+
+            fn try_from(value: &ScVal) -> Result<$ty> {
+                match variant_name(value)?.as_str() {
+                    $(stringify!($variant) => Ok($ty::$variant),)*
+                    other => Err(Error::Decode(format!(
+                        "unknown {} variant `{other}`",
+                        stringify!($ty)
+                    ))),
+                }
+            }
+        }
+    };
+}
+
+unit_enum!(MilestoneStatus {
+    Pending,
+    Released,
+    Disputed,
+    Unfunded
+});
+unit_enum!(EscrowStatus {
+    Created,
+    Active,
+    Completed,
+    Cancelled,
+    Disputed,
+    Resolved,
+    Draft,
+    Proposed,
+    Open
+});
+unit_enum!(Resolution {
+    None,
+    Depositor,
+    Recipient,
+    Settled
+});
+unit_enum!(DisputeReason {
+    NonDelivery,
+    Quality,
+    Scope,
+    Fraud,
+    Other
+});
+
+impl TryFrom<&Milestone> for ScVal {
+    type Error = Error;
+
+    fn try_from(milestone: &Milestone) -> Result<ScVal> {
+        // Map keys must be sorted, as the contract's own encoding is
+        struct_val(vec![
+            ("amount", milestone.amount.into()),
+            (
+                "description",
+                ScVal::String(ScString(milestone.description.as_str().try_into()?)),
+            ),
+            ("status", milestone.status.try_into()?),
+        ])
+    }
+}
+
+impl TryFrom<&ScVal> for Milestone {
+    type Error = Error;
+
+    fn try_from(value: &ScVal) -> Result<Milestone> {
+        let map = as_map(value)?;
+        Ok(Milestone {
+            amount: i128_field(map, "amount")?,
+            status: field(map, "status")?.try_into()?,
+            description: match field(map, "description")? {
+                ScVal::String(s) => s.0.to_utf8_string_lossy(),
+                other => return Err(unexpected("string", other)),
+            },
+        })
+    }
+}
+
+impl TryFrom<&ScVal> for Escrow {
+    type Error = Error;
+
+    fn try_from(value: &ScVal) -> Result<Escrow> {
+        let map = as_map(value)?;
+        Ok(Escrow {
+            depositor: address_field(map, "depositor")?,
+            recipient: address_field(map, "recipient")?,
+            token_address: address_field(map, "token_address")?,
+            total_amount: i128_field(map, "total_amount")?,
+            total_released: i128_field(map, "total_released")?,
+            milestone_count: u32::try_from(field(map, "milestone_count")?.clone())
+                .map_err(|_| Error::Decode("`milestone_count` is not a u32".into()))?,
+            released_mask: u64_field(map, "released_mask")?,
+            disputed_mask: u64_field(map, "disputed_mask")?,
+            status: field(map, "status")?.try_into()?,
+            deadline: u64_field(map, "deadline")?,
+            resolution: field(map, "resolution")?.try_into()?,
+            assignable: matches!(field(map, "assignable")?, ScVal::Bool(true)),
+        })
+    }
+}
+
+pub(crate) fn symbol(name: &str) -> Result<ScVal> {
+    Ok(ScVal::Symbol(ScSymbol(name.try_into()?)))
+}
+
+pub(crate) fn address(strkey: &str) -> Result<ScVal> {
+    Ok(ScVal::Address(ScAddress::from_str(strkey)?))
+}
+
+pub(crate) fn vec_val(items: Vec<ScVal>) -> Result<ScVal> {
+    Ok(ScVal::Vec(Some(ScVec(VecM::try_from(items)?))))
+}
+
+pub(crate) fn struct_val(fields: Vec<(&str, ScVal)>) -> Result<ScVal> {
+    let entries = fields
+        .into_iter()
+        .map(|(key, val)| {
+            Ok(ScMapEntry {
+                key: symbol(key)?,
+                val,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(ScVal::Map(Some(ScMap(VecM::try_from(entries)?))))
+}
+
+pub(crate) fn as_vec(value: &ScVal) -> Result<&[ScVal]> {
+    match value {
+        ScVal::Vec(Some(items)) => Ok(items.0.as_slice()),
+        other => Err(unexpected("vec", other)),
+    }
+}
+
+pub(crate) fn as_symbol(value: &ScVal) -> Result<String> {
+    match value {
+        ScVal::Symbol(s) => Ok(s.0.to_utf8_string_lossy()),
+        other => Err(unexpected("symbol", other)),
+    }
+}
+
+pub(crate) fn as_address(value: &ScVal) -> Result<String> {
+    match value {
+        ScVal::Address(address) => Ok(address.to_string()),
+        other => Err(unexpected("address", other)),
+    }
+}
+
+pub(crate) fn as_i128(value: &ScVal) -> Result<i128> {
+    match value {
+        ScVal::I128(parts) => Ok(parts.into()),
+        other => Err(unexpected("i128", other)),
+    }
+}
+
+pub(crate) fn as_u64(value: &ScVal) -> Result<u64> {
+    match value {
+        ScVal::U64(n) => Ok(*n),
+        other => Err(unexpected("u64", other)),
+    }
+}
+
+fn variant_name(value: &ScVal) -> Result<String> {
+    match as_vec(value)?.first() {
+        Some(variant) => as_symbol(variant),
+        None => Err(Error::Decode("empty enum value".into())),
+    }
+}
+
+pub(crate) fn as_map(value: &ScVal) -> Result<&ScMap> {
+    match value {
+        ScVal::Map(Some(map)) => Ok(map),
+        other => Err(unexpected("map", other)),
+    }
+}
+
+fn field<'a>(map: &'a ScMap, name: &str) -> Result<&'a ScVal> {
+    map.0
+        .iter()
+        .find(|entry| matches!(&entry.key, ScVal::Symbol(key) if key.0.as_vec() == name.as_bytes()))
+        .map(|entry| &entry.val)
+        .ok_or_else(|| Error::Decode(format!("missing field `{name}`")))
+}
+
+fn address_field(map: &ScMap, name: &str) -> Result<String> {
+    as_address(field(map, name)?)
+}
+
+fn i128_field(map: &ScMap, name: &str) -> Result<i128> {
+    as_i128(field(map, name)?)
+}
+
+fn u64_field(map: &ScMap, name: &str) -> Result<u64> {
+    as_u64(field(map, name)?)
+}
+
+fn unexpected(expected: &str, found: &ScVal) -> Error {
+    Error::Decode(format!("expected {expected}, found {}", found.name()))
+}